@@ -114,6 +114,17 @@ client_request_definitions! {
         params: InterruptConversationParams,
         response: InterruptConversationResponse,
     },
+    /// Respawn a single MCP server of a running conversation. Progress is
+    /// reported through the conversation's event stream.
+    RestartMcpServer {
+        params: RestartMcpServerParams,
+        response: RestartMcpServerResponse,
+    },
+    /// Re-read `mcp_servers` from `config.toml` for a running conversation.
+    ReloadMcpServers {
+        params: ReloadMcpServersParams,
+        response: ReloadMcpServersResponse,
+    },
     AddConversationListener {
         params: AddConversationListenerParams,
         response: AddConversationSubscriptionResponse,
@@ -572,6 +583,27 @@ pub struct InterruptConversationResponse {
 #[serde(rename_all = "camelCase")]
 pub struct SendUserMessageResponse {}
 
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartMcpServerParams {
+    pub conversation_id: ConversationId,
+    pub server_name: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct RestartMcpServerResponse {}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadMcpServersParams {
+    pub conversation_id: ConversationId,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
+#[serde(rename_all = "camelCase")]
+pub struct ReloadMcpServersResponse {}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, TS)]
 #[serde(rename_all = "camelCase")]
 pub struct AddConversationListenerParams {
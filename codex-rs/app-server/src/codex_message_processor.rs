@@ -33,9 +33,13 @@ use codex_app_server_protocol::LoginChatGptCompleteNotification;
 use codex_app_server_protocol::LoginChatGptResponse;
 use codex_app_server_protocol::NewConversationParams;
 use codex_app_server_protocol::NewConversationResponse;
+use codex_app_server_protocol::ReloadMcpServersParams;
+use codex_app_server_protocol::ReloadMcpServersResponse;
 use codex_app_server_protocol::RemoveConversationListenerParams;
 use codex_app_server_protocol::RemoveConversationSubscriptionResponse;
 use codex_app_server_protocol::RequestId;
+use codex_app_server_protocol::RestartMcpServerParams;
+use codex_app_server_protocol::RestartMcpServerResponse;
 use codex_app_server_protocol::Result as JsonRpcResult;
 use codex_app_server_protocol::ResumeConversationParams;
 use codex_app_server_protocol::SendUserMessageParams;
@@ -182,6 +186,12 @@ impl CodexMessageProcessor {
             ClientRequest::InterruptConversation { request_id, params } => {
                 self.interrupt_conversation(request_id, params).await;
             }
+            ClientRequest::RestartMcpServer { request_id, params } => {
+                self.restart_mcp_server(request_id, params).await;
+            }
+            ClientRequest::ReloadMcpServers { request_id, params } => {
+                self.reload_mcp_servers(request_id, params).await;
+            }
             ClientRequest::AddConversationListener { request_id, params } => {
                 self.add_conversation_listener(request_id, params).await;
             }
@@ -1088,6 +1098,57 @@ impl CodexMessageProcessor {
         let _ = conversation.submit(Op::Interrupt).await;
     }
 
+    async fn restart_mcp_server(&self, request_id: RequestId, params: RestartMcpServerParams) {
+        let RestartMcpServerParams {
+            conversation_id,
+            server_name,
+        } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        let _ = conversation
+            .submit(Op::RestartMcpServer { server_name })
+            .await;
+
+        self.outgoing
+            .send_response(request_id, RestartMcpServerResponse {})
+            .await;
+    }
+
+    async fn reload_mcp_servers(&self, request_id: RequestId, params: ReloadMcpServersParams) {
+        let ReloadMcpServersParams { conversation_id } = params;
+        let Ok(conversation) = self
+            .conversation_manager
+            .get_conversation(conversation_id)
+            .await
+        else {
+            let error = JSONRPCErrorError {
+                code: INVALID_REQUEST_ERROR_CODE,
+                message: format!("conversation not found: {conversation_id}"),
+                data: None,
+            };
+            self.outgoing.send_error(request_id, error).await;
+            return;
+        };
+
+        let _ = conversation.submit(Op::ReloadMcpServers).await;
+
+        self.outgoing
+            .send_response(request_id, ReloadMcpServersResponse {})
+            .await;
+    }
+
     async fn add_conversation_listener(
         &mut self,
         request_id: RequestId,
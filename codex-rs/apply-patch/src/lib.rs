@@ -1,6 +1,7 @@
 mod parser;
 mod seek_sequence;
 mod standalone_executable;
+mod unified_diff_parser;
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -31,6 +32,45 @@ pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_too
 
 const APPLY_PATCH_COMMANDS: [&str; 2] = ["apply_patch", "applypatch"];
 
+/// Environment variable read by [`ApplyPatchOptions::from_env`]. Codex sets it
+/// when it runs `apply_patch` in a subprocess.
+pub const APPLY_PATCH_FUZZY_THRESHOLD_ENV_VAR: &str = "CODEX_APPLY_PATCH_FUZZY_THRESHOLD";
+
+/// Options that control how update hunks are located in the target file.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ApplyPatchOptions {
+    /// When set, a hunk whose lines cannot be found (even ignoring whitespace
+    /// and punctuation differences) is applied at the most similar location
+    /// if that location scores at least this similarity (`0.0..=1.0`) and is
+    /// unambiguous. `None` disables the fallback.
+    pub fuzzy_threshold: Option<f64>,
+}
+
+impl ApplyPatchOptions {
+    /// Read the options from [`APPLY_PATCH_FUZZY_THRESHOLD_ENV_VAR`]. Values
+    /// that do not parse as a number in `0.0..=1.0` are ignored.
+    pub fn from_env() -> Self {
+        let fuzzy_threshold = std::env::var(APPLY_PATCH_FUZZY_THRESHOLD_ENV_VAR)
+            .ok()
+            .and_then(|value| value.trim().parse::<f64>().ok())
+            .filter(|threshold| (0.0..=1.0).contains(threshold));
+        Self { fuzzy_threshold }
+    }
+}
+
+/// A hunk that was applied by the fuzzy fallback because its lines did not
+/// match the file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzyMatchReport {
+    pub path: PathBuf,
+    /// 1-based index of the `@@` hunk within the file's update.
+    pub hunk: usize,
+    /// 1-based, inclusive line range in the original file that was matched.
+    pub start_line: usize,
+    pub end_line: usize,
+    pub similarity: f64,
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ApplyPatchError {
     #[error(transparent)]
@@ -167,6 +207,9 @@ pub struct ApplyPatchAction {
 
     /// The working directory that was used to resolve relative paths in the patch.
     pub cwd: PathBuf,
+
+    /// Options the patch was verified with, to be used again when applying it.
+    pub options: ApplyPatchOptions,
 }
 
 impl ApplyPatchAction {
@@ -207,6 +250,7 @@ impl ApplyPatchAction {
                 .expect("path should have parent")
                 .to_path_buf(),
             patch,
+            options: ApplyPatchOptions::default(),
         }
     }
 }
@@ -214,6 +258,16 @@ impl ApplyPatchAction {
 /// cwd must be an absolute path so that we can resolve relative paths in the
 /// patch.
 pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
+    maybe_parse_apply_patch_verified_with_options(argv, cwd, ApplyPatchOptions::default())
+}
+
+/// Like [`maybe_parse_apply_patch_verified`], but locates update hunks
+/// according to `options`.
+pub fn maybe_parse_apply_patch_verified_with_options(
+    argv: &[String],
+    cwd: &Path,
+    options: ApplyPatchOptions,
+) -> MaybeApplyPatchVerified {
     // Detect a raw patch body passed directly as the command or as the body of a bash -lc
     // script. In these cases, report an explicit error rather than applying the patch.
     match argv {
@@ -278,7 +332,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         let ApplyPatchFileUpdate {
                             unified_diff,
                             content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
+                        } = match unified_diff_from_chunks_with_options(&path, &chunks, options) {
                             Ok(diff) => diff,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
@@ -299,6 +353,7 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                 changes,
                 patch,
                 cwd: effective_cwd,
+                options,
             })
         }
         MaybeApplyPatch::ShellParseError(e) => MaybeApplyPatchVerified::ShellParseError(e),
@@ -473,6 +528,16 @@ pub fn apply_patch(
     patch: &str,
     stdout: &mut impl std::io::Write,
     stderr: &mut impl std::io::Write,
+) -> Result<(), ApplyPatchError> {
+    apply_patch_with_options(patch, ApplyPatchOptions::default(), stdout, stderr)
+}
+
+/// Like [`apply_patch`], but locates update hunks according to `options`.
+pub fn apply_patch_with_options(
+    patch: &str,
+    options: ApplyPatchOptions,
+    stdout: &mut impl std::io::Write,
+    stderr: &mut impl std::io::Write,
 ) -> Result<(), ApplyPatchError> {
     let hunks = match parse_patch(patch) {
         Ok(source) => source.hunks,
@@ -496,7 +561,7 @@ pub fn apply_patch(
         }
     };
 
-    apply_hunks(&hunks, stdout, stderr)?;
+    apply_hunks_with_options(&hunks, options, stdout, stderr)?;
 
     Ok(())
 }
@@ -506,6 +571,16 @@ pub fn apply_hunks(
     hunks: &[Hunk],
     stdout: &mut impl std::io::Write,
     stderr: &mut impl std::io::Write,
+) -> Result<(), ApplyPatchError> {
+    apply_hunks_with_options(hunks, ApplyPatchOptions::default(), stdout, stderr)
+}
+
+/// Like [`apply_hunks`], but locates update hunks according to `options`.
+pub fn apply_hunks_with_options(
+    hunks: &[Hunk],
+    options: ApplyPatchOptions,
+    stdout: &mut impl std::io::Write,
+    stderr: &mut impl std::io::Write,
 ) -> Result<(), ApplyPatchError> {
     let _existing_paths: Vec<&Path> = hunks
         .iter()
@@ -534,7 +609,7 @@ pub fn apply_hunks(
         .collect::<Vec<&Path>>();
 
     // Delegate to a helper that applies each hunk to the filesystem.
-    match apply_hunks_to_files(hunks) {
+    match apply_hunks_to_files(hunks, options) {
         Ok(affected) => {
             print_summary(&affected, stdout).map_err(ApplyPatchError::from)?;
             Ok(())
@@ -561,11 +636,16 @@ pub struct AffectedPaths {
     pub added: Vec<PathBuf>,
     pub modified: Vec<PathBuf>,
     pub deleted: Vec<PathBuf>,
+    /// Hunks that were only applied thanks to the fuzzy fallback.
+    pub fuzzy_matches: Vec<FuzzyMatchReport>,
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
 /// Returns an error if the patch could not be applied.
-fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
+fn apply_hunks_to_files(
+    hunks: &[Hunk],
+    options: ApplyPatchOptions,
+) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
@@ -573,6 +653,7 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
+    let mut fuzzy_matches: Vec<FuzzyMatchReport> = Vec::new();
     for hunk in hunks {
         match hunk {
             Hunk::AddFile { path, contents } => {
@@ -597,8 +678,12 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
                 move_path,
                 chunks,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                let AppliedPatch {
+                    new_contents,
+                    fuzzy_matches: file_fuzzy_matches,
+                    ..
+                } = derive_new_contents_from_chunks(path, chunks, options)?;
+                fuzzy_matches.extend(file_fuzzy_matches);
                 if let Some(dest) = move_path {
                     if let Some(parent) = dest.parent()
                         && !parent.as_os_str().is_empty()
@@ -624,12 +709,14 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
         added,
         modified,
         deleted,
+        fuzzy_matches,
     })
 }
 
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
+    fuzzy_matches: Vec<FuzzyMatchReport>,
 }
 
 /// Return *only* the new file contents (joined into a single `String`) after
@@ -637,6 +724,7 @@ struct AppliedPatch {
 fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
+    options: ApplyPatchOptions,
 ) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let original_contents = match std::fs::read_to_string(path) {
         Ok(contents) => contents,
@@ -656,47 +744,67 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let mut fuzzy_matches = Vec::new();
+    let replacements =
+        compute_replacements(&original_lines, path, chunks, options, &mut fuzzy_matches)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
-    if !new_lines.last().is_some_and(String::is_empty) {
+    let missing_final_newline = chunks
+        .last()
+        .is_some_and(|chunk| chunk.is_end_of_file && chunk.missing_final_newline);
+    if !missing_final_newline && !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
     let new_contents = new_lines.join("\n");
     Ok(AppliedPatch {
         original_contents,
         new_contents,
+        fuzzy_matches,
     })
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
 /// new lines, given the patch `chunks`. Each replacement is returned as
-/// `(start_index, old_len, new_lines)`.
+/// `(start_index, old_len, new_lines)`. Hunks that could only be placed by the
+/// fuzzy fallback are recorded in `fuzzy_matches`.
 fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
+    options: ApplyPatchOptions,
+    fuzzy_matches: &mut Vec<FuzzyMatchReport>,
 ) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
     let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
     let mut line_index: usize = 0;
 
-    for chunk in chunks {
+    for (chunk_idx, chunk) in chunks.iter().enumerate() {
+        let mut fuzzy = FuzzyLocator {
+            original_lines,
+            path,
+            hunk: chunk_idx + 1,
+            threshold: options.fuzzy_threshold,
+            fuzzy_matches: &mut *fuzzy_matches,
+        };
+
         // If a chunk has a `change_context`, we use seek_sequence to find it, then
         // adjust our `line_index` to continue from there.
         if let Some(ctx_line) = &chunk.change_context {
-            if let Some(idx) = seek_sequence::seek_sequence(
-                original_lines,
-                std::slice::from_ref(ctx_line),
-                line_index,
-                false,
-            ) {
+            let pattern = std::slice::from_ref(ctx_line);
+            if let Some(idx) =
+                seek_sequence::seek_sequence(original_lines, pattern, line_index, false)
+            {
                 line_index = idx + 1;
             } else {
-                return Err(ApplyPatchError::ComputeReplacements(format!(
-                    "Failed to find context '{}' in {}",
-                    ctx_line,
-                    path.display()
-                )));
+                match fuzzy.locate(pattern, line_index, false) {
+                    Ok(idx) => line_index = idx + 1,
+                    Err(hint) => {
+                        return Err(ApplyPatchError::ComputeReplacements(format!(
+                            "Failed to find context '{}' in {}{hint}",
+                            ctx_line,
+                            path.display()
+                        )));
+                    }
+                }
             }
         }
 
@@ -745,16 +853,24 @@ fn compute_replacements(
             );
         }
 
-        if let Some(start_idx) = found {
-            replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
-            line_index = start_idx + pattern.len();
-        } else {
-            return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
-                path.display(),
-                chunk.old_lines.join("\n"),
-            )));
-        }
+        let (found, new_segment) = match found {
+            Some(start_idx) => (start_idx, new_slice.to_vec()),
+            None => {
+                let start_idx = fuzzy
+                    .locate(pattern, line_index, chunk.is_end_of_file)
+                    .map_err(|hint| {
+                        ApplyPatchError::ComputeReplacements(format!(
+                            "Failed to find expected lines in {}:\n{}{hint}",
+                            path.display(),
+                            chunk.old_lines.join("\n"),
+                        ))
+                    })?;
+                let window = &original_lines[start_idx..start_idx + pattern.len()];
+                (start_idx, merge_fuzzy_hunk(window, pattern, new_slice))
+            }
+        };
+        replacements.push((found, pattern.len(), new_segment));
+        line_index = found + pattern.len();
     }
 
     replacements.sort_by(|(lhs_idx, _, _), (rhs_idx, _, _)| lhs_idx.cmp(rhs_idx));
@@ -762,6 +878,75 @@ fn compute_replacements(
     Ok(replacements)
 }
 
+/// Replacement for the `window` of the file that a hunk's `old_lines` were
+/// fuzzily matched to. The patch's copies of context lines are the ones that
+/// failed to match, so context positions keep the file's own text; only the
+/// lines the hunk removes are dropped and the lines it adds inserted.
+fn merge_fuzzy_hunk(window: &[String], old_lines: &[String], new_lines: &[String]) -> Vec<String> {
+    let mut merged = Vec::with_capacity(new_lines.len());
+    for op in similar::capture_diff_slices(similar::Algorithm::Myers, old_lines, new_lines) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        match tag {
+            similar::DiffTag::Equal => merged.extend_from_slice(&window[old_range]),
+            similar::DiffTag::Delete => {}
+            similar::DiffTag::Insert | similar::DiffTag::Replace => {
+                merged.extend_from_slice(&new_lines[new_range]);
+            }
+        }
+    }
+    merged
+}
+
+/// Fallback used by [`compute_replacements`] once the exact and
+/// whitespace-insensitive searches have failed for a hunk.
+struct FuzzyLocator<'a> {
+    original_lines: &'a [String],
+    path: &'a Path,
+    hunk: usize,
+    threshold: Option<f64>,
+    fuzzy_matches: &'a mut Vec<FuzzyMatchReport>,
+}
+
+impl FuzzyLocator<'_> {
+    /// Return the index of the accepted match, or a hint for the error
+    /// message explaining why no location was good enough.
+    fn locate(&mut self, pattern: &[String], start: usize, eof: bool) -> Result<usize, String> {
+        let Some(threshold) = self.threshold else {
+            return Err(String::new());
+        };
+        let Some(candidate) =
+            seek_sequence::fuzzy_seek_sequence(self.original_lines, pattern, start, eof)
+        else {
+            return Err(String::new());
+        };
+        let start_line = candidate.start + 1;
+        let end_line = candidate.start + pattern.len();
+        let percent = candidate.similarity * 100.0;
+        if let Some((other, other_similarity)) = candidate.runner_up {
+            return Err(format!(
+                "\nLines {start_line}-{end_line} ({percent:.0}% similar) and lines {}-{} ({:.0}% similar) match about equally well; include more context lines to disambiguate.",
+                other + 1,
+                other + pattern.len(),
+                other_similarity * 100.0
+            ));
+        }
+        if candidate.similarity < threshold {
+            return Err(format!(
+                "\nClosest match is lines {start_line}-{end_line} ({percent:.0}% similar), below the fuzzy match threshold of {:.0}%.",
+                threshold * 100.0
+            ));
+        }
+        self.fuzzy_matches.push(FuzzyMatchReport {
+            path: self.path.to_path_buf(),
+            hunk: self.hunk,
+            start_line,
+            end_line,
+            similarity: candidate.similarity,
+        });
+        Ok(candidate.start)
+    }
+}
+
 /// Apply the `(start_index, old_len, new_lines)` replacements to `original_lines`,
 /// returning the modified file contents as a vector of lines.
 fn apply_replacements(
@@ -804,15 +989,33 @@ pub fn unified_diff_from_chunks(
     unified_diff_from_chunks_with_context(path, chunks, 1)
 }
 
+pub fn unified_diff_from_chunks_with_options(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    options: ApplyPatchOptions,
+) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
+    unified_diff(path, chunks, 1, options)
+}
+
 pub fn unified_diff_from_chunks_with_context(
     path: &Path,
     chunks: &[UpdateFileChunk],
     context: usize,
+) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
+    unified_diff(path, chunks, context, ApplyPatchOptions::default())
+}
+
+fn unified_diff(
+    path: &Path,
+    chunks: &[UpdateFileChunk],
+    context: usize,
+    options: ApplyPatchOptions,
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
     let AppliedPatch {
         original_contents,
         new_contents,
-    } = derive_new_contents_from_chunks(path, chunks)?;
+        ..
+    } = derive_new_contents_from_chunks(path, chunks, options)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
     Ok(ApplyPatchFileUpdate {
@@ -837,6 +1040,23 @@ pub fn print_summary(
     for path in &affected.deleted {
         writeln!(out, "D {}", path.display())?;
     }
+    if !affected.fuzzy_matches.is_empty() {
+        writeln!(
+            out,
+            "Some hunks did not match the file exactly and were applied at the most similar location; check that these edits landed where intended:"
+        )?;
+        for m in &affected.fuzzy_matches {
+            writeln!(
+                out,
+                "  {} hunk {}: lines {}-{} ({:.0}% similar)",
+                m.path.display(),
+                m.hunk,
+                m.start_line,
+                m.end_line,
+                m.similarity * 100.0
+            )?;
+        }
+    }
     Ok(())
 }
 
@@ -1598,10 +1818,90 @@ g
                 )]),
                 patch: argv[1].clone(),
                 cwd: session_dir.path().to_path_buf(),
+                options: ApplyPatchOptions::default(),
             })
         );
     }
 
+    #[test]
+    fn test_git_style_unified_diff_is_verified_and_applied() {
+        let session_dir = tempdir().unwrap();
+        fs::write(session_dir.path().join("source.txt"), "one\ntwo\nthree\n").unwrap();
+
+        let diff = r#"diff --git a/source.txt b/renamed.txt
+similarity index 80%
+rename from source.txt
+rename to renamed.txt
+--- a/source.txt
++++ b/renamed.txt
+@@ -1,3 +1,3 @@
+ one
+-two
++TWO
+ three
+"#;
+        let argv = vec!["apply_patch".to_string(), diff.to_string()];
+
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, session_dir.path())
+        else {
+            panic!("expected the unified diff to be verified");
+        };
+        assert_eq!(
+            action.changes(),
+            &HashMap::from([(
+                session_dir.path().join("source.txt"),
+                ApplyPatchFileChange::Update {
+                    unified_diff: "@@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n".to_string(),
+                    move_path: Some(session_dir.path().join("renamed.txt")),
+                    new_content: "one\nTWO\nthree\n".to_string(),
+                },
+            )])
+        );
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let args = parse_patch(&action.patch).unwrap();
+        let hunks: Vec<Hunk> = args
+            .hunks
+            .into_iter()
+            .map(|hunk| match hunk {
+                Hunk::UpdateFile {
+                    path,
+                    move_path,
+                    chunks,
+                } => Hunk::UpdateFile {
+                    path: session_dir.path().join(path),
+                    move_path: move_path.map(|p| session_dir.path().join(p)),
+                    chunks,
+                },
+                other => other,
+            })
+            .collect();
+        apply_hunks(&hunks, &mut stdout, &mut stderr).unwrap();
+        assert!(!session_dir.path().join("source.txt").exists());
+        assert_eq!(
+            fs::read_to_string(session_dir.path().join("renamed.txt")).unwrap(),
+            "one\nTWO\nthree\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_diff_without_final_newline_is_applied() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        fs::write(&path, "one\ntwo\n").unwrap();
+
+        let diff = format!(
+            "--- {path}\n+++ {path}\n@@ -1,2 +1,2 @@\n one\n-two\n+TWO\n\\ No newline at end of file\n",
+            path = path.display()
+        );
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&diff, &mut stdout, &mut stderr).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "one\nTWO");
+    }
+
     #[test]
     fn test_apply_patch_fails_on_write_error() {
         let dir = tempdir().unwrap();
@@ -1621,4 +1921,156 @@ g
         let result = apply_patch(&patch, &mut stdout, &mut stderr);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_fuzzy_fallback_applies_and_reports_drifted_hunk() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("drift.py");
+        fs::write(
+            &path,
+            "def greet(name):\n    print(f\"Hello, {name}!\")\n    return name\n",
+        )
+        .unwrap();
+
+        // The model remembered a slightly different version of the function.
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+ def greet(name):
+-    print(f"Hello {{name}}")
++    print(f"Hi, {{name}}!")
+     return name"#,
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let exact = apply_patch(&patch, &mut stdout, &mut stderr);
+        assert!(exact.is_err());
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch_with_options(
+            &patch,
+            ApplyPatchOptions {
+                fuzzy_threshold: Some(0.8),
+            },
+            &mut stdout,
+            &mut stderr,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "def greet(name):\n    print(f\"Hi, {name}!\")\n    return name\n"
+        );
+        let stdout = String::from_utf8(stdout).unwrap();
+        assert!(
+            stdout.contains(&format!("  {} hunk 1: lines 1-3 (", path.display())),
+            "{stdout}"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_fallback_keeps_the_files_context_lines() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.py");
+        fs::write(
+            &path,
+            "TIMEOUT_SECONDS = 30\nRETRIES = 3\nBACKOFF_FACTOR = 2\n",
+        )
+        .unwrap();
+
+        // Both context lines are misspelled; only the middle line changes.
+        let patch = wrap_patch(&format!(
+            r#"*** Update File: {}
+@@
+ TIMEOUT_SECOND = 30
+-RETRIES = 3
++RETRIES = 5
+ BACKOF_FACTOR = 2"#,
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch_with_options(
+            &patch,
+            ApplyPatchOptions {
+                fuzzy_threshold: Some(0.8),
+            },
+            &mut stdout,
+            &mut stderr,
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "TIMEOUT_SECONDS = 30\nRETRIES = 5\nBACKOFF_FACTOR = 2\n"
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_fallback_refuses_nearly_tied_locations() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("calls.rs");
+        let contents =
+            "let total_count = compute_checked(a, b);\nlet total_count = compute_checked(a, c);\n";
+        fs::write(&path, contents).unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-let total_count = compute_checked(a, bx);\n+let total_count = 0;",
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch_with_options(
+            &patch,
+            ApplyPatchOptions {
+                fuzzy_threshold: Some(0.8),
+            },
+            &mut stdout,
+            &mut stderr,
+        );
+        assert!(result.is_err());
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(
+            stderr.contains(
+                "Lines 1-1 (98% similar) and lines 2-2 (95% similar) match about equally well"
+            ),
+            "{stderr}"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+
+    #[test]
+    fn test_fuzzy_fallback_refuses_matches_below_threshold() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("unrelated.txt");
+        fs::write(&path, "alpha\nbeta\ngamma\n").unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-alphabet soup\n+delta",
+            path.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch_with_options(
+            &patch,
+            ApplyPatchOptions {
+                fuzzy_threshold: Some(0.9),
+            },
+            &mut stdout,
+            &mut stderr,
+        );
+        assert!(result.is_err());
+        let stderr = String::from_utf8(stderr).unwrap();
+        assert!(
+            stderr.contains("below the fuzzy match threshold of 90%"),
+            "{stderr}"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "alpha\nbeta\ngamma\n");
+    }
 }
@@ -22,7 +22,11 @@
 //!
 //! The parser below is a little more lenient than the explicit spec and allows for
 //! leading/trailing whitespace around patch markers.
+//!
+//! Standard unified diffs are accepted as well; see [`crate::unified_diff_parser`].
 use crate::ApplyPatchArgs;
+use crate::unified_diff_parser::looks_like_unified_diff;
+use crate::unified_diff_parser::parse_unified_diff;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -101,6 +105,11 @@ pub struct UpdateFileChunk {
     /// If set to true, `old_lines` must occur at the end of the source file.
     /// (Tolerance around trailing newlines should be encouraged.)
     pub is_end_of_file: bool,
+
+    /// If set to true, the file must not end with a newline once `new_lines`
+    /// are applied, as marked by `\ No newline at end of file` in unified
+    /// diffs. Only meaningful for the last chunk of a file.
+    pub missing_final_newline: bool,
 }
 
 pub fn parse_patch(patch: &str) -> Result<ApplyPatchArgs, ParseError> {
@@ -153,6 +162,14 @@ enum ParseMode {
 
 fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, ParseError> {
     let lines: Vec<&str> = patch.trim().lines().collect();
+    if let Some(diff_lines) = find_unified_diff(&lines, &mode) {
+        let hunks = parse_unified_diff(diff_lines)?;
+        return Ok(ApplyPatchArgs {
+            hunks,
+            patch: diff_lines.join("\n"),
+            workdir: None,
+        });
+    }
     let lines: &[&str] = match check_patch_boundaries_strict(&lines) {
         Ok(()) => &lines,
         Err(e) => match mode {
@@ -193,6 +210,33 @@ fn check_patch_boundaries_strict(lines: &[&str]) -> Result<(), ParseError> {
     check_start_and_end_lines_strict(first_line, last_line)
 }
 
+/// Returns the lines of a unified diff, if that is what `lines` contains. In
+/// lenient mode the diff may be wrapped in heredoc markers.
+fn find_unified_diff<'a>(lines: &'a [&'a str], mode: &ParseMode) -> Option<&'a [&'a str]> {
+    if looks_like_unified_diff(lines) {
+        return Some(lines);
+    }
+    match mode {
+        ParseMode::Strict => None,
+        ParseMode::Lenient => {
+            strip_heredoc_markers(lines).filter(|inner| looks_like_unified_diff(inner))
+        }
+    }
+}
+
+/// Returns the lines between `<<EOF` (possibly quoted) and `EOF` markers.
+fn strip_heredoc_markers<'a>(lines: &'a [&'a str]) -> Option<&'a [&'a str]> {
+    match lines {
+        [first, .., last]
+            if (first == &"<<EOF" || first == &"<<'EOF'" || first == &"<<\"EOF\"")
+                && last.ends_with("EOF") =>
+        {
+            Some(&lines[1..lines.len() - 1])
+        }
+        _ => None,
+    }
+}
+
 /// If we are in lenient mode, we check if the first line starts with `<<EOF`
 /// (possibly quoted) and the last line ends with `EOF`. There must be at least
 /// 4 lines total because the heredoc markers take up 2 lines and the patch text
@@ -204,19 +248,11 @@ fn check_patch_boundaries_lenient<'a>(
     original_lines: &'a [&'a str],
     original_parse_error: ParseError,
 ) -> Result<&'a [&'a str], ParseError> {
-    match original_lines {
-        [first, .., last] => {
-            if (first == &"<<EOF" || first == &"<<'EOF'" || first == &"<<\"EOF\"")
-                && last.ends_with("EOF")
-                && original_lines.len() >= 4
-            {
-                let inner_lines = &original_lines[1..original_lines.len() - 1];
-                match check_patch_boundaries_strict(inner_lines) {
-                    Ok(()) => Ok(inner_lines),
-                    Err(e) => Err(e),
-                }
-            } else {
-                Err(original_parse_error)
+    match strip_heredoc_markers(original_lines) {
+        Some(inner_lines) if original_lines.len() >= 4 => {
+            match check_patch_boundaries_strict(inner_lines) {
+                Ok(()) => Ok(inner_lines),
+                Err(e) => Err(e),
             }
         }
         _ => Err(original_parse_error),
@@ -377,6 +413,7 @@ fn parse_update_file_chunk(
         old_lines: Vec::new(),
         new_lines: Vec::new(),
         is_end_of_file: false,
+        missing_final_newline: false,
     };
     let mut parsed_lines = 0;
     for line in &lines[start_index..] {
@@ -498,7 +535,8 @@ fn test_parse_patch() {
                     change_context: Some("def f():".to_string()),
                     old_lines: vec!["    pass".to_string()],
                     new_lines: vec!["    return 123".to_string()],
-                    is_end_of_file: false
+                    is_end_of_file: false,
+                    missing_final_newline: false
                 }]
             }
         ]
@@ -525,7 +563,8 @@ fn test_parse_patch() {
                     change_context: None,
                     old_lines: vec![],
                     new_lines: vec!["line".to_string()],
-                    is_end_of_file: false
+                    is_end_of_file: false,
+                    missing_final_newline: false
                 }],
             },
             AddFile {
@@ -556,6 +595,7 @@ fn test_parse_patch() {
                 old_lines: vec!["import foo".to_string()],
                 new_lines: vec!["import foo".to_string(), "bar".to_string()],
                 is_end_of_file: false,
+                missing_final_newline: false,
             }],
         }]
     );
@@ -576,6 +616,7 @@ fn test_parse_patch_lenient() {
             old_lines: vec!["import foo".to_string()],
             new_lines: vec!["import foo".to_string(), "bar".to_string()],
             is_end_of_file: false,
+            missing_final_newline: false,
         }],
     }];
     let expected_error =
@@ -721,7 +762,8 @@ fn test_update_file_chunk() {
                     "add".to_string(),
                     "context2".to_string()
                 ],
-                is_end_of_file: false
+                is_end_of_file: false,
+                missing_final_newline: false
             }),
             6
         ))
@@ -733,7 +775,8 @@ fn test_update_file_chunk() {
                 change_context: None,
                 old_lines: vec![],
                 new_lines: vec!["line".to_string()],
-                is_end_of_file: true
+                is_end_of_file: true,
+                missing_final_newline: false
             }),
             3
         ))
@@ -73,26 +73,6 @@ pub(crate) fn seek_sequence(
     // differences when locating context lines.
     // ------------------------------------------------------------------
 
-    fn normalise(s: &str) -> String {
-        s.trim()
-            .chars()
-            .map(|c| match c {
-                // Various dash / hyphen code-points → ASCII '-'
-                '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
-                | '\u{2212}' => '-',
-                // Fancy single quotes → '\''
-                '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
-                // Fancy double quotes → '"'
-                '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
-                // Non-breaking space and other odd spaces → normal space
-                '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
-                | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
-                | '\u{3000}' => ' ',
-                other => other,
-            })
-            .collect::<String>()
-    }
-
     for i in search_start..=lines.len().saturating_sub(pattern.len()) {
         let mut ok = true;
         for (p_idx, pat) in pattern.iter().enumerate() {
@@ -109,8 +89,169 @@ pub(crate) fn seek_sequence(
     None
 }
 
+/// Normalise common Unicode punctuation to ASCII and trim surrounding
+/// whitespace.
+fn normalise(s: &str) -> String {
+    s.trim()
+        .chars()
+        .map(|c| match c {
+            // Various dash / hyphen code-points → ASCII '-'
+            '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}'
+            | '\u{2212}' => '-',
+            // Fancy single quotes → '\''
+            '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => '\'',
+            // Fancy double quotes → '"'
+            '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => '"',
+            // Non-breaking space and other odd spaces → normal space
+            '\u{00A0}' | '\u{2002}' | '\u{2003}' | '\u{2004}' | '\u{2005}' | '\u{2006}'
+            | '\u{2007}' | '\u{2008}' | '\u{2009}' | '\u{200A}' | '\u{202F}' | '\u{205F}'
+            | '\u{3000}' => ' ',
+            other => other,
+        })
+        .collect::<String>()
+}
+
+/// Location found by [`fuzzy_seek_sequence`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FuzzyMatch {
+    /// Index of the first matched line.
+    pub(crate) start: usize,
+    /// Mean per-line similarity in `0.0..=1.0`.
+    pub(crate) similarity: f64,
+    /// `(start, similarity)` of another location that scored within
+    /// [`AMBIGUITY_MARGIN`] of this one, so `start` is only one of several
+    /// candidates.
+    pub(crate) runner_up: Option<(usize, f64)>,
+}
+
+/// A fuzzy match must beat every other location by at least this much
+/// similarity to be trusted.
+pub(crate) const AMBIGUITY_MARGIN: f64 = 0.03;
+
+/// Locate the window of `lines` (at or after `start`) that is most similar to
+/// `pattern`. Used as a last resort once [`seek_sequence`] fails, e.g. when a
+/// model gets a few characters of a context line wrong.
+///
+/// Each line is compared with a normalised Levenshtein similarity after
+/// collapsing whitespace and normalising punctuation as in the final
+/// [`seek_sequence`] pass; a window scores the mean over its lines. Callers
+/// decide whether the returned score is good enough to apply the hunk.
+pub(crate) fn fuzzy_seek_sequence(
+    lines: &[String],
+    pattern: &[String],
+    start: usize,
+    eof: bool,
+) -> Option<FuzzyMatch> {
+    if pattern.is_empty() || pattern.len() > lines.len() {
+        return None;
+    }
+    let last_start = lines.len() - pattern.len();
+    if start > last_start {
+        return None;
+    }
+    let pattern: Vec<Vec<char>> = pattern.iter().map(|p| comparable_chars(p)).collect();
+    let lines: Vec<Vec<char>> = lines.iter().map(|l| comparable_chars(l)).collect();
+
+    // Mirror `seek_sequence`: a hunk anchored at the end of the file should
+    // match there if it reasonably can.
+    if eof {
+        let similarity = window_similarity(&lines[last_start..], &pattern, 0.0);
+        if let Some(similarity) = similarity
+            && similarity >= EOF_PREFERENCE_SIMILARITY
+        {
+            return Some(FuzzyMatch {
+                start: last_start,
+                similarity,
+                runner_up: None,
+            });
+        }
+    }
+
+    let mut best: Option<(usize, f64)> = None;
+    let mut runner_up: Option<(usize, f64)> = None;
+    for i in start..=last_start {
+        // Windows further than the margin below the best can neither win nor
+        // make the best ambiguous.
+        let floor = best.map_or(0.0, |(_, b)| (b - AMBIGUITY_MARGIN).max(0.0));
+        let Some(similarity) = window_similarity(&lines[i..i + pattern.len()], &pattern, floor)
+        else {
+            continue;
+        };
+        match best {
+            Some((_, b)) if similarity <= b => {
+                if runner_up.is_none_or(|(_, r)| similarity > r) {
+                    runner_up = Some((i, similarity));
+                }
+            }
+            _ => {
+                runner_up = best;
+                best = Some((i, similarity));
+            }
+        }
+    }
+    best.map(|(start, similarity)| FuzzyMatch {
+        start,
+        similarity,
+        runner_up: runner_up.filter(|(_, r)| similarity - r < AMBIGUITY_MARGIN),
+    })
+}
+
+/// Windows at the end of the file are preferred for end-of-file hunks when
+/// they score at least this well.
+const EOF_PREFERENCE_SIMILARITY: f64 = 0.5;
+
+/// Mean similarity of `window` to `pattern`, or `None` once it is certain to
+/// fall below `floor`.
+fn window_similarity(window: &[Vec<char>], pattern: &[Vec<char>], floor: f64) -> Option<f64> {
+    let len = pattern.len() as f64;
+    let mut total = 0.0;
+    for (idx, (line, pat)) in window.iter().zip(pattern).enumerate() {
+        // Even if every remaining line matched perfectly, could this window
+        // still reach `floor`?
+        let remaining = (pattern.len() - idx) as f64;
+        if (total + remaining) / len < floor {
+            return None;
+        }
+        total += line_similarity(line, pat);
+    }
+    let similarity = total / len;
+    (similarity >= floor).then_some(similarity)
+}
+
+fn comparable_chars(line: &str) -> Vec<char> {
+    normalise(line)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .collect()
+}
+
+/// `1 - levenshtein(a, b) / max(len(a), len(b))`.
+fn line_similarity(a: &[char], b: &[char]) -> f64 {
+    let longest = a.len().max(b.len());
+    if longest == 0 {
+        return 1.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut curr = vec![0; b.len() + 1];
+    for (i, ca) in a.iter().enumerate() {
+        curr[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != cb);
+            curr[j + 1] = substitution.min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    1.0 - prev[b.len()] as f64 / longest as f64
+}
+
 #[cfg(test)]
 mod tests {
+    use super::fuzzy_seek_sequence;
     use super::seek_sequence;
     use std::string::ToString;
 
@@ -148,4 +289,55 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_fuzzy_match_tolerates_small_differences() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "    let total = compute(a, b);",
+            "    println!(\"{total}\");",
+            "}",
+        ]);
+        // The model misremembered an identifier and the spacing.
+        let pattern = to_vec(&["let total = compute(a,b);", "println!(\"{totl}\");"]);
+        assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
+
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, false).expect("fuzzy match");
+        assert_eq!(found.start, 1);
+        assert_eq!(found.runner_up, None);
+        assert!(found.similarity > 0.9, "similarity {}", found.similarity);
+    }
+
+    #[test]
+    fn test_fuzzy_match_flags_ambiguous_windows() {
+        let lines = to_vec(&["alpha", "beta", "alpha", "beta"]);
+        let pattern = to_vec(&["alpha", "bet"]);
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, false).expect("fuzzy match");
+        assert_eq!(found.start, 0);
+        assert_eq!(found.runner_up.map(|(start, _)| start), Some(2));
+    }
+
+    #[test]
+    fn test_fuzzy_match_flags_nearly_tied_windows() {
+        let lines = to_vec(&[
+            "let total_count = compute_checked(a, b);",
+            "let total_count = compute_checked(a, c);",
+        ]);
+        // One edit away from the first line and two from the second.
+        let pattern = to_vec(&["let total_count = compute_checked(a, bx);"]);
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, false).expect("fuzzy match");
+        assert_eq!(found.start, 0);
+        let (other, similarity) = found.runner_up.expect("a close second location");
+        assert_eq!(other, 1);
+        assert!(found.similarity > similarity);
+    }
+
+    #[test]
+    fn test_fuzzy_match_ignores_clearly_worse_windows() {
+        let lines = to_vec(&["let total = compute(a, b);", "something else entirely"]);
+        let pattern = to_vec(&["let totl = compute(a, b);"]);
+        let found = fuzzy_seek_sequence(&lines, &pattern, 0, false).expect("fuzzy match");
+        assert_eq!(found.start, 0);
+        assert_eq!(found.runner_up, None);
+    }
 }
@@ -0,0 +1,545 @@
+//! Parses standard unified diffs, as produced by `diff -u` or `git diff`, into
+//! the same [`Hunk`]s as the `*** Begin Patch` format. Many models emit this
+//! format naturally, so accepting it lets them edit files without first
+//! learning the bespoke grammar.
+//!
+//! Supported:
+//! - `--- old` / `+++ new` file headers, optionally preceded by a
+//!   `diff --git a/old b/new` line and git's extended headers.
+//! - New files (`--- /dev/null`), deleted files (`+++ /dev/null`) and renames
+//!   (`rename from` / `rename to`, or differing `---`/`+++` paths).
+//! - `\ No newline at end of file` markers.
+//!
+//! Hunks are located by their context and removed lines, exactly like
+//! `*** Update File` chunks; the line numbers in `@@` headers are ignored
+//! because models frequently get them wrong. The line counts only tell body
+//! lines such as `--- x` apart from the headers of the next file. The git
+//! `a/` and `b/` prefixes are stripped from paths.
+
+use std::path::PathBuf;
+
+use crate::parser::Hunk;
+use crate::parser::ParseError;
+use crate::parser::UpdateFileChunk;
+
+const DIFF_HEADER: &str = "diff ";
+const GIT_DIFF_HEADER: &str = "diff --git ";
+const OLD_FILE_HEADER: &str = "--- ";
+const NEW_FILE_HEADER: &str = "+++ ";
+const HUNK_HEADER: &str = "@@";
+const DEV_NULL: &str = "/dev/null";
+const NEW_FILE_MODE: &str = "new file mode ";
+const DELETED_FILE_MODE: &str = "deleted file mode ";
+const RENAME_FROM: &str = "rename from ";
+const RENAME_TO: &str = "rename to ";
+const OLD_PATH_PREFIX: &str = "a/";
+const NEW_PATH_PREFIX: &str = "b/";
+
+/// Returns true if `lines` starts like a unified diff rather than an
+/// `*** Begin Patch` envelope.
+pub(crate) fn looks_like_unified_diff(lines: &[&str]) -> bool {
+    match lines {
+        [first, ..] if first.starts_with(GIT_DIFF_HEADER) => true,
+        [first, second, ..] => is_file_header_pair(first, second),
+        _ => false,
+    }
+}
+
+pub(crate) fn parse_unified_diff(lines: &[&str]) -> Result<Vec<Hunk>, ParseError> {
+    let mut hunks = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index].trim().is_empty() {
+            index += 1;
+            continue;
+        }
+        let (hunk, parsed_lines) = parse_file_diff(&lines[index..], index + 1)?;
+        hunks.extend(hunk);
+        index += parsed_lines;
+    }
+    if hunks.is_empty() {
+        return Err(ParseError::InvalidPatchError(
+            "The unified diff does not change the contents or name of any file".to_string(),
+        ));
+    }
+    Ok(hunks)
+}
+
+fn is_file_header_pair(old: &str, new: &str) -> bool {
+    old.starts_with(OLD_FILE_HEADER) && new.starts_with(NEW_FILE_HEADER)
+}
+
+fn starts_file_diff(lines: &[&str]) -> bool {
+    match lines {
+        [first, ..] if first.starts_with(DIFF_HEADER) => true,
+        [first, second, ..] => is_file_header_pair(first, second),
+        _ => false,
+    }
+}
+
+/// Parses the diff of a single file starting at `lines[0]`. Returns `None`
+/// for file diffs that only change metadata such as the file mode.
+fn parse_file_diff(
+    lines: &[&str],
+    line_number: usize,
+) -> Result<(Option<Hunk>, usize), ParseError> {
+    let mut index = 0;
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut is_new_file = false;
+    let mut is_deleted_file = false;
+
+    if lines[0].starts_with(DIFF_HEADER) {
+        if let Some(paths) = lines[0].strip_prefix(GIT_DIFF_HEADER)
+            && let Some((old, new)) = split_git_diff_paths(paths)
+        {
+            old_path = Some(old);
+            new_path = Some(new);
+        }
+        index += 1;
+        // Extended headers. Anything not listed here (index, similarity,
+        // mode changes, ...) does not affect the contents and is skipped.
+        while index < lines.len()
+            && !starts_file_diff(&lines[index..])
+            && !lines[index].starts_with(HUNK_HEADER)
+        {
+            let line = lines[index];
+            if line.starts_with(NEW_FILE_MODE) {
+                is_new_file = true;
+            } else if line.starts_with(DELETED_FILE_MODE) {
+                is_deleted_file = true;
+            } else if let Some(path) = line.strip_prefix(RENAME_FROM) {
+                old_path = Some(unquote(path));
+            } else if let Some(path) = line.strip_prefix(RENAME_TO) {
+                new_path = Some(unquote(path));
+            } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+                return Err(ParseError::InvalidHunkError {
+                    message: "Binary diffs are not supported".to_string(),
+                    line_number: line_number + index,
+                });
+            }
+            index += 1;
+        }
+    }
+
+    if index + 1 < lines.len() && is_file_header_pair(lines[index], lines[index + 1]) {
+        old_path = header_path(&lines[index][OLD_FILE_HEADER.len()..], OLD_PATH_PREFIX);
+        new_path = header_path(&lines[index + 1][NEW_FILE_HEADER.len()..], NEW_PATH_PREFIX);
+        is_new_file |= old_path.is_none();
+        is_deleted_file |= new_path.is_none();
+        index += 2;
+    } else if index == 0 {
+        return Err(ParseError::InvalidHunkError {
+            message: format!(
+                "Expected a '--- <path>' and '+++ <path>' file header, got: '{}'",
+                lines[0]
+            ),
+            line_number,
+        });
+    }
+
+    let mut chunks: Vec<UpdateFileChunk> = Vec::new();
+    while index < lines.len() && lines[index].starts_with(HUNK_HEADER) {
+        let (chunk, parsed_lines) = parse_hunk(&lines[index..], line_number + index)?;
+        chunks.push(chunk);
+        index += parsed_lines;
+    }
+
+    let hunk = if is_new_file {
+        let path = new_path.ok_or_else(|| missing_path_error(line_number))?;
+        let mut contents = String::new();
+        for chunk in &chunks {
+            if !chunk.old_lines.is_empty() {
+                return Err(ParseError::InvalidHunkError {
+                    message: format!("Diff for new file '{path}' must only contain added lines"),
+                    line_number,
+                });
+            }
+            for line in &chunk.new_lines {
+                contents.push_str(line);
+                contents.push('\n');
+            }
+        }
+        if chunks
+            .last()
+            .is_some_and(|chunk| chunk.missing_final_newline)
+        {
+            contents.pop();
+        }
+        Some(Hunk::AddFile {
+            path: PathBuf::from(path),
+            contents,
+        })
+    } else if is_deleted_file {
+        let path = old_path.ok_or_else(|| missing_path_error(line_number))?;
+        Some(Hunk::DeleteFile {
+            path: PathBuf::from(path),
+        })
+    } else {
+        let path = old_path.ok_or_else(|| missing_path_error(line_number))?;
+        let new_path = new_path.ok_or_else(|| missing_path_error(line_number))?;
+        if let Some(chunk) = chunks.iter().find(|chunk| chunk.old_lines.is_empty()) {
+            return Err(ParseError::InvalidHunkError {
+                message: format!(
+                    "Hunk adding {} line(s) to '{path}' has no context lines; include at least one unchanged line around the change",
+                    chunk.new_lines.len()
+                ),
+                line_number,
+            });
+        }
+        let move_path = (new_path != path).then(|| PathBuf::from(new_path));
+        if chunks.is_empty() && move_path.is_none() {
+            None
+        } else {
+            Some(Hunk::UpdateFile {
+                path: PathBuf::from(path),
+                move_path,
+                chunks,
+            })
+        }
+    };
+
+    Ok((hunk, index))
+}
+
+/// Parses one `@@ -a,b +c,d @@` hunk and returns it with the number of lines
+/// it spans. The hunk ends at the next hunk or file header, or at the first
+/// line that is not part of a hunk body. File headers are only recognized
+/// once the line counts from the `@@` header are used up.
+fn parse_hunk(lines: &[&str], line_number: usize) -> Result<(UpdateFileChunk, usize), ParseError> {
+    let mut chunk = UpdateFileChunk {
+        change_context: None,
+        old_lines: Vec::new(),
+        new_lines: Vec::new(),
+        is_end_of_file: false,
+        missing_final_newline: false,
+    };
+    let (mut old_remaining, mut new_remaining) = hunk_line_counts(lines[0]).unwrap_or((0, 0));
+    // Bare empty lines are usually blank context lines whose leading space was
+    // stripped, but trailing ones are just separators before the next file.
+    let mut trailing_blank_lines = 0;
+    let mut index = 1;
+    while index < lines.len() {
+        let line = lines[index];
+        if line.starts_with(HUNK_HEADER)
+            || (starts_file_diff(&lines[index..])
+                && !is_counted_body(&lines[index..], old_remaining, new_remaining))
+        {
+            break;
+        }
+        match line.chars().next() {
+            None => {
+                chunk.old_lines.push(String::new());
+                chunk.new_lines.push(String::new());
+                old_remaining = old_remaining.saturating_sub(1);
+                new_remaining = new_remaining.saturating_sub(1);
+                trailing_blank_lines += 1;
+                index += 1;
+                continue;
+            }
+            Some(' ') => {
+                chunk.old_lines.push(line[1..].to_string());
+                chunk.new_lines.push(line[1..].to_string());
+                old_remaining = old_remaining.saturating_sub(1);
+                new_remaining = new_remaining.saturating_sub(1);
+            }
+            Some('-') => {
+                chunk.old_lines.push(line[1..].to_string());
+                old_remaining = old_remaining.saturating_sub(1);
+            }
+            Some('+') => {
+                chunk.new_lines.push(line[1..].to_string());
+                new_remaining = new_remaining.saturating_sub(1);
+            }
+            Some('\\') => {
+                // Applies to the line before it. Either way the hunk touches
+                // the end of the file.
+                chunk.is_end_of_file = true;
+                if !lines[index - 1].starts_with('-') {
+                    chunk.missing_final_newline = true;
+                }
+            }
+            _ => break,
+        }
+        trailing_blank_lines = 0;
+        index += 1;
+    }
+
+    chunk
+        .old_lines
+        .truncate(chunk.old_lines.len() - trailing_blank_lines);
+    chunk
+        .new_lines
+        .truncate(chunk.new_lines.len() - trailing_blank_lines);
+    if chunk.old_lines.is_empty() && chunk.new_lines.is_empty() {
+        return Err(ParseError::InvalidHunkError {
+            message: "Hunk does not contain any lines".to_string(),
+            line_number,
+        });
+    }
+
+    Ok((chunk, index))
+}
+
+/// Whether `lines`, which start like the headers of the next file, are still
+/// part of the current hunk according to its remaining line counts. Counts
+/// that are too large are common in model output, so a `---`/`+++` pair that
+/// is directly followed by a hunk header is taken as a file header unless
+/// the pair would use up the counts exactly.
+fn is_counted_body(lines: &[&str], old_remaining: usize, new_remaining: usize) -> bool {
+    if old_remaining == 0 && new_remaining == 0 {
+        return false;
+    }
+    let followed_by_hunk = lines
+        .get(2)
+        .is_some_and(|line| line.starts_with(HUNK_HEADER));
+    !followed_by_hunk || (old_remaining <= 1 && new_remaining <= 1)
+}
+
+/// The old and new line counts of a `@@ -a,b +c,d @@` header, where an
+/// omitted count means one line. Returns `None` for malformed headers.
+fn hunk_line_counts(header: &str) -> Option<(usize, usize)> {
+    let ranges = header
+        .strip_prefix(HUNK_HEADER)?
+        .split(HUNK_HEADER)
+        .next()?;
+    let mut ranges = ranges.split_whitespace();
+    let count = |range: Option<&str>, sign: char| -> Option<usize> {
+        let range = range?.strip_prefix(sign)?;
+        match range.split_once(',') {
+            Some((_, count)) => count.parse().ok(),
+            None => range.parse::<usize>().ok().map(|_| 1),
+        }
+    };
+    Some((count(ranges.next(), '-')?, count(ranges.next(), '+')?))
+}
+
+/// Splits the `a/old b/new` part of a `diff --git` line. Paths containing
+/// spaces are only unambiguous when both sides are equal, which covers every
+/// case except renames, and those carry `rename from`/`rename to` headers.
+fn split_git_diff_paths(paths: &str) -> Option<(String, String)> {
+    let paths = paths.trim();
+    if let Some(rest) = paths.strip_prefix(OLD_PATH_PREFIX) {
+        let half = (rest.len().saturating_sub(NEW_PATH_PREFIX.len() + 1)) / 2;
+        if rest.is_char_boundary(half)
+            && rest[half..].starts_with(" b/")
+            && rest[..half] == rest[half + 3..]
+        {
+            return Some((rest[..half].to_string(), rest[half + 3..].to_string()));
+        }
+    }
+    let (old, new) = paths.split_once(' ')?;
+    Some((
+        strip_path_prefix(&unquote(old), OLD_PATH_PREFIX),
+        strip_path_prefix(&unquote(new), NEW_PATH_PREFIX),
+    ))
+}
+
+/// Extracts the path from a `---`/`+++` header, dropping any trailing
+/// timestamp. Returns `None` for `/dev/null`.
+fn header_path(header: &str, prefix: &str) -> Option<String> {
+    let path = header.split('\t').next().unwrap_or_default().trim_end();
+    let path = unquote(path);
+    if path == DEV_NULL {
+        None
+    } else {
+        Some(strip_path_prefix(&path, prefix))
+    }
+}
+
+fn strip_path_prefix(path: &str, prefix: &str) -> String {
+    path.strip_prefix(prefix).unwrap_or(path).to_string()
+}
+
+fn unquote(path: &str) -> String {
+    let path = path.trim();
+    path.strip_prefix('"')
+        .and_then(|p| p.strip_suffix('"'))
+        .unwrap_or(path)
+        .to_string()
+}
+
+fn missing_path_error(line_number: usize) -> ParseError {
+    ParseError::InvalidHunkError {
+        message: "Could not determine the file path for this diff".to_string(),
+        line_number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(diff: &str) -> Result<Vec<Hunk>, ParseError> {
+        let lines: Vec<&str> = diff.lines().collect();
+        assert!(looks_like_unified_diff(&lines));
+        parse_unified_diff(&lines)
+    }
+
+    fn strings(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn parses_git_diff_with_update_add_delete_and_rename() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 3b18e51..a4b2c3d 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,3 +1,3 @@ mod foo;
+ fn main() {
+-    println!(\"hi\");
++    println!(\"hello\");
+ }
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..e69de29
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1,2 @@
++first
++second
+\\ No newline at end of file
+diff --git a/old.txt b/old.txt
+deleted file mode 100644
+--- a/old.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-gone
+diff --git a/from.txt b/to.txt
+similarity index 100%
+rename from from.txt
+rename to to.txt
+";
+        assert_eq!(
+            parse(diff),
+            Ok(vec![
+                Hunk::UpdateFile {
+                    path: PathBuf::from("src/lib.rs"),
+                    move_path: None,
+                    chunks: vec![UpdateFileChunk {
+                        change_context: None,
+                        old_lines: strings(&["fn main() {", "    println!(\"hi\");", "}"]),
+                        new_lines: strings(&["fn main() {", "    println!(\"hello\");", "}"]),
+                        is_end_of_file: false,
+                        missing_final_newline: false,
+                    }],
+                },
+                Hunk::AddFile {
+                    path: PathBuf::from("notes.txt"),
+                    contents: "first\nsecond".to_string(),
+                },
+                Hunk::DeleteFile {
+                    path: PathBuf::from("old.txt"),
+                },
+                Hunk::UpdateFile {
+                    path: PathBuf::from("from.txt"),
+                    move_path: Some(PathBuf::from("to.txt")),
+                    chunks: Vec::new(),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn parses_plain_diff_with_timestamps_and_multiple_hunks() {
+        let diff = "\
+--- a.txt\t2024-01-01 00:00:00.000000000 +0000
++++ a.txt\t2024-01-02 00:00:00.000000000 +0000
+@@ -1,2 +1,2 @@
+-one
++uno
+ two
+
+@@ -9,2 +9,2 @@
+ nine
+-ten
++diez
+\\ No newline at end of file
+";
+        assert_eq!(
+            parse(diff),
+            Ok(vec![Hunk::UpdateFile {
+                path: PathBuf::from("a.txt"),
+                move_path: None,
+                chunks: vec![
+                    UpdateFileChunk {
+                        change_context: None,
+                        old_lines: strings(&["one", "two"]),
+                        new_lines: strings(&["uno", "two"]),
+                        is_end_of_file: false,
+                        missing_final_newline: false,
+                    },
+                    UpdateFileChunk {
+                        change_context: None,
+                        old_lines: strings(&["nine", "ten"]),
+                        new_lines: strings(&["nine", "diez"]),
+                        is_end_of_file: true,
+                        missing_final_newline: true,
+                    },
+                ],
+            }])
+        );
+    }
+
+    #[test]
+    fn header_like_lines_inside_a_hunk_are_body_lines() {
+        let diff = "\
+--- a/notes.md
++++ b/notes.md
+@@ -1,3 +1,3 @@
+ # Notes
+--- old rule
++++ new rule
+ end
+--- a/other.md
++++ b/other.md
+@@ -1 +1 @@
+-x
++y
+";
+        assert_eq!(
+            parse(diff),
+            Ok(vec![
+                Hunk::UpdateFile {
+                    path: PathBuf::from("notes.md"),
+                    move_path: None,
+                    chunks: vec![UpdateFileChunk {
+                        change_context: None,
+                        old_lines: strings(&["# Notes", "-- old rule", "end"]),
+                        new_lines: strings(&["# Notes", "++ new rule", "end"]),
+                        is_end_of_file: false,
+                        missing_final_newline: false,
+                    }],
+                },
+                Hunk::UpdateFile {
+                    path: PathBuf::from("other.md"),
+                    move_path: None,
+                    chunks: vec![UpdateFileChunk {
+                        change_context: None,
+                        old_lines: strings(&["x"]),
+                        new_lines: strings(&["y"]),
+                        is_end_of_file: false,
+                        missing_final_newline: false,
+                    }],
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn rejects_insertions_without_context() {
+        let diff = "\
+--- a/a.txt
++++ b/a.txt
+@@ -3,0 +4 @@
++inserted
+";
+        assert!(matches!(
+            parse(diff),
+            Err(ParseError::InvalidHunkError { message, .. }) if message.contains("no context lines")
+        ));
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+use std::time::SystemTime;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::anyhow;
+use codex_common::CliConfigOverrides;
+use codex_common::elapsed::format_duration;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::usage::UsageGrouping;
+use codex_core::usage::UsageSummary;
+use codex_core::usage::parse_since;
+use codex_core::usage::read_records;
+use codex_core::usage::summarize;
+use codex_protocol::num_format::format_with_separators;
+
+/// Report tokens used and estimated cost, from `~/.codex/usage.jsonl`.
+///
+/// Costs come from `[pricing]` in config.toml and are left blank for models
+/// without a price.
+#[derive(Debug, clap::Parser)]
+pub struct UsageCommand {
+    #[clap(flatten)]
+    pub config_overrides: CliConfigOverrides,
+
+    /// Only count usage since this long ago (`30m`, `12h`, `7d`, `2w`) or
+    /// since this UTC date (`2025-10-01`).
+    #[arg(long, value_name = "WHEN")]
+    pub since: Option<String>,
+
+    /// What to group usage by.
+    #[arg(long, value_enum, default_value_t = UsageBy::Project)]
+    pub by: UsageBy,
+
+    /// Output the report as JSON.
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum UsageBy {
+    Project,
+    Model,
+    /// Calendar day in UTC.
+    Day,
+    Session,
+}
+
+impl From<UsageBy> for UsageGrouping {
+    fn from(by: UsageBy) -> Self {
+        match by {
+            UsageBy::Project => UsageGrouping::Project,
+            UsageBy::Model => UsageGrouping::Model,
+            UsageBy::Day => UsageGrouping::Day,
+            UsageBy::Session => UsageGrouping::Session,
+        }
+    }
+}
+
+impl UsageCommand {
+    pub async fn run(self) -> Result<()> {
+        let overrides = self
+            .config_overrides
+            .parse_overrides()
+            .map_err(|e| anyhow!(e))?;
+        let config = Config::load_with_cli_overrides(overrides, ConfigOverrides::default())
+            .await
+            .context("failed to load configuration")?;
+
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| parse_since(since, SystemTime::now()))
+            .transpose()
+            .map_err(|e| anyhow!(e))?;
+        let records =
+            read_records(&config.codex_home, since).context("failed to read usage log")?;
+        let summaries = summarize(&records, self.by.into(), &config.pricing);
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&summaries)?);
+            return Ok(());
+        }
+
+        if summaries.is_empty() {
+            println!("No usage recorded yet.");
+            return Ok(());
+        }
+
+        let key_header = match self.by {
+            UsageBy::Project => "Project",
+            UsageBy::Model => "Model",
+            UsageBy::Day => "Day",
+            UsageBy::Session => "Session",
+        };
+        let header = [
+            key_header, "Requests", "Input", "Cached", "Output", "Total", "Time", "Cost",
+        ]
+        .map(str::to_string);
+        let format_cost =
+            |cost: Option<f64>| cost.map_or_else(|| "-".to_string(), |c| format!("${c:.2}"));
+        let mut rows: Vec<[String; 8]> = summaries
+            .iter()
+            .map(|summary| {
+                [
+                    summary.key.clone(),
+                    format_with_separators(summary.requests),
+                    format_with_separators(summary.usage.input_tokens),
+                    format_with_separators(summary.usage.cached_input_tokens),
+                    format_with_separators(summary.usage.output_tokens),
+                    format_with_separators(summary.usage.total_tokens),
+                    format_duration(Duration::from_millis(summary.duration_ms)),
+                    format_cost(summary.cost),
+                ]
+            })
+            .collect();
+        if rows.len() > 1 {
+            let sum = |field: fn(&UsageSummary) -> u64| summaries.iter().map(field).sum::<u64>();
+            let total = |field| format_with_separators(sum(field));
+            let costs: Vec<f64> = summaries
+                .iter()
+                .filter_map(|summary| summary.cost)
+                .collect();
+            rows.push([
+                "Total".to_string(),
+                total(|summary| summary.requests),
+                total(|summary| summary.usage.input_tokens),
+                total(|summary| summary.usage.cached_input_tokens),
+                total(|summary| summary.usage.output_tokens),
+                total(|summary| summary.usage.total_tokens),
+                format_duration(Duration::from_millis(sum(|summary| summary.duration_ms))),
+                format_cost((!costs.is_empty()).then(|| costs.iter().sum())),
+            ]);
+        }
+
+        let mut widths = header.clone().map(|cell| cell.chars().count());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+        for row in std::iter::once(&header).chain(&rows) {
+            // Left-align the key column and right-align the numbers.
+            let line = row
+                .iter()
+                .zip(widths)
+                .enumerate()
+                .map(|(i, (cell, width))| {
+                    if i == 0 {
+                        format!("{cell:<width$}")
+                    } else {
+                        format!("{cell:>width$}")
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("{}", line.trim_end());
+        }
+
+        Ok(())
+    }
+}
@@ -1,6 +1,8 @@
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::patch_review::partial_action;
+use crate::patch_review::review_feedback;
 use crate::protocol::FileChange;
 use crate::protocol::ReviewDecision;
 use crate::safety::SafetyCheck;
@@ -31,6 +33,9 @@ pub(crate) enum InternalApplyPatchInvocation {
 pub(crate) struct ApplyPatchExec {
     pub(crate) action: ApplyPatchAction,
     pub(crate) user_explicitly_approved_this_action: bool,
+    /// Set when the user applied only some hunks; appended to the tool output
+    /// so the model learns what was rejected and why.
+    pub(crate) review_feedback: Option<String>,
 }
 
 pub(crate) async fn apply_patch(
@@ -52,6 +57,7 @@ pub(crate) async fn apply_patch(
         } => InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: user_explicitly_approved,
+            review_feedback: None,
         }),
         SafetyCheck::AskUser => {
             // Compute a readable summary of path changes to include in the
@@ -64,11 +70,35 @@ pub(crate) async fn apply_patch(
             let rx_approve = sess
                 .request_patch_approval(sub_id.to_owned(), call_id.to_owned(), &action, None, None)
                 .await;
-            match rx_approve.await.unwrap_or_default() {
+            let decision = rx_approve.await.unwrap_or_default();
+            if matches!(decision, ReviewDecision::Approved)
+                && let Some(review) = sess.take_patch_review(sub_id).await
+            {
+                let feedback = review_feedback(&action, &review);
+                return match partial_action(&action, &review) {
+                    Ok(Some(partial)) => {
+                        InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
+                            action: partial,
+                            user_explicitly_approved_this_action: true,
+                            review_feedback: Some(feedback),
+                        })
+                    }
+                    Ok(None) => InternalApplyPatchInvocation::Output(Err(
+                        FunctionCallError::RespondToModel(feedback),
+                    )),
+                    Err(err) => InternalApplyPatchInvocation::Output(Err(
+                        FunctionCallError::RespondToModel(format!(
+                            "patch rejected: the hunks the user accepted could not be applied on their own ({err}).\n{feedback}"
+                        )),
+                    )),
+                };
+            }
+            match decision {
                 ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
                     InternalApplyPatchInvocation::DelegateToExec(ApplyPatchExec {
                         action,
                         user_explicitly_approved_this_action: true,
+                        review_feedback: None,
                     })
                 }
                 ReviewDecision::Denied | ReviewDecision::Abort => {
@@ -0,0 +1,213 @@
+//! Enforcement of the per-task limits configured in [`TaskBudget`].
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::config_types::TaskBudget;
+use crate::protocol::TokenUsage;
+
+/// What a sub-agent used while running for a task, counted against the
+/// task's own budget once the sub-agent returns.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BudgetUsage {
+    pub(crate) turns: u32,
+    pub(crate) tokens: u64,
+    pub(crate) tool_calls: u32,
+}
+
+impl BudgetUsage {
+    pub(crate) fn add(&mut self, other: BudgetUsage) {
+        self.turns = self.turns.saturating_add(other.turns);
+        self.tokens = self.tokens.saturating_add(other.tokens);
+        self.tool_calls = self.tool_calls.saturating_add(other.tool_calls);
+    }
+}
+
+/// Counts what a running task has used so far against its budget.
+pub(crate) struct BudgetTracker {
+    budget: TaskBudget,
+    started: Instant,
+    turns: u32,
+    tokens: u64,
+    tool_calls: u32,
+}
+
+impl BudgetTracker {
+    pub(crate) fn new(budget: TaskBudget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            turns: 0,
+            tokens: 0,
+            tool_calls: 0,
+        }
+    }
+
+    /// Time left before the task times out, if it has a timeout.
+    pub(crate) fn remaining_time(&self) -> Option<Duration> {
+        self.budget
+            .timeout_sec
+            .map(|timeout| timeout.saturating_sub(self.started.elapsed()))
+    }
+
+    /// Record one completed model request and the tool calls it made.
+    pub(crate) fn record_turn(&mut self, usage: Option<&TokenUsage>, tool_calls: usize) {
+        self.turns = self.turns.saturating_add(1);
+        if let Some(usage) = usage {
+            self.tokens = self.tokens.saturating_add(usage.total_tokens);
+        }
+        self.tool_calls = self
+            .tool_calls
+            .saturating_add(u32::try_from(tool_calls).unwrap_or(u32::MAX));
+    }
+
+    /// Count what a sub-agent used as if the task had used it itself.
+    pub(crate) fn record_sub_agent(&mut self, usage: BudgetUsage) {
+        self.turns = self.turns.saturating_add(usage.turns);
+        self.tokens = self.tokens.saturating_add(usage.tokens);
+        self.tool_calls = self.tool_calls.saturating_add(usage.tool_calls);
+    }
+
+    /// The part of the budget not used yet, for a sub-agent to run within.
+    pub(crate) fn remaining(&self) -> TaskBudget {
+        TaskBudget {
+            max_turns: self
+                .budget
+                .max_turns
+                .map(|max| max.saturating_sub(self.turns)),
+            max_total_tokens: self
+                .budget
+                .max_total_tokens
+                .map(|max| max.saturating_sub(self.tokens)),
+            max_tool_calls: self
+                .budget
+                .max_tool_calls
+                .map(|max| max.saturating_sub(self.tool_calls)),
+            timeout_sec: self.remaining_time(),
+        }
+    }
+
+    /// Describes the first limit the task has reached, if any. Checked
+    /// between requests, so a task always finishes its current request.
+    pub(crate) fn exceeded(&self) -> Option<String> {
+        let TaskBudget {
+            max_turns,
+            max_total_tokens,
+            max_tool_calls,
+            timeout_sec,
+        } = &self.budget;
+        if let Some(max) = max_turns
+            && self.turns >= *max
+        {
+            return Some(format!("reached the limit of {max} turns"));
+        }
+        if let Some(max) = max_total_tokens
+            && self.tokens >= *max
+        {
+            return Some(format!(
+                "used {} tokens, over the limit of {max}",
+                self.tokens
+            ));
+        }
+        if let Some(max) = max_tool_calls
+            && self.tool_calls >= *max
+        {
+            return Some(format!("reached the limit of {max} tool calls"));
+        }
+        if let Some(timeout) = timeout_sec
+            && self.started.elapsed() >= *timeout
+        {
+            return Some(self.timeout_reason());
+        }
+        None
+    }
+
+    /// Describes the timeout, for a task stopped by [`Self::remaining_time`]
+    /// running out mid-request.
+    pub(crate) fn timeout_reason(&self) -> String {
+        let timeout = self.budget.timeout_sec.unwrap_or_default();
+        format!("ran for longer than {timeout:?}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(total_tokens: u64) -> TokenUsage {
+        TokenUsage {
+            total_tokens,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn reports_the_first_exhausted_limit() {
+        let mut tracker = BudgetTracker::new(TaskBudget {
+            max_turns: Some(3),
+            max_total_tokens: Some(1_000),
+            max_tool_calls: Some(5),
+            timeout_sec: None,
+        });
+
+        tracker.record_turn(Some(&usage(400)), 2);
+        assert_eq!(tracker.exceeded(), None);
+
+        tracker.record_turn(Some(&usage(700)), 1);
+        assert_eq!(
+            tracker.exceeded().as_deref(),
+            Some("used 1100 tokens, over the limit of 1000")
+        );
+
+        let mut tracker = BudgetTracker::new(TaskBudget {
+            max_tool_calls: Some(2),
+            ..Default::default()
+        });
+        tracker.record_turn(None, 2);
+        assert_eq!(
+            tracker.exceeded().as_deref(),
+            Some("reached the limit of 2 tool calls")
+        );
+    }
+
+    #[test]
+    fn sub_agent_usage_counts_against_the_task() {
+        let mut tracker = BudgetTracker::new(TaskBudget {
+            max_turns: Some(10),
+            max_total_tokens: Some(1_000),
+            ..Default::default()
+        });
+        tracker.record_turn(Some(&usage(300)), 1);
+        assert_eq!(
+            tracker.remaining(),
+            TaskBudget {
+                max_turns: Some(9),
+                max_total_tokens: Some(700),
+                max_tool_calls: None,
+                timeout_sec: None,
+            }
+        );
+
+        tracker.record_sub_agent(BudgetUsage {
+            turns: 2,
+            tokens: 800,
+            tool_calls: 3,
+        });
+        assert_eq!(tracker.remaining().max_total_tokens, Some(0));
+        assert_eq!(
+            tracker.exceeded().as_deref(),
+            Some("used 1100 tokens, over the limit of 1000")
+        );
+    }
+
+    #[test]
+    fn unlimited_budget_is_never_exceeded() {
+        let mut tracker = BudgetTracker::new(TaskBudget::default());
+        for _ in 0..100 {
+            tracker.record_turn(Some(&usage(1_000_000)), 10);
+        }
+        assert_eq!(tracker.exceeded(), None);
+        assert_eq!(tracker.remaining_time(), None);
+    }
+}
@@ -0,0 +1,438 @@
+//! Cassettes: model responses recorded to a file and served back by the
+//! `replay` provider, so agent runs can be reproduced without a live model.
+//!
+//! A cassette is JSONL with one [`CassetteEntry`] per model request. The
+//! `replay` provider also accepts a rollout file, in which case each recorded
+//! model response is replayed in order (matching by turn only).
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+
+use codex_protocol::ConversationId;
+use codex_protocol::models::ResponseItem;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde::Serialize;
+use sha1::Digest;
+use sha1::Sha1;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
+use crate::client_common::ResponseStream;
+use crate::config_types::CassetteMatch;
+use crate::error::CodexErr;
+use crate::error::Result;
+use crate::protocol::EventMsg;
+use crate::protocol::RolloutItem;
+use crate::protocol::RolloutLine;
+use crate::protocol::TokenUsage;
+
+/// One recorded model request and the events its response produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CassetteEntry {
+    /// Zero-based index of the request within its conversation.
+    turn: usize,
+    /// See [`request_hash`]. Absent for entries read from a rollout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    request_hash: Option<String>,
+    events: Vec<ResponseEvent>,
+}
+
+/// Requests recorded or replayed so far per conversation. Kept outside
+/// `ModelClient` because a conversation gets a new client whenever a turn
+/// changes the model settings.
+static TURNS: LazyLock<Mutex<HashMap<ConversationId, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+fn turns() -> Result<std::sync::MutexGuard<'static, HashMap<ConversationId, usize>>> {
+    TURNS
+        .lock()
+        .map_err(|_| CodexErr::Fatal("cassette turn counter lock poisoned".to_string()))
+}
+
+fn current_turn(conversation_id: ConversationId) -> Result<usize> {
+    Ok(turns()?.get(&conversation_id).copied().unwrap_or_default())
+}
+
+fn advance_turn(conversation_id: ConversationId) -> Result<usize> {
+    let mut turns = turns()?;
+    let turn = turns.entry(conversation_id).or_default();
+    let current = *turn;
+    *turn += 1;
+    Ok(current)
+}
+
+/// Drop the request count of a conversation that has shut down.
+pub(crate) fn forget_conversation(conversation_id: ConversationId) -> Result<()> {
+    turns()?.remove(&conversation_id);
+    Ok(())
+}
+
+/// Hash of the request input, with `cwd` replaced by a placeholder so a
+/// cassette recorded in one checkout still matches in another.
+fn request_hash(prompt: &Prompt, cwd: &Path) -> String {
+    let input = serde_json::to_string(&prompt.get_formatted_input()).unwrap_or_default();
+    let input = input.replace(&cwd.to_string_lossy().into_owned(), "<cwd>");
+    let mut hasher = Sha1::new();
+    hasher.update(input.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Tee `stream` into the cassette at `path`. A response is only recorded
+/// once it completes, so retried requests are recorded once.
+pub(crate) fn record(
+    mut stream: ResponseStream,
+    path: PathBuf,
+    prompt: &Prompt,
+    cwd: &Path,
+    conversation_id: ConversationId,
+) -> ResponseStream {
+    let request_hash = request_hash(prompt, cwd);
+    let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
+    tokio::spawn(async move {
+        let mut events = Vec::new();
+        while let Some(event) = stream.next().await {
+            if let Ok(event) = &event {
+                events.push(event.clone());
+                if matches!(event, ResponseEvent::Completed { .. }) {
+                    let turn = match advance_turn(conversation_id) {
+                        Ok(turn) => turn,
+                        Err(err) => {
+                            let _ = tx.send(Err(err)).await;
+                            break;
+                        }
+                    };
+                    let entry = CassetteEntry {
+                        turn,
+                        request_hash: Some(request_hash.clone()),
+                        events: std::mem::take(&mut events),
+                    };
+                    if let Err(err) = append_entry(&path, &entry).await {
+                        warn!("failed to record cassette {}: {err}", path.display());
+                    }
+                }
+            }
+            if tx.send(event).await.is_err() {
+                break;
+            }
+        }
+    });
+    ResponseStream { rx_event: rx }
+}
+
+async fn append_entry(path: &Path, entry: &CassetteEntry) -> std::io::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await?;
+    file.write_all(line.as_bytes()).await
+}
+
+/// Serve the recorded response for this request from the cassette or
+/// rollout at `path`.
+pub(crate) async fn replay(
+    path: &Path,
+    match_by: CassetteMatch,
+    prompt: &Prompt,
+    cwd: &Path,
+    conversation_id: ConversationId,
+) -> Result<ResponseStream> {
+    let contents = tokio::fs::read_to_string(path).await.map_err(|err| {
+        CodexErr::Fatal(format!(
+            "failed to read replay file {}: {err}",
+            path.display()
+        ))
+    })?;
+    let entries = parse_entries(&contents)
+        .map_err(|err| CodexErr::Fatal(format!("invalid replay file {}: {err}", path.display())))?;
+
+    let turn = current_turn(conversation_id)?;
+    let entry = match match_by {
+        CassetteMatch::Turn => entries.iter().find(|entry| entry.turn == turn),
+        CassetteMatch::Hash => {
+            let hash = request_hash(prompt, cwd);
+            // The same request may be recorded more than once; prefer the
+            // recording closest to where this conversation is.
+            entries
+                .iter()
+                .filter(|entry| entry.request_hash.as_deref() == Some(hash.as_str()))
+                .min_by_key(|entry| entry.turn.abs_diff(turn))
+        }
+    };
+    let Some(entry) = entry else {
+        return Err(CodexErr::Fatal(format!(
+            "no recorded response for request {} in {} (matching by {match_by:?})",
+            turn + 1,
+            path.display()
+        )));
+    };
+    advance_turn(conversation_id)?;
+
+    let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(entry.events.len().max(1));
+    for event in entry.events.iter().cloned() {
+        // The channel has room for every event, so this never fails.
+        let _ = tx.try_send(Ok(event));
+    }
+    Ok(ResponseStream { rx_event: rx })
+}
+
+/// Parse a cassette, or a rollout when the first line is session metadata.
+fn parse_entries(contents: &str) -> serde_json::Result<Vec<CassetteEntry>> {
+    let lines = contents.lines().filter(|line| !line.trim().is_empty());
+    let is_rollout = lines
+        .clone()
+        .next()
+        .and_then(|line| serde_json::from_str::<RolloutLine>(line).ok())
+        .is_some_and(|line| matches!(line.item, RolloutItem::SessionMeta(_)));
+    if !is_rollout {
+        return lines.map(serde_json::from_str).collect();
+    }
+
+    let mut items = Vec::new();
+    for line in lines {
+        items.push(serde_json::from_str::<RolloutLine>(line)?.item);
+    }
+    Ok(entries_from_rollout(items))
+}
+
+/// Rebuild model responses from a rollout. Each response starts with the
+/// token count reported when it completed, followed by its output items
+/// interleaved with the tool outputs that answered them.
+fn entries_from_rollout(items: Vec<RolloutItem>) -> Vec<CassetteEntry> {
+    let mut responses: Vec<(Option<TokenUsage>, Vec<ResponseItem>)> = Vec::new();
+    let mut pending_usage = None;
+    for item in items {
+        match item {
+            RolloutItem::EventMsg(EventMsg::TokenCount(ev)) => {
+                pending_usage = Some(ev.info.map(|info| info.last_token_usage));
+            }
+            RolloutItem::ResponseItem(item) if is_model_output(&item) => {
+                match (pending_usage.take(), responses.last_mut()) {
+                    (None, Some((_, outputs))) => outputs.push(item),
+                    (usage, _) => responses.push((usage.flatten(), vec![item])),
+                }
+            }
+            RolloutItem::ResponseItem(_) => {}
+            // A user message starts a new task, so the next output is a new
+            // response even without a token count.
+            RolloutItem::EventMsg(EventMsg::UserMessage(_)) => {
+                pending_usage.get_or_insert(None);
+            }
+            RolloutItem::SessionMeta(_)
+            | RolloutItem::Compacted(_)
+            | RolloutItem::TurnContext(_)
+            | RolloutItem::EventMsg(_) => {}
+        }
+    }
+
+    responses
+        .into_iter()
+        .enumerate()
+        .map(|(turn, (token_usage, outputs))| {
+            let mut events = vec![ResponseEvent::Created];
+            events.extend(outputs.into_iter().map(ResponseEvent::OutputItemDone));
+            events.push(ResponseEvent::Completed {
+                response_id: format!("replay-{turn}"),
+                token_usage,
+            });
+            CassetteEntry {
+                turn,
+                request_hash: None,
+                events,
+            }
+        })
+        .collect()
+}
+
+fn is_model_output(item: &ResponseItem) -> bool {
+    match item {
+        ResponseItem::Message { role, .. } => role == "assistant",
+        ResponseItem::Reasoning { .. }
+        | ResponseItem::LocalShellCall { .. }
+        | ResponseItem::FunctionCall { .. }
+        | ResponseItem::CustomToolCall { .. }
+        | ResponseItem::WebSearchCall { .. } => true,
+        ResponseItem::FunctionCallOutput { .. }
+        | ResponseItem::CustomToolCallOutput { .. }
+        | ResponseItem::Other => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn message(role: &str, text: &str) -> ResponseItem {
+        ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![ContentItem::OutputText {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn function_call(call_id: &str) -> ResponseItem {
+        ResponseItem::FunctionCall {
+            id: None,
+            name: "shell".to_string(),
+            arguments: "{}".to_string(),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    fn event_kinds(entry: &CassetteEntry) -> Vec<String> {
+        entry
+            .events
+            .iter()
+            .map(|event| match event {
+                ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. }) => {
+                    match &content[0] {
+                        ContentItem::OutputText { text } => format!("message:{text}"),
+                        _ => "message".to_string(),
+                    }
+                }
+                ResponseEvent::OutputItemDone(ResponseItem::FunctionCall { call_id, .. }) => {
+                    format!("call:{call_id}")
+                }
+                ResponseEvent::Created => "created".to_string(),
+                ResponseEvent::Completed { .. } => "completed".to_string(),
+                other => format!("{other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn rollout_responses_are_split_at_token_counts() {
+        let token_count = || {
+            RolloutItem::EventMsg(EventMsg::TokenCount(crate::protocol::TokenCountEvent {
+                info: None,
+                rate_limits: None,
+            }))
+        };
+        let entries = entries_from_rollout(vec![
+            RolloutItem::ResponseItem(message("user", "fix the tests")),
+            token_count(),
+            RolloutItem::ResponseItem(function_call("call-1")),
+            RolloutItem::ResponseItem(ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload {
+                    content: "ok".to_string(),
+                    success: Some(true),
+                },
+            }),
+            token_count(),
+            RolloutItem::ResponseItem(message("assistant", "done")),
+        ]);
+
+        let kinds: Vec<Vec<String>> = entries.iter().map(event_kinds).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                vec!["created", "call:call-1", "completed"],
+                vec!["created", "message:done", "completed"],
+            ]
+        );
+        assert_eq!(entries[1].turn, 1);
+    }
+
+    #[test]
+    fn request_hash_ignores_the_working_directory() {
+        let prompt_in = |cwd: &str| Prompt {
+            input: vec![message("user", &format!("<cwd>{cwd}</cwd> fix it"))],
+            ..Default::default()
+        };
+        assert_eq!(
+            request_hash(&prompt_in("/ci/run-1/repo"), Path::new("/ci/run-1/repo")),
+            request_hash(&prompt_in("/home/me/repo"), Path::new("/home/me/repo"))
+        );
+        assert_ne!(
+            request_hash(&prompt_in("/ci/run-1/repo"), Path::new("/ci/run-1/repo")),
+            request_hash(&prompt_in("/home/me/other"), Path::new("/home/me/repo"))
+        );
+    }
+
+    #[tokio::test]
+    async fn replay_serves_entries_in_turn_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassette.jsonl");
+        let entries = [
+            CassetteEntry {
+                turn: 0,
+                request_hash: None,
+                events: vec![ResponseEvent::OutputItemDone(message("assistant", "first"))],
+            },
+            CassetteEntry {
+                turn: 1,
+                request_hash: None,
+                events: vec![ResponseEvent::OutputItemDone(message(
+                    "assistant",
+                    "second",
+                ))],
+            },
+        ];
+        for entry in &entries {
+            append_entry(&path, entry).await.unwrap();
+        }
+
+        let conversation_id = ConversationId::new();
+        let prompt = Prompt::default();
+        let mut replayed = Vec::new();
+        for _ in 0..2 {
+            let mut stream = replay(
+                &path,
+                CassetteMatch::Turn,
+                &prompt,
+                dir.path(),
+                conversation_id,
+            )
+            .await
+            .unwrap();
+            while let Some(event) = stream.next().await {
+                if let Ok(ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. })) =
+                    event
+                    && let ContentItem::OutputText { text } = &content[0]
+                {
+                    replayed.push(text.clone());
+                }
+            }
+        }
+        assert_eq!(replayed, vec!["first", "second"]);
+
+        let err = replay(
+            &path,
+            CassetteMatch::Turn,
+            &prompt,
+            dir.path(),
+            conversation_id,
+        )
+        .await
+        .err()
+        .unwrap();
+        assert!(
+            err.to_string()
+                .contains("no recorded response for request 3"),
+            "{err}"
+        );
+
+        forget_conversation(conversation_id).unwrap();
+        assert!(!TURNS.lock().unwrap().contains_key(&conversation_id));
+    }
+}
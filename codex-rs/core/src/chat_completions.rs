@@ -4,6 +4,7 @@ use crate::ModelProviderInfo;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
+use crate::client_common::SSE_IDLE_TIMEOUT_MESSAGE;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::RetryLimitReachedError;
@@ -412,10 +413,7 @@ async fn process_chat_sse<S>(
             }
             Err(_) => {
                 let _ = tx_event
-                    .send(Err(CodexErr::Stream(
-                        "idle timeout waiting for SSE".into(),
-                        None,
-                    )))
+                    .send(Err(CodexErr::Stream(SSE_IDLE_TIMEOUT_MESSAGE.into(), None)))
                     .await;
                 return;
             }
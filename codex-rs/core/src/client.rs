@@ -25,21 +25,27 @@ use tracing::debug;
 use tracing::trace;
 use tracing::warn;
 
+use crate::cassette;
 use crate::chat_completions::AggregateStreamExt;
 use crate::chat_completions::stream_chat_completions;
+use crate::client_common::ModelRole;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::client_common::ResponsesApiRequest;
+use crate::client_common::SSE_IDLE_TIMEOUT_MESSAGE;
 use crate::client_common::create_reasoning_param_for_request;
 use crate::client_common::create_text_param_for_request;
 use crate::config::Config;
+use crate::config_types::ModelRoute;
 use crate::default_client::create_client;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::error::UsageLimitReachedError;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
 use crate::model_family::ModelFamily;
+use crate::model_family::derive_default_model_family;
+use crate::model_family::find_family_for_model;
 use crate::model_provider_info::ModelProviderInfo;
 use crate::model_provider_info::WireApi;
 use crate::openai_model_info::get_model_info;
@@ -123,46 +129,82 @@ impl ModelClient {
     /// Dispatches to either the Responses or Chat implementation depending on
     /// the provider config.  Public callers always invoke `stream()` – the
     /// specialised helpers are private to avoid accidental misuse.
+    ///
+    /// With `cassette.record` set, every completed response is also appended
+    /// to the cassette; the `replay` wire API serves responses from one.
     pub async fn stream(&self, prompt: &Prompt) -> Result<ResponseStream> {
-        match self.provider.wire_api {
-            WireApi::Responses => self.stream_responses(prompt).await,
-            WireApi::Chat => {
-                // Create the raw streaming connection first.
-                let response_stream = stream_chat_completions(
-                    prompt,
-                    &self.config.model_family,
-                    &self.client,
-                    &self.provider,
-                    &self.otel_event_manager,
-                )
-                .await?;
-
-                // Wrap it with the aggregation adapter so callers see *only*
-                // the final assistant message per turn (matching the
-                // behaviour of the Responses API).
-                let mut aggregated = if self.config.show_raw_agent_reasoning {
-                    crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
-                } else {
-                    response_stream.aggregate()
-                };
+        let stream = match self.provider.wire_api {
+            WireApi::Responses => self.stream_responses(prompt).await?,
+            WireApi::Chat => self.stream_chat(prompt).await?,
+            WireApi::Replay => return self.stream_replay(prompt).await,
+        };
+        Ok(match &self.config.cassette.record {
+            Some(path) => cassette::record(
+                stream,
+                self.config.cwd.join(path),
+                prompt,
+                &self.config.cwd,
+                self.conversation_id,
+            ),
+            None => stream,
+        })
+    }
+
+    /// Implementation for Chat Completions providers.
+    async fn stream_chat(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        // Create the raw streaming connection first.
+        let response_stream = stream_chat_completions(
+            prompt,
+            &self.config.model_family,
+            &self.client,
+            &self.provider,
+            &self.otel_event_manager,
+        )
+        .await?;
 
-                // Bridge the aggregated stream back into a standard
-                // `ResponseStream` by forwarding events through a channel.
-                let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
+        // Wrap it with the aggregation adapter so callers see *only*
+        // the final assistant message per turn (matching the
+        // behaviour of the Responses API).
+        let mut aggregated = if self.config.show_raw_agent_reasoning {
+            crate::chat_completions::AggregatedChatStream::streaming_mode(response_stream)
+        } else {
+            response_stream.aggregate()
+        };
 
-                tokio::spawn(async move {
-                    use futures::StreamExt;
-                    while let Some(ev) = aggregated.next().await {
-                        // Exit early if receiver hung up.
-                        if tx.send(ev).await.is_err() {
-                            break;
-                        }
-                    }
-                });
+        // Bridge the aggregated stream back into a standard
+        // `ResponseStream` by forwarding events through a channel.
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(16);
 
-                Ok(ResponseStream { rx_event: rx })
+        tokio::spawn(async move {
+            use futures::StreamExt;
+            while let Some(ev) = aggregated.next().await {
+                // Exit early if receiver hung up.
+                if tx.send(ev).await.is_err() {
+                    break;
+                }
             }
-        }
+        });
+
+        Ok(ResponseStream { rx_event: rx })
+    }
+
+    /// Serves recorded responses instead of calling a model.
+    async fn stream_replay(&self, prompt: &Prompt) -> Result<ResponseStream> {
+        let cassette = &self.config.cassette;
+        let Some(path) = &cassette.replay else {
+            return Err(CodexErr::Fatal(
+                "the replay provider needs `cassette.replay` to point at a cassette or rollout file"
+                    .to_string(),
+            ));
+        };
+        cassette::replay(
+            &self.config.cwd.join(path),
+            cassette.match_by,
+            prompt,
+            &self.config.cwd,
+            self.conversation_id,
+        )
+        .await
     }
 
     /// Implementation for the OpenAI *Responses* experimental API.
@@ -425,6 +467,10 @@ impl ModelClient {
         self.provider.clone()
     }
 
+    pub fn get_config(&self) -> Arc<Config> {
+        self.config.clone()
+    }
+
     pub fn get_otel_event_manager(&self) -> OtelEventManager {
         self.otel_event_manager.clone()
     }
@@ -452,6 +498,88 @@ impl ModelClient {
     pub fn get_auth_manager(&self) -> Option<Arc<AuthManager>> {
         self.auth_manager.clone()
     }
+
+    /// The client for requests in `role`: this one, or one for the model
+    /// routed to `role` under `[models]`.
+    pub fn for_role(&self, role: ModelRole) -> ModelClient {
+        let routes = &self.config.model_routes;
+        let route = match role {
+            ModelRole::Planning => &routes.planning,
+            ModelRole::Editing => &routes.editing,
+            ModelRole::Compaction => &routes.compaction,
+        };
+        match route {
+            Some(route) => self.with_route(route),
+            None => self.clone(),
+        }
+    }
+
+    /// A client for `route` that shares this client's auth, reasoning
+    /// settings and conversation.
+    pub fn with_route(&self, route: &ModelRoute) -> ModelClient {
+        let model = route.model();
+        let provider_id = route.provider();
+        if model == self.config.model
+            && provider_id.is_none_or(|id| id == self.config.model_provider_id)
+        {
+            return self.clone();
+        }
+
+        let mut config = (*self.config).clone();
+        config.model = model.to_string();
+        config.model_family =
+            find_family_for_model(model).unwrap_or_else(|| derive_default_model_family(model));
+        if let Some(info) = get_model_info(&config.model_family) {
+            config.model_context_window = Some(info.context_window);
+            config.model_max_output_tokens = Some(info.max_output_tokens);
+            // A limit set for the base model must still fit this model, so
+            // auto-compaction happens before its window overflows.
+            let limit = config.model_auto_compact_token_limit;
+            config.model_auto_compact_token_limit = match info.auto_compact_token_limit {
+                Some(own) => Some(limit.map_or(own, |limit| limit.min(own))),
+                None => limit.map(|limit| limit.min(info.context_window as i64)),
+            };
+        }
+        let mut provider = self.provider.clone();
+        // Provider ids in `[models]` are checked when the config is loaded.
+        if let Some(id) = provider_id
+            && let Some(routed) = config.model_providers.get(id)
+        {
+            config.model_provider_id = id.to_string();
+            config.model_provider = routed.clone();
+            provider = routed.clone();
+        }
+
+        let otel_event_manager = self
+            .otel_event_manager
+            .with_model(config.model.as_str(), config.model_family.slug.as_str());
+        ModelClient {
+            config: Arc::new(config),
+            auth_manager: self.auth_manager.clone(),
+            otel_event_manager,
+            client: self.client.clone(),
+            provider,
+            conversation_id: self.conversation_id,
+            effort: self.effort,
+            summary: self.summary,
+        }
+    }
+}
+
+/// Whether `err` means the provider is down or overloaded rather than that
+/// the request was bad: the connection failed, the provider kept answering
+/// 5xx, or the stream went idle. Such requests may succeed on another
+/// provider.
+pub(crate) fn is_failover_error(err: &CodexErr) -> bool {
+    match err {
+        CodexErr::Reqwest(_) | CodexErr::InternalServerError => true,
+        CodexErr::RetryLimit(RetryLimitReachedError { status, .. })
+        | CodexErr::UnexpectedStatus(UnexpectedResponseError { status, .. }) => {
+            status.is_server_error()
+        }
+        CodexErr::Stream(message, _) => message == SSE_IDLE_TIMEOUT_MESSAGE,
+        _ => false,
+    }
 }
 
 enum StreamAttemptError {
@@ -702,10 +830,7 @@ async fn process_sse<S>(
             }
             Err(_) => {
                 let _ = tx_event
-                    .send(Err(CodexErr::Stream(
-                        "idle timeout waiting for SSE".into(),
-                        None,
-                    )))
+                    .send(Err(CodexErr::Stream(SSE_IDLE_TIMEOUT_MESSAGE.into(), None)))
                     .await;
                 return;
             }
@@ -9,6 +9,7 @@ use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
 use codex_protocol::config_types::Verbosity as VerbosityConfig;
 use codex_protocol::models::ResponseItem;
 use futures::Stream;
+use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
 use std::borrow::Cow;
@@ -21,6 +22,10 @@ use tokio::sync::mpsc;
 /// Review thread system prompt. Edit `core/src/review_prompt.md` to customize.
 pub const REVIEW_PROMPT: &str = include_str!("../review_prompt.md");
 
+/// Error message for a stream that produced no event within the provider's
+/// `stream_idle_timeout_ms`.
+pub(crate) const SSE_IDLE_TIMEOUT_MESSAGE: &str = "idle timeout waiting for SSE";
+
 /// API request payload for a single model turn
 #[derive(Default, Debug, Clone)]
 pub struct Prompt {
@@ -68,7 +73,17 @@ impl Prompt {
     }
 }
 
-#[derive(Debug)]
+/// What a request is for, which selects its model from `[models]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModelRole {
+    Planning,
+    Editing,
+    Compaction,
+}
+
+/// Serializable so responses can be recorded to and replayed from cassettes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ResponseEvent {
     Created,
     OutputItemDone(ResponseItem),
@@ -182,6 +197,18 @@ pub(crate) mod tools {
         Freeform(FreeformTool),
     }
 
+    impl ToolSpec {
+        /// The name the model calls this tool by.
+        pub(crate) fn name(&self) -> &str {
+            match self {
+                ToolSpec::Function(ResponsesApiTool { name, .. }) => name,
+                ToolSpec::LocalShell {} => "local_shell",
+                ToolSpec::WebSearch {} => "web_search",
+                ToolSpec::Freeform(FreeformTool { name, .. }) => name,
+            }
+        }
+    }
+
     #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
     pub struct FreeformTool {
         pub(crate) name: String,
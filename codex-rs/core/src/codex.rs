@@ -1,19 +1,30 @@
 use std::borrow::Cow;
 use std::fmt::Debug;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::AuthManager;
+use crate::budget::BudgetTracker;
+use crate::budget::BudgetUsage;
 use crate::client_common::REVIEW_PROMPT;
 use crate::event_mapping::map_response_item_to_event_messages;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::HookOutcome;
+use crate::hooks::HookRunner;
+use crate::hooks::SessionStartSource;
+use crate::hooks::format_hook_context;
 use crate::review_format::format_review_findings_block;
 use crate::terminal;
 use crate::user_notification::UserNotifier;
 use async_channel::Receiver;
 use async_channel::Sender;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchOptions;
 use codex_protocol::ConversationId;
 use codex_protocol::protocol::ConversationPathResponseEvent;
 use codex_protocol::protocol::ExitedReviewModeEvent;
@@ -41,7 +52,9 @@ use crate::client::ModelClient;
 use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::config::Config;
+use crate::config_types::ModelRoute;
 use crate::config_types::ShellEnvironmentPolicy;
+use crate::config_types::TaskBudget;
 use crate::conversation_history::ConversationHistory;
 use crate::environment_context::EnvironmentContext;
 use crate::error::CodexErr;
@@ -57,6 +70,7 @@ use crate::executor::ExecutorConfig;
 use crate::executor::normalize_exec_result;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::model_family::find_family_for_model;
+use crate::model_router::ModelRouter;
 use crate::openai_model_info::get_model_info;
 use crate::openai_tools::ToolsConfig;
 use crate::openai_tools::ToolsConfigParams;
@@ -80,6 +94,7 @@ use crate::protocol::ListCustomPromptsResponseEvent;
 use crate::protocol::Op;
 use crate::protocol::PatchApplyBeginEvent;
 use crate::protocol::PatchApplyEndEvent;
+use crate::protocol::PatchReview;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReviewDecision;
 use crate::protocol::ReviewOutputEvent;
@@ -87,6 +102,7 @@ use crate::protocol::SandboxPolicy;
 use crate::protocol::SessionConfiguredEvent;
 use crate::protocol::StreamErrorEvent;
 use crate::protocol::Submission;
+use crate::protocol::TaskCompleteEvent;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TurnDiffEvent;
@@ -117,6 +133,7 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::InitialHistory;
 
 pub mod compact;
+mod mcp_servers;
 use self::compact::build_compacted_history;
 use self::compact::collect_user_messages;
 
@@ -164,6 +181,7 @@ impl Codex {
             approval_policy: config.approval_policy,
             sandbox_policy: config.sandbox_policy.clone(),
             notify: UserNotifier::new(config.notify.clone()),
+            hooks: HookRunner::new(config.hooks.clone()),
             cwd: config.cwd.clone(),
         };
 
@@ -258,6 +276,7 @@ pub(crate) struct TurnContext {
     pub(crate) tools_config: ToolsConfig,
     pub(crate) is_review_mode: bool,
     pub(crate) final_output_json_schema: Option<Value>,
+    pub(crate) budget: TaskBudget,
 }
 
 impl TurnContext {
@@ -292,6 +311,8 @@ struct ConfigureSession {
 
     notify: UserNotifier,
 
+    hooks: HookRunner,
+
     /// Working directory that should be treated as the *root* of the
     /// session. All relative paths supplied by the model as well as the
     /// execution sandbox are resolved against this directory **instead**
@@ -321,6 +342,7 @@ impl Session {
             approval_policy,
             sandbox_policy,
             notify,
+            hooks,
             cwd,
         } = configure_session;
         debug!("Configuring session: model={model}; provider={provider:?}");
@@ -446,6 +468,8 @@ impl Session {
                 use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                 include_view_image_tool: config.include_view_image_tool,
                 experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
+                include_spawn_agent_tool: config.include_spawn_agent_tool,
+                allowed_tools: config.allowed_tools.as_deref(),
             }),
             user_instructions,
             base_instructions,
@@ -455,12 +479,14 @@ impl Session {
             cwd,
             is_review_mode: false,
             final_output_json_schema: None,
+            budget: config.budget.clone(),
         };
         let services = SessionServices {
             mcp_connection_manager,
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: notify,
+            hooks,
             rollout: Mutex::new(Some(rollout_recorder)),
             user_shell: default_shell,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -469,6 +495,9 @@ impl Session {
                 turn_context.cwd.clone(),
                 config.codex_linux_sandbox_exe.clone(),
             )),
+            apply_patch_options: ApplyPatchOptions {
+                fuzzy_threshold: config.apply_patch_fuzzy_threshold,
+            },
         };
 
         let sess = Arc::new(Session {
@@ -480,6 +509,11 @@ impl Session {
             next_internal_sub_id: AtomicU64::new(0),
         });
 
+        let start_source = match initial_history {
+            InitialHistory::Resumed(_) => SessionStartSource::Resume,
+            InitialHistory::New | InitialHistory::Forked(_) => SessionStartSource::Startup,
+        };
+
         // Dispatch the SessionConfiguredEvent first and then report any errors.
         // If resuming, include converted initial messages in the payload so UIs can render them immediately.
         let initial_messages = initial_history.get_event_msgs();
@@ -503,6 +537,28 @@ impl Session {
             sess.send_event(event).await;
         }
 
+        let outcome = sess
+            .run_hooks(
+                INITIAL_SUBMIT_ID,
+                &turn_context.cwd,
+                HookEvent::SessionStart {
+                    source: start_source,
+                },
+            )
+            .await;
+        if let Some(context) = outcome.context_text() {
+            sess.record_conversation_items(&[ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: format_hook_context(&context),
+                }],
+            }])
+            .await;
+        }
+
+        mcp_servers::spawn_mcp_health_monitor(&sess);
+
         Ok((sess, turn_context))
     }
 
@@ -638,6 +694,34 @@ impl Session {
         rx_approve
     }
 
+    /// Emit an approval request raised by a sub-agent (an
+    /// `ExecApprovalRequest` or `ApplyPatchApprovalRequest`) as this
+    /// session's own request and await the user's decision.
+    pub(crate) async fn request_forwarded_approval(
+        &self,
+        sub_id: String,
+        msg: EventMsg,
+    ) -> ReviewDecision {
+        let (tx_approve, rx_approve) = oneshot::channel();
+        let event_id = sub_id.clone();
+        let prev_entry = {
+            let mut active = self.active_turn.lock().await;
+            match active.as_mut() {
+                Some(at) => {
+                    let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_approval(sub_id, tx_approve)
+                }
+                None => None,
+            }
+        };
+        if prev_entry.is_some() {
+            warn!("Overwriting existing pending approval for sub_id: {event_id}");
+        }
+
+        self.send_event(Event { id: event_id, msg }).await;
+        rx_approve.await.unwrap_or_default()
+    }
+
     pub async fn notify_approval(&self, sub_id: &str, decision: ReviewDecision) {
         let entry = {
             let mut active = self.active_turn.lock().await;
@@ -659,6 +743,72 @@ impl Session {
         }
     }
 
+    /// Resolve a pending patch approval with a per-hunk review. The review is
+    /// picked up by the waiting `apply_patch` call via [`Self::take_patch_review`].
+    pub async fn notify_patch_review(&self, sub_id: &str, review: PatchReview) {
+        {
+            let mut active = self.active_turn.lock().await;
+            if let Some(at) = active.as_mut() {
+                let mut ts = at.turn_state.lock().await;
+                ts.insert_patch_review(sub_id.to_string(), review);
+            }
+        }
+        self.notify_approval(sub_id, ReviewDecision::Approved).await;
+    }
+
+    /// Publish the running task's remaining budget for the sub-agents it
+    /// spawns; see [`Self::remaining_budget`].
+    async fn set_remaining_budget(&self, budget: TaskBudget) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.set_remaining_budget(budget);
+        }
+    }
+
+    pub(crate) async fn remaining_budget(&self) -> Option<TaskBudget> {
+        let active = self.active_turn.lock().await;
+        match active.as_ref() {
+            Some(at) => {
+                let ts = at.turn_state.lock().await;
+                ts.remaining_budget()
+            }
+            None => None,
+        }
+    }
+
+    /// Count a finished sub-agent's usage against the running task, which
+    /// picks it up via [`Self::take_sub_agent_usage`] after the tool call.
+    pub(crate) async fn add_sub_agent_usage(&self, usage: BudgetUsage) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.add_sub_agent_usage(usage);
+        }
+    }
+
+    async fn take_sub_agent_usage(&self) -> BudgetUsage {
+        let mut active = self.active_turn.lock().await;
+        match active.as_mut() {
+            Some(at) => {
+                let mut ts = at.turn_state.lock().await;
+                ts.take_sub_agent_usage()
+            }
+            None => BudgetUsage::default(),
+        }
+    }
+
+    pub(crate) async fn take_patch_review(&self, sub_id: &str) -> Option<PatchReview> {
+        let mut active = self.active_turn.lock().await;
+        match active.as_mut() {
+            Some(at) => {
+                let mut ts = at.turn_state.lock().await;
+                ts.take_patch_review(sub_id)
+            }
+            None => None,
+        }
+    }
+
     /// Records input items: always append to conversation history and
     /// persist these response items to rollout.
     async fn record_conversation_items(&self, items: &[ResponseItem]) {
@@ -963,6 +1113,28 @@ impl Session {
         self.send_event(event).await;
     }
 
+    /// Run the hooks configured for `event` in `cwd`, reporting hooks that
+    /// failed to the user.
+    pub(crate) async fn run_hooks(
+        &self,
+        sub_id: &str,
+        cwd: &Path,
+        event: HookEvent,
+    ) -> HookOutcome {
+        if !self.services.hooks.has_hooks(&event) {
+            return HookOutcome::default();
+        }
+        let outcome = self
+            .services
+            .hooks
+            .run(self.conversation_id, cwd, event)
+            .await;
+        for error in &outcome.errors {
+            self.notify_background_event(sub_id, error.clone()).await;
+        }
+        outcome
+    }
+
     async fn notify_stream_error(&self, sub_id: &str, message: impl Into<String>) {
         let event = Event {
             id: sub_id.to_string(),
@@ -1178,6 +1350,8 @@ async fn submission_loop(
                     use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
                     include_view_image_tool: config.include_view_image_tool,
                     experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
+                    include_spawn_agent_tool: config.include_spawn_agent_tool,
+                    allowed_tools: config.allowed_tools.as_deref(),
                 });
 
                 let new_turn_context = TurnContext {
@@ -1191,6 +1365,7 @@ async fn submission_loop(
                     cwd: new_cwd.clone(),
                     is_review_mode: false,
                     final_output_json_schema: None,
+                    budget: prev.budget.clone(),
                 };
 
                 // Install the new persistent context for subsequent tasks/turns.
@@ -1214,10 +1389,15 @@ async fn submission_loop(
                     .get_otel_event_manager()
                     .user_prompt(&items);
                 // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items).await {
+                if let Err(items) = inject_user_input(&sess, &turn_context, &sub.id, items).await {
                     // no current task, spawn a new one
-                    sess.spawn_task(Arc::clone(&turn_context), sub.id, items, RegularTask)
-                        .await;
+                    sess.spawn_task(
+                        Arc::clone(&turn_context),
+                        sub.id,
+                        items,
+                        RegularTask::default(),
+                    )
+                    .await;
                 }
             }
             Op::UserTurn {
@@ -1235,7 +1415,7 @@ async fn submission_loop(
                     .get_otel_event_manager()
                     .user_prompt(&items);
                 // attempt to inject input into current task
-                if let Err(items) = sess.inject_input(items).await {
+                if let Err(items) = inject_user_input(&sess, &turn_context, &sub.id, items).await {
                     // Derive a fresh TurnContext for this turn using the provided overrides.
                     let provider = turn_context.client.get_provider();
                     let auth_manager = turn_context.client.get_auth_manager();
@@ -1282,6 +1462,8 @@ async fn submission_loop(
                             include_view_image_tool: config.include_view_image_tool,
                             experimental_unified_exec_tool: config
                                 .use_experimental_unified_exec_tool,
+                            include_spawn_agent_tool: config.include_spawn_agent_tool,
+                            allowed_tools: config.allowed_tools.as_deref(),
                         }),
                         user_instructions: turn_context.user_instructions.clone(),
                         base_instructions: turn_context.base_instructions.clone(),
@@ -1291,6 +1473,7 @@ async fn submission_loop(
                         cwd,
                         is_review_mode: false,
                         final_output_json_schema,
+                        budget: turn_context.budget.clone(),
                     };
 
                     // if the environment context has changed, record it in the conversation history
@@ -1316,8 +1499,13 @@ async fn submission_loop(
                     turn_context = Arc::new(fresh_turn_context);
 
                     // no current task, spawn a new one with the per-turn context
-                    sess.spawn_task(Arc::clone(&turn_context), sub.id, items, RegularTask)
-                        .await;
+                    sess.spawn_task(
+                        Arc::clone(&turn_context),
+                        sub.id,
+                        items,
+                        RegularTask::default(),
+                    )
+                    .await;
                 }
             }
             Op::ExecApproval { id, decision } => match decision {
@@ -1332,6 +1520,9 @@ async fn submission_loop(
                 }
                 other => sess.notify_approval(&id, other).await,
             },
+            Op::PatchReview { id, review } => {
+                sess.notify_patch_review(&id, review).await;
+            }
             Op::AddToHistory { text } => {
                 let id = sess.conversation_id;
                 let config = config.clone();
@@ -1389,6 +1580,12 @@ async fn submission_loop(
                 };
                 sess.send_event(event).await;
             }
+            Op::RestartMcpServer { server_name } => {
+                mcp_servers::restart_mcp_server(&sess, &sub.id, &server_name).await;
+            }
+            Op::ReloadMcpServers => {
+                mcp_servers::reload_mcp_servers(&sess, &config, &sub.id).await;
+            }
             Op::ListCustomPrompts => {
                 let sub_id = sub.id.clone();
 
@@ -1422,6 +1619,8 @@ async fn submission_loop(
             Op::Shutdown => {
                 sess.abort_all_tasks(TurnAbortReason::Interrupted).await;
                 info!("Shutting down Codex instance");
+                sess.run_hooks(&sub.id, &turn_context.cwd, HookEvent::SessionStop)
+                    .await;
 
                 // Gracefully flush and shutdown rollout recorder on session end so tests
                 // that inspect the rollout file do not race with the background writer.
@@ -1491,9 +1690,107 @@ async fn submission_loop(
             }
         }
     }
+    // No more requests can be made, so stop counting them for the cassette.
+    if let Err(err) = crate::cassette::forget_conversation(sess.conversation_id) {
+        warn!("{err}");
+    }
     debug!("Agent loop exited");
 }
 
+/// Add user input to the running turn, or return it when there is none so
+/// the caller can start one (whose task runs the `user_prompt_submit` hooks).
+/// With such hooks, a running turn gets the input once they have run on a
+/// task of their own, so a slow hook never holds up the submission loop.
+async fn inject_user_input(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    sub_id: &str,
+    items: Vec<InputItem>,
+) -> Result<(), Vec<InputItem>> {
+    let prompt_hooks = HookEvent::UserPromptSubmit {
+        prompt: String::new(),
+    };
+    if !sess.services.hooks.has_hooks(&prompt_hooks) {
+        return sess.inject_input(items).await;
+    }
+    if sess.active_turn.lock().await.is_none() {
+        return Err(items);
+    }
+    let sess = Arc::clone(sess);
+    let turn_context = Arc::clone(turn_context);
+    let sub_id = sub_id.to_string();
+    tokio::spawn(async move {
+        let Some(items) = run_prompt_hooks(&sess, &sub_id, &turn_context.cwd, items).await else {
+            return;
+        };
+        // The turn may have ended while the hooks ran.
+        if let Err(items) = sess.inject_input(items).await {
+            sess.spawn_task(
+                turn_context,
+                sub_id,
+                items,
+                RegularTask::with_hooked_input(),
+            )
+            .await;
+        }
+    });
+    Ok(())
+}
+
+/// Run the `user_prompt_submit` hooks over a prompt. Returns `None` when a
+/// hook blocked the prompt, after telling the user why.
+pub(crate) async fn run_prompt_hooks(
+    sess: &Session,
+    sub_id: &str,
+    cwd: &Path,
+    mut items: Vec<InputItem>,
+) -> Option<Vec<InputItem>> {
+    let prompt = items
+        .iter()
+        .filter_map(|item| match item {
+            InputItem::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let outcome = sess
+        .run_hooks(sub_id, cwd, HookEvent::UserPromptSubmit { prompt })
+        .await;
+    let context = outcome.context_text();
+
+    if let Some(reason) = outcome.blocked {
+        sess.send_event(Event {
+            id: sub_id.to_string(),
+            msg: EventMsg::Error(ErrorEvent {
+                message: format!("Prompt blocked by hook: {reason}"),
+            }),
+        })
+        .await;
+        // Without a running turn nothing else would end this submission.
+        if sess.active_turn.lock().await.is_none() {
+            sess.send_event(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::TaskComplete(TaskCompleteEvent {
+                    last_agent_message: None,
+                }),
+            })
+            .await;
+        }
+        return None;
+    }
+
+    if let Some(serde_json::Value::String(prompt)) = outcome.updated_input {
+        items.retain(|item| !matches!(item, InputItem::Text { .. }));
+        items.insert(0, InputItem::Text { text: prompt });
+    }
+    if let Some(context) = context {
+        items.push(InputItem::Text {
+            text: format_hook_context(&context),
+        });
+    }
+    Some(items)
+}
+
 /// Spawn a review thread using the given prompt.
 async fn spawn_review_thread(
     sess: Arc<Session>,
@@ -1502,7 +1799,12 @@ async fn spawn_review_thread(
     sub_id: String,
     review_request: ReviewRequest,
 ) {
-    let model = config.review_model.clone();
+    // `[models] review` takes precedence over `review_model`.
+    let review_route = config.model_routes.review.as_ref();
+    let model = review_route.map_or_else(
+        || config.review_model.clone(),
+        |route| route.model().to_string(),
+    );
     let review_model_family = find_family_for_model(&model)
         .unwrap_or_else(|| parent_turn_context.client.get_model_family());
     let tools_config = ToolsConfig::new(&ToolsConfigParams {
@@ -1513,16 +1815,25 @@ async fn spawn_review_thread(
         use_streamable_shell_tool: false,
         include_view_image_tool: false,
         experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
+        include_spawn_agent_tool: false,
+        allowed_tools: None,
     });
 
     let base_instructions = REVIEW_PROMPT.to_string();
     let review_prompt = review_request.prompt.clone();
-    let provider = parent_turn_context.client.get_provider();
+    let mut provider = parent_turn_context.client.get_provider();
     let auth_manager = parent_turn_context.client.get_auth_manager();
     let model_family = review_model_family.clone();
 
     // Build per‑turn client with the requested model/family.
     let mut per_turn_config = (*config).clone();
+    if let Some(id) = review_route.and_then(ModelRoute::provider)
+        && let Some(routed) = config.model_providers.get(id)
+    {
+        per_turn_config.model_provider_id = id.to_string();
+        per_turn_config.model_provider = routed.clone();
+        provider = routed.clone();
+    }
     per_turn_config.model = model.clone();
     per_turn_config.model_family = model_family.clone();
     per_turn_config.model_reasoning_effort = Some(ReasoningEffortConfig::Low);
@@ -1561,6 +1872,7 @@ async fn spawn_review_thread(
         cwd: parent_turn_context.cwd.clone(),
         is_review_mode: true,
         final_output_json_schema: None,
+        budget: parent_turn_context.budget.clone(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -1635,8 +1947,14 @@ pub(crate) async fn run_task(
     // many turns, from the perspective of the user, it is a single turn.
     let mut turn_diff_tracker = TurnDiffTracker::new();
     let mut auto_compact_recently_attempted = false;
+    let mut budget = BudgetTracker::new(turn_context.budget.clone());
+    let mut router = ModelRouter::new(&turn_context.client);
 
     loop {
+        if let Some(reason) = budget.exceeded() {
+            return abort_for_budget(&sess, &sub_id, reason).await;
+        }
+
         // Note that pending_input would be something like a message the user
         // submitted through the UI while the model was running. Though the UI
         // may support this, the model might not.
@@ -1680,30 +1998,58 @@ pub(crate) async fn run_task(
                 })
             })
             .collect();
-        match run_turn(
+        // Review threads already run on the review model.
+        let client = if is_review_mode {
+            turn_context.client.clone()
+        } else {
+            router.client()
+        };
+        let mut streamed_text = false;
+        sess.set_remaining_budget(budget.remaining()).await;
+        let turn = run_turn(
             &sess,
             turn_context.as_ref(),
+            &client,
             &mut turn_diff_tracker,
+            &mut streamed_text,
             sub_id.clone(),
             turn_input,
-        )
-        .await
-        {
+        );
+        let turn_result = match budget.remaining_time() {
+            Some(remaining) => match tokio::time::timeout(remaining, turn).await {
+                Ok(turn_result) => turn_result,
+                Err(_) => {
+                    return abort_for_budget(&sess, &sub_id, budget.timeout_reason()).await;
+                }
+            },
+            None => turn.await,
+        };
+        match turn_result {
             Ok(turn_output) => {
                 let TurnRunResult {
                     processed_items,
                     total_token_usage,
                 } = turn_output;
-                let limit = turn_context
-                    .client
-                    .get_auto_compact_token_limit()
-                    .unwrap_or(i64::MAX);
+                let limit = client.get_auto_compact_token_limit().unwrap_or(i64::MAX);
                 let total_usage_tokens = total_token_usage
                     .as_ref()
                     .map(TokenUsage::tokens_in_context_window);
                 let token_limit_reached = total_usage_tokens
                     .map(|tokens| (tokens as i64) >= limit)
                     .unwrap_or(false);
+                let tool_outputs = processed_items
+                    .iter()
+                    .filter_map(|processed| processed.response.as_ref());
+                if let Some(fallback) = router.record_tool_calls(tool_outputs) {
+                    sess.notify_background_event(
+                        &sub_id,
+                        format!(
+                            "Switching to {} for the rest of this turn after repeated invalid tool calls",
+                            fallback.get_model()
+                        ),
+                    )
+                    .await;
+                }
                 let mut items_to_record_in_conversation_history = Vec::<ResponseItem>::new();
                 let mut responses = Vec::<ResponseInputItem>::new();
                 for processed_response_item in processed_items {
@@ -1797,6 +2143,9 @@ pub(crate) async fn run_task(
                     }
                 }
 
+                budget.record_turn(total_token_usage.as_ref(), responses.len());
+                budget.record_sub_agent(sess.take_sub_agent_usage().await);
+
                 // Only attempt to take the lock if there is something to record.
                 if !items_to_record_in_conversation_history.is_empty() {
                     if is_review_mode {
@@ -1847,6 +2196,21 @@ pub(crate) async fn run_task(
                 continue;
             }
             Err(e) => {
+                if !is_review_mode && let Some(backup) = router.fail_over(&e) {
+                    let mut message = format!(
+                        "{e}; switched to provider `{}` with model `{}`",
+                        backup.get_config().model_provider_id,
+                        backup.get_model()
+                    );
+                    if streamed_text {
+                        message.push_str(
+                            ", which answers from the start, so text shown before the failure may repeat",
+                        );
+                    }
+                    warn!("{message}");
+                    sess.notify_background_event(&sub_id, message).await;
+                    continue;
+                }
                 info!("Turn error: {e:#}");
                 let event = Event {
                     id: sub_id.clone(),
@@ -1880,6 +2244,16 @@ pub(crate) async fn run_task(
     last_agent_message
 }
 
+/// Stop the task because it went over its budget. The abort is delivered
+/// like an interrupt, as a `TurnAborted` event instead of `TaskComplete`.
+async fn abort_for_budget(sess: &Arc<Session>, sub_id: &str, reason: String) -> Option<String> {
+    sess.notify_background_event(sub_id, format!("Task budget exceeded: {reason}"))
+        .await;
+    sess.abort_current_task(TurnAbortReason::BudgetExceeded);
+    // Aborting cancels this task; wait here until that happens.
+    std::future::pending().await
+}
+
 /// Parse the review output; when not valid JSON, build a structured
 /// fallback that carries the plain text as the overall explanation.
 ///
@@ -1905,15 +2279,24 @@ fn parse_review_output_event(text: &str) -> ReviewOutputEvent {
     }
 }
 
+/// `streamed_text` is set once any assistant text has been shown, so a
+/// caller that re-runs the request elsewhere can say that it may repeat.
 async fn run_turn(
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     turn_diff_tracker: &mut TurnDiffTracker,
+    streamed_text: &mut bool,
     sub_id: String,
     input: Vec<ResponseItem>,
 ) -> CodexResult<TurnRunResult> {
     let mcp_tools = sess.services.mcp_connection_manager.list_all_tools();
-    let router = ToolRouter::from_config(&turn_context.tools_config, Some(mcp_tools));
+    // A routed model may take a different shell or `apply_patch` flavor.
+    let tools_config = turn_context.tools_config.for_model_family(
+        &client.get_model_family(),
+        client.get_config().include_apply_patch_tool,
+    );
+    let router = ToolRouter::from_config(&tools_config, Some(mcp_tools));
 
     let prompt = Prompt {
         input,
@@ -1928,7 +2311,9 @@ async fn run_turn(
             &router,
             sess,
             turn_context,
+            client,
             turn_diff_tracker,
+            streamed_text,
             &sub_id,
             &prompt,
         )
@@ -1948,7 +2333,7 @@ async fn run_turn(
             Err(CodexErr::UsageNotIncluded) => return Err(CodexErr::UsageNotIncluded),
             Err(e) => {
                 // Use the configured provider-specific stream retry budget.
-                let max_retries = turn_context.client.get_provider().stream_max_retries();
+                let max_retries = client.get_provider().stream_max_retries();
                 if retries < max_retries {
                     retries += 1;
                     let delay = match e {
@@ -1995,11 +2380,40 @@ struct TurnRunResult {
     total_token_usage: Option<TokenUsage>,
 }
 
+/// Append one model request to the usage log read by `codex usage`.
+/// Awaited before the turn goes on, so the record is written by the time
+/// the task completes and a short-lived `codex exec` exits.
+async fn record_usage(
+    sess: &Session,
+    turn_context: &TurnContext,
+    client: &ModelClient,
+    usage: TokenUsage,
+    duration: Duration,
+) {
+    let config = client.get_config();
+    if let Err(e) = crate::usage::append_record(
+        &config.codex_home,
+        sess.conversation_id,
+        &turn_context.cwd,
+        &config.model_provider_id,
+        &client.get_model(),
+        usage,
+        duration,
+    )
+    .await
+    {
+        warn!("failed to append to usage log: {e}");
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn try_run_turn(
     router: &crate::tools::ToolRouter,
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     turn_diff_tracker: &mut TurnDiffTracker,
+    streamed_text: &mut bool,
     sub_id: &str,
     prompt: &Prompt,
 ) -> CodexResult<TurnRunResult> {
@@ -2062,12 +2476,18 @@ async fn try_run_turn(
         cwd: turn_context.cwd.clone(),
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
-        model: turn_context.client.get_model(),
-        effort: turn_context.client.get_reasoning_effort(),
-        summary: turn_context.client.get_reasoning_summary(),
+        model: client.get_model(),
+        effort: client.get_reasoning_effort(),
+        summary: client.get_reasoning_summary(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
-    let mut stream = turn_context.client.clone().stream(&prompt).await?;
+    // Time spent waiting on the model only. Tool calls run inside this loop
+    // and may wait on commands, approvals or child agents, so the wall clock
+    // from the request to `Completed` would overstate it.
+    let mut model_time = Duration::ZERO;
+    let started = Instant::now();
+    let mut stream = client.stream(&prompt).await?;
+    model_time += started.elapsed();
 
     let mut output = Vec::new();
 
@@ -2075,7 +2495,9 @@ async fn try_run_turn(
         // Poll the next item from the model stream. We must inspect *both* Ok and Err
         // cases so that transient stream failures (e.g., dropped SSE connection before
         // `response.completed`) bubble up and trigger the caller's retry logic.
+        let waiting = Instant::now();
         let event = stream.next().await;
+        model_time += waiting.elapsed();
         let Some(event) = event else {
             // Channel closed without yielding a final Completed event or explicit error.
             // Treat as a disconnected stream so the caller can retry.
@@ -2128,6 +2550,14 @@ async fn try_run_turn(
             } => {
                 sess.update_token_usage_info(sub_id, turn_context, token_usage.as_ref())
                     .await;
+                record_usage(
+                    sess,
+                    turn_context,
+                    client,
+                    token_usage.clone().unwrap_or_default(),
+                    model_time,
+                )
+                .await;
 
                 let unified_diff = turn_diff_tracker.get_unified_diff();
                 if let Ok(Some(unified_diff)) = unified_diff {
@@ -2150,6 +2580,7 @@ async fn try_run_turn(
                 // In review child threads, suppress assistant text deltas; the
                 // UI will show a selection popup from the final ReviewOutput.
                 if !turn_context.is_review_mode {
+                    *streamed_text = true;
                     let event = Event {
                         id: sub_id.to_string(),
                         msg: EventMsg::AgentMessageDelta(AgentMessageDeltaEvent { delta }),
@@ -2686,6 +3117,8 @@ mod tests {
             use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
             include_view_image_tool: config.include_view_image_tool,
             experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
+            include_spawn_agent_tool: config.include_spawn_agent_tool,
+            allowed_tools: config.allowed_tools.as_deref(),
         });
         let turn_context = TurnContext {
             client,
@@ -2698,12 +3131,14 @@ mod tests {
             tools_config,
             is_review_mode: false,
             final_output_json_schema: None,
+            budget: config.budget.clone(),
         };
         let services = SessionServices {
             mcp_connection_manager: McpConnectionManager::default(),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -2712,6 +3147,7 @@ mod tests {
                 turn_context.cwd.clone(),
                 None,
             )),
+            apply_patch_options: ApplyPatchOptions::default(),
         };
         let session = Session {
             conversation_id,
@@ -2759,6 +3195,8 @@ mod tests {
             use_streamable_shell_tool: config.use_experimental_streamable_shell_tool,
             include_view_image_tool: config.include_view_image_tool,
             experimental_unified_exec_tool: config.use_experimental_unified_exec_tool,
+            include_spawn_agent_tool: config.include_spawn_agent_tool,
+            allowed_tools: config.allowed_tools.as_deref(),
         });
         let turn_context = Arc::new(TurnContext {
             client,
@@ -2771,12 +3209,14 @@ mod tests {
             tools_config,
             is_review_mode: false,
             final_output_json_schema: None,
+            budget: config.budget.clone(),
         });
         let services = SessionServices {
             mcp_connection_manager: McpConnectionManager::default(),
             session_manager: ExecSessionManager::default(),
             unified_exec_manager: UnifiedExecSessionManager::default(),
             notifier: UserNotifier::default(),
+            hooks: HookRunner::default(),
             rollout: Mutex::new(None),
             user_shell: shell::Shell::Unknown,
             show_raw_agent_reasoning: config.show_raw_agent_reasoning,
@@ -2785,6 +3225,7 @@ mod tests {
                 config.cwd.clone(),
                 None,
             )),
+            apply_patch_options: ApplyPatchOptions::default(),
         };
         let session = Arc::new(Session {
             conversation_id,
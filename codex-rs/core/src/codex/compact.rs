@@ -4,6 +4,8 @@ use super::Session;
 use super::TurnContext;
 use super::get_last_assistant_message_from_turn;
 use crate::Prompt;
+use crate::client::ModelClient;
+use crate::client_common::ModelRole;
 use crate::client_common::ResponseEvent;
 use crate::error::CodexErr;
 use crate::error::Result as CodexResult;
@@ -79,22 +81,23 @@ async fn run_compact_task_inner(
         ..Default::default()
     };
 
-    let max_retries = turn_context.client.get_provider().stream_max_retries();
+    let client = turn_context.client.for_role(ModelRole::Compaction);
+    let max_retries = client.get_provider().stream_max_retries();
     let mut retries = 0;
 
     let rollout_item = RolloutItem::TurnContext(TurnContextItem {
         cwd: turn_context.cwd.clone(),
         approval_policy: turn_context.approval_policy,
         sandbox_policy: turn_context.sandbox_policy.clone(),
-        model: turn_context.client.get_model(),
-        effort: turn_context.client.get_reasoning_effort(),
-        summary: turn_context.client.get_reasoning_summary(),
+        model: client.get_model(),
+        effort: client.get_reasoning_effort(),
+        summary: client.get_reasoning_summary(),
     });
     sess.persist_rollout_items(&[rollout_item]).await;
 
     loop {
         let attempt_result =
-            drain_to_completed(&sess, turn_context.as_ref(), &sub_id, &prompt).await;
+            drain_to_completed(&sess, turn_context.as_ref(), &client, &sub_id, &prompt).await;
 
         match attempt_result {
             Ok(()) => {
@@ -230,10 +233,11 @@ pub(crate) fn build_compacted_history(
 async fn drain_to_completed(
     sess: &Session,
     turn_context: &TurnContext,
+    client: &ModelClient,
     sub_id: &str,
     prompt: &Prompt,
 ) -> CodexResult<()> {
-    let mut stream = turn_context.client.clone().stream(prompt).await?;
+    let mut stream = client.stream(prompt).await?;
     loop {
         let maybe_event = stream.next().await;
         let Some(event) = maybe_event else {
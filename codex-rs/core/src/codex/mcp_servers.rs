@@ -0,0 +1,194 @@
+//! Mid-session lifecycle of MCP servers: user-requested restarts and reloads,
+//! plus a background monitor that restarts crashed stdio servers with backoff
+//! and refreshes the tool list when a server reports that it changed.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Weak;
+use std::time::Instant;
+
+use tracing::error;
+use tracing::info;
+use tracing::warn;
+
+use super::INITIAL_SUBMIT_ID;
+use super::Session;
+use crate::config::Config;
+use crate::config::load_global_mcp_servers;
+use crate::mcp_connection_manager::HEALTH_CHECK_INTERVAL;
+use crate::mcp_connection_manager::RestartBackoff;
+use crate::protocol::ErrorEvent;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::McpListToolsResponseEvent;
+
+/// Handle `Op::RestartMcpServer`.
+pub(crate) async fn restart_mcp_server(sess: &Session, sub_id: &str, server_name: &str) {
+    match sess
+        .services
+        .mcp_connection_manager
+        .restart_server(server_name)
+        .await
+    {
+        Ok(()) => {
+            sess.notify_background_event(sub_id, format!("MCP server `{server_name}` restarted"))
+                .await;
+        }
+        Err(e) => {
+            send_error(
+                sess,
+                sub_id,
+                format!("MCP server `{server_name}` failed to restart: {e:#}"),
+            )
+            .await;
+        }
+    }
+    send_tools(sess, sub_id).await;
+}
+
+/// Handle `Op::ReloadMcpServers`.
+///
+/// Only `mcp_servers` from `config.toml` is re-read; profile and command-line
+/// overrides that were applied when the session started are not re-evaluated.
+pub(crate) async fn reload_mcp_servers(sess: &Session, config: &Config, sub_id: &str) {
+    let mcp_servers = match load_global_mcp_servers(&config.codex_home).await {
+        Ok(servers) => servers.into_iter().collect::<HashMap<_, _>>(),
+        Err(e) => {
+            send_error(
+                sess,
+                sub_id,
+                format!("Failed to read MCP servers from config.toml: {e}"),
+            )
+            .await;
+            return;
+        }
+    };
+
+    let outcome = sess
+        .services
+        .mcp_connection_manager
+        .reload(mcp_servers)
+        .await;
+
+    let message = if outcome.started.is_empty() && outcome.stopped.is_empty() {
+        "MCP servers reloaded: no changes".to_string()
+    } else {
+        let mut parts = Vec::new();
+        if !outcome.started.is_empty() {
+            parts.push(format!("started {}", outcome.started.join(", ")));
+        }
+        if !outcome.stopped.is_empty() {
+            parts.push(format!("stopped {}", outcome.stopped.join(", ")));
+        }
+        format!("MCP servers reloaded: {}", parts.join("; "))
+    };
+    sess.notify_background_event(sub_id, message).await;
+
+    for (server_name, err) in outcome.errors {
+        send_error(
+            sess,
+            sub_id,
+            format!("MCP client for `{server_name}` failed to start: {err:#}"),
+        )
+        .await;
+    }
+    send_tools(sess, sub_id).await;
+}
+
+/// Spawn the background task that keeps MCP servers healthy for the lifetime
+/// of the session. The task holds only a weak reference and exits once the
+/// session is dropped.
+pub(crate) fn spawn_mcp_health_monitor(sess: &Arc<Session>) {
+    let weak = Arc::downgrade(sess);
+    let tools_changed = sess.services.mcp_connection_manager.tools_changed();
+    tokio::spawn(async move {
+        let mut backoffs: HashMap<String, RestartBackoff> = HashMap::new();
+        loop {
+            let tools_changed_fired = tokio::select! {
+                _ = tools_changed.notified() => true,
+                _ = tokio::time::sleep(HEALTH_CHECK_INTERVAL) => false,
+            };
+            let Some(sess) = Weak::upgrade(&weak) else {
+                break;
+            };
+            if tools_changed_fired {
+                info!("MCP tool list changed; refreshing tools");
+                sess.services.mcp_connection_manager.refresh_tools().await;
+            }
+            check_health(&sess, &mut backoffs).await;
+        }
+    });
+}
+
+async fn check_health(sess: &Session, backoffs: &mut HashMap<String, RestartBackoff>) {
+    let manager = &sess.services.mcp_connection_manager;
+    let mut crashed = manager.crashed_stdio_servers().await;
+    // A failed restart leaves no client behind, so keep retrying servers we
+    // are already backing off on until they come up or we give up.
+    for (server_name, backoff) in backoffs.iter() {
+        if !backoff.is_exhausted()
+            && !manager.is_running(server_name)
+            && !crashed.contains(server_name)
+        {
+            crashed.push(server_name.clone());
+        }
+    }
+    let now = Instant::now();
+
+    // Forget the backoff of servers that have stayed up since their restart.
+    backoffs.retain(|name, backoff| crashed.contains(name) || !backoff.is_stable(now));
+
+    for server_name in crashed {
+        let backoff = backoffs.entry(server_name.clone()).or_default();
+        if !backoff.is_due(now) {
+            continue;
+        }
+        backoff.record_attempt(now);
+        warn!(
+            "MCP server `{server_name}` exited; restarting (attempt {})",
+            backoff.attempts()
+        );
+
+        match manager.restart_server(&server_name).await {
+            Ok(()) => {
+                sess.notify_background_event(
+                    INITIAL_SUBMIT_ID,
+                    format!("MCP server `{server_name}` crashed and was restarted"),
+                )
+                .await;
+            }
+            Err(e) if backoff.is_exhausted() => {
+                send_error(
+                    sess,
+                    INITIAL_SUBMIT_ID,
+                    format!(
+                        "MCP server `{server_name}` crashed and could not be restarted after {} attempts: {e:#}. Use `/mcp restart {server_name}` to try again.",
+                        backoff.attempts()
+                    ),
+                )
+                .await;
+            }
+            Err(e) => {
+                warn!("failed to restart MCP server `{server_name}`: {e:#}");
+            }
+        }
+    }
+}
+
+async fn send_tools(sess: &Session, sub_id: &str) {
+    let tools = sess.services.mcp_connection_manager.list_all_tools();
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::McpListToolsResponse(McpListToolsResponseEvent { tools }),
+    })
+    .await;
+}
+
+async fn send_error(sess: &Session, sub_id: &str, message: String) {
+    error!("{message}");
+    sess.send_event(Event {
+        id: sub_id.to_string(),
+        msg: EventMsg::Error(ErrorEvent { message }),
+    })
+    .await;
+}
@@ -3,19 +3,28 @@ pub use crate::config_loader::load_config_as_toml;
 use crate::config_loader::load_config_layers_with_overrides;
 use crate::config_loader::merge_toml_values;
 use crate::config_profile::ConfigProfile;
+use crate::config_types::CassetteConfig;
 use crate::config_types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config_types::DiffView;
 use crate::config_types::History;
+use crate::config_types::Hooks;
 use crate::config_types::McpServerConfig;
 use crate::config_types::McpServerTransportConfig;
+use crate::config_types::ModelRoute;
+use crate::config_types::ModelRoutes;
 use crate::config_types::Notifications;
 use crate::config_types::OtelConfig;
 use crate::config_types::OtelConfigToml;
 use crate::config_types::OtelExporterKind;
+use crate::config_types::Pricing;
 use crate::config_types::ReasoningSummaryFormat;
 use crate::config_types::SandboxWorkspaceWrite;
 use crate::config_types::ShellEnvironmentPolicy;
 use crate::config_types::ShellEnvironmentPolicyToml;
+use crate::config_types::TaskBudget;
+use crate::config_types::ThemePalette;
 use crate::config_types::Tui;
+use crate::config_types::TuiKeymap;
 use crate::config_types::UriBasedFileOpener;
 use crate::git_info::resolve_root_git_project_for_trust;
 use crate::model_family::ModelFamily;
@@ -134,6 +143,27 @@ pub struct Config {
     /// and turn completions when not focused.
     pub tui_notifications: Notifications,
 
+    /// Name of the TUI color theme (`[tui] theme`).
+    pub tui_theme: Option<String>,
+
+    /// User-defined TUI color themes (`[tui.themes.<name>]`).
+    pub tui_themes: HashMap<String, ThemePalette>,
+
+    /// Key binding overrides for the TUI (`[tui.keymap]`).
+    pub tui_keymap: TuiKeymap,
+
+    /// Enable vi-style modal editing in the TUI composer.
+    pub tui_vim_mode: bool,
+
+    /// Share vim-mode yanks and puts with the system clipboard.
+    pub tui_vim_clipboard: bool,
+
+    /// Diff layout in the TUI (`[tui] diff_view`).
+    pub tui_diff_view: DiffView,
+
+    /// Copy-based workspace snapshots outside git (`[tui] file_snapshots`).
+    pub tui_file_snapshots: bool,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -158,6 +188,23 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// User commands run before and after tool calls, patches, prompts and
+    /// sessions.
+    pub hooks: Hooks,
+
+    /// Limits applied to every task; see [`TaskBudget`].
+    pub budget: TaskBudget,
+
+    /// Where model responses are recorded to and replayed from.
+    pub cassette: CassetteConfig,
+
+    /// Prices `codex usage` uses to estimate cost.
+    pub pricing: Pricing,
+
+    /// Models used for planning, editing, compaction and review requests
+    /// instead of `model`, and the fallback model.
+    pub model_routes: ModelRoutes,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: UriBasedFileOpener,
@@ -206,6 +253,19 @@ pub struct Config {
     /// Include the `view_image` tool that lets the agent attach a local image path to context.
     pub include_view_image_tool: bool,
 
+    /// Minimum similarity (0.0-1.0) at which `apply_patch` applies a hunk
+    /// whose lines do not match the file exactly. `None` disables the fuzzy
+    /// fallback.
+    pub apply_patch_fuzzy_threshold: Option<f64>,
+
+    /// Include the `spawn_agent` tool that lets the agent delegate a scoped
+    /// task to a child conversation.
+    pub include_spawn_agent_tool: bool,
+
+    /// When set, only tools with these names are offered to the model. Set
+    /// for the child conversations started by `spawn_agent`.
+    pub allowed_tools: Option<Vec<String>>,
+
     /// The active profile name used to derive this `Config` (if any).
     pub active_profile: Option<String>,
 
@@ -582,6 +642,46 @@ pub async fn persist_model_selection(
     Ok(())
 }
 
+/// Persist the selected TUI color theme as `[tui] theme` in `config.toml`.
+pub async fn persist_tui_theme(codex_home: &Path, theme: &str) -> anyhow::Result<()> {
+    let config_path = codex_home.join(CONFIG_TOML_FILE);
+    let serialized = match tokio::fs::read_to_string(&config_path).await {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut doc = if serialized.is_empty() {
+        DocumentMut::new()
+    } else {
+        serialized.parse::<DocumentMut>()?
+    };
+
+    let root = doc.as_table_mut();
+    if root.get("tui").and_then(|item| item.as_table()).is_none() {
+        root.insert("tui", toml_edit::table());
+    }
+    let Some(tui_table) = doc["tui"].as_table_mut() else {
+        return Err(anyhow::anyhow!("tui table missing after initialization"));
+    };
+    tui_table["theme"] = toml_edit::value(theme);
+
+    tokio::fs::create_dir_all(codex_home)
+        .await
+        .with_context(|| {
+            format!(
+                "failed to create Codex home directory at {}",
+                codex_home.display()
+            )
+        })?;
+
+    tokio::fs::write(&config_path, doc.to_string())
+        .await
+        .with_context(|| format!("failed to persist config.toml at {}", config_path.display()))?;
+
+    Ok(())
+}
+
 /// Apply a single dotted-path override onto a TOML value.
 fn apply_toml_override(root: &mut TomlValue, path: &str, value: TomlValue) {
     use toml::value::Table;
@@ -689,6 +789,26 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Lifecycle hooks run around tool calls, patches, prompts and sessions.
+    #[serde(default)]
+    pub hooks: Option<Hooks>,
+
+    /// Limits on turns, tokens, tool calls and time for each task.
+    #[serde(default)]
+    pub budget: Option<TaskBudget>,
+
+    /// Record model responses to a cassette, or replay them from one.
+    #[serde(default)]
+    pub cassette: Option<CassetteConfig>,
+
+    /// Per-model and per-provider prices for `codex usage`.
+    #[serde(default)]
+    pub pricing: Option<Pricing>,
+
+    /// Per-role models and a fallback model.
+    #[serde(default)]
+    pub models: Option<ModelRoutes>,
+
     /// Optional URI-based file opener. If set, citations to files in the model
     /// output will be hyperlinked using the specified URI scheme.
     pub file_opener: Option<UriBasedFileOpener>,
@@ -775,6 +895,17 @@ pub struct ToolsToml {
     /// Enable the `view_image` tool that lets the agent attach local images.
     #[serde(default)]
     pub view_image: Option<bool>,
+
+    /// Let `apply_patch` fall back to the most similar location when a hunk
+    /// does not match the file, as long as the similarity is at least this
+    /// value (0.0-1.0).
+    #[serde(default)]
+    pub apply_patch_fuzzy_threshold: Option<f64>,
+
+    /// Enable the `spawn_agent` tool that lets the agent delegate a scoped
+    /// task to a child conversation.
+    #[serde(default)]
+    pub spawn_agent: Option<bool>,
 }
 
 impl From<ToolsToml> for Tools {
@@ -947,6 +1078,32 @@ impl Config {
             })?
             .clone();
 
+        let model_routes = config_profile.models.or(cfg.models).unwrap_or_default();
+        let routed_providers = [
+            &model_routes.planning,
+            &model_routes.editing,
+            &model_routes.compaction,
+            &model_routes.review,
+        ]
+        .into_iter()
+        .flatten()
+        .chain(&model_routes.failover)
+        .filter_map(ModelRoute::provider)
+        .chain(
+            model_routes
+                .fallback
+                .as_ref()
+                .and_then(|fallback| fallback.provider.as_deref()),
+        );
+        for provider_id in routed_providers {
+            if !model_providers.contains_key(provider_id) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("Model provider `{provider_id}` in `models` not found"),
+                ));
+            }
+        }
+
         let shell_environment_policy = cfg.shell_environment_policy.into();
 
         let resolved_cwd = {
@@ -978,6 +1135,27 @@ impl Config {
             .or(cfg.tools.as_ref().and_then(|t| t.view_image))
             .unwrap_or(true);
 
+        let include_spawn_agent_tool = cfg
+            .tools
+            .as_ref()
+            .and_then(|t| t.spawn_agent)
+            .unwrap_or(false);
+
+        let apply_patch_fuzzy_threshold = cfg
+            .tools
+            .as_ref()
+            .and_then(|t| t.apply_patch_fuzzy_threshold);
+        if let Some(threshold) = apply_patch_fuzzy_threshold
+            && !(0.0..=1.0).contains(&threshold)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "tools.apply_patch_fuzzy_threshold must be between 0.0 and 1.0, got {threshold}"
+                ),
+            ));
+        }
+
         let model = model
             .or(config_profile.model)
             .or(cfg.model)
@@ -1062,6 +1240,11 @@ impl Config {
                 .collect(),
             codex_home,
             history,
+            hooks: cfg.hooks.unwrap_or_default(),
+            budget: cfg.budget.unwrap_or_default(),
+            cassette: cfg.cassette.unwrap_or_default(),
+            pricing: cfg.pricing.unwrap_or_default(),
+            model_routes,
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
 
@@ -1094,6 +1277,9 @@ impl Config {
                 .unwrap_or(false),
             use_experimental_use_rmcp_client: cfg.experimental_use_rmcp_client.unwrap_or(false),
             include_view_image_tool,
+            apply_patch_fuzzy_threshold,
+            include_spawn_agent_tool,
+            allowed_tools: None,
             active_profile: active_profile_name,
             disable_paste_burst: cfg.disable_paste_burst.unwrap_or(false),
             tui_notifications: cfg
@@ -1101,6 +1287,21 @@ impl Config {
                 .as_ref()
                 .map(|t| t.notifications.clone())
                 .unwrap_or_default(),
+            tui_theme: cfg.tui.as_ref().and_then(|t| t.theme.clone()),
+            tui_themes: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.themes.clone())
+                .unwrap_or_default(),
+            tui_keymap: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.keymap.clone())
+                .unwrap_or_default(),
+            tui_vim_mode: cfg.tui.as_ref().is_some_and(|t| t.vim_mode),
+            tui_vim_clipboard: cfg.tui.as_ref().is_some_and(|t| t.vim_clipboard),
+            tui_diff_view: cfg.tui.as_ref().map(|t| t.diff_view).unwrap_or_default(),
+            tui_file_snapshots: cfg.tui.as_ref().is_some_and(|t| t.file_snapshots),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
@@ -1631,6 +1832,68 @@ model = "gpt-4.1"
         Ok(())
     }
 
+    #[test]
+    fn tui_keymap_accepts_one_or_many_chords() {
+        let toml = r#"
+            submit = "ctrl+enter"
+            newline = ["enter", "shift+enter"]
+        "#;
+        let keymap: TuiKeymap = toml::from_str(toml).expect("deserialize keymap");
+        assert_eq!(
+            keymap.submit.map(|chords| chords.chords().join(",")),
+            Some("ctrl+enter".to_string())
+        );
+        assert_eq!(
+            keymap.newline.map(|chords| chords.chords().join(",")),
+            Some("enter,shift+enter".to_string())
+        );
+
+        let unknown = toml::from_str::<TuiKeymap>("explode = \"ctrl+x\"");
+        assert!(unknown.is_err(), "unknown actions should be rejected");
+    }
+
+    #[tokio::test]
+    async fn persist_tui_theme_keeps_other_tui_settings() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let config_path = codex_home.path().join(CONFIG_TOML_FILE);
+
+        tokio::fs::write(
+            &config_path,
+            r#"
+model = "gpt-5-codex"
+
+[tui]
+notifications = true
+theme = "dark"
+
+[tui.themes.solar]
+base = "light"
+accent = "magenta"
+"#,
+        )
+        .await?;
+
+        persist_tui_theme(codex_home.path(), "solar").await?;
+
+        let serialized = tokio::fs::read_to_string(config_path).await?;
+        let parsed: ConfigToml = toml::from_str(&serialized)?;
+        let tui = parsed.tui.expect("tui table should be kept");
+
+        assert_eq!(parsed.model.as_deref(), Some("gpt-5-codex"));
+        assert_eq!(tui.theme.as_deref(), Some("solar"));
+        assert_eq!(tui.notifications, Notifications::Enabled(true));
+        assert_eq!(
+            tui.themes.get("solar"),
+            Some(&ThemePalette {
+                base: Some("light".to_string()),
+                accent: Some("magenta".to_string()),
+                ..Default::default()
+            })
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn persist_model_selection_updates_profile() -> anyhow::Result<()> {
         let codex_home = TempDir::new()?;
@@ -1870,6 +2133,11 @@ model_verbosity = "high"
                 project_doc_fallback_filenames: Vec::new(),
                 codex_home: fixture.codex_home(),
                 history: History::default(),
+                hooks: Hooks::default(),
+                budget: TaskBudget::default(),
+                cassette: CassetteConfig::default(),
+                pricing: Pricing::default(),
+                model_routes: ModelRoutes::default(),
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
                 hide_agent_reasoning: false,
@@ -1886,9 +2154,19 @@ model_verbosity = "high"
                 use_experimental_unified_exec_tool: false,
                 use_experimental_use_rmcp_client: false,
                 include_view_image_tool: true,
+                apply_patch_fuzzy_threshold: None,
+                include_spawn_agent_tool: false,
+                allowed_tools: None,
                 active_profile: Some("o3".to_string()),
                 disable_paste_burst: false,
                 tui_notifications: Default::default(),
+                tui_theme: None,
+                tui_themes: HashMap::new(),
+                tui_keymap: TuiKeymap::default(),
+                tui_vim_mode: false,
+                tui_vim_clipboard: false,
+                tui_diff_view: DiffView::Unified,
+                tui_file_snapshots: false,
                 otel: OtelConfig::default(),
             },
             o3_profile_config
@@ -1931,6 +2209,11 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            hooks: Hooks::default(),
+            budget: TaskBudget::default(),
+            cassette: CassetteConfig::default(),
+            pricing: Pricing::default(),
+            model_routes: ModelRoutes::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -1947,9 +2230,19 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            apply_patch_fuzzy_threshold: None,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
             active_profile: Some("gpt3".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_theme: None,
+            tui_themes: HashMap::new(),
+            tui_keymap: TuiKeymap::default(),
+            tui_vim_mode: false,
+            tui_vim_clipboard: false,
+            tui_diff_view: DiffView::Unified,
+            tui_file_snapshots: false,
             otel: OtelConfig::default(),
         };
 
@@ -2007,6 +2300,11 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            hooks: Hooks::default(),
+            budget: TaskBudget::default(),
+            cassette: CassetteConfig::default(),
+            pricing: Pricing::default(),
+            model_routes: ModelRoutes::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2023,9 +2321,19 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            apply_patch_fuzzy_threshold: None,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
             active_profile: Some("zdr".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_theme: None,
+            tui_themes: HashMap::new(),
+            tui_keymap: TuiKeymap::default(),
+            tui_vim_mode: false,
+            tui_vim_clipboard: false,
+            tui_diff_view: DiffView::Unified,
+            tui_file_snapshots: false,
             otel: OtelConfig::default(),
         };
 
@@ -2069,6 +2377,11 @@ model_verbosity = "high"
             project_doc_fallback_filenames: Vec::new(),
             codex_home: fixture.codex_home(),
             history: History::default(),
+            hooks: Hooks::default(),
+            budget: TaskBudget::default(),
+            cassette: CassetteConfig::default(),
+            pricing: Pricing::default(),
+            model_routes: ModelRoutes::default(),
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
             hide_agent_reasoning: false,
@@ -2085,9 +2398,19 @@ model_verbosity = "high"
             use_experimental_unified_exec_tool: false,
             use_experimental_use_rmcp_client: false,
             include_view_image_tool: true,
+            apply_patch_fuzzy_threshold: None,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
             active_profile: Some("gpt5".to_string()),
             disable_paste_burst: false,
             tui_notifications: Default::default(),
+            tui_theme: None,
+            tui_themes: HashMap::new(),
+            tui_keymap: TuiKeymap::default(),
+            tui_vim_mode: false,
+            tui_vim_clipboard: false,
+            tui_diff_view: DiffView::Unified,
+            tui_file_snapshots: false,
             otel: OtelConfig::default(),
         };
 
@@ -2096,6 +2419,61 @@ model_verbosity = "high"
         Ok(())
     }
 
+    #[test]
+    fn model_routes_come_from_the_profile_and_need_known_providers() -> anyhow::Result<()> {
+        let codex_home = TempDir::new()?;
+        let cfg: ConfigToml = toml::from_str(
+            r#"
+[models]
+planning = "gpt-5"
+editing = { model = "gpt-oss:20b", provider = "oss" }
+
+[profiles.local.models]
+editing = { model = "gpt-oss:20b", provider = "oss" }
+fallback = { model = "gpt-5", provider = "openai" }
+"#,
+        )?;
+
+        let config = Config::load_from_base_config_with_overrides(
+            cfg.clone(),
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(
+            config.model_routes.planning,
+            Some(ModelRoute::Model("gpt-5".to_string()))
+        );
+        assert_eq!(config.model_routes.fallback, None);
+
+        let local = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides {
+                config_profile: Some("local".to_string()),
+                ..Default::default()
+            },
+            codex_home.path().to_path_buf(),
+        )?;
+        assert_eq!(local.model_routes.planning, None);
+        let fallback = local.model_routes.fallback.expect("fallback route");
+        assert_eq!(fallback.after_invalid_tool_calls, 3);
+
+        let unknown: ConfigToml = toml::from_str(
+            r#"
+[models]
+editing = { model = "llama3", provider = "nowhere" }
+"#,
+        )?;
+        let err = Config::load_from_base_config_with_overrides(
+            unknown,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect_err("unknown provider");
+        assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+
+        Ok(())
+    }
+
     #[test]
     fn test_set_project_trusted_writes_explicit_tables() -> anyhow::Result<()> {
         let project_dir = Path::new("/some/path");
@@ -221,6 +221,218 @@ pub enum HistoryPersistence {
     None,
 }
 
+/// Commands run at points of the agent loop, configured under `[hooks]`.
+/// Each hook receives a JSON description of the event on stdin and can block
+/// or adjust the action through its exit code and stdout.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Hooks {
+    /// Before a tool call runs. May block the call or rewrite its input.
+    #[serde(default)]
+    pub pre_tool_use: Vec<HookCommand>,
+
+    /// After a tool call returns. Output is appended to the tool result.
+    #[serde(default)]
+    pub post_tool_use: Vec<HookCommand>,
+
+    /// Before a patch is applied. May block the patch.
+    #[serde(default)]
+    pub pre_patch: Vec<HookCommand>,
+
+    /// After a patch is applied, e.g. to run a formatter.
+    #[serde(default)]
+    pub post_patch: Vec<HookCommand>,
+
+    /// When the user submits a prompt. May block or rewrite the prompt.
+    #[serde(default)]
+    pub user_prompt_submit: Vec<HookCommand>,
+
+    /// When a session starts or is resumed. Output is added to the context.
+    #[serde(default)]
+    pub session_start: Vec<HookCommand>,
+
+    /// When the session shuts down.
+    #[serde(default)]
+    pub session_stop: Vec<HookCommand>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct HookCommand {
+    /// Program and arguments to run.
+    pub command: Vec<String>,
+
+    /// For `pre_tool_use` and `post_tool_use`: only run for these tools.
+    /// Runs for every tool when empty.
+    #[serde(default)]
+    pub tools: Vec<String>,
+
+    /// How long the hook may run before it is killed. Defaults to 60 seconds.
+    #[serde(default, with = "option_duration_secs")]
+    pub timeout_sec: Option<Duration>,
+}
+
+/// Limits on a single task (one user turn and the model/tool loop it starts),
+/// configured under `[budget]`. A task that exceeds any of them is aborted
+/// with `TurnAbortReason::BudgetExceeded`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct TaskBudget {
+    /// Most model requests the task may make.
+    pub max_turns: Option<u32>,
+
+    /// Most tokens (input plus output, summed over requests) the task may use.
+    pub max_total_tokens: Option<u64>,
+
+    /// Most tool calls the task may make.
+    pub max_tool_calls: Option<u32>,
+
+    /// Wall-clock time the task may run for.
+    #[serde(default, with = "option_duration_secs")]
+    pub timeout_sec: Option<Duration>,
+}
+
+/// Recording and replaying model responses, configured under `[cassette]`.
+/// See `docs/config.md#cassette`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct CassetteConfig {
+    /// Append every model response to this file so it can be replayed later.
+    pub record: Option<PathBuf>,
+
+    /// Cassette or rollout file served by the `replay` model provider.
+    pub replay: Option<PathBuf>,
+
+    /// How the `replay` provider picks the recorded response for a request.
+    #[serde(default, rename = "match")]
+    pub match_by: CassetteMatch,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CassetteMatch {
+    /// The n-th request of a conversation gets the n-th recorded response.
+    #[default]
+    Turn,
+    /// A request gets the response recorded for identical request input.
+    Hash,
+}
+
+/// Prices used by `codex usage` to estimate cost, configured under
+/// `[pricing]`. A model entry takes precedence over its provider's entry.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Pricing {
+    /// Keyed by model slug, e.g. `gpt-5`.
+    #[serde(default)]
+    pub models: HashMap<String, Price>,
+
+    /// Keyed by model provider id, e.g. `oss`.
+    #[serde(default)]
+    pub providers: HashMap<String, Price>,
+}
+
+impl Pricing {
+    pub fn price_for(&self, provider: &str, model: &str) -> Option<&Price> {
+        self.models
+            .get(model)
+            .or_else(|| self.providers.get(provider))
+    }
+}
+
+/// Token prices are per million tokens. `per_hour` charges for the time spent
+/// waiting on the model, which suits local models billed by GPU/CPU time.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Price {
+    pub input: Option<f64>,
+
+    /// Defaults to `input` when unset.
+    pub cached_input: Option<f64>,
+
+    pub output: Option<f64>,
+
+    pub per_hour: Option<f64>,
+}
+
+/// Models to use for particular kinds of requests, configured under
+/// `[models]` or `[profiles.<name>.models]`. A role without a route uses the
+/// session's `model`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ModelRoutes {
+    /// The first request of each turn, which answers the user's input.
+    pub planning: Option<ModelRoute>,
+
+    /// Follow-up requests that continue after tool calls.
+    pub editing: Option<ModelRoute>,
+
+    /// Summarizing the conversation when it is compacted.
+    pub compaction: Option<ModelRoute>,
+
+    /// `/review`. Takes precedence over `review_model`.
+    pub review: Option<ModelRoute>,
+
+    /// Model to switch to when the routed model keeps making tool calls that
+    /// cannot be run.
+    pub fallback: Option<ModelFallback>,
+
+    /// Backups, in order, for when a request fails because its provider is
+    /// unreachable, keeps returning 5xx or stops streaming.
+    #[serde(default)]
+    pub failover: Vec<ModelRoute>,
+}
+
+/// A model slug, optionally served by a provider other than the session's,
+/// e.g. `"gpt-5"` or `{ model = "gpt-oss:20b", provider = "oss" }`.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum ModelRoute {
+    Model(String),
+    WithProvider {
+        model: String,
+        provider: Option<String>,
+    },
+}
+
+impl ModelRoute {
+    pub fn model(&self) -> &str {
+        match self {
+            ModelRoute::Model(model) | ModelRoute::WithProvider { model, .. } => model,
+        }
+    }
+
+    /// Key into `model_providers`, or `None` for the session's provider.
+    pub fn provider(&self) -> Option<&str> {
+        match self {
+            ModelRoute::Model(_) => None,
+            ModelRoute::WithProvider { provider, .. } => provider.as_deref(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ModelFallback {
+    pub model: String,
+
+    /// Key into `model_providers`, or `None` for the session's provider.
+    pub provider: Option<String>,
+
+    /// Responses in a row whose tool calls all named an unknown tool or had
+    /// arguments that did not parse before switching for the rest of the
+    /// turn.
+    #[serde(default = "default_invalid_tool_calls_before_fallback")]
+    pub after_invalid_tool_calls: u32,
+}
+
+impl ModelFallback {
+    pub fn route(&self) -> ModelRoute {
+        ModelRoute::WithProvider {
+            model: self.model.clone(),
+            provider: self.provider.clone(),
+        }
+    }
+}
+
+fn default_invalid_tool_calls_before_fallback() -> u32 {
+    3
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Deserialize, Debug, Clone, PartialEq)]
@@ -299,6 +511,103 @@ pub struct Tui {
     /// Defaults to `false`.
     #[serde(default)]
     pub notifications: Notifications,
+
+    /// Color theme: one of the built-in themes (`dark`, `light`,
+    /// `high-contrast`, `deuteranopia`) or a name defined under `[tui.themes]`.
+    #[serde(default)]
+    pub theme: Option<String>,
+
+    /// User-defined color themes, keyed by name.
+    #[serde(default)]
+    pub themes: HashMap<String, ThemePalette>,
+
+    /// Key bindings for TUI actions.
+    #[serde(default)]
+    pub keymap: TuiKeymap,
+
+    /// Enable vi-style modal editing in the composer. Defaults to `false`.
+    #[serde(default)]
+    pub vim_mode: bool,
+
+    /// Share yanks and puts in vim mode with the system clipboard, like vim's
+    /// `clipboard=unnamed`. Defaults to `false`.
+    #[serde(default)]
+    pub vim_clipboard: bool,
+
+    /// Layout for diffs in patch approvals and `/diff`. Defaults to `unified`.
+    #[serde(default)]
+    pub diff_view: DiffView,
+
+    /// Snapshot directories outside git by copying their files before each
+    /// turn, so backtracking can rewind them. Defaults to `false`.
+    #[serde(default)]
+    pub file_snapshots: bool,
+}
+
+/// How the TUI lays out diffs.
+#[derive(Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum DiffView {
+    /// One column with `-`/`+` lines.
+    #[default]
+    Unified,
+    /// Old and new side by side, falling back to unified on narrow terminals.
+    Split,
+}
+
+/// Key chords for each rebindable TUI action, e.g. `submit = "ctrl+enter"`.
+/// Unset actions keep their default bindings.
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TuiKeymap {
+    pub submit: Option<KeyChords>,
+    pub newline: Option<KeyChords>,
+    pub interrupt: Option<KeyChords>,
+    pub open_transcript: Option<KeyChords>,
+    pub backtrack: Option<KeyChords>,
+    pub history_prev: Option<KeyChords>,
+    pub history_next: Option<KeyChords>,
+    pub paste_image: Option<KeyChords>,
+    pub external_editor: Option<KeyChords>,
+}
+
+/// One key chord (`"ctrl+j"`) or several (`["ctrl+j", "shift+enter"]`).
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(untagged)]
+pub enum KeyChords {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyChords {
+    pub fn chords(&self) -> Vec<&str> {
+        match self {
+            KeyChords::One(chord) => vec![chord.as_str()],
+            KeyChords::Many(chords) => chords.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// A user-defined TUI color theme. Every color is optional and falls back to
+/// the `base` theme. Colors are ANSI names (`cyan`, `light-red`, ...).
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct ThemePalette {
+    /// Built-in theme to start from. Defaults to `dark`.
+    pub base: Option<String>,
+    /// Selections, hints and other interactive elements.
+    pub accent: Option<String>,
+    /// Successful commands and other positive outcomes.
+    pub success: Option<String>,
+    /// Errors and failed commands.
+    pub error: Option<String>,
+    /// Codex's own labels, keywords in code blocks.
+    pub brand: Option<String>,
+    /// Added lines in diffs.
+    pub diff_added: Option<String>,
+    /// Removed lines in diffs.
+    pub diff_removed: Option<String>,
+    /// Bold colored text and use stronger backgrounds.
+    pub high_contrast: Option<bool>,
 }
 
 #[derive(Deserialize, Debug, Clone, PartialEq, Default)]
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::env;
 
 use async_trait::async_trait;
+use codex_apply_patch::APPLY_PATCH_FUZZY_THRESHOLD_ENV_VAR;
 
 use crate::CODEX_APPLY_PATCH_ARG1;
 use crate::apply_patch::ApplyPatchExec;
@@ -78,13 +79,20 @@ impl ExecutionBackend for ApplyPatchBackend {
                     })?;
 
                 let patch = exec.action.patch.clone();
+                // Run apply_patch with a minimal environment for determinism and to
+                // avoid leaking host environment variables into the patch process.
+                let mut env = HashMap::new();
+                if let Some(threshold) = exec.action.options.fuzzy_threshold {
+                    env.insert(
+                        APPLY_PATCH_FUZZY_THRESHOLD_ENV_VAR.to_string(),
+                        threshold.to_string(),
+                    );
+                }
                 Ok(ExecParams {
                     command: vec![path_to_codex, CODEX_APPLY_PATCH_ARG1.to_string(), patch],
                     cwd: exec.action.cwd.clone(),
                     timeout_ms: params.timeout_ms,
-                    // Run apply_patch with a minimal environment for determinism and to
-                    // avoid leaking host environment variables into the patch process.
-                    env: HashMap::new(),
+                    env,
                     with_escalated_permissions: params.with_escalated_permissions,
                     justification: params.justification,
                 })
@@ -206,6 +206,7 @@ mod tests {
         let exec = ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: true,
+            review_feedback: None,
         };
         let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
         let request = ExecutionRequest {
@@ -249,6 +250,7 @@ mod tests {
         let exec = ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: false,
+            review_feedback: None,
         };
         let cfg = ExecutorConfig::new(SandboxPolicy::DangerFullAccess, std::env::temp_dir(), None);
         let request = ExecutionRequest {
@@ -293,6 +295,7 @@ mod tests {
         let exec = ApplyPatchExec {
             action,
             user_explicitly_approved_this_action: false,
+            review_feedback: None,
         };
         let cfg = ExecutorConfig::new(SandboxPolicy::ReadOnly, std::env::temp_dir(), None);
         let request = ExecutionRequest {
@@ -143,24 +143,49 @@ pub async fn recent_commits(cwd: &Path, limit: usize) -> Vec<CommitLogEntry> {
     }
 
     let text = String::from_utf8_lossy(&log_out.stdout);
-    let mut entries: Vec<CommitLogEntry> = Vec::new();
-    for line in text.lines() {
-        let mut parts = line.split('\u{001f}');
-        let sha = parts.next().unwrap_or("").trim();
-        let ts_s = parts.next().unwrap_or("").trim();
-        let subject = parts.next().unwrap_or("").trim();
-        if sha.is_empty() || ts_s.is_empty() {
-            continue;
-        }
-        let timestamp = ts_s.parse::<i64>().unwrap_or(0);
-        entries.push(CommitLogEntry {
-            sha: sha.to_string(),
-            timestamp,
-            subject: subject.to_string(),
-        });
+    text.lines().filter_map(parse_commit_log_line).collect()
+}
+
+/// Resolve `rev` (a SHA, branch, tag or expression like `HEAD~1`) to the
+/// commit it names. Returns `None` if it does not name a commit or on
+/// error/timeout.
+pub async fn resolve_commit(cwd: &Path, rev: &str) -> Option<CommitLogEntry> {
+    let fmt = "%H%x1f%ct%x1f%s";
+    let out = run_git_command_with_timeout(
+        &[
+            "log",
+            "-n",
+            "1",
+            &format!("--pretty=format:{fmt}"),
+            rev,
+            "--",
+        ],
+        cwd,
+    )
+    .await?;
+    if !out.status.success() {
+        return None;
     }
+    String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .next()
+        .and_then(parse_commit_log_line)
+}
 
-    entries
+/// Parse one `<sha> <US> <commit_time> <US> <subject>` line of `git log`.
+fn parse_commit_log_line(line: &str) -> Option<CommitLogEntry> {
+    let mut parts = line.split('\u{001f}');
+    let sha = parts.next().unwrap_or("").trim();
+    let ts_s = parts.next().unwrap_or("").trim();
+    let subject = parts.next().unwrap_or("").trim();
+    if sha.is_empty() || ts_s.is_empty() {
+        return None;
+    }
+    Some(CommitLogEntry {
+        sha: sha.to_string(),
+        timestamp: ts_s.parse::<i64>().unwrap_or(0),
+        subject: subject.to_string(),
+    })
 }
 
 /// Returns the closest git sha to HEAD that is on a remote as well as the diff to that sha.
@@ -727,6 +752,22 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_resolve_commit_names_head_and_rejects_unknown_revs() {
+        skip_if_sandbox!();
+
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+
+        let head = resolve_commit(&repo_path, "HEAD")
+            .await
+            .expect("HEAD should resolve");
+        assert_eq!(head.subject, "Initial commit");
+        assert_eq!(head.sha.len(), 40);
+
+        assert!(resolve_commit(&repo_path, "no-such-branch").await.is_none());
+    }
+
     async fn create_test_git_repo_with_remote(temp_dir: &TempDir) -> (PathBuf, String) {
         let repo_path = create_test_git_repo(temp_dir).await;
         let remote_path = temp_dir.path().join("remote.git");
@@ -0,0 +1,449 @@
+//! Lifecycle hooks configured under `[hooks]`.
+//!
+//! Each hook is a command that receives a JSON description of the event on
+//! stdin. Exit code 0 lets the action proceed; stdout may then carry a JSON
+//! [`HookResponse`] or plain text that is fed back to the model. Exit code 2
+//! blocks the action, with stderr as the reason given to the model. Any other
+//! failure is reported to the user and otherwise ignored.
+
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_protocol::ConversationId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::config_types::HookCommand;
+use crate::config_types::Hooks;
+
+/// Time a hook may run when `timeout_sec` is not set.
+const DEFAULT_HOOK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Exit code a hook uses to block the action.
+const BLOCK_EXIT_CODE: i32 = 2;
+
+#[derive(Debug, Default)]
+pub(crate) struct HookRunner {
+    hooks: Hooks,
+}
+
+/// Event passed to hooks, serialized as the `event` field of the payload.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum HookEvent {
+    PreToolUse {
+        tool_name: String,
+        call_id: String,
+        input: Value,
+    },
+    PostToolUse {
+        tool_name: String,
+        call_id: String,
+        input: Value,
+        output: String,
+        success: bool,
+    },
+    PrePatch {
+        call_id: String,
+        patch: String,
+        files: Vec<PathBuf>,
+    },
+    PostPatch {
+        call_id: String,
+        patch: String,
+        files: Vec<PathBuf>,
+        output: String,
+        success: bool,
+    },
+    UserPromptSubmit {
+        prompt: String,
+    },
+    SessionStart {
+        source: SessionStartSource,
+    },
+    SessionStop,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SessionStartSource {
+    Startup,
+    Resume,
+}
+
+#[derive(Serialize)]
+struct HookPayload<'a> {
+    session_id: ConversationId,
+    cwd: &'a Path,
+    #[serde(flatten)]
+    event: &'a HookEvent,
+}
+
+/// JSON a hook may print on stdout when it exits with code 0.
+#[derive(Debug, Default, Deserialize)]
+struct HookResponse {
+    #[serde(default)]
+    decision: Option<HookDecision>,
+    /// Why the action was blocked; shown to the model.
+    #[serde(default)]
+    reason: Option<String>,
+    /// Replacement input for `pre_tool_use` and `user_prompt_submit`.
+    #[serde(default)]
+    updated_input: Option<Value>,
+    /// Text fed back to the model.
+    #[serde(default)]
+    context: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum HookDecision {
+    Allow,
+    Block,
+}
+
+/// Combined result of the hooks run for one event.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct HookOutcome {
+    /// Set when a hook blocked the action.
+    pub(crate) blocked: Option<String>,
+    /// Input the action should use instead of the original.
+    pub(crate) updated_input: Option<Value>,
+    /// Text the hooks asked to feed back to the model.
+    pub(crate) context: Vec<String>,
+    /// Hooks that could not run or failed; reported to the user only.
+    pub(crate) errors: Vec<String>,
+}
+
+impl HookOutcome {
+    /// Hook output to append to what the model sees, if any.
+    pub(crate) fn context_text(&self) -> Option<String> {
+        if self.context.is_empty() {
+            None
+        } else {
+            Some(self.context.join("\n"))
+        }
+    }
+}
+
+impl HookEvent {
+    fn commands<'a>(&self, hooks: &'a Hooks) -> &'a [HookCommand] {
+        match self {
+            HookEvent::PreToolUse { .. } => &hooks.pre_tool_use,
+            HookEvent::PostToolUse { .. } => &hooks.post_tool_use,
+            HookEvent::PrePatch { .. } => &hooks.pre_patch,
+            HookEvent::PostPatch { .. } => &hooks.post_patch,
+            HookEvent::UserPromptSubmit { .. } => &hooks.user_prompt_submit,
+            HookEvent::SessionStart { .. } => &hooks.session_start,
+            HookEvent::SessionStop => &hooks.session_stop,
+        }
+    }
+
+    fn applies_to(&self, hook: &HookCommand) -> bool {
+        match self {
+            HookEvent::PreToolUse { tool_name, .. } | HookEvent::PostToolUse { tool_name, .. } => {
+                hook.tools.is_empty() || hook.tools.iter().any(|tool| tool == tool_name)
+            }
+            _ => true,
+        }
+    }
+
+    /// Let later hooks see the input written by an earlier one.
+    fn replace_input(&mut self, updated: &Value) {
+        match self {
+            HookEvent::PreToolUse { input, .. } => *input = updated.clone(),
+            HookEvent::UserPromptSubmit { prompt } => {
+                if let Value::String(text) = updated {
+                    *prompt = text.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl HookRunner {
+    pub(crate) fn new(hooks: Hooks) -> Self {
+        Self { hooks }
+    }
+
+    /// Whether any hook is configured for the event `event` stands for.
+    pub(crate) fn has_hooks(&self, event: &HookEvent) -> bool {
+        event
+            .commands(&self.hooks)
+            .iter()
+            .any(|hook| event.applies_to(hook))
+    }
+
+    /// Run the hooks for `event` one after another, stopping at the first one
+    /// that blocks.
+    pub(crate) async fn run(
+        &self,
+        session_id: ConversationId,
+        cwd: &Path,
+        mut event: HookEvent,
+    ) -> HookOutcome {
+        let mut outcome = HookOutcome::default();
+        for hook in event.commands(&self.hooks) {
+            if !event.applies_to(hook) {
+                continue;
+            }
+            let payload = HookPayload {
+                session_id,
+                cwd,
+                event: &event,
+            };
+            let Ok(json) = serde_json::to_string(&payload) else {
+                warn!("failed to serialise hook payload");
+                continue;
+            };
+            match run_hook(hook, cwd, json).await {
+                Ok(HookResult::Block(reason)) => {
+                    outcome.blocked = Some(reason);
+                    return outcome;
+                }
+                Ok(HookResult::Proceed(response)) => {
+                    if let Some(context) = response.context.filter(|c| !c.trim().is_empty()) {
+                        outcome.context.push(context);
+                    }
+                    if response.decision == Some(HookDecision::Block) {
+                        outcome.blocked = Some(
+                            response
+                                .reason
+                                .unwrap_or_else(|| "blocked by hook".to_string()),
+                        );
+                        return outcome;
+                    }
+                    if let Some(updated) = response.updated_input {
+                        event.replace_input(&updated);
+                        outcome.updated_input = Some(updated);
+                    }
+                }
+                Err(err) => {
+                    warn!("{err}");
+                    outcome.errors.push(err);
+                }
+            }
+        }
+        outcome
+    }
+}
+
+/// Wrap hook output so the model can tell it apart from the action's own
+/// output.
+pub(crate) fn format_hook_context(context: &str) -> String {
+    format!("<hook_output>\n{context}\n</hook_output>")
+}
+
+enum HookResult {
+    Proceed(HookResponse),
+    Block(String),
+}
+
+async fn run_hook(hook: &HookCommand, cwd: &Path, input: String) -> Result<HookResult, String> {
+    let Some((program, args)) = hook.command.split_first() else {
+        return Err("hook has an empty command".to_string());
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(cwd)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("failed to start hook `{program}`: {e}"))?;
+
+    // Write the input while collecting output, both under the timeout, so a
+    // hook that never reads stdin cannot stall us on a full pipe.
+    let stdin = child.stdin.take();
+    let write_input = async move {
+        if let Some(mut stdin) = stdin {
+            // A hook may exit without reading its input; that is not an error.
+            let _ = stdin.write_all(input.as_bytes()).await;
+        }
+    };
+    let run = async {
+        let ((), output) = tokio::join!(write_input, child.wait_with_output());
+        output
+    };
+
+    let timeout = hook.timeout_sec.unwrap_or(DEFAULT_HOOK_TIMEOUT);
+    let output = match tokio::time::timeout(timeout, run).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(e)) => return Err(format!("hook `{program}` failed: {e}")),
+        Err(_) => {
+            return Err(format!(
+                "hook `{program}` timed out after {}s",
+                timeout.as_secs_f32()
+            ));
+        }
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    match output.status.code() {
+        Some(0) => Ok(HookResult::Proceed(parse_response(stdout))),
+        Some(BLOCK_EXIT_CODE) => {
+            let reason = [stderr, stdout]
+                .into_iter()
+                .find(|text| !text.is_empty())
+                .unwrap_or_else(|| format!("blocked by hook `{program}`"));
+            Ok(HookResult::Block(reason))
+        }
+        _ if stderr.is_empty() => Err(format!("hook `{program}` exited with {}", output.status)),
+        _ => Err(format!(
+            "hook `{program}` exited with {}: {stderr}",
+            output.status
+        )),
+    }
+}
+
+/// Treat stdout as a [`HookResponse`] when it is a JSON object, otherwise as
+/// context for the model.
+fn parse_response(stdout: String) -> HookResponse {
+    if stdout.starts_with('{')
+        && let Ok(response) = serde_json::from_str::<HookResponse>(&stdout)
+    {
+        return response;
+    }
+    HookResponse {
+        context: Some(stdout),
+        ..HookResponse::default()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn sh(script: &str) -> HookCommand {
+        HookCommand {
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            tools: Vec::new(),
+            timeout_sec: None,
+        }
+    }
+
+    fn pre_tool_use(tool_name: &str) -> HookEvent {
+        HookEvent::PreToolUse {
+            tool_name: tool_name.to_string(),
+            call_id: "call-1".to_string(),
+            input: json!({"command": ["ls"]}),
+        }
+    }
+
+    #[tokio::test]
+    async fn exit_code_two_blocks_with_stderr_as_reason() {
+        let cwd = tempfile::tempdir().unwrap();
+        let runner = HookRunner::new(Hooks {
+            pre_tool_use: vec![
+                sh("echo 'edits to migrations/ are not allowed' >&2; exit 2"),
+                sh("echo never runs"),
+            ],
+            ..Hooks::default()
+        });
+
+        let outcome = runner
+            .run(ConversationId::default(), cwd.path(), pre_tool_use("shell"))
+            .await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome {
+                blocked: Some("edits to migrations/ are not allowed".to_string()),
+                ..HookOutcome::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn hooks_read_the_event_and_can_rewrite_input() {
+        let cwd = tempfile::tempdir().unwrap();
+        let runner = HookRunner::new(Hooks {
+            pre_tool_use: vec![
+                // Echo the event name back as context.
+                sh(r#"sed -n 's/.*"event":"\([a-z_]*\)".*/saw \1/p'"#),
+                sh(r#"echo '{"updated_input": {"command": ["ls", "-a"]}}'"#),
+            ],
+            ..Hooks::default()
+        });
+
+        let outcome = runner
+            .run(ConversationId::default(), cwd.path(), pre_tool_use("shell"))
+            .await;
+
+        assert_eq!(
+            outcome,
+            HookOutcome {
+                updated_input: Some(json!({"command": ["ls", "-a"]})),
+                context: vec!["saw pre_tool_use".to_string()],
+                ..HookOutcome::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn tool_filter_and_failures() {
+        let cwd = tempfile::tempdir().unwrap();
+        let mut only_patches = sh("exit 2");
+        only_patches.tools = vec!["apply_patch".to_string()];
+        let mut slow = sh("sleep 5");
+        slow.timeout_sec = Some(Duration::from_millis(100));
+        let runner = HookRunner::new(Hooks {
+            pre_tool_use: vec![only_patches, sh("exit 1"), slow],
+            ..Hooks::default()
+        });
+
+        let event = pre_tool_use("shell");
+        assert!(runner.has_hooks(&event));
+        let outcome = runner
+            .run(ConversationId::default(), cwd.path(), event)
+            .await;
+
+        assert_eq!(outcome.blocked, None);
+        assert_eq!(
+            outcome.errors,
+            vec![
+                "hook `sh` exited with exit status: 1".to_string(),
+                "hook `sh` timed out after 0.1s".to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn timeout_covers_writing_the_input() {
+        let cwd = tempfile::tempdir().unwrap();
+        // Never reads stdin, so a large payload fills the pipe.
+        let mut stuck = sh("sleep 5");
+        stuck.timeout_sec = Some(Duration::from_millis(100));
+        let runner = HookRunner::new(Hooks {
+            user_prompt_submit: vec![stuck],
+            ..Hooks::default()
+        });
+        let event = HookEvent::UserPromptSubmit {
+            prompt: "x".repeat(1024 * 1024),
+        };
+
+        let outcome = tokio::time::timeout(
+            Duration::from_secs(3),
+            runner.run(ConversationId::default(), cwd.path(), event),
+        )
+        .await
+        .expect("hook timeout should cover the stdin write");
+
+        assert_eq!(
+            outcome.errors,
+            vec!["hook `sh` timed out after 0.1s".to_string()]
+        );
+    }
+}
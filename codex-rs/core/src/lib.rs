@@ -8,6 +8,8 @@
 mod apply_patch;
 pub mod auth;
 pub mod bash;
+mod budget;
+mod cassette;
 mod chat_completions;
 mod client;
 mod client_common;
@@ -32,16 +34,20 @@ pub mod executor;
 mod flags;
 pub mod git_info;
 pub mod gpt_oss;
+mod hooks;
 pub mod landlock;
 mod mcp_connection_manager;
 mod mcp_tool_call;
 mod message_history;
 mod model_provider_info;
+mod model_router;
 pub mod parse_command;
 mod truncate;
 mod unified_exec;
+pub mod usage;
 mod user_instructions;
 pub use model_provider_info::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+pub use model_provider_info::BUILT_IN_REPLAY_MODEL_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
@@ -49,6 +55,7 @@ pub use model_provider_info::create_oss_provider_with_base_url;
 mod conversation_manager;
 mod event_mapping;
 pub mod review_format;
+pub mod review_prompts;
 pub use codex_protocol::protocol::InitialHistory;
 pub use conversation_manager::ConversationManager;
 pub use conversation_manager::NewConversation;
@@ -59,6 +66,7 @@ pub mod default_client;
 pub mod model_family;
 mod openai_model_info;
 mod openai_tools;
+pub mod patch_review;
 pub mod project_doc;
 mod rollout;
 pub(crate) mod safety;
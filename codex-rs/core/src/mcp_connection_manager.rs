@@ -5,12 +5,20 @@
 //! helpers to query the available tools across *all* servers and returns them
 //! in a single aggregated map using the fully-qualified tool name
 //! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
+//!
+//! Servers can be restarted or reloaded from configuration mid-session, and
+//! the tool map is refreshed whenever a server sends
+//! `notifications/tools/list_changed`.
 
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsString;
 use std::sync::Arc;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
 use std::time::Duration;
+use std::time::Instant;
 
 use anyhow::Context;
 use anyhow::Result;
@@ -24,6 +32,8 @@ use mcp_types::Tool;
 use serde_json::json;
 use sha1::Digest;
 use sha1::Sha1;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
 use tokio::task::JoinSet;
 use tracing::info;
 use tracing::warn;
@@ -45,6 +55,17 @@ const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// How often crashed stdio servers are looked for.
+pub(crate) const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Give up automatically restarting a crashed server after this many attempts.
+const MAX_RESTART_ATTEMPTS: u32 = 5;
+const RESTART_BASE_DELAY: Duration = Duration::from_secs(1);
+const RESTART_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// A restarted server that stays up this long has its backoff reset.
+const RESTART_STABLE_AFTER: Duration = Duration::from_secs(60);
+
 /// Map that holds a startup error for every MCP server that could **not** be
 /// spawned successfully.
 pub type ClientStartErrors = HashMap<String, anyhow::Error>;
@@ -91,6 +112,18 @@ struct ManagedClient {
     client: McpClientAdapter,
     startup_timeout: Duration,
     tool_timeout: Option<Duration>,
+    /// Only stdio servers are restarted automatically when they crash; remote
+    /// servers are expected to manage their own availability.
+    is_stdio: bool,
+    /// Forwards the client's `tools/list_changed` notifications to the
+    /// manager-wide [`Notify`]. Aborted when the client is dropped.
+    tool_list_watcher: JoinHandle<()>,
+}
+
+impl Drop for ManagedClient {
+    fn drop(&mut self) {
+        self.tool_list_watcher.abort();
+    }
 }
 
 #[derive(Clone)]
@@ -155,19 +188,58 @@ impl McpClientAdapter {
             McpClientAdapter::Rmcp(client) => client.call_tool(name, arguments, timeout).await,
         }
     }
+
+    async fn is_alive(&self) -> bool {
+        match self {
+            McpClientAdapter::Legacy(client) => client.is_alive(),
+            McpClientAdapter::Rmcp(client) => client.is_alive().await,
+        }
+    }
+
+    fn tool_list_changed(&self) -> Arc<Notify> {
+        match self {
+            McpClientAdapter::Legacy(client) => client.tool_list_changed(),
+            McpClientAdapter::Rmcp(client) => client.tool_list_changed(),
+        }
+    }
+}
+
+/// Result of [`McpConnectionManager::reload`].
+#[derive(Default)]
+pub(crate) struct McpReloadOutcome {
+    /// Servers that were added or whose configuration changed and that
+    /// started successfully.
+    pub started: Vec<String>,
+    /// Servers that are no longer configured and were shut down.
+    pub stopped: Vec<String>,
+    /// Servers that failed to (re)start.
+    pub errors: ClientStartErrors,
 }
 
 /// A thin wrapper around a set of running [`McpClient`] instances.
+///
+/// Clients can be replaced while a session is running (see
+/// [`McpConnectionManager::restart_server`] and
+/// [`McpConnectionManager::reload`]), so all state lives behind locks. The
+/// locks are never held across an `.await`.
 #[derive(Default)]
 pub(crate) struct McpConnectionManager {
+    use_rmcp_client: bool,
+
+    /// Server-name -> configuration used to (re)spawn the server.
+    server_configs: RwLock<HashMap<String, McpServerConfig>>,
+
     /// Server-name -> client instance.
     ///
     /// The server name originates from the keys of the `mcp_servers` map in
     /// the user configuration.
-    clients: HashMap<String, ManagedClient>,
+    clients: RwLock<HashMap<String, ManagedClient>>,
 
     /// Fully qualified tool name -> tool instance.
-    tools: HashMap<String, ToolInfo>,
+    tools: RwLock<HashMap<String, ToolInfo>>,
+
+    /// Notified whenever any server reports `notifications/tools/list_changed`.
+    tools_changed: Arc<Notify>,
 }
 
 impl McpConnectionManager {
@@ -183,12 +255,30 @@ impl McpConnectionManager {
         mcp_servers: HashMap<String, McpServerConfig>,
         use_rmcp_client: bool,
     ) -> Result<(Self, ClientStartErrors)> {
+        let manager = Self {
+            use_rmcp_client,
+            ..Self::default()
+        };
+
         // Early exit if no servers are configured.
         if mcp_servers.is_empty() {
-            return Ok((Self::default(), ClientStartErrors::default()));
+            return Ok((manager, ClientStartErrors::default()));
         }
 
-        // Launch all configured servers concurrently.
+        let (clients, errors) = manager.start_servers(mcp_servers.clone()).await;
+        *write_lock(&manager.server_configs) = mcp_servers;
+        *write_lock(&manager.clients) = clients;
+        manager.refresh_tools().await;
+
+        Ok((manager, errors))
+    }
+
+    /// Launch the given servers concurrently. Servers that fail validation or
+    /// startup are reported in the returned `ClientStartErrors`.
+    async fn start_servers(
+        &self,
+        mcp_servers: HashMap<String, McpServerConfig>,
+    ) -> (HashMap<String, ManagedClient>, ClientStartErrors) {
         let mut join_set = JoinSet::new();
         let mut errors = ClientStartErrors::new();
 
@@ -202,67 +292,19 @@ impl McpConnectionManager {
                 continue;
             }
 
-            let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
-            let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
-
+            let use_rmcp_client = self.use_rmcp_client;
+            let tools_changed = Arc::clone(&self.tools_changed);
             join_set.spawn(async move {
-                let McpServerConfig { transport, .. } = cfg;
-                let params = mcp_types::InitializeRequestParams {
-                    capabilities: ClientCapabilities {
-                        experimental: None,
-                        roots: None,
-                        sampling: None,
-                        // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
-                        // indicates this should be an empty object.
-                        elicitation: Some(json!({})),
-                    },
-                    client_info: Implementation {
-                        name: "codex-mcp-client".to_owned(),
-                        version: env!("CARGO_PKG_VERSION").to_owned(),
-                        title: Some("Codex".into()),
-                        // This field is used by Codex when it is an MCP
-                        // server: it should not be used when Codex is
-                        // an MCP client.
-                        user_agent: None,
-                    },
-                    protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
-                };
-
-                let client = match transport {
-                    McpServerTransportConfig::Stdio { command, args, env } => {
-                        let command_os: OsString = command.into();
-                        let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
-                        McpClientAdapter::new_stdio_client(
-                            use_rmcp_client,
-                            command_os,
-                            args_os,
-                            env,
-                            params,
-                            startup_timeout,
-                        )
-                        .await
-                    }
-                    McpServerTransportConfig::StreamableHttp { url, bearer_token } => {
-                        McpClientAdapter::new_streamable_http_client(
-                            server_name.clone(),
-                            url,
-                            bearer_token,
-                            params,
-                            startup_timeout,
-                        )
-                        .await
-                    }
-                }
-                .map(|c| (c, startup_timeout));
-
-                ((server_name, tool_timeout), client)
+                let client =
+                    start_server(server_name.clone(), cfg, use_rmcp_client, tools_changed).await;
+                (server_name, client)
             });
         }
 
         let mut clients: HashMap<String, ManagedClient> = HashMap::with_capacity(join_set.len());
 
         while let Some(res) = join_set.join_next().await {
-            let ((server_name, tool_timeout), client_res) = match res {
+            let (server_name, client_res) = match res {
                 Ok(result) => result,
                 Err(e) => {
                     warn!("Task panic when starting MCP server: {e:#}");
@@ -271,15 +313,8 @@ impl McpConnectionManager {
             };
 
             match client_res {
-                Ok((client, startup_timeout)) => {
-                    clients.insert(
-                        server_name,
-                        ManagedClient {
-                            client,
-                            startup_timeout,
-                            tool_timeout: Some(tool_timeout),
-                        },
-                    );
+                Ok(client) => {
+                    clients.insert(server_name, client);
                 }
                 Err(e) => {
                     errors.insert(server_name, e);
@@ -287,23 +322,13 @@ impl McpConnectionManager {
             }
         }
 
-        let all_tools = match list_all_tools(&clients).await {
-            Ok(tools) => tools,
-            Err(e) => {
-                warn!("Failed to list tools from some MCP servers: {e:#}");
-                Vec::new()
-            }
-        };
-
-        let tools = qualify_tools(all_tools);
-
-        Ok((Self { clients, tools }, errors))
+        (clients, errors)
     }
 
     /// Returns a single map that contains **all** tools. Each key is the
     /// fully-qualified name for the tool.
     pub fn list_all_tools(&self) -> HashMap<String, Tool> {
-        self.tools
+        read_lock(&self.tools)
             .iter()
             .map(|(name, tool)| (name.clone(), tool.tool.clone()))
             .collect()
@@ -316,12 +341,13 @@ impl McpConnectionManager {
         tool: &str,
         arguments: Option<serde_json::Value>,
     ) -> Result<mcp_types::CallToolResult> {
-        let managed = self
-            .clients
-            .get(server)
-            .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
-        let client = managed.client.clone();
-        let timeout = managed.tool_timeout;
+        let (client, timeout) = {
+            let clients = read_lock(&self.clients);
+            let managed = clients
+                .get(server)
+                .ok_or_else(|| anyhow!("unknown MCP server '{server}'"))?;
+            (managed.client.clone(), managed.tool_timeout)
+        };
 
         client
             .call_tool(tool.to_string(), arguments, timeout)
@@ -330,27 +356,230 @@ impl McpConnectionManager {
     }
 
     pub fn parse_tool_name(&self, tool_name: &str) -> Option<(String, String)> {
-        self.tools
+        read_lock(&self.tools)
             .get(tool_name)
             .map(|tool| (tool.server_name.clone(), tool.tool_name.clone()))
     }
+
+    /// Handle that is notified whenever any server reports that its tool list
+    /// changed. Callers should follow up with [`Self::refresh_tools`].
+    pub fn tools_changed(&self) -> Arc<Notify> {
+        Arc::clone(&self.tools_changed)
+    }
+
+    /// Re-query every running server for its tools and replace the cached
+    /// tool map.
+    pub async fn refresh_tools(&self) {
+        let snapshot: Vec<(String, McpClientAdapter, Duration)> = read_lock(&self.clients)
+            .iter()
+            .map(|(name, managed)| {
+                (
+                    name.clone(),
+                    managed.client.clone(),
+                    managed.startup_timeout,
+                )
+            })
+            .collect();
+
+        let all_tools = match list_all_tools(snapshot).await {
+            Ok(tools) => tools,
+            Err(e) => {
+                warn!("Failed to list tools from some MCP servers: {e:#}");
+                Vec::new()
+            }
+        };
+
+        *write_lock(&self.tools) = qualify_tools(all_tools);
+    }
+
+    /// Whether a client for the named server is currently running.
+    pub fn is_running(&self, server_name: &str) -> bool {
+        read_lock(&self.clients).contains_key(server_name)
+    }
+
+    /// Names of stdio servers whose process has exited since they were
+    /// started.
+    pub async fn crashed_stdio_servers(&self) -> Vec<String> {
+        let snapshot: Vec<(String, McpClientAdapter)> = read_lock(&self.clients)
+            .iter()
+            .filter(|(_, managed)| managed.is_stdio)
+            .map(|(name, managed)| (name.clone(), managed.client.clone()))
+            .collect();
+
+        let mut crashed = Vec::new();
+        for (name, client) in snapshot {
+            if !client.is_alive().await {
+                crashed.push(name);
+            }
+        }
+        crashed
+    }
+
+    /// Shut down the named server (if running) and spawn it again from its
+    /// current configuration, then refresh the tool list.
+    pub async fn restart_server(&self, server_name: &str) -> Result<()> {
+        let cfg = read_lock(&self.server_configs)
+            .get(server_name)
+            .cloned()
+            .ok_or_else(|| anyhow!("unknown MCP server '{server_name}'"))?;
+
+        // Drop the old client first so a stdio server is not running twice.
+        write_lock(&self.clients).remove(server_name);
+
+        let result = start_server(
+            server_name.to_string(),
+            cfg,
+            self.use_rmcp_client,
+            Arc::clone(&self.tools_changed),
+        )
+        .await;
+        let outcome = match result {
+            Ok(client) => {
+                write_lock(&self.clients).insert(server_name.to_string(), client);
+                Ok(())
+            }
+            Err(e) => Err(e),
+        };
+
+        self.refresh_tools().await;
+        outcome
+    }
+
+    /// Apply a new set of server configurations: servers that were removed
+    /// are shut down, servers that were added or whose configuration changed
+    /// are (re)started, and untouched servers keep running.
+    pub async fn reload(&self, mcp_servers: HashMap<String, McpServerConfig>) -> McpReloadOutcome {
+        let mut outcome = McpReloadOutcome::default();
+
+        let to_start: HashMap<String, McpServerConfig> = {
+            let configs = read_lock(&self.server_configs);
+            let clients = read_lock(&self.clients);
+            outcome.stopped = configs
+                .keys()
+                .filter(|name| !mcp_servers.contains_key(*name))
+                .cloned()
+                .collect();
+            mcp_servers
+                .iter()
+                .filter(|(name, cfg)| {
+                    configs.get(*name) != Some(*cfg) || !clients.contains_key(*name)
+                })
+                .map(|(name, cfg)| (name.clone(), cfg.clone()))
+                .collect()
+        };
+        outcome.stopped.sort();
+
+        {
+            let mut clients = write_lock(&self.clients);
+            for name in outcome.stopped.iter().chain(to_start.keys()) {
+                clients.remove(name);
+            }
+        }
+
+        let (started, errors) = self.start_servers(to_start).await;
+        outcome.started = started.keys().cloned().collect();
+        outcome.started.sort();
+        outcome.errors = errors;
+
+        write_lock(&self.clients).extend(started);
+        *write_lock(&self.server_configs) = mcp_servers;
+        self.refresh_tools().await;
+
+        outcome
+    }
+}
+
+/// Spawn and initialize a single MCP server.
+async fn start_server(
+    server_name: String,
+    cfg: McpServerConfig,
+    use_rmcp_client: bool,
+    tools_changed: Arc<Notify>,
+) -> Result<ManagedClient> {
+    let startup_timeout = cfg.startup_timeout_sec.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+    let tool_timeout = cfg.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT);
+
+    let McpServerConfig { transport, .. } = cfg;
+    let params = mcp_types::InitializeRequestParams {
+        capabilities: ClientCapabilities {
+            experimental: None,
+            roots: None,
+            sampling: None,
+            // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
+            // indicates this should be an empty object.
+            elicitation: Some(json!({})),
+        },
+        client_info: Implementation {
+            name: "codex-mcp-client".to_owned(),
+            version: env!("CARGO_PKG_VERSION").to_owned(),
+            title: Some("Codex".into()),
+            // This field is used by Codex when it is an MCP
+            // server: it should not be used when Codex is
+            // an MCP client.
+            user_agent: None,
+        },
+        protocol_version: mcp_types::MCP_SCHEMA_VERSION.to_owned(),
+    };
+
+    let is_stdio = matches!(transport, McpServerTransportConfig::Stdio { .. });
+    let client = match transport {
+        McpServerTransportConfig::Stdio { command, args, env } => {
+            let command_os: OsString = command.into();
+            let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
+            McpClientAdapter::new_stdio_client(
+                use_rmcp_client,
+                command_os,
+                args_os,
+                env,
+                params,
+                startup_timeout,
+            )
+            .await
+        }
+        McpServerTransportConfig::StreamableHttp { url, bearer_token } => {
+            McpClientAdapter::new_streamable_http_client(
+                server_name,
+                url,
+                bearer_token,
+                params,
+                startup_timeout,
+            )
+            .await
+        }
+    }?;
+
+    let client_tool_list_changed = client.tool_list_changed();
+    let tool_list_watcher = tokio::spawn(async move {
+        loop {
+            client_tool_list_changed.notified().await;
+            tools_changed.notify_one();
+        }
+    });
+
+    Ok(ManagedClient {
+        client,
+        startup_timeout,
+        tool_timeout: Some(tool_timeout),
+        is_stdio,
+        tool_list_watcher,
+    })
 }
 
 /// Query every server for its available tools and return a single map that
 /// contains **all** tools. Each key is the fully-qualified name for the tool.
-async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<ToolInfo>> {
+async fn list_all_tools(
+    clients: Vec<(String, McpClientAdapter, Duration)>,
+) -> Result<Vec<ToolInfo>> {
+    let server_count = clients.len();
     let mut join_set = JoinSet::new();
 
     // Spawn one task per server so we can query them concurrently. This
     // keeps the overall latency roughly at the slowest server instead of
     // the cumulative latency.
-    for (server_name, managed_client) in clients {
-        let server_name_cloned = server_name.clone();
-        let client_clone = managed_client.client.clone();
-        let startup_timeout = managed_client.startup_timeout;
+    for (server_name, client, startup_timeout) in clients {
         join_set.spawn(async move {
-            let res = client_clone.list_tools(None, Some(startup_timeout)).await;
-            (server_name_cloned, res)
+            let res = client.list_tools(None, Some(startup_timeout)).await;
+            (server_name, res)
         });
     }
 
@@ -384,12 +613,68 @@ async fn list_all_tools(clients: &HashMap<String, ManagedClient>) -> Result<Vec<
     info!(
         "aggregated {} tools from {} servers",
         aggregated.len(),
-        clients.len()
+        server_count
     );
 
     Ok(aggregated)
 }
 
+/// Exponential backoff bookkeeping for automatically restarting a crashed
+/// stdio server.
+#[derive(Debug, Default)]
+pub(crate) struct RestartBackoff {
+    attempts: u32,
+    next_attempt: Option<Instant>,
+    last_restart: Option<Instant>,
+}
+
+impl RestartBackoff {
+    /// Whether another restart may be attempted at `now`.
+    pub fn is_due(&self, now: Instant) -> bool {
+        !self.is_exhausted() && self.next_attempt.is_none_or(|at| now >= at)
+    }
+
+    /// Whether we have given up on restarting this server.
+    pub fn is_exhausted(&self) -> bool {
+        self.attempts >= MAX_RESTART_ATTEMPTS
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// Record a restart attempt made at `now` and schedule the next one.
+    pub fn record_attempt(&mut self, now: Instant) {
+        self.attempts += 1;
+        self.last_restart = Some(now);
+        self.next_attempt = Some(now + restart_delay(self.attempts));
+    }
+
+    /// Whether the server has stayed up long enough after its last restart
+    /// that the backoff can be forgotten.
+    pub fn is_stable(&self, now: Instant) -> bool {
+        self.last_restart
+            .is_some_and(|at| now.duration_since(at) >= RESTART_STABLE_AFTER)
+    }
+}
+
+fn restart_delay(attempts: u32) -> Duration {
+    let exponent = attempts.saturating_sub(1).min(16);
+    RESTART_BASE_DELAY
+        .saturating_mul(1 << exponent)
+        .min(RESTART_MAX_DELAY)
+}
+
+fn read_lock<T>(lock: &RwLock<T>) -> RwLockReadGuard<'_, T> {
+    lock.read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+fn write_lock<T>(lock: &RwLock<T>) -> RwLockWriteGuard<'_, T> {
+    lock.write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
 fn is_valid_mcp_server_name(server_name: &str) -> bool {
     !server_name.is_empty()
         && server_name
@@ -483,4 +768,32 @@ mod tests {
             "my_server__yet_another_e1c3987bd9c50b826cbe1687966f79f0c602d19ca"
         );
     }
+
+    #[test]
+    fn restart_delay_grows_exponentially_and_is_capped() {
+        assert_eq!(restart_delay(1), Duration::from_secs(1));
+        assert_eq!(restart_delay(2), Duration::from_secs(2));
+        assert_eq!(restart_delay(3), Duration::from_secs(4));
+        assert_eq!(restart_delay(10), RESTART_MAX_DELAY);
+    }
+
+    #[test]
+    fn restart_backoff_waits_between_attempts_and_gives_up() {
+        let start = Instant::now();
+        let mut backoff = RestartBackoff::default();
+        assert!(backoff.is_due(start));
+
+        backoff.record_attempt(start);
+        assert_eq!(backoff.attempts(), 1);
+        assert!(!backoff.is_due(start));
+        assert!(backoff.is_due(start + Duration::from_secs(1)));
+        assert!(!backoff.is_stable(start + Duration::from_secs(1)));
+        assert!(backoff.is_stable(start + RESTART_STABLE_AFTER));
+
+        for _ in 1..MAX_RESTART_ATTEMPTS {
+            backoff.record_attempt(start);
+        }
+        assert!(backoff.is_exhausted());
+        assert!(!backoff.is_due(start + RESTART_MAX_DELAY));
+    }
 }
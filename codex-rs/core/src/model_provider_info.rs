@@ -37,6 +37,10 @@ pub enum WireApi {
     /// Regular Chat Completions compatible with `/v1/chat/completions`.
     #[default]
     Chat,
+
+    /// No network at all: responses are served from the file configured as
+    /// `cassette.replay`.
+    Replay,
 }
 
 /// Serializable representation of a provider definition.
@@ -159,6 +163,8 @@ impl ModelProviderInfo {
         match self.wire_api {
             WireApi::Responses => format!("{base_url}/responses{query_string}"),
             WireApi::Chat => format!("{base_url}/chat/completions{query_string}"),
+            // Never requested; kept so logs still show where the provider points.
+            WireApi::Replay => format!("{base_url}{query_string}"),
         }
     }
 
@@ -251,13 +257,16 @@ const DEFAULT_GPT_OSS_PORT: u32 = 8000;
 
 pub const BUILT_IN_OSS_MODEL_PROVIDER_ID: &str = "oss";
 
+pub const BUILT_IN_REPLAY_MODEL_PROVIDER_ID: &str = "replay";
+
 /// Built-in default provider list.
 pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
     use ModelProviderInfo as P;
 
     // We do not want to be in the business of adjucating which third-party
     // providers are bundled with Codex CLI, so we only include the OpenAI and
-    // open source ("oss") providers by default. Users are encouraged to add to
+    // open source ("oss") providers by default, plus the offline "replay"
+    // provider used for testing. Users are encouraged to add to
     // `model_providers` in config.toml to add their own providers.
     [
         (
@@ -300,6 +309,23 @@ pub fn built_in_model_providers() -> HashMap<String, ModelProviderInfo> {
             },
         ),
         (BUILT_IN_OSS_MODEL_PROVIDER_ID, create_oss_provider()),
+        (
+            BUILT_IN_REPLAY_MODEL_PROVIDER_ID,
+            P {
+                name: "Replay".into(),
+                base_url: None,
+                env_key: None,
+                env_key_instructions: None,
+                wire_api: WireApi::Replay,
+                query_params: None,
+                http_headers: None,
+                env_http_headers: None,
+                request_max_retries: None,
+                stream_max_retries: None,
+                stream_idle_timeout_ms: None,
+                requires_openai_auth: false,
+            },
+        ),
     ]
     .into_iter()
     .map(|(k, v)| (k.to_string(), v))
@@ -0,0 +1,304 @@
+//! Picks the model for each request of a turn from `[models]`.
+//!
+//! The first request of a turn answers the user and uses the `planning`
+//! route; the requests that follow tool calls use the `editing` route. When
+//! `fallback` is set and the model keeps making tool calls that cannot be
+//! run, the rest of the turn uses the fallback model instead.
+//!
+//! When a request fails because its provider is down, the turn continues on
+//! the next `failover` model; it takes precedence over both.
+
+use codex_protocol::models::ResponseInputItem;
+
+use crate::client::ModelClient;
+use crate::client::is_failover_error;
+use crate::client_common::ModelRole;
+use crate::error::CodexErr;
+
+/// Tool outputs starting with these mean the model named a tool that does
+/// not exist or passed arguments that did not parse.
+const INVALID_TOOL_CALL_PREFIXES: &[&str] = &[
+    "unsupported call: ",
+    "unsupported custom tool call: ",
+    "failed to parse function arguments",
+];
+
+pub(crate) struct ModelRouter {
+    planning: ModelClient,
+    editing: ModelClient,
+    fallback: Option<Fallback>,
+    failover: Vec<ModelClient>,
+    /// How many `failover` clients have been used; the last one is active.
+    failed_over: usize,
+    requests: usize,
+}
+
+struct Fallback {
+    client: ModelClient,
+    after_invalid_tool_calls: u32,
+    invalid_in_a_row: u32,
+    active: bool,
+}
+
+impl ModelRouter {
+    pub(crate) fn new(client: &ModelClient) -> Self {
+        let fallback = client
+            .get_config()
+            .model_routes
+            .fallback
+            .as_ref()
+            .map(|fallback| Fallback {
+                client: client.with_route(&fallback.route()),
+                after_invalid_tool_calls: fallback.after_invalid_tool_calls.max(1),
+                invalid_in_a_row: 0,
+                active: false,
+            });
+        let failover = client
+            .get_config()
+            .model_routes
+            .failover
+            .iter()
+            .map(|route| client.with_route(route))
+            .collect();
+        Self {
+            planning: client.for_role(ModelRole::Planning),
+            editing: client.for_role(ModelRole::Editing),
+            fallback,
+            failover,
+            failed_over: 0,
+            requests: 0,
+        }
+    }
+
+    /// The client for the next request of the turn.
+    pub(crate) fn client(&mut self) -> ModelClient {
+        self.requests += 1;
+        if let Some(backup) = self.failed_over.checked_sub(1) {
+            return self.failover[backup].clone();
+        }
+        match &self.fallback {
+            Some(fallback) if fallback.active => fallback.client.clone(),
+            _ if self.requests == 1 => self.planning.clone(),
+            _ => self.editing.clone(),
+        }
+    }
+
+    /// Whether a failed request could be retried on another provider.
+    pub(crate) fn can_fail_over(&self) -> bool {
+        self.failed_over < self.failover.len()
+    }
+
+    /// Switch the rest of the turn to the next `failover` client when `err`
+    /// says the active provider is down. Returns the new client.
+    pub(crate) fn fail_over(&mut self, err: &CodexErr) -> Option<&ModelClient> {
+        if !self.can_fail_over() || !is_failover_error(err) {
+            return None;
+        }
+        self.failed_over += 1;
+        self.failover.get(self.failed_over - 1)
+    }
+
+    /// Count the tool calls of one response by their `outputs`. Returns the
+    /// fallback client when this response made the router switch to it.
+    pub(crate) fn record_tool_calls<'a>(
+        &mut self,
+        outputs: impl IntoIterator<Item = &'a ResponseInputItem>,
+    ) -> Option<&ModelClient> {
+        let fallback = self.fallback.as_mut().filter(|fallback| !fallback.active)?;
+        let mut calls = 0;
+        let mut invalid = 0;
+        for output in outputs {
+            if matches!(output, ResponseInputItem::Message { .. }) {
+                continue;
+            }
+            calls += 1;
+            if is_invalid_tool_call(output) {
+                invalid += 1;
+            }
+        }
+        if calls == 0 {
+            return None;
+        }
+        if invalid < calls {
+            fallback.invalid_in_a_row = 0;
+            return None;
+        }
+        fallback.invalid_in_a_row += 1;
+        if fallback.invalid_in_a_row < fallback.after_invalid_tool_calls {
+            return None;
+        }
+        fallback.active = true;
+        Some(&fallback.client)
+    }
+}
+
+fn is_invalid_tool_call(output: &ResponseInputItem) -> bool {
+    let content = match output {
+        ResponseInputItem::FunctionCallOutput { output, .. } => &output.content,
+        ResponseInputItem::CustomToolCallOutput { output, .. } => output,
+        ResponseInputItem::McpToolCallOutput { .. } | ResponseInputItem::Message { .. } => {
+            return false;
+        }
+    };
+    INVALID_TOOL_CALL_PREFIXES
+        .iter()
+        .any(|prefix| content.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_common::SSE_IDLE_TIMEOUT_MESSAGE;
+    use crate::config::Config;
+    use crate::config::ConfigOverrides;
+    use crate::config::ConfigToml;
+    use crate::config_types::ModelRoute;
+    use crate::error::UnexpectedResponseError;
+    use codex_app_server_protocol::AuthMode;
+    use codex_otel::otel_event_manager::OtelEventManager;
+    use codex_protocol::ConversationId;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+    use reqwest::StatusCode;
+    use std::sync::Arc;
+
+    fn client(models: &str) -> ModelClient {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let cfg: ConfigToml = toml::from_str(&format!(
+            "model = \"gpt-5\"\n[model_providers.oss-local]\nname = \"Local\"\nbase_url = \"http://localhost:11434/v1\"\n[models]\n{models}"
+        ))
+        .expect("parse config");
+        let config = Config::load_from_base_config_with_overrides(
+            cfg,
+            ConfigOverrides::default(),
+            codex_home.path().to_path_buf(),
+        )
+        .expect("load config");
+        let conversation_id = ConversationId::default();
+        let otel_event_manager = OtelEventManager::new(
+            conversation_id,
+            config.model.as_str(),
+            config.model_family.slug.as_str(),
+            None,
+            Some(AuthMode::ApiKey),
+            false,
+            "test".to_string(),
+        );
+        let config = Arc::new(config);
+        ModelClient::new(
+            config.clone(),
+            None,
+            otel_event_manager,
+            config.model_provider.clone(),
+            config.model_reasoning_effort,
+            config.model_reasoning_summary,
+            conversation_id,
+        )
+    }
+
+    fn output(content: &str) -> ResponseInputItem {
+        ResponseInputItem::FunctionCallOutput {
+            call_id: "call".to_string(),
+            output: FunctionCallOutputPayload {
+                content: content.to_string(),
+                success: Some(false),
+            },
+        }
+    }
+
+    #[test]
+    fn routes_roles_and_providers() {
+        let client = client(
+            r#"
+editing = { model = "gpt-oss:20b", provider = "oss-local" }
+compaction = "gpt-5-codex"
+"#,
+        );
+
+        let mut router = ModelRouter::new(&client);
+        let planning = router.client();
+        assert_eq!(planning.get_model(), "gpt-5");
+        assert_eq!(planning.get_config().model_provider_id, "openai");
+
+        let editing = router.client();
+        assert_eq!(editing.get_model(), "gpt-oss:20b");
+        assert_eq!(editing.get_config().model_provider_id, "oss-local");
+        assert_eq!(editing.get_provider().name, "Local");
+
+        let compaction = client.for_role(ModelRole::Compaction);
+        assert_eq!(compaction.get_model(), "gpt-5-codex");
+        assert_eq!(compaction.get_config().model_provider_id, "openai");
+    }
+
+    #[test]
+    fn routed_clients_fit_the_auto_compact_limit_to_their_window() {
+        let client = client("");
+        let codex = client.with_route(&ModelRoute::Model("gpt-5-codex".to_string()));
+        assert_eq!(codex.get_auto_compact_token_limit(), Some(350_000));
+
+        let o3 = codex.with_route(&ModelRoute::Model("o3".to_string()));
+        assert_eq!(o3.get_model_context_window(), Some(200_000));
+        assert_eq!(o3.get_auto_compact_token_limit(), Some(200_000));
+    }
+
+    #[test]
+    fn falls_back_after_invalid_tool_calls_in_a_row() {
+        let client = client(
+            r#"
+planning = { model = "gpt-oss:20b", provider = "oss-local" }
+editing = { model = "gpt-oss:20b", provider = "oss-local" }
+fallback = { model = "gpt-5", after_invalid_tool_calls = 2 }
+"#,
+        );
+        let invalid = output("unsupported call: shel");
+        let valid = output("exit code 1");
+
+        let mut router = ModelRouter::new(&client);
+        assert_eq!(router.client().get_model(), "gpt-oss:20b");
+        assert!(router.record_tool_calls([&invalid]).is_none());
+        // A call that ran, even unsuccessfully, resets the count.
+        assert!(router.record_tool_calls([&invalid, &valid]).is_none());
+        assert!(router.record_tool_calls([&invalid]).is_none());
+        // Responses without tool calls do not count either way.
+        assert!(router.record_tool_calls([]).is_none());
+        assert_eq!(router.client().get_model(), "gpt-oss:20b");
+
+        let switched = router
+            .record_tool_calls([&output("failed to parse function arguments: EOF")])
+            .map(ModelClient::get_model);
+        assert_eq!(switched.as_deref(), Some("gpt-5"));
+        assert_eq!(router.client().get_model(), "gpt-5");
+        assert_eq!(router.client().get_config().model_provider_id, "openai");
+    }
+
+    #[test]
+    fn fails_over_in_order_on_provider_errors() {
+        let client = client(
+            r#"
+failover = [{ model = "gpt-oss:20b", provider = "oss-local" }, "o3"]
+"#,
+        );
+        let bad_request = CodexErr::UnexpectedStatus(UnexpectedResponseError {
+            status: StatusCode::BAD_REQUEST,
+            body: String::new(),
+            request_id: None,
+        });
+        let idle = CodexErr::Stream(SSE_IDLE_TIMEOUT_MESSAGE.to_string(), None);
+
+        let mut router = ModelRouter::new(&client);
+        assert_eq!(router.client().get_model(), "gpt-5");
+        assert!(router.fail_over(&bad_request).is_none());
+
+        let backup = router
+            .fail_over(&CodexErr::InternalServerError)
+            .map(|backup| backup.get_config().model_provider_id.clone());
+        assert_eq!(backup.as_deref(), Some("oss-local"));
+        assert_eq!(router.client().get_model(), "gpt-oss:20b");
+
+        let backup = router.fail_over(&idle).map(ModelClient::get_model);
+        assert_eq!(backup.as_deref(), Some("o3"));
+        assert!(!router.can_fail_over());
+        assert!(router.fail_over(&idle).is_none());
+        assert_eq!(router.client().get_model(), "o3");
+    }
+}
@@ -0,0 +1,392 @@
+//! Partial application of a patch that the user reviewed hunk by hunk.
+//!
+//! The client reports which hunks of each file's unified diff it accepted
+//! (see [`PatchReview`]). The accepted hunks are turned back into an
+//! `apply_patch` body so the partial patch runs through the same verification
+//! and exec path as a fully approved one.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatchVerified;
+use codex_apply_patch::maybe_parse_apply_patch_verified_with_options;
+
+use crate::protocol::PatchReview;
+
+/// One `@@` section of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DiffHunk {
+    header: String,
+    /// Number of original lines that precede the hunk.
+    old_skip: usize,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DiffLine {
+    Context(String),
+    Delete(String),
+    Insert(String),
+}
+
+fn parse_hunks(unified_diff: &str) -> Vec<DiffHunk> {
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    for line in unified_diff.lines() {
+        if line.starts_with("@@") {
+            hunks.push(DiffHunk {
+                header: line.to_string(),
+                old_skip: parse_old_skip(line),
+                lines: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        if let Some(text) = line.strip_prefix('-') {
+            hunk.lines.push(DiffLine::Delete(text.to_string()));
+        } else if let Some(text) = line.strip_prefix('+') {
+            hunk.lines.push(DiffLine::Insert(text.to_string()));
+        } else if !line.starts_with('\\') {
+            // Context; `\ No newline at end of file` markers are skipped.
+            let text = line.strip_prefix(' ').unwrap_or(line);
+            hunk.lines.push(DiffLine::Context(text.to_string()));
+        }
+    }
+    hunks
+}
+
+/// Parse the old range of `@@ -start,len +start,len @@`. An empty old range
+/// starts *after* line `start`, a non-empty one *at* line `start`.
+fn parse_old_skip(header: &str) -> usize {
+    let old_range = header
+        .trim_start_matches('@')
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('-'))
+        .unwrap_or("1");
+    let (start, len) = match old_range.split_once(',') {
+        Some((start, len)) => (start, len.parse::<usize>().unwrap_or(1)),
+        None => (old_range, 1),
+    };
+    let start = start.parse::<usize>().unwrap_or(1);
+    if len == 0 {
+        start
+    } else {
+        start.saturating_sub(1)
+    }
+}
+
+/// Number of reviewable hunks in an update's `unified_diff`: one per `@@`
+/// section, or a single hunk for a diff without any (a pure rename). Clients
+/// number hunks with this so their indices match [`PatchReview`]'s.
+pub fn update_hunk_count(unified_diff: &str) -> usize {
+    parse_hunks(unified_diff).len().max(1)
+}
+
+/// Number of reviewable hunks in `change`.
+fn hunk_count(change: &ApplyPatchFileChange) -> usize {
+    match change {
+        ApplyPatchFileChange::Update { unified_diff, .. } => update_hunk_count(unified_diff),
+        ApplyPatchFileChange::Add { .. } | ApplyPatchFileChange::Delete { .. } => 1,
+    }
+}
+
+/// Accepted hunk indices for `path`, sorted, deduplicated and in range.
+fn accepted_for(review: &PatchReview, path: &Path, count: usize) -> Vec<usize> {
+    let mut accepted: Vec<usize> = review
+        .accepted_hunks
+        .get(path)
+        .map(|hunks| hunks.iter().copied().filter(|idx| *idx < count).collect())
+        .unwrap_or_default();
+    accepted.sort_unstable();
+    accepted.dedup();
+    accepted
+}
+
+/// Build the action that applies only the hunks accepted in `review`, or
+/// `None` when nothing was accepted.
+pub(crate) fn partial_action(
+    action: &ApplyPatchAction,
+    review: &PatchReview,
+) -> Result<Option<ApplyPatchAction>, String> {
+    let changes: BTreeMap<&PathBuf, &ApplyPatchFileChange> = action.changes().iter().collect();
+    // A selection outside the patch means the client numbered hunks
+    // differently; applying it would apply hunks the user never saw.
+    for (path, hunks) in &review.accepted_hunks {
+        let count = changes
+            .get(path)
+            .map(|change| hunk_count(change))
+            .ok_or_else(|| {
+                format!(
+                    "the review names {}, which the patch does not change",
+                    path.display()
+                )
+            })?;
+        if let Some(idx) = hunks.iter().find(|idx| **idx >= count) {
+            return Err(format!(
+                "the review accepts hunk {idx} of {}, which has only {count} hunks",
+                path.display()
+            ));
+        }
+    }
+    let mut patch = String::from("*** Begin Patch\n");
+    // Contents the accepted hunks of each updated file should produce.
+    let mut expected: Vec<(&PathBuf, String)> = Vec::new();
+    let mut accepted_any = false;
+    for (path, change) in changes {
+        let accepted = accepted_for(review, path, hunk_count(change));
+        if accepted.is_empty() {
+            continue;
+        }
+        accepted_any = true;
+        let display = path.display();
+        match change {
+            ApplyPatchFileChange::Add { content } => {
+                patch.push_str(&format!("*** Add File: {display}\n"));
+                for line in content.lines() {
+                    patch.push_str(&format!("+{line}\n"));
+                }
+            }
+            ApplyPatchFileChange::Delete { .. } => {
+                patch.push_str(&format!("*** Delete File: {display}\n"));
+            }
+            ApplyPatchFileChange::Update {
+                unified_diff,
+                move_path,
+                ..
+            } => {
+                let original = std::fs::read_to_string(path)
+                    .map_err(|err| format!("failed to read {display}: {err}"))?;
+                patch.push_str(&format!("*** Update File: {display}\n"));
+                if let Some(move_path) = move_path {
+                    patch.push_str(&format!("*** Move to: {}\n", move_path.display()));
+                }
+                let hunks = parse_hunks(unified_diff);
+                if hunks.is_empty() {
+                    // Nothing but a rename: keep the whole file as context.
+                    patch.push_str("@@\n");
+                    for line in original.lines() {
+                        patch.push_str(&format!(" {line}\n"));
+                    }
+                    expected.push((path, original));
+                    continue;
+                }
+                let selected: Vec<&DiffHunk> =
+                    accepted.iter().filter_map(|idx| hunks.get(*idx)).collect();
+                for hunk in &selected {
+                    patch.push_str("@@\n");
+                    for line in &hunk.lines {
+                        let (tag, text) = match line {
+                            DiffLine::Context(text) => (' ', text),
+                            DiffLine::Delete(text) => ('-', text),
+                            DiffLine::Insert(text) => ('+', text),
+                        };
+                        patch.push_str(&format!("{tag}{text}\n"));
+                    }
+                }
+                expected.push((path, apply_hunks_by_line(&original, &selected)));
+            }
+        }
+    }
+    if !accepted_any {
+        return Ok(None);
+    }
+    patch.push_str("*** End Patch\n");
+
+    let argv = vec!["apply_patch".to_string(), patch];
+    let partial =
+        match maybe_parse_apply_patch_verified_with_options(&argv, &action.cwd, action.options) {
+            MaybeApplyPatchVerified::Body(partial) => partial,
+            MaybeApplyPatchVerified::CorrectnessError(err) => return Err(err.to_string()),
+            MaybeApplyPatchVerified::ShellParseError(err) => return Err(format!("{err:?}")),
+            MaybeApplyPatchVerified::NotApplyPatch => {
+                return Err("the accepted hunks did not form a valid patch".to_string());
+            }
+        };
+
+    // The rebuilt hunks only carry a line of context, so make sure each one
+    // landed where the reviewer saw it.
+    for (path, expected) in expected {
+        let matches = match partial.changes().get(path) {
+            Some(ApplyPatchFileChange::Update { new_content, .. }) => {
+                new_content.lines().eq(expected.lines())
+            }
+            _ => false,
+        };
+        if !matches {
+            return Err(format!(
+                "the accepted hunks of {} could not be located unambiguously",
+                path.display()
+            ));
+        }
+    }
+    Ok(Some(partial))
+}
+
+/// Apply `hunks` to `original` using the line numbers in their headers.
+fn apply_hunks_by_line(original: &str, hunks: &[&DiffHunk]) -> String {
+    let original: Vec<&str> = original.lines().collect();
+    let mut out: Vec<&str> = Vec::new();
+    let mut next = 0;
+    for hunk in hunks {
+        let skip = hunk.old_skip.min(original.len());
+        if skip > next {
+            out.extend(&original[next..skip]);
+            next = skip;
+        }
+        for line in &hunk.lines {
+            match line {
+                DiffLine::Context(_) => {
+                    out.extend(original.get(next));
+                    next += 1;
+                }
+                DiffLine::Delete(_) => next += 1,
+                DiffLine::Insert(text) => out.push(text),
+            }
+        }
+    }
+    out.extend(original.get(next..).unwrap_or_default());
+    let mut content = out.join("\n");
+    content.push('\n');
+    content
+}
+
+/// Message for the model listing the hunks the user rejected, with the
+/// comments they left.
+pub(crate) fn review_feedback(action: &ApplyPatchAction, review: &PatchReview) -> String {
+    let changes: BTreeMap<&PathBuf, &ApplyPatchFileChange> = action.changes().iter().collect();
+    let mut total = 0;
+    let mut applied = 0;
+    let mut rejected = String::new();
+    for (path, change) in changes {
+        let count = hunk_count(change);
+        let accepted = accepted_for(review, path, count);
+        total += count;
+        applied += accepted.len();
+        let display = path
+            .strip_prefix(&action.cwd)
+            .unwrap_or(path)
+            .display()
+            .to_string();
+        let hunks = match change {
+            ApplyPatchFileChange::Update { unified_diff, .. } => parse_hunks(unified_diff),
+            _ => Vec::new(),
+        };
+        for idx in (0..count).filter(|idx| !accepted.contains(idx)) {
+            rejected.push('\n');
+            match (change, hunks.get(idx)) {
+                (ApplyPatchFileChange::Add { .. }, _) => {
+                    rejected.push_str(&format!("{display}: adding the file\n"));
+                }
+                (ApplyPatchFileChange::Delete { .. }, _) => {
+                    rejected.push_str(&format!("{display}: deleting the file\n"));
+                }
+                (_, Some(hunk)) => {
+                    rejected.push_str(&format!("{display}, hunk {}:\n{}\n", idx + 1, hunk.header));
+                    for line in &hunk.lines {
+                        match line {
+                            DiffLine::Context(text) => rejected.push_str(&format!(" {text}\n")),
+                            DiffLine::Delete(text) => rejected.push_str(&format!("-{text}\n")),
+                            DiffLine::Insert(text) => rejected.push_str(&format!("+{text}\n")),
+                        }
+                    }
+                }
+                (_, None) => rejected.push_str(&format!("{display}: renaming the file\n")),
+            }
+            for comment in review
+                .comments
+                .iter()
+                .filter(|c| c.path == *path && c.hunk == idx)
+            {
+                rejected.push_str(&format!("User comment: {}\n", comment.comment.trim()));
+            }
+        }
+    }
+    let summary = if applied == 0 {
+        format!("The user reviewed the patch and rejected all {total} hunks; nothing was applied.")
+    } else {
+        format!(
+            "The user reviewed the patch and applied {applied} of {total} hunks. The rejected hunks below were not applied."
+        )
+    };
+    if rejected.is_empty() {
+        summary
+    } else {
+        format!("{summary}\n{rejected}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::HunkComment;
+    use codex_apply_patch::maybe_parse_apply_patch_verified;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    #[test]
+    fn applies_only_accepted_hunks_and_reports_the_rest() {
+        let dir = tempdir().expect("tempdir");
+        let path = dir.path().join("lib.rs");
+        let original = "fn a() {}\n\nx\ny\nz\nw\n\nfn b() {}\n\nq\nr\ns\nt\n\nfn c() {}\n";
+        std::fs::write(&path, original).expect("write");
+        let patch = "*** Begin Patch\n*** Update File: lib.rs\n@@\n-fn a() {}\n+fn a() { 1 }\n@@\n-fn b() {}\n+fn b() { 2 }\n@@\n-fn c() {}\n+fn c() { 3 }\n*** End Patch";
+        let argv = vec!["apply_patch".to_string(), patch.to_string()];
+        let MaybeApplyPatchVerified::Body(action) =
+            maybe_parse_apply_patch_verified(&argv, dir.path())
+        else {
+            panic!("expected a verified patch");
+        };
+        let review = PatchReview {
+            accepted_hunks: HashMap::from([(path.clone(), vec![0, 2])]),
+            comments: vec![HunkComment {
+                path: path.clone(),
+                hunk: 1,
+                comment: "leave b alone".to_string(),
+            }],
+        };
+
+        let partial = partial_action(&action, &review)
+            .expect("partial patch")
+            .expect("some hunks accepted");
+        let Some(ApplyPatchFileChange::Update { new_content, .. }) = partial.changes().get(&path)
+        else {
+            panic!("expected an update");
+        };
+        assert_eq!(
+            new_content,
+            "fn a() { 1 }\n\nx\ny\nz\nw\n\nfn b() {}\n\nq\nr\ns\nt\n\nfn c() { 3 }\n"
+        );
+
+        assert_eq!(
+            review_feedback(&action, &review),
+            "The user reviewed the patch and applied 2 of 3 hunks. The rejected hunks below were not applied.\n\nlib.rs, hunk 2:\n@@ -7,3 +7,3 @@\n \n-fn b() {}\n+fn b() { 2 }\n \nUser comment: leave b alone\n"
+        );
+
+        let none = PatchReview::default();
+        assert_eq!(partial_action(&action, &none), Ok(None));
+
+        let out_of_range = PatchReview {
+            accepted_hunks: HashMap::from([(path.clone(), vec![0, 3])]),
+            comments: Vec::new(),
+        };
+        assert_eq!(
+            partial_action(&action, &out_of_range),
+            Err(format!(
+                "the review accepts hunk 3 of {}, which has only 3 hunks",
+                path.display()
+            ))
+        );
+    }
+
+    #[test]
+    fn parses_hunk_headers() {
+        assert_eq!(parse_old_skip("@@ -7,3 +7,3 @@"), 6);
+        assert_eq!(parse_old_skip("@@ -4,0 +5,2 @@"), 4);
+        assert_eq!(parse_old_skip("@@ -1 +1 @@"), 0);
+    }
+}
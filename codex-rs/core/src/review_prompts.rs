@@ -0,0 +1,68 @@
+use crate::protocol::ReviewRequest;
+
+// Note: The TUI and `codex exec review` both build their review requests
+// here so the prompts the model sees do not depend on the frontend.
+
+/// Review the working tree: staged, unstaged and untracked changes.
+pub fn uncommitted_changes() -> ReviewRequest {
+    ReviewRequest {
+        prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
+        user_facing_hint: "current changes".to_string(),
+    }
+}
+
+/// Review what the current branch would merge into `branch`.
+pub fn base_branch(branch: &str) -> ReviewRequest {
+    ReviewRequest {
+        prompt: format!(
+            "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
+        ),
+        user_facing_hint: format!("changes against '{branch}'"),
+    }
+}
+
+/// Review the changes introduced by commit `sha`. The subject line, when
+/// known, is quoted in the prompt to help the model find the commit.
+pub fn commit(sha: &str, subject: Option<&str>) -> ReviewRequest {
+    let short = sha.chars().take(7).collect::<String>();
+    let prompt = match subject {
+        Some(subject) => format!(
+            "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
+        ),
+        None => format!(
+            "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings."
+        ),
+    };
+    ReviewRequest {
+        prompt,
+        user_facing_hint: format!("commit {short}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn commit_prompt_quotes_the_subject_when_known() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+
+        let request = commit(sha, Some("Fix overflow"));
+        assert_eq!(
+            request.prompt,
+            format!(
+                "Review the code changes introduced by commit {sha} (\"Fix overflow\"). Provide prioritized, actionable findings."
+            )
+        );
+        assert_eq!(request.user_facing_hint, "commit 0123456");
+
+        let request = commit(sha, None);
+        assert_eq!(
+            request.prompt,
+            format!(
+                "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings."
+            )
+        );
+    }
+}
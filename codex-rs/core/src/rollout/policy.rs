@@ -71,6 +71,9 @@ pub(crate) fn should_persist_event_msg(ev: &EventMsg) -> bool {
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::ViewImageToolCall(_)
-        | EventMsg::ConversationPath(_) => false,
+        | EventMsg::ConversationPath(_)
+        | EventMsg::SubAgentBegin(_)
+        | EventMsg::SubAgentActivity(_)
+        | EventMsg::SubAgentEnd(_) => false,
     }
 }
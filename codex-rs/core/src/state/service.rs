@@ -1,9 +1,11 @@
 use crate::RolloutRecorder;
 use crate::exec_command::ExecSessionManager;
 use crate::executor::Executor;
+use crate::hooks::HookRunner;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::unified_exec::UnifiedExecSessionManager;
 use crate::user_notification::UserNotifier;
+use codex_apply_patch::ApplyPatchOptions;
 use tokio::sync::Mutex;
 
 pub(crate) struct SessionServices {
@@ -11,8 +13,10 @@ pub(crate) struct SessionServices {
     pub(crate) session_manager: ExecSessionManager,
     pub(crate) unified_exec_manager: UnifiedExecSessionManager,
     pub(crate) notifier: UserNotifier,
+    pub(crate) hooks: HookRunner,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: crate::shell::Shell,
     pub(crate) show_raw_agent_reasoning: bool,
     pub(crate) executor: Executor,
+    pub(crate) apply_patch_options: ApplyPatchOptions,
 }
@@ -9,6 +9,9 @@ use tokio::task::AbortHandle;
 use codex_protocol::models::ResponseInputItem;
 use tokio::sync::oneshot;
 
+use crate::budget::BudgetUsage;
+use crate::config_types::TaskBudget;
+use crate::protocol::PatchReview;
 use crate::protocol::ReviewDecision;
 use crate::tasks::SessionTask;
 
@@ -60,7 +63,13 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    /// Hunk selections for patch approvals answered with `Op::PatchReview`.
+    patch_reviews: HashMap<String, PatchReview>,
     pending_input: Vec<ResponseInputItem>,
+    /// Budget the task has left, for sub-agents it spawns to run within.
+    remaining_budget: Option<TaskBudget>,
+    /// What sub-agents used since the task last counted it.
+    sub_agent_usage: BudgetUsage,
 }
 
 impl TurnState {
@@ -79,8 +88,33 @@ impl TurnState {
         self.pending_approvals.remove(key)
     }
 
+    pub(crate) fn insert_patch_review(&mut self, key: String, review: PatchReview) {
+        self.patch_reviews.insert(key, review);
+    }
+
+    pub(crate) fn take_patch_review(&mut self, key: &str) -> Option<PatchReview> {
+        self.patch_reviews.remove(key)
+    }
+
+    pub(crate) fn set_remaining_budget(&mut self, budget: TaskBudget) {
+        self.remaining_budget = Some(budget);
+    }
+
+    pub(crate) fn remaining_budget(&self) -> Option<TaskBudget> {
+        self.remaining_budget.clone()
+    }
+
+    pub(crate) fn add_sub_agent_usage(&mut self, usage: BudgetUsage) {
+        self.sub_agent_usage.add(usage);
+    }
+
+    pub(crate) fn take_sub_agent_usage(&mut self) -> BudgetUsage {
+        std::mem::take(&mut self.sub_agent_usage)
+    }
+
     pub(crate) fn clear_pending(&mut self) {
         self.pending_approvals.clear();
+        self.patch_reviews.clear();
         self.pending_input.clear();
     }
 
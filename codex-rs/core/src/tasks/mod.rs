@@ -99,6 +99,15 @@ impl Session {
         }
     }
 
+    /// Abort the running task from inside it, e.g. when it goes over its
+    /// budget. Runs on a separate task because the abort cancels the caller.
+    pub(crate) fn abort_current_task(self: &Arc<Self>, reason: TurnAbortReason) {
+        let sess = Arc::clone(self);
+        tokio::spawn(async move {
+            sess.abort_all_tasks(reason).await;
+        });
+    }
+
     pub async fn on_task_finished(
         self: &Arc<Self>,
         sub_id: String,
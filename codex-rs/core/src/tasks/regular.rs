@@ -3,6 +3,7 @@ use std::sync::Arc;
 use async_trait::async_trait;
 
 use crate::codex::TurnContext;
+use crate::codex::run_prompt_hooks;
 use crate::codex::run_task;
 use crate::protocol::InputItem;
 use crate::state::TaskKind;
@@ -11,7 +12,16 @@ use super::SessionTask;
 use super::SessionTaskContext;
 
 #[derive(Clone, Copy, Default)]
-pub(crate) struct RegularTask;
+pub(crate) struct RegularTask {
+    /// The input already went through the `user_prompt_submit` hooks.
+    hooked_input: bool,
+}
+
+impl RegularTask {
+    pub(crate) fn with_hooked_input() -> Self {
+        Self { hooked_input: true }
+    }
+}
 
 #[async_trait]
 impl SessionTask for RegularTask {
@@ -27,6 +37,12 @@ impl SessionTask for RegularTask {
         input: Vec<InputItem>,
     ) -> Option<String> {
         let sess = session.clone_session();
+        // Inside the task so `Op::Interrupt` can cancel a slow hook.
+        let input = if self.hooked_input {
+            input
+        } else {
+            run_prompt_hooks(&sess, &sub_id, &ctx.cwd, input).await?
+        };
         run_task(sess, ctx, sub_id, input).await
     }
 }
@@ -11,6 +11,10 @@ use codex_protocol::models::ShellToolCallParams;
 use codex_protocol::protocol::FileChange;
 use codex_utils_string::take_bytes_at_char_boundary;
 use mcp_types::CallToolResult;
+use mcp_types::ContentBlock;
+use mcp_types::TextContent;
+use serde_json::Value;
+use serde_json::json;
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -47,6 +51,55 @@ pub enum ToolPayload {
 }
 
 impl ToolPayload {
+    /// JSON form of the call's input handed to `pre_tool_use` and
+    /// `post_tool_use` hooks.
+    pub(crate) fn hook_input(&self) -> Value {
+        match self {
+            ToolPayload::Function { arguments }
+            | ToolPayload::UnifiedExec { arguments }
+            | ToolPayload::Mcp {
+                raw_arguments: arguments,
+                ..
+            } => {
+                serde_json::from_str(arguments).unwrap_or_else(|_| Value::String(arguments.clone()))
+            }
+            ToolPayload::Custom { input } => Value::String(input.clone()),
+            ToolPayload::LocalShell { params } => json!({
+                "command": params.command,
+                "workdir": params.workdir,
+                "timeout_ms": params.timeout_ms,
+            }),
+        }
+    }
+
+    /// The same call with its input replaced by one a hook returned in the
+    /// shape produced by [`ToolPayload::hook_input`].
+    pub(crate) fn with_hook_input(&self, input: Value) -> Result<ToolPayload, String> {
+        let as_text = |input: Value| match input {
+            Value::String(text) => text,
+            other => other.to_string(),
+        };
+        Ok(match self {
+            ToolPayload::Function { .. } => ToolPayload::Function {
+                arguments: as_text(input),
+            },
+            ToolPayload::UnifiedExec { .. } => ToolPayload::UnifiedExec {
+                arguments: as_text(input),
+            },
+            ToolPayload::Mcp { server, tool, .. } => ToolPayload::Mcp {
+                server: server.clone(),
+                tool: tool.clone(),
+                raw_arguments: as_text(input),
+            },
+            ToolPayload::Custom { .. } => ToolPayload::Custom {
+                input: as_text(input),
+            },
+            ToolPayload::LocalShell { .. } => ToolPayload::LocalShell {
+                params: serde_json::from_value(input).map_err(|e| e.to_string())?,
+            },
+        })
+    }
+
     pub fn log_payload(&self) -> Cow<'_, str> {
         match self {
             ToolPayload::Function { arguments } => Cow::Borrowed(arguments),
@@ -84,6 +137,37 @@ impl ToolOutput {
         }
     }
 
+    /// Output as handed to `post_tool_use` hooks.
+    pub(crate) fn hook_text(&self) -> String {
+        match self {
+            ToolOutput::Function { content, .. } => content.clone(),
+            ToolOutput::Mcp { result: Ok(result) } => {
+                serde_json::to_string(result).unwrap_or_default()
+            }
+            ToolOutput::Mcp { result: Err(err) } => err.clone(),
+        }
+    }
+
+    /// Add hook output after the tool's own output.
+    pub(crate) fn append_hook_context(&mut self, context: &str) {
+        match self {
+            ToolOutput::Function { content, .. }
+            | ToolOutput::Mcp {
+                result: Err(content),
+            } => {
+                content.push_str("\n\n");
+                content.push_str(context);
+            }
+            ToolOutput::Mcp { result: Ok(result) } => {
+                result.content.push(ContentBlock::TextContent(TextContent {
+                    annotations: None,
+                    text: context.to_string(),
+                    r#type: "text".to_string(),
+                }));
+            }
+        }
+    }
+
     pub fn into_response(self, call_id: &str, payload: &ToolPayload) -> ResponseInputItem {
         match self {
             ToolOutput::Function { content, success } => {
@@ -193,6 +277,45 @@ mod tests {
         }
     }
 
+    #[test]
+    fn hook_input_round_trips_through_payloads() {
+        let payload = ToolPayload::Function {
+            arguments: r#"{"command":["ls"]}"#.to_string(),
+        };
+        assert_eq!(payload.hook_input(), json!({"command": ["ls"]}));
+
+        let updated = payload
+            .with_hook_input(json!({"command": ["ls", "-a"]}))
+            .expect("function input");
+        match updated {
+            ToolPayload::Function { arguments } => {
+                assert_eq!(arguments, r#"{"command":["ls","-a"]}"#)
+            }
+            _ => panic!("expected a function payload"),
+        }
+
+        let shell = ToolPayload::LocalShell {
+            params: ShellToolCallParams {
+                command: vec!["ls".to_string()],
+                workdir: None,
+                timeout_ms: None,
+                with_escalated_permissions: None,
+                justification: None,
+            },
+        };
+        let updated = shell
+            .with_hook_input(json!({"command": ["pwd"], "workdir": "/tmp"}))
+            .expect("shell input");
+        match updated {
+            ToolPayload::LocalShell { params } => {
+                assert_eq!(params.command, vec!["pwd".to_string()]);
+                assert_eq!(params.workdir.as_deref(), Some("/tmp"));
+            }
+            _ => panic!("expected a local shell payload"),
+        }
+        assert!(shell.with_hook_input(json!("rm -rf /")).is_err());
+    }
+
     #[test]
     fn telemetry_preview_returns_original_within_limits() {
         let content = "short output";
@@ -194,6 +194,8 @@ It is important to remember:
 - You must include a header with your intended action (Add/Delete/Update)
 - You must prefix new lines with `+` even when creating a new file
 - File references can only be relative, NEVER ABSOLUTE.
+
+A standard unified diff (as printed by `git diff` or `diff -u`) is also accepted in place of the envelope above. Include at least one unchanged context line in every hunk; line numbers in `@@` headers are ignored.
 "#
             .to_string(),
         strict: false,
@@ -4,6 +4,7 @@ mod mcp;
 mod plan;
 mod read_file;
 mod shell;
+mod spawn_agent;
 mod unified_exec;
 mod view_image;
 
@@ -15,5 +16,6 @@ pub use mcp::McpHandler;
 pub use plan::PlanHandler;
 pub use read_file::ReadFileHandler;
 pub use shell::ShellHandler;
+pub use spawn_agent::SpawnAgentHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
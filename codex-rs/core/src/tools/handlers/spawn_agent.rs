@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::config_types::SandboxMode;
+use codex_protocol::protocol::SessionSource;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::warn;
+
+use crate::AuthManager;
+use crate::CodexConversation;
+use crate::ConversationManager;
+use crate::NewConversation;
+use crate::budget::BudgetUsage;
+use crate::function_tool::FunctionCallError;
+use crate::protocol::Event;
+use crate::protocol::EventMsg;
+use crate::protocol::InputItem;
+use crate::protocol::Op;
+use crate::protocol::SandboxPolicy;
+use crate::protocol::SubAgentActivityEvent;
+use crate::protocol::SubAgentBeginEvent;
+use crate::protocol::SubAgentEndEvent;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+pub struct SpawnAgentHandler;
+
+#[derive(Deserialize)]
+struct SpawnAgentArgs {
+    task: String,
+    #[serde(default)]
+    instructions: Option<String>,
+    #[serde(default)]
+    tools: Option<Vec<String>>,
+    #[serde(default)]
+    sandbox: Option<SandboxMode>,
+}
+
+#[async_trait]
+impl ToolHandler for SpawnAgentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(
+        &self,
+        invocation: ToolInvocation<'_>,
+    ) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            sub_id,
+            call_id,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "spawn_agent handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let args: SpawnAgentArgs = serde_json::from_str(&arguments).map_err(|e| {
+            FunctionCallError::RespondToModel(format!("failed to parse function arguments: {e:?}"))
+        })?;
+
+        let sandbox_policy = narrow_sandbox_policy(&turn.sandbox_policy, args.sandbox)
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        // The child starts from the parent's configuration, in the parent's
+        // working directory, and cannot spawn agents of its own. It runs
+        // within what is left of the parent's budget, and what it uses is
+        // counted against the parent once it returns.
+        let mut config = (*turn.client.get_config()).clone();
+        if let Some(remaining) = session.remaining_budget().await {
+            config.budget = remaining;
+        }
+        config.cwd = turn.cwd.clone();
+        config.approval_policy = turn.approval_policy;
+        config.sandbox_policy = sandbox_policy;
+        config.include_spawn_agent_tool = false;
+        config.allowed_tools = args.tools;
+        config.notify = None;
+        if let Some(instructions) = args.instructions {
+            config.user_instructions = Some(match config.user_instructions.take() {
+                Some(existing) => format!("{existing}\n\n{instructions}"),
+                None => instructions,
+            });
+        }
+
+        let auth_manager = turn
+            .client
+            .get_auth_manager()
+            .unwrap_or_else(|| AuthManager::shared(config.codex_home.clone(), false));
+        let manager = ConversationManager::new(auth_manager, SessionSource::SubAgent);
+        let NewConversation {
+            conversation_id,
+            conversation,
+            ..
+        } = manager.new_conversation(config).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to start sub-agent: {err}"))
+        })?;
+        let _shutdown = ShutdownOnDrop(conversation.clone());
+
+        session
+            .send_event(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::SubAgentBegin(SubAgentBeginEvent {
+                    call_id: call_id.clone(),
+                    conversation_id,
+                    task: args.task.clone(),
+                }),
+            })
+            .await;
+
+        conversation
+            .submit(Op::UserInput {
+                items: vec![InputItem::Text { text: args.task }],
+            })
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to start sub-agent: {err}"))
+            })?;
+
+        let mut unified_diff = None;
+        let mut error = None;
+        let mut usage = BudgetUsage::default();
+        let outcome = loop {
+            let event = match conversation.next_event().await {
+                Ok(event) => event,
+                Err(err) => {
+                    break Err(FunctionCallError::RespondToModel(format!(
+                        "sub-agent stopped unexpectedly: {err}"
+                    )));
+                }
+            };
+            count_usage(&mut usage, &event.msg);
+
+            // The child's approval requests go to whoever approves the
+            // parent's, and the decision is passed back to the child.
+            if matches!(
+                event.msg,
+                EventMsg::ExecApprovalRequest(_) | EventMsg::ApplyPatchApprovalRequest(_)
+            ) {
+                let is_patch = matches!(event.msg, EventMsg::ApplyPatchApprovalRequest(_));
+                let decision = session
+                    .request_forwarded_approval(sub_id.to_string(), event.msg)
+                    .await;
+                let op = if !is_patch {
+                    Op::ExecApproval {
+                        id: event.id,
+                        decision,
+                    }
+                } else if let Some(review) = session.take_patch_review(sub_id).await {
+                    Op::PatchReview {
+                        id: event.id,
+                        review,
+                    }
+                } else {
+                    Op::PatchApproval {
+                        id: event.id,
+                        decision,
+                    }
+                };
+                if let Err(err) = conversation.submit(op).await {
+                    warn!("failed to pass approval to sub-agent: {err}");
+                }
+                continue;
+            }
+
+            let finished = match &event.msg {
+                EventMsg::TurnDiff(ev) => {
+                    unified_diff = Some(ev.unified_diff.clone());
+                    None
+                }
+                EventMsg::Error(ev) => {
+                    error = Some(ev.message.clone());
+                    None
+                }
+                EventMsg::TaskComplete(ev) => Some(ev.last_agent_message.clone()),
+                EventMsg::TurnAborted(ev) => {
+                    error.get_or_insert_with(|| format!("sub-agent aborted: {:?}", ev.reason));
+                    Some(None)
+                }
+                _ => None,
+            };
+            session
+                .send_event(Event {
+                    id: sub_id.to_string(),
+                    msg: EventMsg::SubAgentActivity(SubAgentActivityEvent {
+                        call_id: call_id.clone(),
+                        msg: Box::new(event.msg),
+                    }),
+                })
+                .await;
+            if let Some(last_agent_message) = finished {
+                break Ok(last_agent_message);
+            }
+        };
+        session.add_sub_agent_usage(usage).await;
+        let last_agent_message = outcome?;
+
+        let success = error.is_none();
+        session
+            .send_event(Event {
+                id: sub_id.to_string(),
+                msg: EventMsg::SubAgentEnd(SubAgentEndEvent {
+                    call_id,
+                    success,
+                    last_agent_message: last_agent_message.clone(),
+                    unified_diff: unified_diff.clone(),
+                }),
+            })
+            .await;
+
+        let content = json!({
+            "status": if success { "completed" } else { "failed" },
+            "error": error,
+            "summary": last_agent_message,
+            "diff": unified_diff,
+        });
+        Ok(ToolOutput::Function {
+            content: content.to_string(),
+            success: Some(success),
+        })
+    }
+}
+
+/// Tally the model requests, tokens and tool calls the child reports in its
+/// events.
+fn count_usage(usage: &mut BudgetUsage, msg: &EventMsg) {
+    match msg {
+        EventMsg::TokenCount(ev) => {
+            // Rate limit updates repeat the last count, so only a request
+            // raises the child's running total.
+            if let Some(info) = &ev.info
+                && info.total_token_usage.total_tokens > usage.tokens
+            {
+                usage.turns = usage.turns.saturating_add(1);
+                usage.tokens = info.total_token_usage.total_tokens;
+            }
+        }
+        EventMsg::ExecCommandBegin(_)
+        | EventMsg::McpToolCallBegin(_)
+        | EventMsg::PatchApplyBegin(_)
+        | EventMsg::WebSearchBegin(_)
+        | EventMsg::ViewImageToolCall(_) => {
+            usage.tool_calls = usage.tool_calls.saturating_add(1);
+        }
+        _ => {}
+    }
+}
+
+/// Shuts the child conversation down when the call ends, including when the
+/// parent's turn is interrupted while the child is still running.
+struct ShutdownOnDrop(Arc<CodexConversation>);
+
+impl Drop for ShutdownOnDrop {
+    fn drop(&mut self) {
+        let conversation = self.0.clone();
+        tokio::spawn(async move {
+            if let Err(err) = conversation.submit(Op::Shutdown).await {
+                warn!("failed to shut down sub-agent: {err}");
+            }
+        });
+    }
+}
+
+/// The sandbox for a sub-agent that asked for `requested`. It may be as
+/// strict as the parent's or stricter, never looser.
+fn narrow_sandbox_policy(
+    parent: &SandboxPolicy,
+    requested: Option<SandboxMode>,
+) -> Result<SandboxPolicy, String> {
+    let Some(requested) = requested else {
+        return Ok(parent.clone());
+    };
+    let parent_mode = match parent {
+        SandboxPolicy::ReadOnly => SandboxMode::ReadOnly,
+        SandboxPolicy::WorkspaceWrite { .. } => SandboxMode::WorkspaceWrite,
+        SandboxPolicy::DangerFullAccess => SandboxMode::DangerFullAccess,
+    };
+    let strictness = |mode: SandboxMode| match mode {
+        SandboxMode::ReadOnly => 2,
+        SandboxMode::WorkspaceWrite => 1,
+        SandboxMode::DangerFullAccess => 0,
+    };
+    if strictness(requested) < strictness(parent_mode) {
+        return Err(format!(
+            "a sub-agent's sandbox cannot be looser than yours ({parent_mode})"
+        ));
+    }
+    if requested == parent_mode {
+        return Ok(parent.clone());
+    }
+    Ok(match requested {
+        SandboxMode::ReadOnly => SandboxPolicy::new_read_only_policy(),
+        SandboxMode::WorkspaceWrite => SandboxPolicy::new_workspace_write_policy(),
+        SandboxMode::DangerFullAccess => SandboxPolicy::DangerFullAccess,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn sandbox_can_only_be_narrowed() {
+        let workspace_write = SandboxPolicy::WorkspaceWrite {
+            writable_roots: vec!["/tmp/extra".into()],
+            network_access: true,
+            exclude_tmpdir_env_var: false,
+            exclude_slash_tmp: false,
+        };
+
+        assert_eq!(
+            narrow_sandbox_policy(&workspace_write, None),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            narrow_sandbox_policy(&workspace_write, Some(SandboxMode::WorkspaceWrite)),
+            Ok(workspace_write.clone())
+        );
+        assert_eq!(
+            narrow_sandbox_policy(&workspace_write, Some(SandboxMode::ReadOnly)),
+            Ok(SandboxPolicy::ReadOnly)
+        );
+        assert_eq!(
+            narrow_sandbox_policy(
+                &SandboxPolicy::DangerFullAccess,
+                Some(SandboxMode::WorkspaceWrite)
+            ),
+            Ok(SandboxPolicy::new_workspace_write_policy())
+        );
+        assert!(
+            narrow_sandbox_policy(&SandboxPolicy::ReadOnly, Some(SandboxMode::WorkspaceWrite))
+                .is_err()
+        );
+        assert!(
+            narrow_sandbox_policy(&workspace_write, Some(SandboxMode::DangerFullAccess)).is_err()
+        );
+    }
+}
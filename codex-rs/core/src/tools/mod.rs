@@ -19,16 +19,20 @@ use crate::executor::ExecutionMode;
 use crate::executor::errors::ExecError;
 use crate::executor::linkers::PreparedExec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::format_hook_context;
 use crate::tools::context::ApplyPatchCommandContext;
 use crate::tools::context::ExecCommandContext;
 use crate::turn_diff_tracker::TurnDiffTracker;
+use codex_apply_patch::ApplyPatchAction;
 use codex_apply_patch::MaybeApplyPatchVerified;
-use codex_apply_patch::maybe_parse_apply_patch_verified;
+use codex_apply_patch::maybe_parse_apply_patch_verified_with_options;
 use codex_protocol::protocol::AskForApproval;
 use codex_utils_string::take_bytes_at_char_boundary;
 use codex_utils_string::take_last_bytes_at_char_boundary;
 pub use router::ToolRouter;
 use serde::Serialize;
+use std::path::PathBuf;
 use tracing::trace;
 
 // Model-formatting limits: clients get full streams; only content sent to the model is truncated.
@@ -65,9 +69,33 @@ pub(crate) async fn handle_container_exec_with_params(
         )));
     }
 
+    // Context from `pre_patch` hooks, shown to the model with the result.
+    let mut patch_hook_context = Vec::new();
+
     // check if this was a patch, and apply it if so
-    let apply_patch_exec = match maybe_parse_apply_patch_verified(&params.command, &params.cwd) {
+    let apply_patch_exec = match maybe_parse_apply_patch_verified_with_options(
+        &params.command,
+        &params.cwd,
+        sess.services.apply_patch_options,
+    ) {
         MaybeApplyPatchVerified::Body(changes) => {
+            let pre_hooks = sess
+                .run_hooks(
+                    &sub_id,
+                    &turn_context.cwd,
+                    HookEvent::PrePatch {
+                        call_id: call_id.clone(),
+                        patch: changes.patch.clone(),
+                        files: changed_files(&changes),
+                    },
+                )
+                .await;
+            if let Some(reason) = pre_hooks.blocked {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "patch rejected by hook: {reason}"
+                )));
+            }
+            patch_hook_context.extend(pre_hooks.context_text());
             match apply_patch::apply_patch(sess, turn_context, &sub_id, &call_id, changes).await {
                 InternalApplyPatchInvocation::Output(item) => return item,
                 InternalApplyPatchInvocation::DelegateToExec(apply_patch_exec) => {
@@ -105,6 +133,7 @@ pub(crate) async fn handle_container_exec_with_params(
             |ApplyPatchExec {
                  action,
                  user_explicitly_approved_this_action,
+                 ..
              }| ApplyPatchCommandContext {
                 user_explicitly_approved_this_action: *user_explicitly_approved_this_action,
                 changes: convert_apply_patch_to_protocol(action),
@@ -114,6 +143,13 @@ pub(crate) async fn handle_container_exec_with_params(
         otel_event_manager,
     };
 
+    let review_feedback = apply_patch_exec
+        .as_ref()
+        .and_then(|exec| exec.review_feedback.clone());
+    let applied_patch = apply_patch_exec
+        .as_ref()
+        .map(|exec| (exec.action.patch.clone(), changed_files(&exec.action)));
+
     let mode = match apply_patch_exec {
         Some(exec) => ExecutionMode::ApplyPatch(exec),
         None => ExecutionMode::Shell,
@@ -149,7 +185,32 @@ pub(crate) async fn handle_container_exec_with_params(
     match output_result {
         Ok(output) => {
             let ExecToolCallOutput { exit_code, .. } = &output;
-            let content = format_exec_output_apply_patch(&output);
+            let mut content = format_exec_output_apply_patch(&output);
+            if let Some(feedback) = review_feedback {
+                content.push_str("\n\n");
+                content.push_str(&feedback);
+            }
+            if let Some((patch, files)) = applied_patch {
+                let post_hooks = sess
+                    .run_hooks(
+                        &sub_id,
+                        &turn_context.cwd,
+                        HookEvent::PostPatch {
+                            call_id: call_id.clone(),
+                            patch,
+                            files,
+                            output: output.aggregated_output.text.clone(),
+                            success: *exit_code == 0,
+                        },
+                    )
+                    .await;
+                patch_hook_context.extend(post_hooks.context_text());
+                patch_hook_context.extend(post_hooks.blocked);
+            }
+            if !patch_hook_context.is_empty() {
+                content.push_str("\n\n");
+                content.push_str(&format_hook_context(&patch_hook_context.join("\n")));
+            }
             if *exit_code == 0 {
                 Ok(content)
             } else {
@@ -169,6 +230,13 @@ pub(crate) async fn handle_container_exec_with_params(
     }
 }
 
+/// Files a patch touches, in a stable order for hooks.
+fn changed_files(action: &ApplyPatchAction) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = action.changes().keys().cloned().collect();
+    files.sort();
+    files
+}
+
 pub fn format_exec_output_apply_patch(exec_output: &ExecToolCallOutput) -> String {
     let ExecToolCallOutput {
         exit_code,
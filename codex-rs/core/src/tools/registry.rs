@@ -8,6 +8,8 @@ use tracing::warn;
 
 use crate::client_common::tools::ToolSpec;
 use crate::function_tool::FunctionCallError;
+use crate::hooks::HookEvent;
+use crate::hooks::format_hook_context;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -59,8 +61,36 @@ impl ToolRegistry {
 
     pub async fn dispatch<'a>(
         &self,
-        invocation: ToolInvocation<'a>,
+        mut invocation: ToolInvocation<'a>,
     ) -> Result<ResponseInputItem, FunctionCallError> {
+        let session = invocation.session;
+        let turn = invocation.turn;
+        let sub_id = invocation.sub_id;
+        let pre_hooks = session
+            .run_hooks(
+                sub_id,
+                &turn.cwd,
+                HookEvent::PreToolUse {
+                    tool_name: invocation.tool_name.clone(),
+                    call_id: invocation.call_id.clone(),
+                    input: invocation.payload.hook_input(),
+                },
+            )
+            .await;
+        let pre_hook_context = pre_hooks.context_text();
+        if let Some(reason) = pre_hooks.blocked {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "tool call blocked by hook: {reason}"
+            )));
+        }
+        if let Some(input) = pre_hooks.updated_input {
+            invocation.payload = invocation.payload.with_hook_input(input).map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "a pre_tool_use hook returned invalid input: {err}"
+                ))
+            })?;
+        }
+
         let tool_name = invocation.tool_name.clone();
         let call_id_owned = invocation.call_id.clone();
         let otel = invocation.turn.client.get_otel_event_manager();
@@ -127,11 +157,42 @@ impl ToolRegistry {
         match result {
             Ok(_) => {
                 let mut guard = output_cell.lock().await;
-                let output = guard.take().ok_or_else(|| {
+                let mut output = guard.take().ok_or_else(|| {
                     FunctionCallError::Fatal("tool produced no output".to_string())
                 })?;
+                let post_hooks = session
+                    .run_hooks(
+                        sub_id,
+                        &turn.cwd,
+                        HookEvent::PostToolUse {
+                            tool_name: tool_name.clone(),
+                            call_id: call_id_owned.clone(),
+                            input: payload_for_response.hook_input(),
+                            output: output.hook_text(),
+                            success: output.success_for_logging(),
+                        },
+                    )
+                    .await;
+                // The call already ran, so a blocking post hook can only
+                // explain itself to the model.
+                let post_hook_context = post_hooks.context_text();
+                let context: Vec<String> =
+                    [pre_hook_context, post_hooks.blocked, post_hook_context]
+                        .into_iter()
+                        .flatten()
+                        .collect();
+                if !context.is_empty() {
+                    output.append_hook_context(&format_hook_context(&context.join("\n")));
+                }
                 Ok(output.into_response(&call_id_owned, &payload_for_response))
             }
+            Err(FunctionCallError::RespondToModel(mut message)) => {
+                if let Some(context) = pre_hook_context {
+                    message.push_str("\n\n");
+                    message.push_str(&format_hook_context(&context));
+                }
+                Err(FunctionCallError::RespondToModel(message))
+            }
             Err(err) => Err(err),
         }
     }
@@ -165,6 +226,12 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drop the specs and handlers of the tools whose name `keep` rejects.
+    pub fn retain_tools(&mut self, keep: impl Fn(&str) -> bool) {
+        self.specs.retain(|spec| keep(spec.name()));
+        self.handlers.retain(|name, _| keep(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
@@ -29,6 +29,8 @@ pub(crate) struct ToolsConfig {
     pub include_view_image_tool: bool,
     pub experimental_unified_exec_tool: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub include_spawn_agent_tool: bool,
+    pub allowed_tools: Option<Vec<String>>,
 }
 
 pub(crate) struct ToolsConfigParams<'a> {
@@ -39,6 +41,8 @@ pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) use_streamable_shell_tool: bool,
     pub(crate) include_view_image_tool: bool,
     pub(crate) experimental_unified_exec_tool: bool,
+    pub(crate) include_spawn_agent_tool: bool,
+    pub(crate) allowed_tools: Option<&'a [String]>,
 }
 
 impl ToolsConfig {
@@ -51,35 +55,72 @@ impl ToolsConfig {
             use_streamable_shell_tool,
             include_view_image_tool,
             experimental_unified_exec_tool,
+            include_spawn_agent_tool,
+            allowed_tools,
         } = params;
-        let shell_type = if *use_streamable_shell_tool {
-            ConfigShellToolType::Streamable
-        } else if model_family.uses_local_shell_tool {
-            ConfigShellToolType::Local
-        } else {
-            ConfigShellToolType::Default
-        };
-
-        let apply_patch_tool_type = match model_family.apply_patch_tool_type {
-            Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
-            Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
-            None => {
-                if *include_apply_patch_tool {
-                    Some(ApplyPatchToolType::Freeform)
-                } else {
-                    None
-                }
-            }
-        };
-
         Self {
-            shell_type,
+            shell_type: shell_type_for(model_family, *use_streamable_shell_tool),
             plan_tool: *include_plan_tool,
-            apply_patch_tool_type,
+            apply_patch_tool_type: apply_patch_tool_type_for(
+                model_family,
+                *include_apply_patch_tool,
+            ),
             web_search_request: *include_web_search_request,
             include_view_image_tool: *include_view_image_tool,
             experimental_unified_exec_tool: *experimental_unified_exec_tool,
             experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            include_spawn_agent_tool: *include_spawn_agent_tool,
+            allowed_tools: allowed_tools.map(<[String]>::to_vec),
+        }
+    }
+
+    /// The same tools for a request routed to a model of `model_family`:
+    /// the shell and `apply_patch` flavors follow that model, everything
+    /// else stays as configured for the turn.
+    pub(crate) fn for_model_family(
+        &self,
+        model_family: &ModelFamily,
+        include_apply_patch_tool: bool,
+    ) -> Self {
+        let use_streamable_shell_tool = matches!(self.shell_type, ConfigShellToolType::Streamable);
+        Self {
+            shell_type: shell_type_for(model_family, use_streamable_shell_tool),
+            apply_patch_tool_type: apply_patch_tool_type_for(
+                model_family,
+                include_apply_patch_tool,
+            ),
+            experimental_supported_tools: model_family.experimental_supported_tools.clone(),
+            ..self.clone()
+        }
+    }
+}
+
+fn shell_type_for(
+    model_family: &ModelFamily,
+    use_streamable_shell_tool: bool,
+) -> ConfigShellToolType {
+    if use_streamable_shell_tool {
+        ConfigShellToolType::Streamable
+    } else if model_family.uses_local_shell_tool {
+        ConfigShellToolType::Local
+    } else {
+        ConfigShellToolType::Default
+    }
+}
+
+fn apply_patch_tool_type_for(
+    model_family: &ModelFamily,
+    include_apply_patch_tool: bool,
+) -> Option<ApplyPatchToolType> {
+    match model_family.apply_patch_tool_type {
+        Some(ApplyPatchToolType::Freeform) => Some(ApplyPatchToolType::Freeform),
+        Some(ApplyPatchToolType::Function) => Some(ApplyPatchToolType::Function),
+        None => {
+            if include_apply_patch_tool {
+                Some(ApplyPatchToolType::Freeform)
+            } else {
+                None
+            }
         }
     }
 }
@@ -294,6 +335,70 @@ fn create_read_file_tool() -> ToolSpec {
         },
     })
 }
+
+fn create_spawn_agent_tool() -> ToolSpec {
+    let mut properties = BTreeMap::new();
+    properties.insert(
+        "task".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "What the sub-agent should do, with all the context it needs; it does not see this conversation."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "instructions".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional standing instructions for the sub-agent, e.g. a role or constraints."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "tools".to_string(),
+        JsonSchema::Array {
+            items: Box::new(JsonSchema::String { description: None }),
+            description: Some(
+                "Optional names of the tools the sub-agent may use, e.g. [\"shell\"]. Defaults to all of your tools except spawn_agent."
+                    .to_string(),
+            ),
+        },
+    );
+    properties.insert(
+        "sandbox".to_string(),
+        JsonSchema::String {
+            description: Some(
+                "Optional sandbox for the sub-agent's commands: \"read-only\" or \"workspace-write\". It can only be as strict as yours or stricter; defaults to yours."
+                    .to_string(),
+            ),
+        },
+    );
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "spawn_agent".to_string(),
+        description: r#"Delegates a self-contained task to a sub-agent that works in its own conversation in the same working directory, then returns its final message and the diff of the files it changed.
+Use it for scoped work whose details you do not need to keep in your own context, such as investigating a question or making a well-defined change."#
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["task".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+/// Whether `name` is one of the `allowed` tools. The shell aliases count as
+/// `shell`.
+fn is_tool_allowed(allowed: &[String], name: &str) -> bool {
+    let name = match name {
+        "container.exec" | "local_shell" => "shell",
+        name => name,
+    };
+    allowed.iter().any(|allowed| allowed == name)
+}
 /// TODO(dylan): deprecate once we get rid of json tool
 #[derive(Serialize, Deserialize)]
 pub(crate) struct ApplyPatchToolArgs {
@@ -507,6 +612,7 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::PlanHandler;
     use crate::tools::handlers::ReadFileHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SpawnAgentHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
     use std::sync::Arc;
@@ -586,6 +692,11 @@ pub(crate) fn build_specs(
         builder.register_handler("view_image", view_image_handler);
     }
 
+    if config.include_spawn_agent_tool {
+        builder.push_spec(create_spawn_agent_tool());
+        builder.register_handler("spawn_agent", Arc::new(SpawnAgentHandler));
+    }
+
     if let Some(mcp_tools) = mcp_tools {
         let mut entries: Vec<(String, mcp_types::Tool)> = mcp_tools.into_iter().collect();
         entries.sort_by(|a, b| a.0.cmp(&b.0));
@@ -603,6 +714,10 @@ pub(crate) fn build_specs(
         }
     }
 
+    if let Some(allowed) = &config.allowed_tools {
+        builder.retain_tools(|name| is_tool_allowed(allowed, name));
+    }
+
     builder
 }
 
@@ -651,6 +766,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
         let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
 
@@ -671,6 +788,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
         let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
 
@@ -692,12 +811,80 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: false,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
         let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
 
         assert_eq_tool_names(&tools, &["unified_exec", "read_file"]);
     }
 
+    #[test]
+    fn test_build_specs_spawn_agent_and_allowed_tools() {
+        let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: true,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            experimental_unified_exec_tool: false,
+            include_spawn_agent_tool: true,
+            allowed_tools: None,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell", "update_plan", "spawn_agent"]);
+
+        let allowed = vec!["shell".to_string()];
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &model_family,
+            include_plan_tool: true,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            experimental_unified_exec_tool: false,
+            include_spawn_agent_tool: true,
+            allowed_tools: Some(&allowed),
+        });
+        let (tools, registry) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell"]);
+        assert!(registry.handler("container.exec").is_some());
+        assert!(registry.handler("spawn_agent").is_none());
+    }
+
+    #[test]
+    fn test_tools_config_for_routed_model_family() {
+        let o3 = find_family_for_model("o3").expect("o3 should be a valid model family");
+        let config = ToolsConfig::new(&ToolsConfigParams {
+            model_family: &o3,
+            include_plan_tool: true,
+            include_apply_patch_tool: false,
+            include_web_search_request: false,
+            use_streamable_shell_tool: false,
+            include_view_image_tool: false,
+            experimental_unified_exec_tool: false,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
+        });
+        let (tools, _) = build_specs(&config, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell", "update_plan"]);
+
+        let codex = find_family_for_model("gpt-5-codex")
+            .expect("gpt-5-codex should be a valid model family");
+        let routed = config.for_model_family(&codex, false);
+        let (tools, _) = build_specs(&routed, Some(HashMap::new())).build();
+        assert_eq_tool_names(&tools, &["shell", "update_plan", "read_file"]);
+
+        let routed = config.for_model_family(&codex, true);
+        let (tools, _) = build_specs(&routed, Some(HashMap::new())).build();
+        assert_eq_tool_names(
+            &tools,
+            &["shell", "update_plan", "apply_patch", "read_file"],
+        );
+    }
+
     #[test]
     fn test_build_specs_mcp_tools() {
         let model_family = find_family_for_model("o3").expect("o3 should be a valid model family");
@@ -709,6 +896,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
         let (tools, _) = build_specs(
             &config,
@@ -814,6 +1003,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
 
         // Intentionally construct a map with keys that would sort alphabetically.
@@ -891,6 +1082,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
 
         let (tools, _) = build_specs(
@@ -960,6 +1153,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
 
         let (tools, _) = build_specs(
@@ -1024,6 +1219,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
 
         let (tools, _) = build_specs(
@@ -1091,6 +1288,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
 
         let (tools, _) = build_specs(
@@ -1170,6 +1369,8 @@ mod tests {
             use_streamable_shell_tool: false,
             include_view_image_tool: true,
             experimental_unified_exec_tool: true,
+            include_spawn_agent_tool: false,
+            allowed_tools: None,
         });
         let (tools, _) = build_specs(
             &config,
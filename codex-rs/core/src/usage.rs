@@ -0,0 +1,334 @@
+//! Persistent record of model usage, read back by `codex usage`.
+//!
+//! Every completed model request appends one line to `~/.codex/usage.jsonl`:
+//!
+//! ````text
+//! {"ts":<unix_seconds>,"session_id":"<uuid>","project":"<path>","provider":"openai","model":"gpt-5","usage":{...},"duration_ms":1234}
+//! ````
+//!
+//! `project` is the Git repository root of the session's working directory,
+//! or the directory itself outside a repository. Costs are not stored; they
+//! are estimated from `[pricing]` when the report is made, so changing a
+//! price applies to past usage too.
+
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use chrono::DateTime;
+use chrono::NaiveDate;
+use codex_protocol::ConversationId;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::config_types::Pricing;
+use crate::git_info::get_git_repo_root;
+use crate::protocol::TokenUsage;
+
+/// Filename that stores the usage log inside `~/.codex`.
+const USAGE_FILENAME: &str = "usage.jsonl";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UsageRecord {
+    pub ts: u64,
+    pub session_id: String,
+    pub project: PathBuf,
+    pub provider: String,
+    pub model: String,
+    pub usage: TokenUsage,
+    /// Time spent waiting on the model for this request: opening the stream
+    /// and awaiting its events. Tool calls, approvals and sub-agents run
+    /// while handling the response are not included.
+    pub duration_ms: u64,
+}
+
+/// Append the usage of one model request to the usage log.
+pub(crate) async fn append_record(
+    codex_home: &Path,
+    conversation_id: ConversationId,
+    cwd: &Path,
+    provider: &str,
+    model: &str,
+    usage: TokenUsage,
+    duration: Duration,
+) -> std::io::Result<()> {
+    let record = UsageRecord {
+        ts: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        session_id: conversation_id.to_string(),
+        project: get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf()),
+        provider: provider.to_string(),
+        model: model.to_string(),
+        usage,
+        duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+    };
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+
+    tokio::fs::create_dir_all(codex_home).await?;
+    // A single write of the whole line with O_APPEND keeps concurrent
+    // sessions from interleaving records.
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(codex_home.join(USAGE_FILENAME))
+        .await?;
+    file.write_all(line.as_bytes()).await?;
+    // Tokio finishes writes in the background; wait so the record is in the
+    // log when this returns.
+    file.flush().await
+}
+
+/// Records in the usage log made at or after `since` (unix seconds).
+/// Lines that cannot be parsed are skipped.
+pub fn read_records(codex_home: &Path, since: Option<u64>) -> std::io::Result<Vec<UsageRecord>> {
+    let contents = match std::fs::read_to_string(codex_home.join(USAGE_FILENAME)) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str::<UsageRecord>(line).ok())
+        .filter(|record| since.is_none_or(|since| record.ts >= since))
+        .collect())
+}
+
+/// What `codex usage --by` groups records by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageGrouping {
+    Project,
+    Model,
+    /// Calendar day in UTC.
+    Day,
+    Session,
+}
+
+/// Usage of one group in a report.
+#[derive(Serialize, Debug, Clone, PartialEq, Default)]
+pub struct UsageSummary {
+    pub key: String,
+    pub requests: u64,
+    pub usage: TokenUsage,
+    /// Total time spent waiting on the model, see [`UsageRecord::duration_ms`].
+    pub duration_ms: u64,
+    /// Estimated cost, or `None` when no record in the group has a price.
+    pub cost: Option<f64>,
+}
+
+/// Group `records` and total each group. Days are listed in order; other
+/// groupings are listed by total tokens, largest first.
+pub fn summarize(
+    records: &[UsageRecord],
+    by: UsageGrouping,
+    pricing: &Pricing,
+) -> Vec<UsageSummary> {
+    let mut groups: BTreeMap<String, UsageSummary> = BTreeMap::new();
+    for record in records {
+        let key = match by {
+            UsageGrouping::Project => record.project.display().to_string(),
+            UsageGrouping::Model => format!("{}/{}", record.provider, record.model),
+            UsageGrouping::Day => i64::try_from(record.ts)
+                .ok()
+                .and_then(|ts| DateTime::from_timestamp(ts, 0))
+                .map(|time| time.date_naive().to_string())
+                .unwrap_or_default(),
+            UsageGrouping::Session => record.session_id.clone(),
+        };
+        let summary = groups.entry(key.clone()).or_insert_with(|| UsageSummary {
+            key,
+            ..Default::default()
+        });
+        summary.requests += 1;
+        summary.usage.add_assign(&record.usage);
+        summary.duration_ms = summary.duration_ms.saturating_add(record.duration_ms);
+        if let Some(cost) = estimate_cost(record, pricing) {
+            *summary.cost.get_or_insert(0.0) += cost;
+        }
+    }
+
+    let mut summaries: Vec<UsageSummary> = groups.into_values().collect();
+    if by != UsageGrouping::Day {
+        summaries.sort_by(|a, b| b.usage.total_tokens.cmp(&a.usage.total_tokens));
+    }
+    summaries
+}
+
+/// Estimated cost of one record, or `None` without a price for its model.
+pub fn estimate_cost(record: &UsageRecord, pricing: &Pricing) -> Option<f64> {
+    let price = pricing.price_for(&record.provider, &record.model)?;
+    let per_million = |tokens: u64, price: Option<f64>| {
+        price.map_or(0.0, |price| tokens as f64 * price / 1_000_000.0)
+    };
+    let usage = &record.usage;
+    let hours = record.duration_ms as f64 / 3_600_000.0;
+    Some(
+        per_million(usage.non_cached_input(), price.input)
+            + per_million(usage.cached_input(), price.cached_input.or(price.input))
+            + per_million(usage.output_tokens, price.output)
+            + price.per_hour.map_or(0.0, |price| hours * price),
+    )
+}
+
+/// Parse `--since`: a duration back from `now` such as `30m`, `12h`, `7d`
+/// or `2w`, or a UTC date such as `2025-10-01`. Returns unix seconds.
+pub fn parse_since(value: &str, now: SystemTime) -> Result<u64, String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let ts = date
+            .and_hms_opt(0, 0, 0)
+            .map(|time| time.and_utc().timestamp());
+        return ts
+            .and_then(|ts| u64::try_from(ts).ok())
+            .ok_or_else(|| format!("invalid date `{value}`"));
+    }
+
+    let invalid = || format!("invalid --since `{value}`; use e.g. `7d`, `12h` or `2025-10-01`");
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return Err(invalid()),
+    };
+    let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    Ok(now.saturating_sub(amount.saturating_mul(unit_secs)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_types::Price;
+    use pretty_assertions::assert_eq;
+
+    fn record(ts: u64, model: &str, input: u64, cached: u64, output: u64) -> UsageRecord {
+        UsageRecord {
+            ts,
+            session_id: "s1".to_string(),
+            project: PathBuf::from("/repo"),
+            provider: "openai".to_string(),
+            model: model.to_string(),
+            usage: TokenUsage {
+                input_tokens: input,
+                cached_input_tokens: cached,
+                output_tokens: output,
+                reasoning_output_tokens: 0,
+                total_tokens: input + output,
+            },
+            duration_ms: 1_800_000,
+        }
+    }
+
+    #[test]
+    fn costs_use_model_price_then_provider_price() {
+        let pricing = Pricing {
+            models: [(
+                "gpt-5".to_string(),
+                Price {
+                    input: Some(1.0),
+                    cached_input: Some(0.1),
+                    output: Some(10.0),
+                    per_hour: None,
+                },
+            )]
+            .into(),
+            providers: [(
+                "oss".to_string(),
+                Price {
+                    per_hour: Some(2.0),
+                    ..Default::default()
+                },
+            )]
+            .into(),
+        };
+
+        let cloud = record(0, "gpt-5", 2_000_000, 1_000_000, 100_000);
+        // 1M uncached input at $1, 1M cached at $0.10, 0.1M output at $10.
+        assert_eq!(estimate_cost(&cloud, &pricing), Some(2.1));
+
+        let local = UsageRecord {
+            provider: "oss".to_string(),
+            ..record(0, "gpt-oss:20b", 1_000, 0, 1_000)
+        };
+        // Half an hour at $2/hour.
+        assert_eq!(estimate_cost(&local, &pricing), Some(1.0));
+
+        let unpriced = record(0, "o3", 1_000, 0, 1_000);
+        assert_eq!(estimate_cost(&unpriced, &pricing), None);
+    }
+
+    #[test]
+    fn summarize_groups_by_day_in_order() {
+        let day = 24 * 60 * 60;
+        let records = vec![
+            record(2 * day + 5, "gpt-5", 10, 0, 5),
+            record(day, "gpt-5", 100, 0, 50),
+            record(day + 60, "o3", 1, 0, 1),
+        ];
+
+        let by_day = summarize(&records, UsageGrouping::Day, &Pricing::default());
+        let keys: Vec<(&str, u64, u64)> = by_day
+            .iter()
+            .map(|s| (s.key.as_str(), s.requests, s.usage.total_tokens))
+            .collect();
+        assert_eq!(keys, vec![("1970-01-02", 2, 152), ("1970-01-03", 1, 15)]);
+        assert_eq!(by_day[0].cost, None);
+
+        let by_model = summarize(&records, UsageGrouping::Model, &Pricing::default());
+        let keys: Vec<&str> = by_model.iter().map(|s| s.key.as_str()).collect();
+        assert_eq!(keys, vec!["openai/gpt-5", "openai/o3"]);
+    }
+
+    #[test]
+    fn parse_since_accepts_durations_and_dates() {
+        let now = UNIX_EPOCH + Duration::from_secs(10 * 24 * 60 * 60);
+        assert_eq!(parse_since("7d", now), Ok(3 * 24 * 60 * 60));
+        assert_eq!(parse_since("1w", now), Ok(3 * 24 * 60 * 60));
+        assert_eq!(parse_since("12h", now), Ok(9 * 24 * 60 * 60 + 12 * 60 * 60));
+        assert_eq!(parse_since("1970-01-02", now), Ok(24 * 60 * 60));
+        assert!(parse_since("7 days", now).is_err());
+        assert!(parse_since("d", now).is_err());
+    }
+
+    #[tokio::test]
+    async fn appended_records_are_read_back() {
+        let home = tempfile::tempdir().unwrap();
+        let usage = TokenUsage {
+            input_tokens: 10,
+            output_tokens: 5,
+            total_tokens: 15,
+            ..Default::default()
+        };
+        for _ in 0..2 {
+            append_record(
+                home.path(),
+                ConversationId::new(),
+                home.path(),
+                "openai",
+                "gpt-5",
+                usage.clone(),
+                Duration::from_millis(1500),
+            )
+            .await
+            .unwrap();
+        }
+
+        let records = read_records(home.path(), None).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].duration_ms, 1500);
+        assert_eq!(records[0].project, home.path());
+        assert_eq!(read_records(home.path(), Some(u64::MAX)).unwrap(), vec![]);
+    }
+}
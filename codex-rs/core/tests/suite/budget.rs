@@ -0,0 +1,74 @@
+#![cfg(not(target_os = "windows"))]
+
+use std::time::Duration;
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::TurnAbortReason;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::sse;
+use core_test_support::responses::sse_response;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use wiremock::Mock;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn timeout_aborts_a_slow_request_with_budget_reason() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(
+            sse_response(sse(vec![
+                ev_assistant_message("msg-1", "too late"),
+                ev_completed("resp-1"),
+            ]))
+            .set_delay(Duration::from_secs(30)),
+        )
+        .mount(&server)
+        .await;
+
+    let codex = test_codex()
+        .with_config(|config| {
+            config.budget.timeout_sec = Some(Duration::from_millis(200));
+        })
+        .build(&server)
+        .await?
+        .codex;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+
+    wait_for_event(&codex, |ev| {
+        matches!(
+            ev,
+            EventMsg::BackgroundEvent(ev)
+                if ev.message == "Task budget exceeded: ran for longer than 200ms"
+        )
+    })
+    .await;
+
+    let aborted = wait_for_event(&codex, |ev| {
+        matches!(ev, EventMsg::TurnAborted(_) | EventMsg::TaskComplete(_))
+    })
+    .await;
+    let EventMsg::TurnAborted(aborted) = aborted else {
+        panic!("expected the task to be aborted, got {aborted:?}");
+    };
+    assert_eq!(aborted.reason, TurnAbortReason::BudgetExceeded);
+
+    Ok(())
+}
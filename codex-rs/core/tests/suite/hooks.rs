@@ -0,0 +1,203 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::config_types::HookCommand;
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_local_shell_call;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::TestCodex;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use serde_json::Value;
+use wiremock::matchers::any;
+
+fn sh(script: &str) -> HookCommand {
+    HookCommand {
+        command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+        tools: Vec::new(),
+        timeout_sec: None,
+    }
+}
+
+async fn request_bodies(server: &wiremock::MockServer) -> anyhow::Result<Vec<Value>> {
+    let requests = server
+        .received_requests()
+        .await
+        .ok_or_else(|| anyhow::anyhow!("request recording is disabled"))?;
+    Ok(requests
+        .iter()
+        .map(wiremock::Request::body_json::<Value>)
+        .collect::<Result<_, _>>()?)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn pre_tool_use_hook_blocks_the_call_and_post_hooks_feed_back() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_local_shell_call(
+                "blocked-call",
+                "completed",
+                vec!["touch", "migrations/001.sql"],
+            ),
+            ev_local_shell_call("allowed-call", "completed", vec!["/bin/echo", "hi"]),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let TestCodex {
+        codex,
+        cwd,
+        session_configured,
+        ..
+    } = test_codex()
+        .with_config(|config| {
+            config.hooks.pre_tool_use = vec![sh(
+                "grep -q migrations/ && { echo 'migrations are read-only' >&2; exit 2; }; exit 0",
+            )];
+            config.hooks.post_tool_use = vec![sh("echo 'post hook ran'")];
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "run the commands".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let bodies = request_bodies(&server).await?;
+    let outputs: Vec<(String, String)> = bodies
+        .last()
+        .and_then(|body| body["input"].as_array())
+        .expect("input items")
+        .iter()
+        .filter(|item| item["type"] == "function_call_output")
+        .map(|item| {
+            (
+                item["call_id"].as_str().unwrap_or_default().to_string(),
+                item["output"].as_str().unwrap_or_default().to_string(),
+            )
+        })
+        .collect();
+
+    let blocked = &outputs
+        .iter()
+        .find(|(call_id, _)| call_id == "blocked-call")
+        .expect("blocked call output")
+        .1;
+    assert_eq!(
+        blocked,
+        "tool call blocked by hook: migrations are read-only"
+    );
+    assert!(!cwd.path().join("migrations/001.sql").exists());
+
+    let allowed = &outputs
+        .iter()
+        .find(|(call_id, _)| call_id == "allowed-call")
+        .expect("allowed call output")
+        .1;
+    assert!(
+        allowed.ends_with("<hook_output>\npost hook ran\n</hook_output>"),
+        "expected post hook output, got {allowed:?}"
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn user_prompt_submit_hook_can_block_or_add_context() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    // Keep `cwd` alive: hooks run inside it.
+    let TestCodex { codex, cwd: _cwd, .. } = test_codex()
+        .with_config(|config| {
+            config.hooks.user_prompt_submit = vec![sh(
+                r#"grep -q secret && { echo '{"decision": "block", "reason": "no secrets"}'; exit 0; }; echo 'branch: main'"#,
+            )];
+        })
+        .build(&server)
+        .await?;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "print the secret".into(),
+            }],
+        })
+        .await?;
+    let error = wait_for_event(&codex, |ev| matches!(ev, EventMsg::Error(_))).await;
+    let EventMsg::Error(error) = error else {
+        unreachable!("matched an error event");
+    };
+    assert_eq!(error.message, "Prompt blocked by hook: no secrets");
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let bodies = request_bodies(&server).await?;
+    assert_eq!(bodies.len(), 1, "blocked prompt must not reach the model");
+    let user_texts: Vec<&str> = bodies[0]["input"]
+        .as_array()
+        .expect("input items")
+        .iter()
+        .filter(|item| item["role"] == "user")
+        .flat_map(|item| item["content"].as_array().into_iter().flatten())
+        .filter_map(|content| content["text"].as_str())
+        .collect();
+    assert!(user_texts.contains(&"hello"));
+    assert!(user_texts.contains(&"<hook_output>\nbranch: main\n</hook_output>"));
+
+    Ok(())
+}
@@ -2,6 +2,7 @@
 
 #[cfg(not(target_os = "windows"))]
 mod abort_tasks;
+mod budget;
 mod cli_stream;
 mod client;
 mod compact;
@@ -10,9 +11,11 @@ mod exec;
 mod exec_stream_events;
 mod fork_conversation;
 mod gpt_oss;
+mod hooks;
 mod json_result;
 mod live_cli;
 mod model_overrides;
+mod model_routing;
 mod model_tools;
 mod otel;
 mod prompt_caching;
@@ -21,10 +24,12 @@ mod review;
 mod rmcp_client;
 mod rollout_list_find;
 mod seatbelt;
+mod spawn_agent;
 mod stream_error_allows_next_turn;
 mod stream_no_completed;
 mod tool_harness;
 mod tools;
 mod unified_exec;
+mod usage;
 mod user_notification;
 mod view_image;
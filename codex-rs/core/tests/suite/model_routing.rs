@@ -0,0 +1,189 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::ModelProviderInfo;
+use codex_core::built_in_model_providers;
+use codex_core::config_types::ModelFallback;
+use codex_core::config_types::ModelRoute;
+use codex_core::config_types::ModelRoutes;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+use wiremock::Mock;
+use wiremock::ResponseTemplate;
+use wiremock::matchers::method;
+use wiremock::matchers::path;
+
+/// The first request of a turn goes to the planning model and the rest to
+/// the editing model, until invalid tool calls switch to the fallback.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_use_role_models_and_fall_back_after_invalid_tool_calls() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let plan_args = json!({
+        "plan": [{"step": "Look around", "status": "in_progress"}],
+    });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call("call-1", "update_plan", &plan_args.to_string()),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_function_call("call-2", "no_such_tool", "{}"),
+                ev_completed("resp-2"),
+            ]),
+            sse(vec![
+                ev_function_call("call-3", "no_such_tool", "{}"),
+                ev_completed("resp-3"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "Done."),
+                ev_completed("resp-4"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.include_plan_tool = true;
+            config.model_routes = ModelRoutes {
+                planning: Some(ModelRoute::Model("planner-model".to_string())),
+                editing: Some(ModelRoute::Model("editor-model".to_string())),
+                fallback: Some(ModelFallback {
+                    model: "fallback-model".to_string(),
+                    provider: None,
+                    after_invalid_tool_calls: 2,
+                }),
+                ..Default::default()
+            };
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "Tidy up.".into(),
+            }],
+        })
+        .await?;
+
+    let switched = wait_for_event(
+        &test.codex,
+        |ev| matches!(ev, EventMsg::BackgroundEvent(ev) if ev.message.contains("fallback-model")),
+    )
+    .await;
+    assert!(matches!(switched, EventMsg::BackgroundEvent(_)));
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.expect("requests recorded");
+    let models = requests
+        .iter()
+        .map(wiremock::Request::body_json::<Value>)
+        .map(|body| body.map(|body| body["model"].as_str().unwrap_or_default().to_string()))
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        models,
+        vec![
+            "planner-model",
+            "editor-model",
+            "editor-model",
+            "fallback-model"
+        ]
+    );
+
+    Ok(())
+}
+
+/// A provider that keeps failing with 5xx hands the turn to the next
+/// failover provider once its stream retries are used up, and the switch is
+/// reported.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failing_provider_fails_over_to_the_next_one() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let down = start_mock_server().await;
+    Mock::given(method("POST"))
+        .and(path("/v1/responses"))
+        .respond_with(ResponseTemplate::new(503))
+        .mount(&down)
+        .await;
+    let backup = start_mock_server().await;
+    mount_sse_sequence(
+        &backup,
+        vec![sse(vec![
+            ev_assistant_message("msg-1", "Answered by the backup."),
+            ev_completed("resp-1"),
+        ])],
+    )
+    .await;
+
+    let backup_uri = backup.uri();
+    let test = test_codex()
+        .with_config(move |config| {
+            config.model_provider.request_max_retries = Some(0);
+            config.model_provider.stream_max_retries = Some(1);
+            config.model_providers.insert(
+                "backup".to_string(),
+                ModelProviderInfo {
+                    name: "Backup".to_string(),
+                    base_url: Some(format!("{backup_uri}/v1")),
+                    ..built_in_model_providers()["openai"].clone()
+                },
+            );
+            config.model_routes.failover = vec![ModelRoute::WithProvider {
+                model: "backup-model".to_string(),
+                provider: Some("backup".to_string()),
+            }];
+        })
+        .build(&down)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "Hello?".into(),
+            }],
+        })
+        .await?;
+
+    let switched =
+        wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::BackgroundEvent(_))).await;
+    let EventMsg::BackgroundEvent(switched) = switched else {
+        unreachable!("waited for BackgroundEvent");
+    };
+    assert!(
+        switched
+            .message
+            .contains("switched to provider `backup` with model `backup-model`"),
+        "unexpected message: {}",
+        switched.message
+    );
+    let message = wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::AgentMessage(_))).await;
+    let EventMsg::AgentMessage(message) = message else {
+        unreachable!("waited for AgentMessage");
+    };
+    assert_eq!(message.message, "Answered by the backup.");
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let failed = down.received_requests().await.expect("requests recorded");
+    assert_eq!(failed.len(), 2);
+    let served = backup.received_requests().await.expect("requests recorded");
+    assert_eq!(served.len(), 1);
+    assert_eq!(served[0].body_json::<Value>()?["model"], "backup-model");
+
+    Ok(())
+}
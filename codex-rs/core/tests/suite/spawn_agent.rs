@@ -0,0 +1,200 @@
+#![cfg(not(target_os = "windows"))]
+
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::TurnAbortReason;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+use serde_json::json;
+
+fn tool_names(body: &Value) -> Vec<String> {
+    body["tools"]
+        .as_array()
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| tool["name"].as_str().or(tool["type"].as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The parent delegates a task; the child runs in its own conversation with
+/// the requested instructions and tools, its activity is reported nested,
+/// and its final message is returned to the parent as the tool output.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spawn_agent_runs_child_and_returns_its_summary() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({
+        "task": "Count the TODO comments in src.",
+        "instructions": "Only report the number.",
+        "tools": ["shell"],
+        "sandbox": "read-only",
+    });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call("spawn-1", "spawn_agent", &args.to_string()),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-child", "There are 3."),
+                ev_completed("resp-child"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "The sub-agent found 3 TODOs."),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| config.include_spawn_agent_tool = true)
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "How many TODOs are there?".into(),
+            }],
+        })
+        .await?;
+
+    let begin = wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::SubAgentBegin(_))).await;
+    let EventMsg::SubAgentBegin(begin) = begin else {
+        unreachable!("waited for SubAgentBegin");
+    };
+    assert_eq!(begin.call_id, "spawn-1");
+    assert_eq!(begin.task, "Count the TODO comments in src.");
+
+    let activity = wait_for_event(&test.codex, |ev| {
+        matches!(ev, EventMsg::SubAgentActivity(activity)
+            if matches!(*activity.msg, EventMsg::AgentMessage(_)))
+    })
+    .await;
+    let EventMsg::SubAgentActivity(activity) = activity else {
+        unreachable!("waited for SubAgentActivity");
+    };
+    let EventMsg::AgentMessage(message) = *activity.msg else {
+        unreachable!("waited for an agent message");
+    };
+    assert_eq!(message.message, "There are 3.");
+
+    let end = wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::SubAgentEnd(_))).await;
+    let EventMsg::SubAgentEnd(end) = end else {
+        unreachable!("waited for SubAgentEnd");
+    };
+    assert!(end.success);
+    assert_eq!(end.last_agent_message.as_deref(), Some("There are 3."));
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let requests = server.received_requests().await.expect("requests recorded");
+    assert_eq!(requests.len(), 3);
+    let bodies: Vec<Value> = requests
+        .iter()
+        .map(wiremock::Request::body_json::<Value>)
+        .collect::<Result<_, _>>()?;
+
+    assert!(tool_names(&bodies[0]).contains(&"spawn_agent".to_string()));
+    // The child only gets the requested tools, and never spawn_agent.
+    assert_eq!(tool_names(&bodies[1]), vec!["shell".to_string()]);
+    let child_input = bodies[1]["input"].to_string();
+    assert!(child_input.contains("Only report the number."));
+    assert!(child_input.contains("Count the TODO comments in src."));
+    assert!(!child_input.contains("How many TODOs are there?"));
+
+    let output = bodies[2]["input"]
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .find(|item| item["type"] == "function_call_output")
+        })
+        .and_then(|item| item["output"].as_str())
+        .expect("spawn_agent output sent back to the parent");
+    let output: Value = serde_json::from_str(output)?;
+    assert_eq!(output["status"], "completed");
+    assert_eq!(output["summary"], "There are 3.");
+
+    Ok(())
+}
+
+/// What a sub-agent uses counts against the parent's budget, so delegating
+/// cannot get around `max_total_tokens`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spawn_agent_usage_counts_against_the_parent_budget() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({ "task": "Summarize the repository." });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call("spawn-1", "spawn_agent", &args.to_string()),
+                ev_completed_with_tokens("resp-1", 100),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-child", "A small Rust workspace."),
+                ev_completed_with_tokens("resp-child", 950),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-2", "Over budget, but still answering."),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex()
+        .with_config(|config| {
+            config.include_spawn_agent_tool = true;
+            config.budget.max_total_tokens = Some(1_000);
+        })
+        .build(&server)
+        .await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "What is in this repository?".into(),
+            }],
+        })
+        .await?;
+
+    let end = wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::SubAgentEnd(_))).await;
+    let EventMsg::SubAgentEnd(end) = end else {
+        unreachable!("waited for SubAgentEnd");
+    };
+    assert!(end.success);
+
+    let aborted = wait_for_event(&test.codex, |ev| {
+        matches!(ev, EventMsg::TurnAborted(_) | EventMsg::TaskComplete(_))
+    })
+    .await;
+    let EventMsg::TurnAborted(aborted) = aborted else {
+        panic!("expected the parent to stop at its budget, got {aborted:?}");
+    };
+    assert_eq!(aborted.reason, TurnAbortReason::BudgetExceeded);
+
+    // The parent never sends the request after the sub-agent returns.
+    let requests = server.received_requests().await.expect("requests recorded");
+    assert_eq!(requests.len(), 2);
+
+    Ok(())
+}
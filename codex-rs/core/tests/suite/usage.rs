@@ -0,0 +1,131 @@
+#![cfg(not(target_os = "windows"))]
+
+use std::time::Duration;
+
+use codex_core::protocol::AskForApproval;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SandboxPolicy;
+use codex_core::usage::read_records;
+use codex_protocol::config_types::ReasoningSummary;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed_with_tokens;
+use core_test_support::responses::ev_function_call;
+use core_test_support::responses::mount_sse_once_match;
+use core_test_support::responses::mount_sse_sequence;
+use core_test_support::responses::sse;
+use core_test_support::responses::start_mock_server;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex::test_codex;
+use core_test_support::wait_for_event;
+use pretty_assertions::assert_eq;
+use serde_json::json;
+use wiremock::matchers::any;
+
+/// Each completed model request is appended to the usage log with its
+/// session, model and token counts.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn completed_request_is_logged_for_codex_usage() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_assistant_message("msg-1", "done"),
+            ev_completed_with_tokens("resp-1", 42),
+        ]),
+    )
+    .await;
+
+    let test = test_codex().build(&server).await?;
+    test.codex
+        .submit(Op::UserInput {
+            items: vec![InputItem::Text {
+                text: "hello".into(),
+            }],
+        })
+        .await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    // The record is written in the background; give it a moment.
+    let mut records = Vec::new();
+    for _ in 0..50 {
+        records = read_records(test.home.path(), None)?;
+        if !records.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+
+    assert_eq!(records.len(), 1);
+    let record = &records[0];
+    assert_eq!(
+        record.session_id,
+        test.session_configured.session_id.to_string()
+    );
+    assert_eq!(record.model, test.session_configured.model);
+    assert_eq!(record.provider, "openai");
+    assert_eq!(record.usage.total_tokens, 42);
+    assert_eq!(record.project, test.cwd.path().to_path_buf());
+
+    Ok(())
+}
+
+/// The logged duration covers waiting on the model, not the tool calls run
+/// while its response streams in.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn logged_duration_excludes_tool_call_time() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let args = json!({
+        "command": ["/bin/sh", "-c", "sleep 2"],
+        "timeout_ms": 10_000,
+    });
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_function_call("call-sleep", "shell", &serde_json::to_string(&args)?),
+                ev_completed_with_tokens("resp-1", 10),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed_with_tokens("resp-2", 20),
+            ]),
+        ],
+    )
+    .await;
+
+    let test = test_codex().build(&server).await?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text {
+                text: "sleep for a while".into(),
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: ReasoningSummary::Auto,
+        })
+        .await?;
+    wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TaskComplete(_))).await;
+
+    let records = read_records(test.home.path(), None)?;
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert!(
+            record.duration_ms < 1_000,
+            "tool time counted as model time: {}ms",
+            record.duration_ms
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+//! Delegates approval requests to an external program (`--approval-command`)
+//! so that headless runs can escalate without a human at the keyboard.
+//!
+//! The program is spawned once per request. It receives the request event as
+//! a single JSON object on stdin (the same shape `--json` consumers see for
+//! `exec_approval_request` / `apply_patch_approval_request`) and answers with
+//! a `ReviewDecision` on stdout, either as a bare word (`approved`) or as
+//! `{"decision": "approved"}`. Anything else, including a non-zero exit or
+//! no answer within the timeout, counts as `denied`.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use codex_core::protocol::Event;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::Op;
+use codex_core::protocol::ReviewDecision;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+pub(crate) struct ApprovalCommand {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+#[derive(Deserialize)]
+struct DecisionResponse {
+    decision: ReviewDecision,
+}
+
+impl ApprovalCommand {
+    /// Parse a shell-style command line such as `./approve.sh --strict`. The
+    /// program gets `timeout_secs` seconds to answer each request.
+    pub(crate) fn parse(command_line: &str, timeout_secs: u64) -> anyhow::Result<Self> {
+        let mut words = shlex::split(command_line)
+            .ok_or_else(|| anyhow::anyhow!("invalid --approval-command: {command_line}"))?
+            .into_iter();
+        let program = words
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("--approval-command must not be empty"))?;
+        Ok(Self {
+            program,
+            args: words.collect(),
+            timeout: Duration::from_secs(timeout_secs),
+        })
+    }
+
+    /// If `event` is an approval request, ask the program for a decision and
+    /// return the op that answers the request.
+    pub(crate) async fn respond_to(&self, event: &Event) -> Option<Op> {
+        let summary = match &event.msg {
+            EventMsg::ExecApprovalRequest(ev) => format!("command `{}`", ev.command.join(" ")),
+            EventMsg::ApplyPatchApprovalRequest(ev) => {
+                format!("patch touching {} file(s)", ev.changes.len())
+            }
+            _ => return None,
+        };
+
+        let decision = match self.review(&event.msg).await {
+            Ok(decision) => decision,
+            Err(err) => {
+                eprintln!("approval command failed, denying {summary}: {err}");
+                ReviewDecision::Denied
+            }
+        };
+        eprintln!("approval command answered {decision} for {summary}");
+
+        let id = event.id.clone();
+        Some(match event.msg {
+            EventMsg::ExecApprovalRequest(_) => Op::ExecApproval { id, decision },
+            _ => Op::PatchApproval { id, decision },
+        })
+    }
+
+    async fn review(&self, msg: &EventMsg) -> anyhow::Result<ReviewDecision> {
+        let request = serde_json::to_vec(msg)?;
+
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| anyhow::anyhow!("failed to spawn `{}`: {err}", self.program))?;
+
+        let stdin = child.stdin.take();
+        let answer = async move {
+            if let Some(mut stdin) = stdin {
+                // The program may decide without reading its input; a closed
+                // pipe is not an error.
+                let _ = stdin.write_all(&request).await;
+            }
+            child.wait_with_output().await
+        };
+        // On timeout the child is dropped, and `kill_on_drop` kills it.
+        let output = tokio::time::timeout(self.timeout, answer)
+            .await
+            .map_err(|_| {
+                anyhow::anyhow!(
+                    "`{}` did not answer within {}s",
+                    self.program,
+                    self.timeout.as_secs()
+                )
+            })??;
+        if !output.status.success() {
+            anyhow::bail!("`{}` exited with {}", self.program, output.status);
+        }
+        parse_decision(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+fn parse_decision(stdout: &str) -> anyhow::Result<ReviewDecision> {
+    let stdout = stdout.trim();
+    if stdout.starts_with('{') {
+        let response: DecisionResponse = serde_json::from_str(stdout)?;
+        return Ok(response.decision);
+    }
+    serde_json::from_value(serde_json::Value::String(stdout.to_string()))
+        .map_err(|_| anyhow::anyhow!("unrecognized decision {stdout:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_bare_and_json_decisions() {
+        assert_eq!(
+            parse_decision("approved\n").unwrap(),
+            ReviewDecision::Approved
+        );
+        assert_eq!(
+            parse_decision(r#"{"decision": "approved_for_session"}"#).unwrap(),
+            ReviewDecision::ApprovedForSession
+        );
+        assert_eq!(parse_decision("abort").unwrap(), ReviewDecision::Abort);
+        assert!(parse_decision("yes please").is_err());
+        assert!(parse_decision("").is_err());
+    }
+}
@@ -0,0 +1,492 @@
+//! `codex exec batch`: runs the tasks listed in a JSONL file as independent
+//! sessions, several at a time, and summarizes how each one went.
+//!
+//! Every task gets a directory under `--output-dir` with `events.jsonl` (the
+//! raw protocol events), `last_message.txt` and a copy of the session's
+//! rollout. `summary.json` and a table on stdout report each task's status,
+//! exit code, token usage and changed files. Tasks that share a `cwd` run
+//! one after another so their changed files can be told apart.
+
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+
+use anyhow::Context;
+use codex_common::elapsed::format_duration;
+use codex_core::AuthManager;
+use codex_core::ConversationManager;
+use codex_core::NewConversation;
+use codex_core::config::Config;
+use codex_core::config::ConfigOverrides;
+use codex_core::git_info::get_git_repo_root;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputItem;
+use codex_core::protocol::Op;
+use codex_core::protocol::SessionSource;
+use codex_core::protocol::TurnAbortReason;
+use codex_protocol::num_format::format_with_separators;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::Mutex;
+use tokio::sync::Semaphore;
+
+use crate::EXIT_CODE_BUDGET_EXCEEDED;
+use crate::approval_command::ApprovalCommand;
+use crate::cli::BatchArgs;
+
+/// One line of the tasks file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchTask {
+    /// Name for the task's output directory; defaults to `task-<line>`.
+    id: Option<String>,
+    prompt: String,
+    cwd: PathBuf,
+    /// Config overrides for this task, keyed like `-c` (dotted paths allowed).
+    #[serde(default)]
+    config: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Settings shared by every task, taken from the `codex exec` flags.
+pub(crate) struct BatchSettings {
+    pub(crate) overrides: ConfigOverrides,
+    pub(crate) cli_kv_overrides: Vec<(String, toml::Value)>,
+    pub(crate) skip_git_repo_check: bool,
+    pub(crate) approval_command: Option<Arc<ApprovalCommand>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TaskStatus {
+    Completed,
+    Failed,
+    BudgetExceeded,
+}
+
+#[derive(Debug, Serialize)]
+struct TaskSummary {
+    id: String,
+    cwd: PathBuf,
+    status: TaskStatus,
+    /// What `codex exec` would have exited with for this task alone.
+    exit_code: i32,
+    tokens_used: u64,
+    changed_files: Vec<String>,
+    duration_secs: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Run every task in `args.tasks_file` and report on them. Returns whether
+/// all tasks completed.
+pub(crate) async fn run_batch(
+    args: &BatchArgs,
+    settings: BatchSettings,
+    auth_manager: Arc<AuthManager>,
+) -> anyhow::Result<bool> {
+    let tasks = read_tasks(&args.tasks_file)?;
+    std::fs::create_dir_all(&args.output_dir).with_context(|| {
+        format!(
+            "failed to create output directory {}",
+            args.output_dir.display()
+        )
+    })?;
+
+    let manager = Arc::new(ConversationManager::new(auth_manager, SessionSource::Exec));
+    let settings = Arc::new(settings);
+    let jobs = Arc::new(Semaphore::new(args.jobs.max(1)));
+    let mut cwd_locks: HashMap<PathBuf, Arc<Mutex<()>>> = HashMap::new();
+
+    let mut handles = Vec::with_capacity(tasks.len());
+    for (id, task) in tasks {
+        let task_dir = args.output_dir.join(&id);
+        let manager = Arc::clone(&manager);
+        let settings = Arc::clone(&settings);
+        let jobs = Arc::clone(&jobs);
+        let cwd_key = task.cwd.canonicalize().unwrap_or_else(|_| task.cwd.clone());
+        let cwd_lock = Arc::clone(cwd_locks.entry(cwd_key).or_default());
+        handles.push(tokio::spawn(async move {
+            // Take the cwd first so a waiting task does not hold a job slot.
+            let _cwd = cwd_lock.lock_owned().await;
+            let _permit = jobs.acquire_owned().await;
+            eprintln!("[{id}] started in {}", task.cwd.display());
+            let started = Instant::now();
+            let cwd = task.cwd.clone();
+            let mut summary = match run_one(&manager, &settings, task, &task_dir).await {
+                Ok(summary) => summary,
+                Err(err) => TaskSummary {
+                    id: id.clone(),
+                    cwd,
+                    status: TaskStatus::Failed,
+                    exit_code: 1,
+                    tokens_used: 0,
+                    changed_files: Vec::new(),
+                    duration_secs: 0.0,
+                    error: Some(format!("{err:#}")),
+                },
+            };
+            summary.id = id;
+            summary.duration_secs = started.elapsed().as_secs_f64();
+            eprintln!(
+                "[{}] {} in {}",
+                summary.id,
+                status_label(summary.status),
+                format_duration(started.elapsed())
+            );
+            summary
+        }));
+    }
+
+    let mut summaries = Vec::with_capacity(handles.len());
+    for handle in handles {
+        summaries.push(handle.await?);
+    }
+
+    let summary_path = args.output_dir.join("summary.json");
+    std::fs::write(&summary_path, serde_json::to_string_pretty(&summaries)?)
+        .with_context(|| format!("failed to write {}", summary_path.display()))?;
+    print_summary_table(&summaries);
+
+    Ok(summaries
+        .iter()
+        .all(|summary| summary.status == TaskStatus::Completed))
+}
+
+/// Parse the tasks file and assign each task a unique id.
+fn read_tasks(path: &Path) -> anyhow::Result<Vec<(String, BatchTask)>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read tasks file {}", path.display()))?;
+    let mut seen = HashSet::new();
+    let mut tasks = Vec::new();
+    for (idx, line) in contents.lines().enumerate() {
+        let line_number = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let task: BatchTask = serde_json::from_str(line)
+            .with_context(|| format!("{}:{line_number}: invalid task", path.display()))?;
+        let id = sanitize_id(task.id.as_deref().unwrap_or(&format!("task-{line_number}")));
+        if id.is_empty() || id.chars().all(|c| c == '.') || id == "summary.json" {
+            anyhow::bail!(
+                "{}:{line_number}: task id `{id}` cannot be used as a directory name",
+                path.display()
+            );
+        }
+        if !seen.insert(id.clone()) {
+            anyhow::bail!("{}:{line_number}: duplicate task id `{id}`", path.display());
+        }
+        tasks.push((id, task));
+    }
+    if tasks.is_empty() {
+        anyhow::bail!("no tasks in {}", path.display());
+    }
+    Ok(tasks)
+}
+
+/// Keep ids usable as directory names.
+fn sanitize_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+async fn run_one(
+    manager: &ConversationManager,
+    settings: &BatchSettings,
+    task: BatchTask,
+    task_dir: &Path,
+) -> anyhow::Result<TaskSummary> {
+    std::fs::create_dir_all(task_dir)
+        .with_context(|| format!("failed to create {}", task_dir.display()))?;
+    let cwd = task.cwd.canonicalize().unwrap_or(task.cwd);
+
+    let mut cli_kv_overrides = settings.cli_kv_overrides.clone();
+    cli_kv_overrides.extend(
+        task.config
+            .into_iter()
+            .map(|(key, value)| (key, codex_utils_json_to_toml::json_to_toml(value))),
+    );
+    let overrides = ConfigOverrides {
+        cwd: Some(cwd.clone()),
+        ..settings.overrides.clone()
+    };
+    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+
+    if !settings.skip_git_repo_check && get_git_repo_root(&config.cwd).is_none() {
+        anyhow::bail!(
+            "{} is not inside a git repository and --skip-git-repo-check was not specified",
+            config.cwd.display()
+        );
+    }
+
+    let status_before = git_status(&config.cwd).await;
+
+    let NewConversation {
+        conversation_id,
+        conversation,
+        session_configured,
+    } = manager.new_conversation(config.clone()).await?;
+
+    conversation
+        .submit(Op::UserTurn {
+            items: vec![InputItem::Text { text: task.prompt }],
+            cwd: config.cwd.clone(),
+            approval_policy: config.approval_policy,
+            sandbox_policy: config.sandbox_policy.clone(),
+            model: config.model.clone(),
+            effort: config.model_reasoning_effort,
+            summary: config.model_reasoning_summary,
+            final_output_json_schema: None,
+        })
+        .await?;
+
+    let events_path = task_dir.join("events.jsonl");
+    let mut events = std::io::BufWriter::new(
+        std::fs::File::create(&events_path)
+            .with_context(|| format!("failed to create {}", events_path.display()))?,
+    );
+    let mut status = TaskStatus::Completed;
+    let mut error = None;
+    let mut tokens_used = 0;
+    let mut last_message = None;
+    loop {
+        let event = conversation.next_event().await?;
+        writeln!(events, "{}", serde_json::to_string(&event)?)?;
+        match &event.msg {
+            EventMsg::Error(ev) => {
+                status = TaskStatus::Failed;
+                error = Some(ev.message.clone());
+            }
+            EventMsg::TokenCount(ev) => {
+                if let Some(info) = &ev.info {
+                    tokens_used = info.total_token_usage.blended_total();
+                }
+            }
+            EventMsg::TaskComplete(ev) => {
+                last_message = ev.last_agent_message.clone();
+                conversation.submit(Op::Shutdown).await?;
+            }
+            EventMsg::TurnAborted(ev) => {
+                if ev.reason == TurnAbortReason::BudgetExceeded {
+                    status = TaskStatus::BudgetExceeded;
+                } else {
+                    status = TaskStatus::Failed;
+                    error = Some(format!("turn aborted: {:?}", ev.reason));
+                }
+                conversation.submit(Op::Shutdown).await?;
+            }
+            EventMsg::ShutdownComplete => break,
+            _ => {}
+        }
+        if let Some(approval_command) = settings.approval_command.as_deref()
+            && let Some(op) = approval_command.respond_to(&event).await
+        {
+            conversation.submit(op).await?;
+        }
+    }
+    events.flush()?;
+    manager.remove_conversation(&conversation_id).await;
+
+    std::fs::write(
+        task_dir.join("last_message.txt"),
+        last_message.unwrap_or_default(),
+    )?;
+    if let Err(err) = std::fs::copy(
+        &session_configured.rollout_path,
+        task_dir.join("rollout.jsonl"),
+    ) {
+        tracing::warn!(
+            "failed to copy rollout {}: {err}",
+            session_configured.rollout_path.display()
+        );
+    }
+
+    let changed_files = match (status_before, git_status(&config.cwd).await) {
+        (Some(before), Some(after)) => changed_paths(&before, &after),
+        _ => Vec::new(),
+    };
+
+    Ok(TaskSummary {
+        id: String::new(),
+        cwd: config.cwd.clone(),
+        status,
+        exit_code: match status {
+            TaskStatus::Completed => 0,
+            TaskStatus::Failed => 1,
+            TaskStatus::BudgetExceeded => EXIT_CODE_BUDGET_EXCEEDED,
+        },
+        tokens_used,
+        changed_files,
+        duration_secs: 0.0,
+        error,
+    })
+}
+
+/// `git status --porcelain` lines for `cwd`, or `None` outside a repository.
+async fn git_status(cwd: &Path) -> Option<BTreeSet<String>> {
+    let output = tokio::process::Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=all"])
+        .current_dir(cwd)
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_string)
+            .collect(),
+    )
+}
+
+/// Paths whose status changed during the task. Files that were already
+/// dirty beforehand and kept the same status are not counted.
+fn changed_paths(before: &BTreeSet<String>, after: &BTreeSet<String>) -> Vec<String> {
+    after
+        .difference(before)
+        .filter_map(|line| line.get(3..))
+        .map(str::to_string)
+        .collect()
+}
+
+fn status_label(status: TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Completed => "completed",
+        TaskStatus::Failed => "failed",
+        TaskStatus::BudgetExceeded => "budget exceeded",
+    }
+}
+
+// The summary table is the batch's final output, so it goes to stdout.
+#[allow(clippy::print_stdout)]
+fn print_summary_table(summaries: &[TaskSummary]) {
+    let rows: Vec<[String; 5]> = summaries
+        .iter()
+        .map(|summary| {
+            [
+                summary.id.clone(),
+                status_label(summary.status).to_string(),
+                summary.exit_code.to_string(),
+                format_with_separators(summary.tokens_used),
+                summary.changed_files.len().to_string(),
+            ]
+        })
+        .collect();
+    let header = ["TASK", "STATUS", "EXIT", "TOKENS", "CHANGED FILES"].map(str::to_string);
+    let mut widths = header.clone().map(|cell| cell.len());
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    for row in std::iter::once(&header).chain(&rows) {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reads_tasks_with_default_and_sanitized_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        std::fs::write(
+            &path,
+            r#"{"prompt": "migrate", "cwd": "repos/a"}
+
+{"id": "repo b", "prompt": "migrate", "cwd": "repos/b", "config": {"model": "o3"}}
+"#,
+        )
+        .unwrap();
+
+        let tasks = read_tasks(&path).unwrap();
+        let ids: Vec<&str> = tasks.iter().map(|(id, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["task-1", "repo_b"]);
+        assert_eq!(tasks[1].1.config["model"], "o3");
+    }
+
+    #[test]
+    fn rejects_duplicate_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        std::fs::write(
+            &path,
+            "{\"id\": \"a\", \"prompt\": \"x\", \"cwd\": \".\"}\n{\"id\": \"a\", \"prompt\": \"y\", \"cwd\": \".\"}\n",
+        )
+        .unwrap();
+
+        let err = read_tasks(&path).unwrap_err();
+        assert!(err.to_string().contains("duplicate task id `a`"), "{err}");
+    }
+
+    #[test]
+    fn rejects_ids_that_collide_once_sanitized() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        std::fs::write(
+            &path,
+            "{\"id\": \"a b\", \"prompt\": \"x\", \"cwd\": \".\"}\n{\"id\": \"a_b\", \"prompt\": \"y\", \"cwd\": \".\"}\n",
+        )
+        .unwrap();
+
+        let err = read_tasks(&path).unwrap_err();
+        assert!(err.to_string().contains("duplicate task id `a_b`"), "{err}");
+    }
+
+    #[test]
+    fn rejects_ids_outside_the_task_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tasks.jsonl");
+        for id in ["..", ".", "summary.json", ""] {
+            std::fs::write(
+                &path,
+                format!("{{\"id\": \"{id}\", \"prompt\": \"x\", \"cwd\": \".\"}}\n"),
+            )
+            .unwrap();
+
+            let err = read_tasks(&path).unwrap_err();
+            assert!(
+                err.to_string()
+                    .contains("cannot be used as a directory name"),
+                "{id}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn changed_paths_ignores_files_dirty_before_the_task() {
+        let before: BTreeSet<String> = [" M README.md".to_string()].into();
+        let after: BTreeSet<String> = [
+            " M README.md".to_string(),
+            " M src/lib.rs".to_string(),
+            "?? src/new.rs".to_string(),
+        ]
+        .into();
+        assert_eq!(
+            changed_paths(&before, &after),
+            vec!["src/lib.rs".to_string(), "src/new.rs".to_string()]
+        );
+    }
+}
@@ -3,6 +3,8 @@ use clap::ValueEnum;
 use codex_common::CliConfigOverrides;
 use std::path::PathBuf;
 
+use crate::review_report::ReviewPriority;
+
 #[derive(Parser, Debug)]
 #[command(version)]
 pub struct Cli {
@@ -44,6 +46,27 @@ pub struct Cli {
     )]
     pub dangerously_bypass_approvals_and_sandbox: bool,
 
+    /// Program that decides approval requests instead of a human. It receives
+    /// each request as JSON on stdin and prints a decision (`approved`,
+    /// `approved_for_session`, `denied` or `abort`) on stdout. Without it,
+    /// approvals are never requested.
+    #[arg(
+        long = "approval-command",
+        value_name = "COMMAND",
+        conflicts_with = "dangerously_bypass_approvals_and_sandbox"
+    )]
+    pub approval_command: Option<String>,
+
+    /// Deny an approval request when `--approval-command` has not answered
+    /// within this many seconds.
+    #[arg(
+        long = "approval-timeout",
+        value_name = "SECONDS",
+        default_value_t = 60,
+        requires = "approval_command"
+    )]
+    pub approval_timeout: u64,
+
     /// Tell the agent to use the specified directory as its working root.
     #[clap(long = "cd", short = 'C', value_name = "DIR")]
     pub cwd: Option<PathBuf>,
@@ -71,6 +94,31 @@ pub struct Cli {
     #[arg(long = "include-plan-tool", default_value_t = false)]
     pub include_plan_tool: bool,
 
+    /// Abort the task after this many model requests.
+    #[arg(long = "max-turns", value_name = "N")]
+    pub max_turns: Option<u32>,
+
+    /// Abort the task once it has used this many tokens.
+    #[arg(long = "max-total-tokens", value_name = "N")]
+    pub max_total_tokens: Option<u64>,
+
+    /// Abort the task after this many tool calls.
+    #[arg(long = "max-tool-calls", value_name = "N")]
+    pub max_tool_calls: Option<u32>,
+
+    /// Abort the task after it has run for this many seconds.
+    #[arg(long = "timeout", value_name = "SECONDS")]
+    pub timeout: Option<u64>,
+
+    /// Append every model response to this cassette file for later `--replay`.
+    #[arg(long = "record", value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+
+    /// Serve model responses from this cassette or rollout file instead of
+    /// calling a model.
+    #[arg(long = "replay", value_name = "FILE", conflicts_with = "oss")]
+    pub replay: Option<PathBuf>,
+
     /// Specifies file where the last message from the agent should be written.
     #[arg(long = "output-last-message", short = 'o', value_name = "FILE")]
     pub last_message_file: Option<PathBuf>,
@@ -85,6 +133,13 @@ pub struct Cli {
 pub enum Command {
     /// Resume a previous session by id or pick the most recent with --last.
     Resume(ResumeArgs),
+
+    /// Review code changes and report the findings, e.g. as a CI gate.
+    Review(ReviewArgs),
+
+    /// Run many tasks, e.g. one prompt across several repositories, in
+    /// parallel.
+    Batch(BatchArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -103,6 +158,56 @@ pub struct ResumeArgs {
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct ReviewArgs {
+    /// Review the changes on the current branch against this base branch or
+    /// ref. Without `--base` or `--commit`, reviews uncommitted changes.
+    #[arg(long = "base", value_name = "REF", conflicts_with = "commit")]
+    pub base: Option<String>,
+
+    /// Review the changes introduced by this commit.
+    #[arg(long = "commit", value_name = "SHA")]
+    pub commit: Option<String>,
+
+    /// Write the findings to this file as SARIF 2.1.0.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+
+    /// Write the findings to this file as JUnit XML.
+    #[arg(long = "junit", value_name = "FILE")]
+    pub junit: Option<PathBuf>,
+
+    /// Exit with status 4 if any finding has this priority or a more severe
+    /// one (`p0` is the most severe).
+    #[arg(long = "fail-on", value_enum, value_name = "PRIORITY")]
+    pub fail_on: Option<ReviewPriority>,
+
+    /// Drop findings whose confidence score is below this value (0.0 to 1.0).
+    #[arg(long = "min-confidence", value_name = "SCORE", default_value_t = 0.0)]
+    pub min_confidence: f32,
+
+    /// Additional instructions for the reviewer.
+    #[arg(value_name = "PROMPT")]
+    pub prompt: Option<String>,
+}
+
+#[derive(Parser, Debug)]
+pub struct BatchArgs {
+    /// JSONL file with one task per line: `{"prompt": ..., "cwd": ...}`,
+    /// optionally with an `id` and `config` overrides.
+    #[arg(value_name = "TASKS_FILE")]
+    pub tasks_file: PathBuf,
+
+    /// How many tasks to run at the same time.
+    #[arg(long = "jobs", short = 'j', value_name = "N", default_value_t = 4)]
+    pub jobs: usize,
+
+    /// Directory for each task's events, last message and rollout, and the
+    /// batch summary.
+    #[arg(long = "output-dir", value_name = "DIR", default_value = "codex-batch")]
+    pub output_dir: PathBuf,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
@@ -17,11 +17,15 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TurnAbortReason;
 use codex_core::protocol::TurnDiffEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::review_format::format_review_findings_block;
 use codex_protocol::num_format::format_with_separators;
 use owo_colors::OwoColorize;
 use owo_colors::Style;
@@ -105,6 +109,53 @@ impl EventProcessorWithHumanOutput {
             }
         }
     }
+
+    /// Print the sub-agent events worth following, indented under the
+    /// `sub-agent` header. The rest are left out.
+    fn print_sub_agent_activity(&self, msg: EventMsg) {
+        let text = match msg {
+            EventMsg::AgentMessage(AgentMessageEvent { message }) => message,
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, .. }) => format!(
+                "{} {}",
+                "exec".style(self.magenta),
+                escape_command(&command).style(self.bold)
+            ),
+            EventMsg::ExecCommandEnd(ExecCommandEndEvent {
+                exit_code,
+                duration,
+                ..
+            }) => {
+                let duration = format_duration(duration);
+                if exit_code == 0 {
+                    format!("{}", format!("succeeded in {duration}").style(self.green))
+                } else {
+                    format!(
+                        "{}",
+                        format!("exited {exit_code} in {duration}").style(self.red)
+                    )
+                }
+            }
+            EventMsg::McpToolCallBegin(McpToolCallBeginEvent { invocation, .. }) => format!(
+                "{} {}",
+                "tool".style(self.magenta),
+                format_mcp_invocation(&invocation).style(self.bold)
+            ),
+            EventMsg::PatchApplyEnd(PatchApplyEndEvent { success, .. }) => {
+                if success {
+                    format!("{}", "apply_patch succeeded".style(self.green))
+                } else {
+                    format!("{}", "apply_patch failed".style(self.red))
+                }
+            }
+            EventMsg::Error(ErrorEvent { message }) => {
+                format!("{} {message}", "ERROR:".style(self.red))
+            }
+            _ => return,
+        };
+        for line in text.lines() {
+            eprintln!("  {} {line}", "│".style(self.dimmed));
+        }
+    }
 }
 
 struct PatchApplyBegin {
@@ -508,12 +559,48 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 TurnAbortReason::ReviewEnded => {
                     ts_msg!(self, "task aborted: review ended");
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    ts_msg!(self, "{}", "task aborted: budget exceeded".style(self.red));
+                    return CodexStatus::InitiateShutdown;
+                }
             },
             EventMsg::ShutdownComplete => return CodexStatus::Shutdown,
             EventMsg::ConversationPath(_) => {}
             EventMsg::UserMessage(_) => {}
             EventMsg::EnteredReviewMode(_) => {}
-            EventMsg::ExitedReviewMode(_) => {}
+            EventMsg::ExitedReviewMode(ev) => {
+                if let Some(output) = ev.review_output {
+                    ts_msg!(self, "{}", "review".style(self.magenta).style(self.italic));
+                    let explanation = output.overall_explanation.trim();
+                    if !explanation.is_empty() {
+                        eprintln!("{explanation}");
+                    }
+                    if !output.findings.is_empty() {
+                        eprintln!(
+                            "{}",
+                            format_review_findings_block(&output.findings, None).trim_start()
+                        );
+                    }
+                }
+            }
+            EventMsg::SubAgentBegin(SubAgentBeginEvent { task, .. }) => {
+                ts_msg!(
+                    self,
+                    "{}\n{}",
+                    "sub-agent".style(self.italic).style(self.magenta),
+                    task,
+                );
+            }
+            EventMsg::SubAgentActivity(SubAgentActivityEvent { msg, .. }) => {
+                self.print_sub_agent_activity(*msg);
+            }
+            EventMsg::SubAgentEnd(SubAgentEndEvent { success, .. }) => {
+                if success {
+                    ts_msg!(self, "{}", "sub-agent completed".style(self.green));
+                } else {
+                    ts_msg!(self, "{}", "sub-agent failed".style(self.red));
+                }
+            }
             EventMsg::AgentMessageDelta(_) => {}
             EventMsg::AgentReasoningDelta(_) => {}
             EventMsg::AgentReasoningRawContentDelta(_) => {}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
 
 use crate::event_processor::CodexStatus;
@@ -18,6 +19,8 @@ use crate::exec_events::McpToolCallStatus;
 use crate::exec_events::PatchApplyStatus;
 use crate::exec_events::PatchChangeKind;
 use crate::exec_events::ReasoningItem;
+use crate::exec_events::SubAgentItem;
+use crate::exec_events::SubAgentStatus;
 use crate::exec_events::ThreadErrorEvent;
 use crate::exec_events::ThreadEvent;
 use crate::exec_events::ThreadItem;
@@ -43,8 +46,13 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TaskStartedEvent;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_protocol::plan_tool::StepStatus;
 use codex_protocol::plan_tool::UpdatePlanArgs;
@@ -53,7 +61,8 @@ use tracing::warn;
 
 pub struct EventProcessorWithJsonOutput {
     last_message_path: Option<PathBuf>,
-    next_event_id: AtomicU64,
+    // Shared with the processors of sub-agents so item ids stay unique.
+    next_event_id: Arc<AtomicU64>,
     // Tracks running commands by call_id, including the associated item id.
     running_commands: HashMap<String, RunningCommand>,
     running_patch_applies: HashMap<String, PatchApplyBeginEvent>,
@@ -62,6 +71,8 @@ pub struct EventProcessorWithJsonOutput {
     last_total_token_usage: Option<codex_core::protocol::TokenUsage>,
     running_mcp_tool_calls: HashMap<String, RunningMcpToolCall>,
     last_critical_error: Option<ThreadErrorEvent>,
+    // Tracks running sub-agents by the call_id of their spawn_agent call.
+    running_sub_agents: HashMap<String, RunningSubAgent>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +87,13 @@ struct RunningTodoList {
     items: Vec<TodoItem>,
 }
 
+struct RunningSubAgent {
+    task: String,
+    item_id: String,
+    // Turns the sub-agent's own events into items.
+    processor: EventProcessorWithJsonOutput,
+}
+
 #[derive(Debug, Clone)]
 struct RunningMcpToolCall {
     server: String,
@@ -87,13 +105,14 @@ impl EventProcessorWithJsonOutput {
     pub fn new(last_message_path: Option<PathBuf>) -> Self {
         Self {
             last_message_path,
-            next_event_id: AtomicU64::new(0),
+            next_event_id: Arc::new(AtomicU64::new(0)),
             running_commands: HashMap::new(),
             running_patch_applies: HashMap::new(),
             running_todo_list: None,
             last_total_token_usage: None,
             running_mcp_tool_calls: HashMap::new(),
             last_critical_error: None,
+            running_sub_agents: HashMap::new(),
         }
     }
 
@@ -118,6 +137,12 @@ impl EventProcessorWithJsonOutput {
             }
             EventMsg::TaskStarted(ev) => self.handle_task_started(ev),
             EventMsg::TaskComplete(_) => self.handle_task_complete(),
+            EventMsg::TurnAborted(ev) if ev.reason == TurnAbortReason::BudgetExceeded => {
+                self.last_critical_error = Some(ThreadErrorEvent {
+                    message: "turn aborted: task budget exceeded".to_string(),
+                });
+                self.handle_task_complete()
+            }
             EventMsg::Error(ev) => {
                 let error = ThreadErrorEvent {
                     message: ev.message.clone(),
@@ -129,6 +154,9 @@ impl EventProcessorWithJsonOutput {
                 message: ev.message.clone(),
             })],
             EventMsg::PlanUpdate(ev) => self.handle_plan_update(ev),
+            EventMsg::SubAgentBegin(ev) => self.handle_sub_agent_begin(ev),
+            EventMsg::SubAgentActivity(ev) => self.handle_sub_agent_activity(ev),
+            EventMsg::SubAgentEnd(ev) => self.handle_sub_agent_end(ev),
             _ => Vec::new(),
         }
     }
@@ -150,6 +178,7 @@ impl EventProcessorWithJsonOutput {
     fn handle_web_search_end(&self, ev: &WebSearchEndEvent) -> Vec<ThreadEvent> {
         let item = ThreadItem {
             id: self.get_next_item_id(),
+            parent_id: None,
             details: ThreadItemDetails::WebSearch(WebSearchItem {
                 query: ev.query.clone(),
             }),
@@ -161,6 +190,7 @@ impl EventProcessorWithJsonOutput {
     fn handle_agent_message(&self, payload: &AgentMessageEvent) -> Vec<ThreadEvent> {
         let item = ThreadItem {
             id: self.get_next_item_id(),
+            parent_id: None,
 
             details: ThreadItemDetails::AgentMessage(AgentMessageItem {
                 text: payload.message.clone(),
@@ -173,6 +203,7 @@ impl EventProcessorWithJsonOutput {
     fn handle_reasoning_event(&self, ev: &AgentReasoningEvent) -> Vec<ThreadEvent> {
         let item = ThreadItem {
             id: self.get_next_item_id(),
+            parent_id: None,
 
             details: ThreadItemDetails::Reasoning(ReasoningItem {
                 text: ev.text.clone(),
@@ -205,6 +236,7 @@ impl EventProcessorWithJsonOutput {
 
         let item = ThreadItem {
             id: item_id,
+            parent_id: None,
             details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                 command: command_string,
                 aggregated_output: String::new(),
@@ -232,6 +264,7 @@ impl EventProcessorWithJsonOutput {
 
         let item = ThreadItem {
             id: item_id,
+            parent_id: None,
             details: ThreadItemDetails::McpToolCall(McpToolCallItem {
                 server,
                 tool,
@@ -266,6 +299,7 @@ impl EventProcessorWithJsonOutput {
 
         let item = ThreadItem {
             id: item_id,
+            parent_id: None,
             details: ThreadItemDetails::McpToolCall(McpToolCallItem {
                 server,
                 tool,
@@ -300,6 +334,7 @@ impl EventProcessorWithJsonOutput {
             };
             let item = ThreadItem {
                 id: self.get_next_item_id(),
+                parent_id: None,
 
                 details: ThreadItemDetails::FileChange(FileChangeItem {
                     changes: running_patch_apply
@@ -336,6 +371,7 @@ impl EventProcessorWithJsonOutput {
         };
         let item = ThreadItem {
             id: item_id,
+            parent_id: None,
 
             details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                 command,
@@ -365,6 +401,7 @@ impl EventProcessorWithJsonOutput {
             running.items = items.clone();
             let item = ThreadItem {
                 id: running.item_id.clone(),
+                parent_id: None,
                 details: ThreadItemDetails::TodoList(TodoListItem { items }),
             };
             return vec![ThreadEvent::ItemUpdated(ItemUpdatedEvent { item })];
@@ -377,11 +414,95 @@ impl EventProcessorWithJsonOutput {
         });
         let item = ThreadItem {
             id: item_id,
+            parent_id: None,
             details: ThreadItemDetails::TodoList(TodoListItem { items }),
         };
         vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
     }
 
+    fn handle_sub_agent_begin(&mut self, ev: &SubAgentBeginEvent) -> Vec<ThreadEvent> {
+        let item_id = self.get_next_item_id();
+        let processor = EventProcessorWithJsonOutput {
+            next_event_id: self.next_event_id.clone(),
+            ..EventProcessorWithJsonOutput::new(None)
+        };
+        self.running_sub_agents.insert(
+            ev.call_id.clone(),
+            RunningSubAgent {
+                task: ev.task.clone(),
+                item_id: item_id.clone(),
+                processor,
+            },
+        );
+
+        let item = ThreadItem {
+            id: item_id,
+            parent_id: None,
+            details: ThreadItemDetails::SubAgent(SubAgentItem {
+                task: ev.task.clone(),
+                summary: None,
+                status: SubAgentStatus::InProgress,
+            }),
+        };
+        vec![ThreadEvent::ItemStarted(ItemStartedEvent { item })]
+    }
+
+    fn handle_sub_agent_activity(&mut self, ev: &SubAgentActivityEvent) -> Vec<ThreadEvent> {
+        let Some(running) = self.running_sub_agents.get_mut(&ev.call_id) else {
+            warn!(
+                call_id = ev.call_id,
+                "SubAgentActivity without matching SubAgentBegin; skipping"
+            );
+            return Vec::new();
+        };
+        let event = Event {
+            id: String::new(),
+            msg: (*ev.msg).clone(),
+        };
+        // Only the sub-agent's items are reported; its thread and turn
+        // events are summed up by the sub_agent item.
+        running
+            .processor
+            .collect_thread_events(&event)
+            .into_iter()
+            .filter_map(|mut thread_event| {
+                let (ThreadEvent::ItemStarted(ItemStartedEvent { item })
+                | ThreadEvent::ItemUpdated(ItemUpdatedEvent { item })
+                | ThreadEvent::ItemCompleted(ItemCompletedEvent { item })) = &mut thread_event
+                else {
+                    return None;
+                };
+                item.parent_id = Some(running.item_id.clone());
+                Some(thread_event)
+            })
+            .collect()
+    }
+
+    fn handle_sub_agent_end(&mut self, ev: &SubAgentEndEvent) -> Vec<ThreadEvent> {
+        let Some(running) = self.running_sub_agents.remove(&ev.call_id) else {
+            warn!(
+                call_id = ev.call_id,
+                "SubAgentEnd without matching SubAgentBegin; skipping item.completed"
+            );
+            return Vec::new();
+        };
+        let status = if ev.success {
+            SubAgentStatus::Completed
+        } else {
+            SubAgentStatus::Failed
+        };
+        let item = ThreadItem {
+            id: running.item_id,
+            parent_id: None,
+            details: ThreadItemDetails::SubAgent(SubAgentItem {
+                task: running.task,
+                summary: ev.last_agent_message.clone(),
+                status,
+            }),
+        };
+        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent { item })]
+    }
+
     fn handle_task_started(&mut self, _: &TaskStartedEvent) -> Vec<ThreadEvent> {
         self.last_critical_error = None;
         vec![ThreadEvent::TurnStarted(TurnStartedEvent {})]
@@ -403,6 +524,7 @@ impl EventProcessorWithJsonOutput {
         if let Some(running) = self.running_todo_list.take() {
             let item = ThreadItem {
                 id: running.item_id,
+                parent_id: None,
                 details: ThreadItemDetails::TodoList(TodoListItem {
                     items: running.items,
                 }),
@@ -444,13 +566,17 @@ impl EventProcessor for EventProcessorWithJsonOutput {
 
         let Event { msg, .. } = event;
 
-        if let EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) = msg {
-            if let Some(output_file) = self.last_message_path.as_deref() {
-                handle_last_message(last_agent_message.as_deref(), output_file);
+        match msg {
+            EventMsg::TaskComplete(TaskCompleteEvent { last_agent_message }) => {
+                if let Some(output_file) = self.last_message_path.as_deref() {
+                    handle_last_message(last_agent_message.as_deref(), output_file);
+                }
+                CodexStatus::InitiateShutdown
             }
-            CodexStatus::InitiateShutdown
-        } else {
-            CodexStatus::Running
+            EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::BudgetExceeded,
+            }) => CodexStatus::InitiateShutdown,
+            _ => CodexStatus::Running,
         }
     }
 }
@@ -89,6 +89,9 @@ pub struct ThreadErrorEvent {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 pub struct ThreadItem {
     pub id: String,
+    /// For the activity of a sub-agent, the id of its `sub_agent` item.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
     #[serde(flatten)]
     pub details: ThreadItemDetails,
 }
@@ -117,6 +120,10 @@ pub enum ThreadItemDetails {
     /// Tracks the agent's running to-do list. It starts when the plan is first
     /// issued, updates as steps change state, and completes when the turn ends.
     TodoList(TodoListItem),
+    /// A task delegated to a sub-agent. It starts when the sub-agent is
+    /// spawned and completes when it finishes; the sub-agent's own items
+    /// carry this item's id as their `parent_id`.
+    SubAgent(SubAgentItem),
     /// Describes a non-fatal error surfaced as an item.
     Error(ErrorItem),
 }
@@ -209,6 +216,26 @@ pub struct WebSearchItem {
     pub query: String,
 }
 
+/// The status of a sub-agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SubAgentStatus {
+    #[default]
+    InProgress,
+    Completed,
+    Failed,
+}
+
+/// A task delegated to a sub-agent.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
+pub struct SubAgentItem {
+    pub task: String,
+    /// The sub-agent's final message, once it has finished.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub status: SubAgentStatus,
+}
+
 /// An error notification.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, TS)]
 pub struct ErrorItem {
@@ -4,15 +4,19 @@
 // For both modes, any other output must be written to stderr.
 #![deny(clippy::print_stdout)]
 
+mod approval_command;
+mod batch;
 mod cli;
 mod event_processor;
 mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
+mod review_report;
 
 pub use cli::Cli;
 use codex_core::AuthManager;
 use codex_core::BUILT_IN_OSS_MODEL_PROVIDER_ID;
+use codex_core::BUILT_IN_REPLAY_MODEL_PROVIDER_ID;
 use codex_core::ConversationManager;
 use codex_core::NewConversation;
 use codex_core::config::Config;
@@ -25,6 +29,8 @@ use codex_core::protocol::InputItem;
 use codex_core::protocol::Op;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TaskCompleteEvent;
+use codex_core::protocol::TurnAbortReason;
+use codex_core::protocol::TurnAbortedEvent;
 use codex_ollama::DEFAULT_OSS_MODEL;
 use codex_protocol::config_types::SandboxMode;
 use event_processor_with_human_output::EventProcessorWithHumanOutput;
@@ -34,6 +40,7 @@ use serde_json::Value;
 use std::io::IsTerminal;
 use std::io::Read;
 use std::path::PathBuf;
+use std::sync::Arc;
 use supports_color::Stream;
 use tracing::debug;
 use tracing::error;
@@ -41,12 +48,20 @@ use tracing::info;
 use tracing_subscriber::EnvFilter;
 use tracing_subscriber::prelude::*;
 
+use crate::approval_command::ApprovalCommand;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_conversation_path_by_id_str;
 
+/// Exit status when `--max-turns`, `--max-total-tokens`, `--max-tool-calls`
+/// or `--timeout` (or `[budget]` in config.toml) stopped the task.
+pub const EXIT_CODE_BUDGET_EXCEEDED: i32 = 3;
+
+/// Exit status when `codex exec review --fail-on` matched a finding.
+pub const EXIT_CODE_REVIEW_FAILED: i32 = 4;
+
 pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> anyhow::Result<()> {
     if let Err(err) = set_default_originator("codex_exec") {
         tracing::warn!(?err, "Failed to set codex exec originator override {err:?}");
@@ -60,6 +75,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         config_profile,
         full_auto,
         dangerously_bypass_approvals_and_sandbox,
+        approval_command,
+        approval_timeout,
         cwd,
         skip_git_repo_check,
         color,
@@ -69,6 +86,12 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         prompt,
         output_schema: output_schema_path,
         include_plan_tool,
+        max_turns,
+        max_total_tokens,
+        max_tool_calls,
+        timeout,
+        record,
+        replay,
         config_overrides,
     } = cli;
 
@@ -77,45 +100,48 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         // Allow prompt before the subcommand by falling back to the parent-level prompt
         // when the Resume subcommand did not provide its own prompt.
         Some(ExecCommand::Resume(args)) => args.prompt.clone().or(prompt),
+        Some(ExecCommand::Review(_)) | Some(ExecCommand::Batch(_)) => None,
         None => prompt,
     };
+    let review_args = match &command {
+        Some(ExecCommand::Review(args)) => Some(args),
+        _ => None,
+    };
+    let review_request = match review_args {
+        Some(args) => {
+            let review_cwd = match &cwd {
+                Some(cwd) => cwd.clone(),
+                None => std::env::current_dir()?,
+            };
+            Some(review_report::review_request(args, &review_cwd).await)
+        }
+        None => None,
+    };
 
-    let prompt = match prompt_arg {
-        Some(p) if p != "-" => p,
-        // Either `-` was passed or no positional arg.
-        maybe_dash => {
-            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
-            // user explicitly forced reading via `-`.
-            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
+    let prompt = if let Some(review_request) = &review_request {
+        // A review builds its own prompt from the subcommand's arguments.
+        review_request.prompt.clone()
+    } else if matches!(command, Some(ExecCommand::Batch(_))) {
+        // Every batch task brings its own prompt.
+        String::new()
+    } else {
+        read_prompt(prompt_arg)
+    };
 
-            if std::io::stdin().is_terminal() && !force_stdin {
-                eprintln!(
-                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
-                );
-                std::process::exit(1);
-            }
+    let output_schema = load_output_schema(output_schema_path);
 
-            // Ensure the user knows we are waiting on stdin, as they may
-            // have gotten into this state by mistake. If so, and they are not
-            // writing to stdin, Codex will hang indefinitely, so this should
-            // help them debug in that case.
-            if !force_stdin {
-                eprintln!("Reading prompt from stdin...");
-            }
-            let mut buffer = String::new();
-            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
-                eprintln!("Failed to read prompt from stdin: {e}");
-                std::process::exit(1);
-            } else if buffer.trim().is_empty() {
-                eprintln!("No prompt provided via stdin.");
-                std::process::exit(1);
-            }
-            buffer
+    let approval_command = match approval_command
+        .as_deref()
+        .map(|command_line| ApprovalCommand::parse(command_line, approval_timeout))
+    {
+        Some(Ok(approval_command)) => Some(approval_command),
+        Some(Err(e)) => {
+            eprintln!("{e}");
+            std::process::exit(1);
         }
+        None => None,
     };
 
-    let output_schema = load_output_schema(output_schema_path);
-
     let (stdout_with_ansi, stderr_with_ansi) = match color {
         cli::Color::Always => (true, true),
         cli::Color::Never => (false, false),
@@ -159,6 +185,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
 
     let model_provider = if oss {
         Some(BUILT_IN_OSS_MODEL_PROVIDER_ID.to_string())
+    } else if replay.is_some() {
+        Some(BUILT_IN_REPLAY_MODEL_PROVIDER_ID.to_string())
     } else {
         None // No specific model provider override.
     };
@@ -169,8 +197,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         review_model: None,
         config_profile,
         // This CLI is intended to be headless and has no affordances for asking
-        // the user for approval.
-        approval_policy: Some(AskForApproval::Never),
+        // the user for approval. With an approval command the configured
+        // policy applies and the command answers the requests.
+        approval_policy: approval_command.is_none().then_some(AskForApproval::Never),
         sandbox_mode,
         cwd: cwd.map(|p| p.canonicalize().unwrap_or(p)),
         model_provider,
@@ -183,7 +212,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
     };
     // Parse `-c` overrides.
-    let cli_kv_overrides = match config_overrides.parse_overrides() {
+    let mut cli_kv_overrides = match config_overrides.parse_overrides() {
         Ok(v) => v,
         Err(e) => {
             eprintln!("Error parsing -c overrides: {e}");
@@ -191,7 +220,31 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     };
 
-    let config = Config::load_with_cli_overrides(cli_kv_overrides, overrides).await?;
+    // Budget flags take precedence over `[budget]` in config.toml and `-c`.
+    for (key, value) in [
+        ("budget.max_turns", max_turns.map(u64::from)),
+        ("budget.max_total_tokens", max_total_tokens),
+        ("budget.max_tool_calls", max_tool_calls.map(u64::from)),
+        ("budget.timeout_sec", timeout),
+    ] {
+        if let Some(value) = value {
+            let value = toml::Value::Integer(i64::try_from(value).unwrap_or(i64::MAX));
+            cli_kv_overrides.push((key.to_string(), value));
+        }
+    }
+
+    // Cassette paths given on the command line are relative to where codex
+    // was started, not to `--cd`.
+    for (key, path) in [("cassette.record", record), ("cassette.replay", replay)] {
+        if let Some(path) = path {
+            let path = std::path::absolute(&path).unwrap_or(path);
+            let value = toml::Value::String(path.to_string_lossy().into_owned());
+            cli_kv_overrides.push((key.to_string(), value));
+        }
+    }
+
+    let config =
+        Config::load_with_cli_overrides(cli_kv_overrides.clone(), overrides.clone()).await?;
 
     let otel = codex_core::otel_init::build_provider(&config, env!("CARGO_PKG_VERSION"));
 
@@ -232,6 +285,20 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             .map_err(|e| anyhow::anyhow!("OSS setup failed: {e}"))?;
     }
 
+    if let Some(ExecCommand::Batch(args)) = &command {
+        let settings = batch::BatchSettings {
+            overrides,
+            cli_kv_overrides,
+            skip_git_repo_check,
+            approval_command: approval_command.map(Arc::new),
+        };
+        let auth_manager = AuthManager::shared(config.codex_home.clone(), true);
+        if !batch::run_batch(args, settings, auth_manager).await? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let default_cwd = config.cwd.to_path_buf();
     let default_approval_policy = config.approval_policy;
     let default_sandbox_policy = config.sandbox_policy.clone();
@@ -252,8 +319,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         conversation_id: _,
         conversation,
         session_configured,
-    } = if let Some(ExecCommand::Resume(args)) = command {
-        let resume_path = resolve_resume_path(&config, &args).await?;
+    } = if let Some(ExecCommand::Resume(args)) = &command {
+        let resume_path = resolve_resume_path(&config, args).await?;
 
         if let Some(path) = resume_path {
             conversation_manager
@@ -336,29 +403,46 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
 
-    // Send the prompt.
-    let items: Vec<InputItem> = vec![InputItem::Text { text: prompt }];
-    let initial_prompt_task_id = conversation
-        .submit(Op::UserTurn {
-            items,
-            cwd: default_cwd,
-            approval_policy: default_approval_policy,
-            sandbox_policy: default_sandbox_policy,
-            model: default_model,
-            effort: default_effort,
-            summary: default_summary,
-            final_output_json_schema: output_schema,
-        })
-        .await?;
+    // Send the prompt, or start the review.
+    let review_root = default_cwd.clone();
+    let initial_prompt_task_id = if let Some(review_request) = review_request {
+        conversation.submit(Op::Review { review_request }).await?
+    } else {
+        let items: Vec<InputItem> = vec![InputItem::Text { text: prompt }];
+        conversation
+            .submit(Op::UserTurn {
+                items,
+                cwd: default_cwd,
+                approval_policy: default_approval_policy,
+                sandbox_policy: default_sandbox_policy,
+                model: default_model,
+                effort: default_effort,
+                summary: default_summary,
+                final_output_json_schema: output_schema,
+            })
+            .await?
+    };
     info!("Sent prompt with event ID: {initial_prompt_task_id}");
 
     // Run the loop until the task is complete.
     // Track whether a fatal error was reported by the server so we can
     // exit with a non-zero status for automation-friendly signaling.
     let mut error_seen = false;
+    let mut budget_exceeded = false;
+    let mut review_output = None;
     while let Some(event) = rx.recv().await {
-        if matches!(event.msg, EventMsg::Error(_)) {
-            error_seen = true;
+        match &event.msg {
+            EventMsg::Error(_) => error_seen = true,
+            EventMsg::ExitedReviewMode(ev) => review_output = ev.review_output.clone(),
+            EventMsg::TurnAborted(TurnAbortedEvent {
+                reason: TurnAbortReason::BudgetExceeded,
+            }) => budget_exceeded = true,
+            _ => {}
+        }
+        if let Some(approval_command) = approval_command.as_ref()
+            && let Some(op) = approval_command.respond_to(&event).await
+        {
+            conversation.submit(op).await?;
         }
         let shutdown: CodexStatus = event_processor.process_event(event);
         match shutdown {
@@ -372,13 +456,63 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
     }
     event_processor.print_final_output();
+    if budget_exceeded {
+        std::process::exit(EXIT_CODE_BUDGET_EXCEEDED);
+    }
     if error_seen {
         std::process::exit(1);
     }
+    if let Some(args) = review_args {
+        let Some(review_output) = review_output else {
+            eprintln!("The review did not complete.");
+            std::process::exit(1);
+        };
+        if review_report::write_reports(args, &review_output, &review_root)? {
+            std::process::exit(EXIT_CODE_REVIEW_FAILED);
+        }
+    }
 
     Ok(())
 }
 
+/// Use `prompt_arg` as the prompt, or read the prompt from stdin when it is
+/// missing or `-`.
+fn read_prompt(prompt_arg: Option<String>) -> String {
+    match prompt_arg {
+        Some(p) if p != "-" => p,
+        // Either `-` was passed or no positional arg.
+        maybe_dash => {
+            // When no arg (None) **and** stdin is a TTY, bail out early – unless the
+            // user explicitly forced reading via `-`.
+            let force_stdin = matches!(maybe_dash.as_deref(), Some("-"));
+
+            if std::io::stdin().is_terminal() && !force_stdin {
+                eprintln!(
+                    "No prompt provided. Either specify one as an argument or pipe the prompt into stdin."
+                );
+                std::process::exit(1);
+            }
+
+            // Ensure the user knows we are waiting on stdin, as they may
+            // have gotten into this state by mistake. If so, and they are not
+            // writing to stdin, Codex will hang indefinitely, so this should
+            // help them debug in that case.
+            if !force_stdin {
+                eprintln!("Reading prompt from stdin...");
+            }
+            let mut buffer = String::new();
+            if let Err(e) = std::io::stdin().read_to_string(&mut buffer) {
+                eprintln!("Failed to read prompt from stdin: {e}");
+                std::process::exit(1);
+            } else if buffer.trim().is_empty() {
+                eprintln!("No prompt provided via stdin.");
+                std::process::exit(1);
+            }
+            buffer
+        }
+    }
+}
+
 async fn resolve_resume_path(
     config: &Config,
     args: &crate::cli::ResumeArgs,
@@ -0,0 +1,355 @@
+//! Reports for `codex exec review`: the review request sent to the agent and
+//! the SARIF and JUnit files written from its findings for CI.
+
+use std::path::Path;
+
+use anyhow::Context;
+use clap::ValueEnum;
+use codex_core::git_info::resolve_commit;
+use codex_core::protocol::ReviewFinding;
+use codex_core::protocol::ReviewOutputEvent;
+use codex_core::protocol::ReviewRequest;
+use codex_core::review_prompts;
+use serde_json::Value;
+use serde_json::json;
+
+use crate::cli::ReviewArgs;
+
+/// Finding priority, from `p0` (most severe) to `p3`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+#[value(rename_all = "lower")]
+pub enum ReviewPriority {
+    P0,
+    P1,
+    P2,
+    P3,
+}
+
+impl ReviewPriority {
+    fn rank(self) -> i32 {
+        self as i32
+    }
+}
+
+/// The review request for `args`, run from `cwd`. A commit under review is
+/// resolved so the prompt can quote its subject, as the TUI's picker does.
+pub(crate) async fn review_request(args: &ReviewArgs, cwd: &Path) -> ReviewRequest {
+    let mut request = if let Some(base) = &args.base {
+        review_prompts::base_branch(base)
+    } else if let Some(commit) = &args.commit {
+        match resolve_commit(cwd, commit).await {
+            Some(entry) => review_prompts::commit(&entry.sha, Some(&entry.subject)),
+            None => review_prompts::commit(commit, None),
+        }
+    } else {
+        review_prompts::uncommitted_changes()
+    };
+    if let Some(instructions) = args.prompt.as_deref().map(str::trim)
+        && !instructions.is_empty()
+    {
+        request.prompt.push_str("\n\n");
+        request.prompt.push_str(instructions);
+    }
+    request
+}
+
+/// Write the reports requested by `args` and return whether any reported
+/// finding fails the run under `--fail-on`.
+pub(crate) fn write_reports(
+    args: &ReviewArgs,
+    output: &ReviewOutputEvent,
+    root: &Path,
+) -> anyhow::Result<bool> {
+    let findings = reported_findings(output, args.min_confidence);
+    if let Some(path) = &args.sarif {
+        let sarif = serde_json::to_string_pretty(&to_sarif(&findings, root))?;
+        std::fs::write(path, sarif)
+            .with_context(|| format!("failed to write SARIF report to {}", path.display()))?;
+    }
+    if let Some(path) = &args.junit {
+        std::fs::write(path, to_junit(&findings, args.fail_on, root))
+            .with_context(|| format!("failed to write JUnit report to {}", path.display()))?;
+    }
+    Ok(findings
+        .iter()
+        .any(|finding| is_failure(finding, args.fail_on)))
+}
+
+/// Findings at or above the confidence floor, in the order reported.
+pub(crate) fn reported_findings(
+    output: &ReviewOutputEvent,
+    min_confidence: f32,
+) -> Vec<&ReviewFinding> {
+    output
+        .findings
+        .iter()
+        .filter(|finding| finding.confidence_score >= min_confidence)
+        .collect()
+}
+
+/// Whether `finding` should fail the run under `fail_on`.
+pub(crate) fn is_failure(finding: &ReviewFinding, fail_on: Option<ReviewPriority>) -> bool {
+    fail_on.is_some_and(|threshold| finding.priority <= threshold.rank())
+}
+
+/// Label of the SARIF rule for `priority`. Priorities outside `0..=3` are
+/// clamped so every result refers to one of the rules in the report.
+fn priority_label(priority: i32) -> String {
+    format!("P{}", priority.clamp(0, 3))
+}
+
+/// `path` relative to `root` when it is inside it, with `/` separators as
+/// SARIF and most CI viewers expect.
+fn display_path(path: &Path, root: &Path) -> String {
+    let path = path.strip_prefix(root).unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// SARIF 2.1.0 log with one result per finding.
+pub(crate) fn to_sarif(findings: &[&ReviewFinding], root: &Path) -> Value {
+    let results: Vec<Value> = findings
+        .iter()
+        .map(|finding| {
+            let level = match finding.priority {
+                i32::MIN..=1 => "error",
+                2 => "warning",
+                _ => "note",
+            };
+            let location = &finding.code_location;
+            json!({
+                "ruleId": priority_label(finding.priority),
+                "level": level,
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": display_path(&location.absolute_file_path, root),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                        "region": {
+                            "startLine": location.line_range.start.max(1),
+                            "endLine": location.line_range.end.max(location.line_range.start).max(1),
+                        },
+                    },
+                }],
+                "properties": {
+                    "title": finding.title,
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": (0..=3).map(|priority| json!({
+                        "id": priority_label(priority),
+                        "shortDescription": { "text": format!("Priority {priority} review finding") },
+                    })).collect::<Vec<_>>(),
+                },
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": { "uri": format!("file://{}/", root.display()) },
+            },
+            "results": results,
+        }],
+    })
+}
+
+/// JUnit XML with one test case per finding. Findings that fail the run
+/// under `fail_on` are failures; the rest pass with the finding as output.
+pub(crate) fn to_junit(
+    findings: &[&ReviewFinding],
+    fail_on: Option<ReviewPriority>,
+    root: &Path,
+) -> String {
+    let failures = findings
+        .iter()
+        .filter(|finding| is_failure(finding, fail_on))
+        .count();
+    let tests = findings.len().max(1);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"codex review\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    xml.push_str(&format!(
+        "  <testsuite name=\"codex review\" tests=\"{tests}\" failures=\"{failures}\">\n"
+    ));
+    if findings.is_empty() {
+        xml.push_str("    <testcase classname=\"codex review\" name=\"no findings\"/>\n");
+    }
+    for finding in findings {
+        let location = &finding.code_location;
+        let path = display_path(&location.absolute_file_path, root);
+        let range = &location.line_range;
+        let name = format!(
+            "[{}] {} ({path}:{}-{})",
+            priority_label(finding.priority),
+            finding.title,
+            range.start,
+            range.end
+        );
+        let details = format!(
+            "{}\n\nconfidence: {:.2}",
+            finding.body, finding.confidence_score
+        );
+        xml.push_str(&format!(
+            "    <testcase classname=\"{}\" name=\"{}\">\n",
+            xml_escape(&path),
+            xml_escape(&name)
+        ));
+        if is_failure(finding, fail_on) {
+            xml.push_str(&format!(
+                "      <failure message=\"{}\" type=\"{}\">{}</failure>\n",
+                xml_escape(&finding.title),
+                priority_label(finding.priority),
+                xml_escape(&details)
+            ));
+        } else {
+            xml.push_str(&format!(
+                "      <system-out>{}</system-out>\n",
+                xml_escape(&details)
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not valid XML.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ReviewCodeLocation;
+    use codex_core::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn finding(title: &str, priority: i32, confidence_score: f32) -> ReviewFinding {
+        ReviewFinding {
+            title: title.to_string(),
+            body: "Details & <context>".to_string(),
+            confidence_score,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from("/repo/src/lib.rs"),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+        }
+    }
+
+    #[test]
+    fn threshold_and_confidence_select_failures() {
+        let output = ReviewOutputEvent {
+            findings: vec![
+                finding("Null deref", 0, 0.9),
+                finding("Style nit", 3, 0.9),
+                finding("Unsure", 1, 0.2),
+            ],
+            ..Default::default()
+        };
+
+        let reported = reported_findings(&output, 0.5);
+        let titles: Vec<&str> = reported.iter().map(|f| f.title.as_str()).collect();
+        assert_eq!(titles, vec!["Null deref", "Style nit"]);
+
+        let failing: Vec<bool> = reported
+            .iter()
+            .map(|f| is_failure(f, Some(ReviewPriority::P1)))
+            .collect();
+        assert_eq!(failing, vec![true, false]);
+        assert!(!is_failure(reported[0], None));
+    }
+
+    #[test]
+    fn sarif_result_points_at_relative_path() {
+        let finding = finding("Null deref", 1, 0.8);
+        let sarif = to_sarif(&[&finding], Path::new("/repo"));
+
+        assert_eq!(sarif["version"], "2.1.0");
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "P1");
+        assert_eq!(result["level"], "error");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/lib.rs");
+        assert_eq!(location["region"]["startLine"], 10);
+        assert_eq!(location["region"]["endLine"], 12);
+    }
+
+    #[test]
+    fn sarif_rule_ids_stay_within_the_declared_rules() {
+        let low = finding("Way too severe", -1, 0.8);
+        let high = finding("Barely a nit", 7, 0.8);
+        let sarif = to_sarif(&[&low, &high], Path::new("/repo"));
+
+        let run = &sarif["runs"][0];
+        let rule_ids: Vec<&Value> = run["tool"]["driver"]["rules"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|rule| &rule["id"])
+            .collect();
+        assert_eq!(run["results"][0]["ruleId"], "P0");
+        assert_eq!(run["results"][1]["ruleId"], "P3");
+        for result in run["results"].as_array().unwrap() {
+            assert!(rule_ids.contains(&&result["ruleId"]));
+        }
+    }
+
+    #[test]
+    fn junit_marks_findings_over_threshold_as_failures() {
+        let blocker = finding("Null deref", 0, 0.9);
+        let nit = finding("Style nit", 3, 0.9);
+        let xml = to_junit(
+            &[&blocker, &nit],
+            Some(ReviewPriority::P1),
+            Path::new("/repo"),
+        );
+
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="codex review" tests="2" failures="1">
+  <testsuite name="codex review" tests="2" failures="1">
+    <testcase classname="src/lib.rs" name="[P0] Null deref (src/lib.rs:10-12)">
+      <failure message="Null deref" type="P0">Details &amp; &lt;context&gt;
+
+confidence: 0.90</failure>
+    </testcase>
+    <testcase classname="src/lib.rs" name="[P3] Style nit (src/lib.rs:10-12)">
+      <system-out>Details &amp; &lt;context&gt;
+
+confidence: 0.90</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -12,6 +12,9 @@ use codex_core::protocol::McpToolCallEndEvent;
 use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::PatchApplyEndEvent;
 use codex_core::protocol::SessionConfiguredEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::WebSearchEndEvent;
 use codex_exec::event_processor_with_jsonl_output::EventProcessorWithJsonOutput;
 use codex_exec::exec_events::AgentMessageItem;
@@ -25,6 +28,8 @@ use codex_exec::exec_events::McpToolCallStatus;
 use codex_exec::exec_events::PatchApplyStatus;
 use codex_exec::exec_events::PatchChangeKind;
 use codex_exec::exec_events::ReasoningItem;
+use codex_exec::exec_events::SubAgentItem;
+use codex_exec::exec_events::SubAgentStatus;
 use codex_exec::exec_events::ThreadErrorEvent;
 use codex_exec::exec_events::ThreadEvent;
 use codex_exec::exec_events::ThreadItem;
@@ -110,6 +115,7 @@ fn web_search_end_emits_item_completed() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::WebSearch(WebSearchItem { query }),
             },
         })]
@@ -143,6 +149,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         vec![ThreadEvent::ItemStarted(ItemStartedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::TodoList(ExecTodoListItem {
                     items: vec![
                         ExecTodoItem {
@@ -182,6 +189,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
         vec![ThreadEvent::ItemUpdated(ItemUpdatedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::TodoList(ExecTodoListItem {
                     items: vec![
                         ExecTodoItem {
@@ -212,6 +220,7 @@ fn plan_update_emits_todo_list_started_updated_and_completed() {
             ThreadEvent::ItemCompleted(ItemCompletedEvent {
                 item: ThreadItem {
                     id: "item_0".to_string(),
+                    parent_id: None,
                     details: ThreadItemDetails::TodoList(ExecTodoListItem {
                         items: vec![
                             ExecTodoItem {
@@ -255,6 +264,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
         vec![ThreadEvent::ItemStarted(ItemStartedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::McpToolCall(McpToolCallItem {
                     server: "server_a".to_string(),
                     tool: "tool_x".to_string(),
@@ -283,6 +293,7 @@ fn mcp_tool_call_begin_and_end_emit_item_events() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::McpToolCall(McpToolCallItem {
                     server: "server_a".to_string(),
                     tool: "tool_x".to_string(),
@@ -326,6 +337,7 @@ fn mcp_tool_call_failure_sets_failed_status() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::McpToolCall(McpToolCallItem {
                     server: "server_b".to_string(),
                     tool: "tool_y".to_string(),
@@ -396,6 +408,7 @@ fn agent_reasoning_produces_item_completed_reasoning() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::Reasoning(ReasoningItem {
                     text: "thinking...".to_string(),
                 }),
@@ -419,6 +432,7 @@ fn agent_message_produces_item_completed_agent_message() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::AgentMessage(AgentMessageItem {
                     text: "hello".to_string(),
                 }),
@@ -514,6 +528,7 @@ fn exec_command_end_success_produces_completed_command_item() {
         vec![ThreadEvent::ItemStarted(ItemStartedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                     command: "bash -lc 'echo hi'".to_string(),
                     aggregated_output: String::new(),
@@ -543,6 +558,7 @@ fn exec_command_end_success_produces_completed_command_item() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                     command: "bash -lc 'echo hi'".to_string(),
                     aggregated_output: "hi\n".to_string(),
@@ -573,6 +589,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
         vec![ThreadEvent::ItemStarted(ItemStartedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                     command: "sh -c 'exit 1'".to_string(),
                     aggregated_output: String::new(),
@@ -602,6 +619,7 @@ fn exec_command_end_failure_produces_failed_command_item() {
         vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
             item: ThreadItem {
                 id: "item_0".to_string(),
+                parent_id: None,
                 details: ThreadItemDetails::CommandExecution(CommandExecutionItem {
                     command: "sh -c 'exit 1'".to_string(),
                     aggregated_output: String::new(),
@@ -770,6 +788,89 @@ fn patch_apply_failure_produces_item_completed_patchapply_failed() {
     }
 }
 
+#[test]
+fn sub_agent_items_are_nested_under_the_sub_agent() {
+    let mut ep = EventProcessorWithJsonOutput::new(None);
+    let begin = ep.collect_thread_events(&event(
+        "s1",
+        EventMsg::SubAgentBegin(SubAgentBeginEvent {
+            call_id: "spawn-1".to_string(),
+            conversation_id: codex_protocol::ConversationId::new(),
+            task: "count the TODOs".to_string(),
+        }),
+    ));
+    assert_eq!(
+        begin,
+        vec![ThreadEvent::ItemStarted(ItemStartedEvent {
+            item: ThreadItem {
+                id: "item_0".to_string(),
+                parent_id: None,
+                details: ThreadItemDetails::SubAgent(SubAgentItem {
+                    task: "count the TODOs".to_string(),
+                    summary: None,
+                    status: SubAgentStatus::InProgress,
+                }),
+            },
+        })]
+    );
+
+    let activity = |msg| {
+        event(
+            "s1",
+            EventMsg::SubAgentActivity(SubAgentActivityEvent {
+                call_id: "spawn-1".to_string(),
+                msg: Box::new(msg),
+            }),
+        )
+    };
+    // The sub-agent's turn events are not reported; its items are.
+    let started = ep.collect_thread_events(&activity(EventMsg::TaskStarted(
+        codex_core::protocol::TaskStartedEvent {
+            model_context_window: None,
+        },
+    )));
+    assert_eq!(started, vec![]);
+    let message = ep.collect_thread_events(&activity(EventMsg::AgentMessage(AgentMessageEvent {
+        message: "There are 3.".to_string(),
+    })));
+    assert_eq!(
+        message,
+        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
+            item: ThreadItem {
+                id: "item_1".to_string(),
+                parent_id: Some("item_0".to_string()),
+                details: ThreadItemDetails::AgentMessage(AgentMessageItem {
+                    text: "There are 3.".to_string(),
+                }),
+            },
+        })]
+    );
+
+    let end = ep.collect_thread_events(&event(
+        "s1",
+        EventMsg::SubAgentEnd(SubAgentEndEvent {
+            call_id: "spawn-1".to_string(),
+            success: true,
+            last_agent_message: Some("There are 3.".to_string()),
+            unified_diff: None,
+        }),
+    ));
+    assert_eq!(
+        end,
+        vec![ThreadEvent::ItemCompleted(ItemCompletedEvent {
+            item: ThreadItem {
+                id: "item_0".to_string(),
+                parent_id: None,
+                details: ThreadItemDetails::SubAgent(SubAgentItem {
+                    task: "count the TODOs".to_string(),
+                    summary: Some("There are 3.".to_string()),
+                    status: SubAgentStatus::Completed,
+                }),
+            },
+        })]
+    );
+}
+
 #[test]
 fn task_complete_produces_turn_completed_with_usage() {
     let mut ep = EventProcessorWithJsonOutput::new(None);
@@ -0,0 +1,102 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_local_shell_call;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+use serde_json::Value;
+
+/// Runs one turn whose only tool call needs approval and returns the request
+/// the approver received and whether the command ran.
+async fn run_with_approver(
+    approver_script: &str,
+    extra_args: &[&str],
+) -> anyhow::Result<(Value, bool)> {
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_local_shell_call("call-1", "completed", vec!["touch", "created.txt"]),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let log_dir = tempfile::tempdir()?;
+    let request_path = log_dir.path().join("request.json");
+    let approver = format!(
+        "sh -c 'cat > {}; {approver_script}'",
+        request_path.display()
+    );
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-c")
+        .arg("approval_policy=\"untrusted\"")
+        .arg("--sandbox")
+        .arg("danger-full-access")
+        .arg("--approval-command")
+        .arg(approver)
+        .args(extra_args)
+        .arg("create the file")
+        .assert()
+        .success();
+
+    let request: Value = serde_json::from_str(&std::fs::read_to_string(&request_path)?)?;
+    let created = test.cwd_path().join("created.txt").exists();
+    Ok((request, created))
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approval_command_receives_request_and_approves() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let (request, created) = run_with_approver("echo approved", &[]).await?;
+
+    assert_eq!(request["type"], "exec_approval_request");
+    assert_eq!(
+        request["command"],
+        serde_json::json!(["touch", "created.txt"])
+    );
+    assert!(created);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn failing_approval_command_denies() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let (request, created) = run_with_approver("exit 1", &[]).await?;
+
+    assert_eq!(request["type"], "exec_approval_request");
+    assert!(!created);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn hanging_approval_command_times_out_and_denies() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let started = std::time::Instant::now();
+    let (request, created) =
+        run_with_approver("exec sleep 30", &["--approval-timeout", "1"]).await?;
+
+    assert_eq!(request["type"], "exec_approval_request");
+    assert!(!created);
+    assert!(started.elapsed() < std::time::Duration::from_secs(20));
+
+    Ok(())
+}
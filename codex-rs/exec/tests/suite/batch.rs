@@ -0,0 +1,91 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+use predicates::str::contains;
+use serde_json::Value;
+
+/// `codex exec batch` runs each task in its own directory, keeps per-task
+/// output, and exits non-zero when any task fails.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn batch_runs_tasks_and_writes_summary() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let test = test_codex_exec();
+    let cwd = test.cwd_path();
+    std::fs::create_dir_all(cwd.join("repo-a"))?;
+    std::fs::create_dir_all(cwd.join("repo-b"))?;
+    let tasks_file = cwd.join("tasks.jsonl");
+    std::fs::write(
+        &tasks_file,
+        [
+            r#"{"id": "a", "prompt": "upgrade deps", "cwd": "repo-a"}"#,
+            r#"{"prompt": "upgrade deps", "cwd": "repo-b"}"#,
+            r#"{"id": "broken", "prompt": "upgrade deps", "cwd": "repo-b", "config": {"model_reasoning_effort": "extreme"}}"#,
+        ]
+        .join("\n"),
+    )?;
+
+    let server = responses::start_mock_server().await;
+    responses::mount_sse(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-1", "Dependencies upgraded."),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("batch")
+        .arg(&tasks_file)
+        .arg("--jobs")
+        .arg("2")
+        .arg("--output-dir")
+        .arg("out")
+        .assert()
+        .code(1)
+        .stdout(contains("TASK"))
+        .stdout(contains("task-2  completed"));
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+
+    let out = cwd.join("out");
+    let summary: Value = serde_json::from_str(&std::fs::read_to_string(out.join("summary.json"))?)?;
+    let statuses: Vec<(&str, &str)> = summary
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| {
+            (
+                task["id"].as_str().unwrap(),
+                task["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![
+            ("a", "completed"),
+            ("task-2", "completed"),
+            ("broken", "failed")
+        ]
+    );
+    assert!(summary[2]["error"].is_string());
+
+    assert_eq!(
+        std::fs::read_to_string(out.join("a/last_message.txt"))?,
+        "Dependencies upgraded."
+    );
+    let events = std::fs::read_to_string(out.join("task-2/events.jsonl"))?;
+    assert!(events.lines().any(|line| line.contains("task_complete")));
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use codex_exec::EXIT_CODE_BUDGET_EXCEEDED;
+use core_test_support::responses;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_local_shell_call;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+
+/// A model that keeps calling tools is stopped by `--max-turns` and `exec`
+/// exits with the documented status.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn max_turns_aborts_with_budget_exit_code() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    // Every response asks for another tool call, so only the budget ends
+    // the task.
+    responses::mount_sse(
+        &server,
+        sse(vec![
+            ev_local_shell_call("call-1", "completed", vec!["/bin/echo", "again"]),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--sandbox")
+        .arg("danger-full-access")
+        .arg("--max-turns")
+        .arg("2")
+        .arg("loop forever")
+        .assert()
+        .code(EXIT_CODE_BUDGET_EXCEEDED);
+
+    let requests = server.received_requests().await.unwrap();
+    assert_eq!(requests.len(), 2);
+
+    Ok(())
+}
@@ -1,7 +1,12 @@
 // Aggregates all former standalone integration tests as modules.
 mod apply_patch;
+mod approval_command;
 mod auth_env;
+mod batch;
+mod budget;
 mod output_schema;
+mod replay;
 mod resume;
+mod review;
 mod sandbox;
 mod server_error_exit;
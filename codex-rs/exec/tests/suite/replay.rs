@@ -0,0 +1,82 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::ev_local_shell_call;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+use walkdir::WalkDir;
+
+/// A run recorded with `--record` replays offline with `--replay`, from the
+/// cassette and from the session rollout, and ends with the same message.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn recorded_run_replays_without_a_model() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let test = test_codex_exec();
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_local_shell_call("call-1", "completed", vec!["/bin/echo", "hi"]),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_assistant_message("msg-1", "All tests pass."),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let cassette = test.cwd_path().join("cassette.jsonl");
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("--sandbox")
+        .arg("danger-full-access")
+        .arg("--record")
+        .arg(&cassette)
+        .arg("run the tests")
+        .assert()
+        .success();
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+    assert_eq!(std::fs::read_to_string(&cassette)?.lines().count(), 2);
+
+    let rollout = WalkDir::new(test.home_path().join("sessions"))
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| entry.file_type().is_file())
+        .map(walkdir::DirEntry::into_path)
+        .expect("recorded session rollout");
+
+    for (source, last_message) in [
+        (&cassette, "from-cassette.txt"),
+        (&rollout, "from-rollout.txt"),
+    ] {
+        // No server: every response must come from the replay file.
+        test.cmd()
+            .arg("--skip-git-repo-check")
+            .arg("--sandbox")
+            .arg("danger-full-access")
+            .arg("--replay")
+            .arg(source)
+            .arg("-o")
+            .arg(last_message)
+            .arg("run the tests")
+            .assert()
+            .success();
+        assert_eq!(
+            std::fs::read_to_string(test.cwd_path().join(last_message))?,
+            "All tests pass.",
+            "replaying {}",
+            source.display()
+        );
+    }
+    assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+    Ok(())
+}
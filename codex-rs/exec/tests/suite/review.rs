@@ -0,0 +1,100 @@
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::expect_used, clippy::unwrap_used)]
+
+use codex_exec::EXIT_CODE_REVIEW_FAILED;
+use core_test_support::responses;
+use core_test_support::responses::ev_assistant_message;
+use core_test_support::responses::ev_completed;
+use core_test_support::responses::sse;
+use core_test_support::skip_if_no_network;
+use core_test_support::test_codex_exec::test_codex_exec;
+use serde_json::Value;
+use wiremock::matchers::any;
+
+/// `codex exec review` sends a review request, writes the findings as SARIF
+/// and JUnit, and fails the run when a finding meets `--fail-on`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn review_writes_reports_and_fails_on_threshold() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let test = test_codex_exec();
+    let cwd = test.cwd_path().canonicalize()?;
+    let review = serde_json::json!({
+        "findings": [
+            {
+                "title": "Possible overflow",
+                "body": "`len + 1` can overflow.",
+                "confidence_score": 0.8,
+                "priority": 1,
+                "code_location": {
+                    "absolute_file_path": cwd.join("src/lib.rs"),
+                    "line_range": {"start": 4, "end": 6}
+                }
+            },
+            {
+                "title": "Unclear name",
+                "body": "Consider renaming `x`.",
+                "confidence_score": 0.3,
+                "priority": 3,
+                "code_location": {
+                    "absolute_file_path": cwd.join("src/main.rs"),
+                    "line_range": {"start": 1, "end": 1}
+                }
+            }
+        ],
+        "overall_correctness": "patch is incorrect",
+        "overall_explanation": "One overflow.",
+        "overall_confidence_score": 0.7
+    });
+    let server = responses::start_mock_server().await;
+    responses::mount_sse_once_match(
+        &server,
+        any(),
+        sse(vec![
+            ev_assistant_message("msg-1", &review.to_string()),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let sarif_path = cwd.join("review.sarif");
+    let junit_path = cwd.join("review.xml");
+    test.cmd_with_server(&server)
+        .arg("--skip-git-repo-check")
+        .arg("-C")
+        .arg(&cwd)
+        .arg("review")
+        .arg("--base")
+        .arg("main")
+        .arg("--sarif")
+        .arg(&sarif_path)
+        .arg("--junit")
+        .arg(&junit_path)
+        .arg("--fail-on")
+        .arg("p1")
+        .arg("--min-confidence")
+        .arg("0.5")
+        .assert()
+        .code(EXIT_CODE_REVIEW_FAILED);
+
+    let requests = server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[0].body)?;
+    assert!(
+        body.to_string()
+            .contains("Review the code changes against the base branch 'main'"),
+        "review prompt should target the base branch"
+    );
+
+    let sarif: Value = serde_json::from_str(&std::fs::read_to_string(&sarif_path)?)?;
+    let results = sarif["runs"][0]["results"].as_array().unwrap();
+    assert_eq!(results.len(), 1, "low-confidence finding is dropped");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+        "src/lib.rs"
+    );
+
+    let junit = std::fs::read_to_string(&junit_path)?;
+    assert!(junit.contains(r#"<testsuite name="codex review" tests="1" failures="1">"#));
+
+    Ok(())
+}
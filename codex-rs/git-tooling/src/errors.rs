@@ -26,6 +26,12 @@ pub enum GitToolingError {
     NonRelativePath { path: PathBuf },
     #[error("path {path:?} escapes the repository root")]
     PathEscapesRepository { path: PathBuf },
+    #[error("{path:?} has more than {max_files} files or {max_bytes} bytes to snapshot")]
+    SnapshotTooLarge {
+        path: PathBuf,
+        max_files: usize,
+        max_bytes: u64,
+    },
     #[error("failed to process path inside worktree")]
     PathPrefix(#[from] std::path::StripPrefixError),
     #[error(transparent)]
@@ -0,0 +1,249 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tempfile::Builder;
+use tempfile::TempDir;
+use walkdir::WalkDir;
+
+use crate::GitToolingError;
+
+/// Directory names that are never copied into a file snapshot.
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+/// Largest number of files a single file snapshot may copy.
+const MAX_SNAPSHOT_FILES: usize = 10_000;
+
+/// Largest total size of the files a single file snapshot may copy.
+const MAX_SNAPSHOT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// Copies a directory's files into a private temporary store so the
+/// directory can later be restored without git. Files whose size and
+/// modification time are unchanged since the previous capture share the
+/// copy made then.
+pub struct FileSnapshotStore {
+    root: PathBuf,
+    blobs: Arc<TempDir>,
+    next_blob: usize,
+    last: Option<Arc<BTreeMap<PathBuf, SnapshotEntry>>>,
+    max_files: usize,
+    max_bytes: u64,
+}
+
+/// Directory state captured by [`FileSnapshotStore::capture`]. The copies it
+/// refers to live as long as any snapshot from the same store.
+#[derive(Debug, Clone)]
+pub struct FileSnapshot {
+    root: PathBuf,
+    blobs: Arc<TempDir>,
+    entries: Arc<BTreeMap<PathBuf, SnapshotEntry>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SnapshotEntry {
+    blob: PathBuf,
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileSnapshotStore {
+    /// Creates an empty store for snapshots of `root`.
+    pub fn new(root: &Path) -> Result<Self, GitToolingError> {
+        let blobs = Builder::new().prefix("codex-file-snapshot-").tempdir()?;
+        Ok(Self {
+            root: root.to_path_buf(),
+            blobs: Arc::new(blobs),
+            next_blob: 0,
+            last: None,
+            max_files: MAX_SNAPSHOT_FILES,
+            max_bytes: MAX_SNAPSHOT_BYTES,
+        })
+    }
+
+    /// Copies every regular file under the root, skipping `.git`,
+    /// `node_modules` and `target` directories. The whole tree is checked
+    /// against the size limits before anything is copied.
+    pub fn capture(&mut self) -> Result<FileSnapshot, GitToolingError> {
+        let files = self.list_files()?;
+        let mut entries = BTreeMap::new();
+        for (path, metadata) in files {
+            let relative = path.strip_prefix(&self.root)?.to_path_buf();
+            let modified = metadata.modified().ok();
+            let unchanged = self
+                .last
+                .as_ref()
+                .and_then(|last| last.get(&relative))
+                .filter(|previous| {
+                    modified.is_some()
+                        && previous.modified == modified
+                        && previous.len == metadata.len()
+                })
+                .cloned();
+            let snapshot_entry = match unchanged {
+                Some(previous) => previous,
+                None => {
+                    let blob = PathBuf::from(self.next_blob.to_string());
+                    self.next_blob += 1;
+                    fs::copy(&path, self.blobs.path().join(&blob))?;
+                    SnapshotEntry {
+                        blob,
+                        len: metadata.len(),
+                        modified,
+                    }
+                }
+            };
+            entries.insert(relative, snapshot_entry);
+        }
+
+        let entries = Arc::new(entries);
+        self.last = Some(Arc::clone(&entries));
+        Ok(FileSnapshot {
+            root: self.root.clone(),
+            blobs: Arc::clone(&self.blobs),
+            entries,
+        })
+    }
+
+    /// Regular files under the root with their metadata, or
+    /// [`GitToolingError::SnapshotTooLarge`] as soon as a limit is crossed.
+    fn list_files(&self) -> Result<Vec<(PathBuf, fs::Metadata)>, GitToolingError> {
+        let mut files = Vec::new();
+        let mut total_bytes = 0u64;
+        let walker = WalkDir::new(&self.root).into_iter().filter_entry(|entry| {
+            !(entry.depth() > 0
+                && entry.file_type().is_dir()
+                && SKIPPED_DIRS.iter().any(|dir| entry.file_name() == *dir))
+        });
+        for entry in walker {
+            let entry = entry?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let metadata = entry.metadata()?;
+            total_bytes += metadata.len();
+            if files.len() >= self.max_files || total_bytes > self.max_bytes {
+                return Err(GitToolingError::SnapshotTooLarge {
+                    path: self.root.clone(),
+                    max_files: self.max_files,
+                    max_bytes: self.max_bytes,
+                });
+            }
+            files.push((entry.into_path(), metadata));
+        }
+        Ok(files)
+    }
+}
+
+impl FileSnapshot {
+    /// Directory the snapshot was taken of.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Number of files captured.
+    pub fn file_count(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Restore every file captured in `snapshot` to its captured contents,
+/// recreating deleted files. Files created after the snapshot are left in
+/// place, matching [`crate::restore_ghost_commit`].
+pub fn restore_file_snapshot(snapshot: &FileSnapshot) -> Result<(), GitToolingError> {
+    for (relative, entry) in snapshot.entries.iter() {
+        let target = snapshot.root.join(relative);
+        if let Ok(metadata) = fs::metadata(&target)
+            && metadata.is_file()
+            && entry.modified.is_some()
+            && metadata.modified().ok() == entry.modified
+            && metadata.len() == entry.len
+        {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(snapshot.blobs.path().join(&entry.blob), &target)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn restore_brings_back_modified_and_deleted_files() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        fs::create_dir_all(root.join("src"))?;
+        fs::write(root.join("src/lib.rs"), "fn original() {}\n")?;
+        fs::write(root.join("notes.txt"), "keep me\n")?;
+        fs::create_dir_all(root.join(".git"))?;
+        fs::write(root.join(".git/HEAD"), "ref: refs/heads/main\n")?;
+
+        let mut store = FileSnapshotStore::new(root)?;
+        let snapshot = store.capture()?;
+        assert_eq!(snapshot.file_count(), 2);
+
+        fs::write(root.join("src/lib.rs"), "fn changed() {}\n")?;
+        fs::remove_file(root.join("notes.txt"))?;
+        fs::write(root.join("new.txt"), "created later\n")?;
+
+        restore_file_snapshot(&snapshot)?;
+
+        assert_eq!(
+            fs::read_to_string(root.join("src/lib.rs"))?,
+            "fn original() {}\n"
+        );
+        assert_eq!(fs::read_to_string(root.join("notes.txt"))?, "keep me\n");
+        assert_eq!(fs::read_to_string(root.join("new.txt"))?, "created later\n");
+        Ok(())
+    }
+
+    #[test]
+    fn unchanged_files_share_one_copy() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        fs::write(root.join("a.txt"), "a\n")?;
+        fs::write(root.join("b.txt"), "b\n")?;
+
+        let mut store = FileSnapshotStore::new(root)?;
+        let first = store.capture()?;
+        fs::write(root.join("b.txt"), "b, but longer\n")?;
+        let second = store.capture()?;
+
+        assert_eq!(store.next_blob, 3);
+        assert_eq!(
+            first.entries.get(Path::new("a.txt")),
+            second.entries.get(Path::new("a.txt"))
+        );
+
+        restore_file_snapshot(&first)?;
+        assert_eq!(fs::read_to_string(root.join("b.txt"))?, "b\n");
+        Ok(())
+    }
+
+    #[test]
+    fn oversized_tree_is_rejected_before_copying() -> Result<(), GitToolingError> {
+        let temp = tempfile::tempdir()?;
+        let root = temp.path();
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(root.join(name), "contents\n")?;
+        }
+
+        let mut store = FileSnapshotStore::new(root)?;
+        store.max_files = 2;
+        assert!(matches!(
+            store.capture(),
+            Err(GitToolingError::SnapshotTooLarge { max_files: 2, .. })
+        ));
+        assert_eq!(store.next_blob, 0);
+        assert_eq!(fs::read_dir(store.blobs.path())?.count(), 0);
+        Ok(())
+    }
+}
@@ -1,11 +1,15 @@
 use std::fmt;
 
 mod errors;
+mod file_snapshots;
 mod ghost_commits;
 mod operations;
 mod platform;
 
 pub use errors::GitToolingError;
+pub use file_snapshots::FileSnapshot;
+pub use file_snapshots::FileSnapshotStore;
+pub use file_snapshots::restore_file_snapshot;
 pub use ghost_commits::CreateGhostCommitOptions;
 pub use ghost_commits::create_ghost_commit;
 pub use ghost_commits::restore_ghost_commit;
@@ -14,6 +14,7 @@
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 use std::sync::atomic::AtomicI64;
 use std::sync::atomic::Ordering;
 use std::time::Duration;
@@ -37,6 +38,7 @@ use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolNotification;
 use mcp_types::ModelContextProtocolRequest;
 use mcp_types::RequestId;
+use mcp_types::ToolListChangedNotification;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use tokio::io::AsyncBufReadExt;
@@ -44,6 +46,7 @@ use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::Notify;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::time;
@@ -76,6 +79,13 @@ pub struct McpClient {
 
     /// Monotonically increasing counter used to generate request IDs.
     id_counter: AtomicI64,
+
+    /// Cleared by the reader task once the server closes its STDOUT, which is
+    /// how we observe that the child process has exited or crashed.
+    alive: Arc<AtomicBool>,
+
+    /// Signalled whenever the server sends `notifications/tools/list_changed`.
+    tool_list_changed: Arc<Notify>,
 }
 
 impl McpClient {
@@ -111,6 +121,8 @@ impl McpClient {
 
         let (outgoing_tx, mut outgoing_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
         let pending: Arc<Mutex<HashMap<i64, PendingSender>>> = Arc::new(Mutex::new(HashMap::new()));
+        let alive = Arc::new(AtomicBool::new(true));
+        let tool_list_changed = Arc::new(Notify::new());
 
         // Spawn writer task. It listens on the `outgoing_rx` channel and
         // writes messages to the child's STDIN.
@@ -141,6 +153,8 @@ impl McpClient {
         // STDOUT and dispatches responses to the pending map.
         let reader_handle = {
             let pending = pending.clone();
+            let alive = alive.clone();
+            let tool_list_changed = tool_list_changed.clone();
             let mut lines = BufReader::new(stdout).lines();
 
             tokio::spawn(async move {
@@ -153,9 +167,13 @@ impl McpClient {
                         Ok(JSONRPCMessage::Error(err)) => {
                             Self::dispatch_error(err, &pending).await;
                         }
-                        Ok(JSONRPCMessage::Notification(JSONRPCNotification { .. })) => {
-                            // For now we only log server-initiated notifications.
+                        Ok(JSONRPCMessage::Notification(JSONRPCNotification {
+                            method, ..
+                        })) => {
                             info!("<- notification: {}", line);
+                            if method == ToolListChangedNotification::METHOD {
+                                tool_list_changed.notify_one();
+                            }
                         }
                         Ok(other) => {
                             // Batch responses and requests are currently not
@@ -167,6 +185,7 @@ impl McpClient {
                         }
                     }
                 }
+                alive.store(false, Ordering::SeqCst);
             })
         };
 
@@ -181,9 +200,24 @@ impl McpClient {
             outgoing_tx,
             pending,
             id_counter: AtomicI64::new(1),
+            alive,
+            tool_list_changed,
         })
     }
 
+    /// Returns `false` once the server process has closed its STDOUT, i.e. it
+    /// exited or crashed and will not answer any further requests.
+    pub fn is_alive(&self) -> bool {
+        self.alive.load(Ordering::SeqCst)
+    }
+
+    /// Handle that is notified whenever the server reports that its tool list
+    /// changed. Only a single permit is stored, so bursts of notifications
+    /// collapse into one wakeup.
+    pub fn tool_list_changed(&self) -> Arc<Notify> {
+        self.tool_list_changed.clone()
+    }
+
     /// Send an arbitrary MCP request and await the typed result.
     ///
     /// If `timeout` is `None` the call waits indefinitely. If `Some(duration)`
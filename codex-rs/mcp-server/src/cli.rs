@@ -0,0 +1,24 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+/// Environment variable read for the bearer token when `--listen` is used.
+pub const DEFAULT_BEARER_TOKEN_ENV_VAR: &str = "CODEX_MCP_SERVER_TOKEN";
+
+#[derive(Debug, Parser)]
+pub struct McpServerCli {
+    /// Serve MCP over streamable HTTP on this address (e.g. `127.0.0.1:8080`)
+    /// instead of stdio.
+    #[arg(long, value_name = "ADDR")]
+    pub listen: Option<SocketAddr>,
+
+    /// Environment variable holding the bearer token that HTTP clients must
+    /// send in the `Authorization` header.
+    #[arg(
+        long,
+        value_name = "ENV_VAR",
+        default_value = DEFAULT_BEARER_TOKEN_ENV_VAR,
+        requires = "listen"
+    )]
+    pub bearer_token_env_var: String,
+}
@@ -5,9 +5,12 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use crate::conversation_resources::ConversationResources;
 use crate::exec_approval::handle_exec_approval_request;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotification;
 use crate::outgoing_message::OutgoingNotificationMeta;
+use crate::outgoing_message::OutgoingNotificationParams;
 use crate::patch_approval::handle_patch_approval_request;
 use codex_core::CodexConversation;
 use codex_core::ConversationManager;
@@ -25,6 +28,8 @@ use codex_core::protocol::TaskCompleteEvent;
 use codex_protocol::ConversationId;
 use mcp_types::CallToolResult;
 use mcp_types::ContentBlock;
+use mcp_types::ProgressNotificationParams;
+use mcp_types::ProgressToken;
 use mcp_types::RequestId;
 use mcp_types::TextContent;
 use serde_json::json;
@@ -32,10 +37,15 @@ use tokio::sync::Mutex;
 
 pub(crate) const INVALID_PARAMS_ERROR_CODE: i64 = -32602;
 
+/// Maximum number of characters of an agent message included in a progress
+/// notification.
+const PROGRESS_MESSAGE_MAX_CHARS: usize = 120;
+
 /// Run a complete Codex session and stream events back to the client.
 ///
 /// On completion (success or error) the function sends the appropriate
 /// `tools/call` response so the LLM can continue the conversation.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_codex_tool_session(
     id: RequestId,
     initial_prompt: String,
@@ -43,6 +53,8 @@ pub async fn run_codex_tool_session(
     outgoing: Arc<OutgoingMessageSender>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    progress_token: Option<ProgressToken>,
+    resources: Arc<ConversationResources>,
 ) {
     let NewConversation {
         conversation_id,
@@ -106,13 +118,17 @@ pub async fn run_codex_tool_session(
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         id,
         running_requests_id_to_codex_uuid,
+        progress_token,
+        resources,
     )
     .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 pub async fn run_codex_tool_session_reply(
     conversation: Arc<CodexConversation>,
     outgoing: Arc<OutgoingMessageSender>,
@@ -120,6 +136,8 @@ pub async fn run_codex_tool_session_reply(
     prompt: String,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
     conversation_id: ConversationId,
+    progress_token: Option<ProgressToken>,
+    resources: Arc<ConversationResources>,
 ) {
     running_requests_id_to_codex_uuid
         .lock()
@@ -142,23 +160,32 @@ pub async fn run_codex_tool_session_reply(
 
     run_codex_tool_session_inner(
         conversation,
+        conversation_id,
         outgoing,
         request_id,
         running_requests_id_to_codex_uuid,
+        progress_token,
+        resources,
     )
     .await;
 }
 
 async fn run_codex_tool_session_inner(
     codex: Arc<CodexConversation>,
+    conversation_id: ConversationId,
     outgoing: Arc<OutgoingMessageSender>,
     request_id: RequestId,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    progress_token: Option<ProgressToken>,
+    resources: Arc<ConversationResources>,
 ) {
     let request_id_str = match &request_id {
         RequestId::String(s) => s.clone(),
         RequestId::Integer(n) => n.to_string(),
     };
+    // Progress must increase with every notification, so count the events we
+    // report rather than guessing at a total.
+    let mut progress: u64 = 0;
 
     // Stream events until the task needs to pause for user interaction or
     // completes.
@@ -172,6 +199,20 @@ async fn run_codex_tool_session_inner(
                     )
                     .await;
 
+                if let Some(progress_token) = &progress_token
+                    && let Some(message) = progress_message_for_event(&event.msg)
+                {
+                    progress += 1;
+                    send_progress(
+                        &outgoing,
+                        &request_id,
+                        progress_token.clone(),
+                        progress,
+                        message,
+                    )
+                    .await;
+                }
+
                 match event.msg {
                     EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
                         command,
@@ -242,6 +283,35 @@ async fn run_codex_tool_session_inner(
                             .remove(&request_id);
                         break;
                     }
+                    EventMsg::TurnAborted(_) => {
+                        // A `notifications/cancelled` for this request already
+                        // unregistered it, and the spec says a cancelled
+                        // request must not receive a response. Any other abort
+                        // still needs to conclude the tool call.
+                        let still_running = running_requests_id_to_codex_uuid
+                            .lock()
+                            .await
+                            .remove(&request_id)
+                            .is_some();
+                        if still_running {
+                            let result = CallToolResult {
+                                content: vec![ContentBlock::TextContent(TextContent {
+                                    r#type: "text".to_string(),
+                                    text: "Codex turn was aborted".to_string(),
+                                    annotations: None,
+                                })],
+                                is_error: Some(true),
+                                structured_content: None,
+                            };
+                            outgoing.send_response(request_id.clone(), result).await;
+                        }
+                        break;
+                    }
+                    EventMsg::TurnDiff(turn_diff) => {
+                        resources
+                            .record_turn_diff(conversation_id, turn_diff.unified_diff)
+                            .await;
+                    }
                     EventMsg::SessionConfigured(_) => {
                         tracing::error!("unexpected SessionConfigured event");
                     }
@@ -271,18 +341,19 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::StreamError(_)
                     | EventMsg::PatchApplyBegin(_)
                     | EventMsg::PatchApplyEnd(_)
-                    | EventMsg::TurnDiff(_)
                     | EventMsg::WebSearchBegin(_)
                     | EventMsg::WebSearchEnd(_)
                     | EventMsg::GetHistoryEntryResponse(_)
                     | EventMsg::PlanUpdate(_)
-                    | EventMsg::TurnAborted(_)
                     | EventMsg::ConversationPath(_)
                     | EventMsg::UserMessage(_)
                     | EventMsg::ShutdownComplete
                     | EventMsg::ViewImageToolCall(_)
                     | EventMsg::EnteredReviewMode(_)
-                    | EventMsg::ExitedReviewMode(_) => {
+                    | EventMsg::ExitedReviewMode(_)
+                    | EventMsg::SubAgentBegin(_)
+                    | EventMsg::SubAgentActivity(_)
+                    | EventMsg::SubAgentEnd(_) => {
                         // For now, we do not do anything extra for these
                         // events. Note that
                         // send(codex_event_to_notification(&event)) above has
@@ -310,3 +381,112 @@ async fn run_codex_tool_session_inner(
         }
     }
 }
+
+/// Summarize `msg` for a `notifications/progress` message, or `None` if the
+/// event is not worth reporting as progress.
+fn progress_message_for_event(msg: &EventMsg) -> Option<String> {
+    match msg {
+        EventMsg::TaskStarted(_) => Some("Codex started working".to_string()),
+        EventMsg::ExecCommandBegin(ev) => Some(format!("Running `{}`", ev.command.join(" "))),
+        EventMsg::ExecApprovalRequest(_) | EventMsg::ApplyPatchApprovalRequest(_) => {
+            Some("Waiting for approval".to_string())
+        }
+        EventMsg::PatchApplyBegin(ev) => Some(match ev.changes.len() {
+            1 => "Editing 1 file".to_string(),
+            n => format!("Editing {n} files"),
+        }),
+        EventMsg::McpToolCallBegin(ev) => Some(format!(
+            "Calling {}.{}",
+            ev.invocation.server, ev.invocation.tool
+        )),
+        EventMsg::WebSearchBegin(_) => Some("Searching the web".to_string()),
+        EventMsg::PlanUpdate(_) => Some("Updated the plan".to_string()),
+        EventMsg::AgentMessage(ev) => {
+            let first_line = ev.message.lines().next().unwrap_or_default().trim();
+            if first_line.is_empty() {
+                return None;
+            }
+            let mut message: String = first_line
+                .chars()
+                .take(PROGRESS_MESSAGE_MAX_CHARS)
+                .collect();
+            if first_line.chars().count() > PROGRESS_MESSAGE_MAX_CHARS {
+                message.push('…');
+            }
+            Some(message)
+        }
+        _ => None,
+    }
+}
+
+async fn send_progress(
+    outgoing: &OutgoingMessageSender,
+    request_id: &RequestId,
+    progress_token: ProgressToken,
+    progress: u64,
+    message: String,
+) {
+    let params = ProgressNotificationParams {
+        message: Some(message),
+        progress: progress as f64,
+        progress_token,
+        total: None,
+    };
+    // Tag the notification with the originating request like the event
+    // notifications, so the HTTP transport can stream it on that request.
+    let params = serde_json::to_value(params).and_then(|event| {
+        serde_json::to_value(OutgoingNotificationParams {
+            meta: Some(OutgoingNotificationMeta::new(Some(request_id.clone()))),
+            event,
+        })
+    });
+    match params {
+        Ok(params) => {
+            outgoing
+                .send_notification(OutgoingNotification {
+                    method: "notifications/progress".to_string(),
+                    params: Some(params),
+                })
+                .await;
+        }
+        Err(err) => tracing::error!("failed to serialize progress notification: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::ExecCommandBeginEvent;
+    use codex_core::protocol::TokenCountEvent;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn progress_messages_summarize_events() {
+        let exec = EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: "call-1".to_string(),
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: PathBuf::from("/tmp"),
+            parsed_cmd: Vec::new(),
+        });
+        assert_eq!(
+            progress_message_for_event(&exec),
+            Some("Running `cargo test`".to_string())
+        );
+
+        let long_message = "a".repeat(PROGRESS_MESSAGE_MAX_CHARS + 10);
+        let agent = EventMsg::AgentMessage(AgentMessageEvent {
+            message: format!("{long_message}\nsecond line"),
+        });
+        assert_eq!(
+            progress_message_for_event(&agent),
+            Some(format!("{}…", "a".repeat(PROGRESS_MESSAGE_MAX_CHARS)))
+        );
+
+        let tokens = EventMsg::TokenCount(TokenCountEvent {
+            info: None,
+            rate_limits: None,
+        });
+        assert_eq!(progress_message_for_event(&tokens), None);
+    }
+}
@@ -0,0 +1,352 @@
+//! Publishes Codex conversations as MCP resources.
+//!
+//! Every conversation started through this server is exposed as
+//! `codex://conversations/<id>` (a Markdown transcript read from its rollout
+//! file). Conversations whose most recent turn changed files additionally
+//! expose `codex://conversations/<id>/diff` with that turn's unified diff.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_core::Cursor as RolloutCursor;
+use codex_core::RolloutRecorder;
+use codex_core::SessionMeta;
+use codex_core::find_conversation_path_by_id_str;
+use codex_core::protocol::EventMsg;
+use codex_core::protocol::InputMessageKind;
+use codex_protocol::ConversationId;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::SessionSource;
+use mcp_types::ListResourceTemplatesResult;
+use mcp_types::ListResourcesResult;
+use mcp_types::ReadResourceResult;
+use mcp_types::ReadResourceResultContents;
+use mcp_types::Resource;
+use mcp_types::ResourceTemplate;
+use mcp_types::TextResourceContents;
+use tokio::sync::Mutex;
+
+const URI_PREFIX: &str = "codex://conversations/";
+const DIFF_SUFFIX: &str = "/diff";
+const TRANSCRIPT_MIME_TYPE: &str = "text/markdown";
+const DIFF_MIME_TYPE: &str = "text/x-diff";
+const PAGE_SIZE: usize = 50;
+
+/// Identifies one of the resources published for a conversation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConversationResource {
+    Transcript(ConversationId),
+    Diff(ConversationId),
+}
+
+impl ConversationResource {
+    pub(crate) fn parse(uri: &str) -> Option<Self> {
+        let rest = uri.strip_prefix(URI_PREFIX)?;
+        match rest.strip_suffix(DIFF_SUFFIX) {
+            Some(id) => ConversationId::from_string(id).ok().map(Self::Diff),
+            None => ConversationId::from_string(rest).ok().map(Self::Transcript),
+        }
+    }
+
+    pub(crate) fn uri(&self) -> String {
+        match self {
+            Self::Transcript(id) => format!("{URI_PREFIX}{id}"),
+            Self::Diff(id) => format!("{URI_PREFIX}{id}{DIFF_SUFFIX}"),
+        }
+    }
+}
+
+pub(crate) struct ConversationResources {
+    codex_home: PathBuf,
+    /// Latest `TurnDiff` per conversation. Turn diffs are not persisted in
+    /// rollouts, so they are only available for conversations run by this
+    /// server process.
+    turn_diffs: Mutex<HashMap<ConversationId, String>>,
+}
+
+impl ConversationResources {
+    pub(crate) fn new(codex_home: PathBuf) -> Self {
+        Self {
+            codex_home,
+            turn_diffs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) async fn record_turn_diff(&self, conversation_id: ConversationId, diff: String) {
+        self.turn_diffs.lock().await.insert(conversation_id, diff);
+    }
+
+    /// Handle `resources/list`. The cursor is the rollout pagination cursor.
+    pub(crate) async fn list(&self, cursor: Option<String>) -> Result<ListResourcesResult, String> {
+        let cursor = cursor
+            .map(|c| serde_json::from_value::<RolloutCursor>(serde_json::Value::String(c)))
+            .transpose()
+            .map_err(|e| format!("invalid cursor: {e}"))?;
+        let page = RolloutRecorder::list_conversations(
+            &self.codex_home,
+            PAGE_SIZE,
+            cursor.as_ref(),
+            &[SessionSource::Mcp],
+        )
+        .await
+        .map_err(|e| format!("failed to list conversations: {e}"))?;
+
+        let turn_diffs = self.turn_diffs.lock().await;
+        let mut resources = Vec::new();
+        for item in page.items {
+            let Some(meta) = item
+                .head
+                .first()
+                .and_then(|line| serde_json::from_value::<SessionMeta>(line.clone()).ok())
+            else {
+                continue;
+            };
+            let timestamp = item.updated_at.or(item.created_at).unwrap_or_default();
+            resources.push(Resource {
+                annotations: None,
+                description: Some(format!(
+                    "Transcript of the Codex conversation in {} ({timestamp})",
+                    meta.cwd.display()
+                )),
+                mime_type: Some(TRANSCRIPT_MIME_TYPE.to_string()),
+                name: format!("conversation-{}", meta.id),
+                size: None,
+                title: Some(format!("Codex conversation {}", meta.id)),
+                uri: ConversationResource::Transcript(meta.id).uri(),
+            });
+            if turn_diffs.contains_key(&meta.id) {
+                resources.push(Resource {
+                    annotations: None,
+                    description: Some("Unified diff of the most recent turn".to_string()),
+                    mime_type: Some(DIFF_MIME_TYPE.to_string()),
+                    name: format!("conversation-{}-diff", meta.id),
+                    size: None,
+                    title: Some(format!("Codex conversation {} turn diff", meta.id)),
+                    uri: ConversationResource::Diff(meta.id).uri(),
+                });
+            }
+        }
+
+        let next_cursor = page
+            .next_cursor
+            .and_then(|c| serde_json::to_value(c).ok())
+            .and_then(|v| v.as_str().map(str::to_string));
+        Ok(ListResourcesResult {
+            next_cursor,
+            resources,
+        })
+    }
+
+    /// Handle `resources/read`.
+    pub(crate) async fn read(&self, uri: &str) -> Result<ReadResourceResult, String> {
+        let resource =
+            ConversationResource::parse(uri).ok_or_else(|| format!("unknown resource: {uri}"))?;
+        let (text, mime_type) = match resource {
+            ConversationResource::Transcript(id) => {
+                (self.read_transcript(id).await?, TRANSCRIPT_MIME_TYPE)
+            }
+            ConversationResource::Diff(id) => {
+                let diff = self
+                    .turn_diffs
+                    .lock()
+                    .await
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| format!("no turn diff recorded for conversation {id}"))?;
+                (diff, DIFF_MIME_TYPE)
+            }
+        };
+        Ok(ReadResourceResult {
+            contents: vec![ReadResourceResultContents::TextResourceContents(
+                TextResourceContents {
+                    mime_type: Some(mime_type.to_string()),
+                    text,
+                    uri: uri.to_string(),
+                },
+            )],
+        })
+    }
+
+    /// Only conversations started through this server are readable, matching
+    /// what `list` publishes; other sessions are reported as not found.
+    async fn read_transcript(&self, conversation_id: ConversationId) -> Result<String, String> {
+        let not_found = || format!("conversation not found: {conversation_id}");
+        let path = find_conversation_path_by_id_str(&self.codex_home, &conversation_id.to_string())
+            .await
+            .map_err(|e| format!("failed to locate conversation {conversation_id}: {e}"))?
+            .ok_or_else(not_found)?;
+        let history = RolloutRecorder::get_rollout_history(&path)
+            .await
+            .map_err(|e| format!("failed to read conversation {conversation_id}: {e}"))?;
+        let source = history
+            .get_rollout_items()
+            .into_iter()
+            .find_map(|item| match item {
+                RolloutItem::SessionMeta(line) => Some(line.meta.source),
+                _ => None,
+            });
+        if source != Some(SessionSource::Mcp) {
+            return Err(not_found());
+        }
+        Ok(render_transcript(
+            &history.get_event_msgs().unwrap_or_default(),
+        ))
+    }
+}
+
+/// URI templates advertised via `resources/templates/list`.
+pub(crate) fn resource_templates() -> ListResourceTemplatesResult {
+    ListResourceTemplatesResult {
+        next_cursor: None,
+        resource_templates: vec![
+            ResourceTemplate {
+                annotations: None,
+                description: Some("Transcript of a Codex conversation".to_string()),
+                mime_type: Some(TRANSCRIPT_MIME_TYPE.to_string()),
+                name: "conversation".to_string(),
+                title: Some("Codex conversation".to_string()),
+                uri_template: format!("{URI_PREFIX}{{conversationId}}"),
+            },
+            ResourceTemplate {
+                annotations: None,
+                description: Some(
+                    "Unified diff of the most recent turn of a Codex conversation".to_string(),
+                ),
+                mime_type: Some(DIFF_MIME_TYPE.to_string()),
+                name: "conversation-diff".to_string(),
+                title: Some("Codex conversation turn diff".to_string()),
+                uri_template: format!("{URI_PREFIX}{{conversationId}}{DIFF_SUFFIX}"),
+            },
+        ],
+    }
+}
+
+/// Render user and agent messages as a Markdown transcript. Injected context
+/// such as environment details and AGENTS.md instructions is skipped.
+fn render_transcript(events: &[EventMsg]) -> String {
+    let mut sections = Vec::new();
+    for event in events {
+        match event {
+            EventMsg::UserMessage(ev) => {
+                if matches!(ev.kind, None | Some(InputMessageKind::Plain)) {
+                    sections.push(format!("## User\n\n{}", ev.message.trim()));
+                }
+            }
+            EventMsg::AgentMessage(ev) => {
+                sections.push(format!("## Codex\n\n{}", ev.message.trim()));
+            }
+            _ => {}
+        }
+    }
+    let mut transcript = sections.join("\n\n");
+    transcript.push('\n');
+    transcript
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_core::protocol::AgentMessageEvent;
+    use codex_core::protocol::UserMessageEvent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_transcript_and_diff_uris() {
+        let id = ConversationId::new();
+        let transcript = ConversationResource::Transcript(id);
+        let diff = ConversationResource::Diff(id);
+
+        assert_eq!(
+            ConversationResource::parse(&transcript.uri()),
+            Some(transcript)
+        );
+        assert_eq!(ConversationResource::parse(&diff.uri()), Some(diff));
+        assert_eq!(
+            ConversationResource::parse("codex://conversations/nope"),
+            None
+        );
+        assert_eq!(ConversationResource::parse("file:///tmp/x"), None);
+    }
+
+    fn write_rollout(codex_home: &std::path::Path, source: SessionSource) -> ConversationId {
+        let id = ConversationId::new();
+        let dir = codex_home.join("sessions/2025/01/02");
+        std::fs::create_dir_all(&dir).expect("create sessions dir");
+        let lines = [
+            serde_json::json!({
+                "timestamp": "2025-01-02T03:04:05Z",
+                "type": "session_meta",
+                "payload": {
+                    "id": id,
+                    "timestamp": "2025-01-02T03:04:05Z",
+                    "cwd": ".",
+                    "originator": "test",
+                    "cli_version": "0.0.0",
+                    "instructions": null,
+                    "source": source,
+                },
+            }),
+            serde_json::json!({
+                "timestamp": "2025-01-02T03:04:05Z",
+                "type": "event_msg",
+                "payload": {"type": "user_message", "message": "hello", "kind": "plain"},
+            }),
+        ];
+        let contents: String = lines.iter().map(|line| format!("{line}\n")).collect();
+        std::fs::write(
+            dir.join(format!("rollout-2025-01-02T03-04-05-{id}.jsonl")),
+            contents,
+        )
+        .expect("write rollout");
+        id
+    }
+
+    #[tokio::test]
+    async fn reads_only_mcp_transcripts() {
+        let codex_home = tempfile::tempdir().expect("create temp dir");
+        let mcp = write_rollout(codex_home.path(), SessionSource::Mcp);
+        let tui = write_rollout(codex_home.path(), SessionSource::Cli);
+        let resources = ConversationResources::new(codex_home.path().to_path_buf());
+
+        let read = resources
+            .read(&ConversationResource::Transcript(mcp).uri())
+            .await
+            .expect("read mcp transcript");
+        let ReadResourceResultContents::TextResourceContents(contents) = &read.contents[0] else {
+            panic!("expected text contents");
+        };
+        assert_eq!(contents.text, "## User\n\nhello\n");
+
+        assert_eq!(
+            resources
+                .read(&ConversationResource::Transcript(tui).uri())
+                .await
+                .err(),
+            Some(format!("conversation not found: {tui}"))
+        );
+    }
+
+    #[test]
+    fn transcript_skips_injected_context() {
+        let events = vec![
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "<environment_context>...</environment_context>".to_string(),
+                kind: Some(InputMessageKind::EnvironmentContext),
+                images: None,
+            }),
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "fix the build".to_string(),
+                kind: Some(InputMessageKind::Plain),
+                images: None,
+            }),
+            EventMsg::AgentMessage(AgentMessageEvent {
+                message: "Done.\n".to_string(),
+            }),
+        ];
+
+        assert_eq!(
+            render_transcript(&events),
+            "## User\n\nfix the build\n\n## Codex\n\nDone.\n"
+        );
+    }
+}
@@ -0,0 +1,526 @@
+//! Streamable HTTP transport for the MCP server (`codex mcp-server --listen`).
+//!
+//! Implements the [streamable HTTP transport] on a single `/mcp` endpoint:
+//!
+//! - `POST` carries one JSON-RPC message from the client. An `initialize`
+//!   request starts a new session whose id is returned in the
+//!   `Mcp-Session-Id` header. Every other request is answered with an SSE
+//!   stream that carries the messages related to it (event notifications,
+//!   elicitations, progress) and ends with its response.
+//! - `GET` opens an SSE stream for server messages that are not tied to a
+//!   request.
+//! - `DELETE` ends the session. Sessions without open streams that see no
+//!   requests for [`SESSION_IDLE_TIMEOUT`] are ended as well.
+//!
+//! Each session gets its own [`MessageProcessor`], while all sessions share
+//! the [`ServerState`] and therefore the same `ConversationManager`.
+//!
+//! [streamable HTTP transport]: https://modelcontextprotocol.io/specification/2025-06-18/basic/transports#streamable-http
+
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::io::Result as IoResult;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::Weak;
+use std::time::Duration;
+use std::time::Instant;
+
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::http::header::AUTHORIZATION;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::get;
+use mcp_types::InitializeRequest;
+use mcp_types::JSONRPCMessage;
+use mcp_types::ModelContextProtocolRequest;
+use mcp_types::RequestId;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::info;
+use tracing::warn;
+
+use crate::CHANNEL_CAPACITY;
+use crate::message_processor::MessageProcessor;
+use crate::message_processor::ServerState;
+use crate::outgoing_message::OutgoingMessage;
+use crate::outgoing_message::OutgoingMessageSender;
+
+const MCP_PATH: &str = "/mcp";
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+const SSE_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+/// Maximum number of unrelated server messages kept for a session that has
+/// no `GET` stream open. Older messages are dropped first.
+const MAX_BACKLOG: usize = 256;
+/// Sessions are ended after this long without requests or open streams,
+/// since clients that go away without `DELETE` would otherwise keep them.
+const SESSION_IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const SESSION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+type SessionMap = HashMap<String, Arc<HttpSession>>;
+type HttpError = (StatusCode, String);
+
+struct AppState {
+    /// SHA-256 of the bearer token. Comparing digests keeps the time taken
+    /// by `authorize` independent of how much of a guess matches the token.
+    bearer_token_digest: [u8; 32],
+    server: ServerState,
+    sessions: Mutex<SessionMap>,
+}
+
+/// Serve MCP over streamable HTTP on `addr` until the process is terminated.
+pub(crate) async fn serve(
+    addr: SocketAddr,
+    bearer_token: String,
+    server: ServerState,
+) -> IoResult<()> {
+    let state = Arc::new(AppState {
+        bearer_token_digest: Sha256::digest(bearer_token.as_bytes()).into(),
+        server,
+        sessions: Mutex::new(HashMap::new()),
+    });
+    tokio::spawn(expire_idle_sessions(Arc::downgrade(&state)));
+    let router = Router::new()
+        .route(
+            MCP_PATH,
+            get(handle_get).post(handle_post).delete(handle_delete),
+        )
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(
+        "codex mcp-server listening on http://{}{MCP_PATH}",
+        listener.local_addr()?
+    );
+    axum::serve(listener, router).await
+}
+
+async fn handle_post(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(err) = authorize(&state, &headers) {
+        return err.into_response();
+    }
+    let message = match serde_json::from_slice::<JSONRPCMessage>(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                format!("expected a single JSON-RPC message: {e}"),
+            )
+                .into_response();
+        }
+    };
+
+    let is_initialize = matches!(
+        &message,
+        JSONRPCMessage::Request(request) if request.method == InitializeRequest::METHOD
+    );
+    let (session_id, session) = if is_initialize {
+        let session_id = uuid::Uuid::new_v4().to_string();
+        let session = HttpSession::spawn(state.server.clone());
+        lock(&state.sessions).insert(session_id.clone(), session.clone());
+        info!("started MCP session {session_id}");
+        (session_id, session)
+    } else {
+        match find_session(&state, &headers) {
+            Ok(found) => found,
+            Err(err) => return err.into_response(),
+        }
+    };
+
+    let mut response = match message {
+        JSONRPCMessage::Request(request) => {
+            let rx = session.open_request_stream(request.id.clone());
+            if session
+                .incoming_tx
+                .send(JSONRPCMessage::Request(request))
+                .await
+                .is_err()
+            {
+                return (StatusCode::GONE, "session has shut down").into_response();
+            }
+            sse_response(rx, None)
+        }
+        other => {
+            if session.incoming_tx.send(other).await.is_err() {
+                return (StatusCode::GONE, "session has shut down").into_response();
+            }
+            StatusCode::ACCEPTED.into_response()
+        }
+    };
+    if let Ok(value) = HeaderValue::from_str(&session_id) {
+        response.headers_mut().insert(SESSION_ID_HEADER, value);
+    }
+    response
+}
+
+async fn handle_get(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if let Err(err) = authorize(&state, &headers) {
+        return err.into_response();
+    }
+    match find_session(&state, &headers) {
+        Ok((_, session)) => {
+            let rx = session.open_standalone_stream();
+            sse_response(rx, Some(KeepAlive::new().interval(SSE_KEEP_ALIVE_INTERVAL)))
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+async fn handle_delete(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Response {
+    if let Err(err) = authorize(&state, &headers) {
+        return err.into_response();
+    }
+    match find_session(&state, &headers) {
+        Ok((session_id, _)) => {
+            lock(&state.sessions).remove(&session_id);
+            info!("closed MCP session {session_id}");
+            StatusCode::OK.into_response()
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+/// End sessions that have been idle for [`SESSION_IDLE_TIMEOUT`] until the
+/// server state is dropped.
+async fn expire_idle_sessions(state: Weak<AppState>) {
+    let mut interval = tokio::time::interval(SESSION_SWEEP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+        let now = Instant::now();
+        lock(&state.sessions).retain(|session_id, session| {
+            let idle = session.is_idle(now);
+            if idle {
+                info!("expired idle MCP session {session_id}");
+            }
+            !idle
+        });
+    }
+}
+
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), HttpError> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match token {
+        Some(token) if Sha256::digest(token.as_bytes())[..] == state.bearer_token_digest => Ok(()),
+        _ => Err((
+            StatusCode::UNAUTHORIZED,
+            "missing or invalid bearer token".to_string(),
+        )),
+    }
+}
+
+fn find_session(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<(String, Arc<HttpSession>), HttpError> {
+    let Some(session_id) = headers
+        .get(SESSION_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "missing Mcp-Session-Id header".to_string(),
+        ));
+    };
+    match lock(&state.sessions).get(session_id) {
+        Some(session) => {
+            *lock(&session.last_request) = Instant::now();
+            Ok((session_id.to_string(), session.clone()))
+        }
+        None => Err((
+            StatusCode::NOT_FOUND,
+            format!("unknown session: {session_id}"),
+        )),
+    }
+}
+
+fn sse_response(
+    rx: mpsc::UnboundedReceiver<JSONRPCMessage>,
+    keep_alive: Option<KeepAlive>,
+) -> Response {
+    let stream = UnboundedReceiverStream::new(rx).map(|message| {
+        let data = serde_json::to_string(&message).unwrap_or_else(|e| {
+            warn!("failed to serialize JSONRPCMessage: {e}");
+            String::new()
+        });
+        Ok::<_, Infallible>(Event::default().event("message").data(data))
+    });
+    let sse = Sse::new(stream);
+    match keep_alive {
+        Some(keep_alive) => sse.keep_alive(keep_alive).into_response(),
+        None => sse.into_response(),
+    }
+}
+
+/// One client session: a [`MessageProcessor`] fed through `incoming_tx`, and
+/// a router task that delivers its output to the open SSE streams.
+struct HttpSession {
+    incoming_tx: mpsc::Sender<JSONRPCMessage>,
+    streams: Arc<Mutex<SessionStreams>>,
+    last_request: Mutex<Instant>,
+}
+
+impl HttpSession {
+    fn spawn(server: ServerState) -> Arc<Self> {
+        let (incoming_tx, mut incoming_rx) = mpsc::channel::<JSONRPCMessage>(CHANNEL_CAPACITY);
+        let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<OutgoingMessage>();
+        let streams = Arc::new(Mutex::new(SessionStreams::default()));
+
+        let mut processor = MessageProcessor::new(OutgoingMessageSender::new(outgoing_tx), server);
+        tokio::spawn(async move {
+            while let Some(msg) = incoming_rx.recv().await {
+                processor.process_message(msg).await;
+            }
+        });
+
+        tokio::spawn({
+            let streams = streams.clone();
+            async move {
+                while let Some(outgoing_message) = outgoing_rx.recv().await {
+                    lock(&streams).route(outgoing_message.into());
+                }
+            }
+        });
+
+        Arc::new(Self {
+            incoming_tx,
+            streams,
+            last_request: Mutex::new(Instant::now()),
+        })
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        !lock(&self.streams).has_open_stream()
+            && now.saturating_duration_since(*lock(&self.last_request)) >= SESSION_IDLE_TIMEOUT
+    }
+
+    /// Register the stream for `request_id` before the request is processed so
+    /// that nothing emitted for it is missed.
+    fn open_request_stream(
+        &self,
+        request_id: RequestId,
+    ) -> mpsc::UnboundedReceiver<JSONRPCMessage> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        lock(&self.streams).requests.insert(request_id, tx);
+        rx
+    }
+
+    fn open_standalone_stream(&self) -> mpsc::UnboundedReceiver<JSONRPCMessage> {
+        lock(&self.streams).open_standalone()
+    }
+}
+
+#[derive(Default)]
+struct SessionStreams {
+    /// SSE streams of in-flight client requests, closed once the response
+    /// has been sent.
+    requests: HashMap<RequestId, mpsc::UnboundedSender<JSONRPCMessage>>,
+    /// The stream opened with `GET`, if any.
+    standalone: Option<mpsc::UnboundedSender<JSONRPCMessage>>,
+    /// Messages waiting for a `GET` stream.
+    backlog: VecDeque<JSONRPCMessage>,
+}
+
+impl SessionStreams {
+    /// Open the `GET` stream, replacing any previous one, and flush the
+    /// backlog into it.
+    fn open_standalone(&mut self) -> mpsc::UnboundedReceiver<JSONRPCMessage> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        for message in self.backlog.drain(..) {
+            let _ = tx.send(message);
+        }
+        self.standalone = Some(tx);
+        rx
+    }
+
+    /// Whether a client is still connected to one of the session's streams.
+    fn has_open_stream(&self) -> bool {
+        self.requests.values().any(|tx| !tx.is_closed())
+            || self.standalone.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+
+    fn route(&mut self, message: JSONRPCMessage) {
+        match &message {
+            JSONRPCMessage::Response(_) | JSONRPCMessage::Error(_) => {
+                let Some(request_id) = related_request_id(&message) else {
+                    return;
+                };
+                // Dropping the sender ends the SSE stream after the response.
+                match self.requests.remove(&request_id) {
+                    Some(tx) => {
+                        let _ = tx.send(message);
+                    }
+                    None => warn!("no open stream for response to {request_id:?}"),
+                }
+            }
+            JSONRPCMessage::Request(_) | JSONRPCMessage::Notification(_) => {
+                let message = match related_request_id(&message)
+                    .and_then(|request_id| self.requests.get(&request_id))
+                {
+                    Some(tx) => match tx.send(message) {
+                        Ok(()) => return,
+                        Err(mpsc::error::SendError(message)) => message,
+                    },
+                    None => message,
+                };
+                self.send_standalone(message);
+            }
+        }
+    }
+
+    fn send_standalone(&mut self, message: JSONRPCMessage) {
+        let message = match &self.standalone {
+            Some(tx) => match tx.send(message) {
+                Ok(()) => return,
+                Err(mpsc::error::SendError(message)) => {
+                    self.standalone = None;
+                    message
+                }
+            },
+            None => message,
+        };
+        if self.backlog.len() == MAX_BACKLOG {
+            self.backlog.pop_front();
+        }
+        self.backlog.push_back(message);
+    }
+}
+
+/// The client request that `message` belongs to: the id of a response, the
+/// `_meta.requestId` of a notification, or the tool call that an elicitation
+/// request was raised for.
+fn related_request_id(message: &JSONRPCMessage) -> Option<RequestId> {
+    match message {
+        JSONRPCMessage::Response(response) => Some(response.id.clone()),
+        JSONRPCMessage::Error(error) => Some(error.id.clone()),
+        JSONRPCMessage::Notification(notification) => notification
+            .params
+            .as_ref()
+            .and_then(|params| params.get("_meta"))
+            .and_then(|meta| meta.get("requestId"))
+            .and_then(|id| serde_json::from_value(id.clone()).ok()),
+        JSONRPCMessage::Request(request) => request
+            .params
+            .as_ref()
+            .and_then(|params| params.get("codex_mcp_tool_call_id"))
+            .and_then(|id| id.as_str())
+            .map(|id| match id.parse::<i64>() {
+                Ok(n) => RequestId::Integer(n),
+                Err(_) => RequestId::String(id.to_string()),
+            }),
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mcp_types::JSONRPC_VERSION;
+    use mcp_types::JSONRPCNotification;
+    use mcp_types::JSONRPCResponse;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn notification(params: serde_json::Value) -> JSONRPCMessage {
+        JSONRPCMessage::Notification(JSONRPCNotification {
+            jsonrpc: JSONRPC_VERSION.into(),
+            method: "codex/event".to_string(),
+            params: Some(params),
+        })
+    }
+
+    #[test]
+    fn routes_related_messages_to_request_stream() {
+        let mut streams = SessionStreams::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        streams.requests.insert(RequestId::Integer(7), tx);
+
+        let related = notification(json!({ "_meta": { "requestId": 7 }, "msg": {} }));
+        let response = JSONRPCMessage::Response(JSONRPCResponse {
+            jsonrpc: JSONRPC_VERSION.into(),
+            id: RequestId::Integer(7),
+            result: json!({}),
+        });
+        streams.route(related.clone());
+        streams.route(response.clone());
+
+        assert_eq!(rx.try_recv().ok(), Some(related));
+        assert_eq!(rx.try_recv().ok(), Some(response));
+        // The stream is closed once the response has been delivered.
+        assert!(streams.requests.is_empty());
+        assert!(matches!(
+            rx.try_recv(),
+            Err(mpsc::error::TryRecvError::Disconnected)
+        ));
+    }
+
+    #[test]
+    fn unrelated_messages_wait_for_standalone_stream() {
+        let mut streams = SessionStreams::default();
+        let unrelated = notification(json!({ "msg": {} }));
+        streams.route(unrelated.clone());
+
+        let mut rx = streams.open_standalone();
+        assert_eq!(rx.try_recv().ok(), Some(unrelated));
+        assert!(streams.backlog.is_empty());
+    }
+
+    #[test]
+    fn disconnected_streams_do_not_keep_a_session_open() {
+        let mut streams = SessionStreams::default();
+        assert!(!streams.has_open_stream());
+
+        let rx = streams.open_standalone();
+        assert!(streams.has_open_stream());
+        drop(rx);
+        assert!(!streams.has_open_stream());
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        streams.requests.insert(RequestId::Integer(1), tx);
+        assert!(streams.has_open_stream());
+        drop(rx);
+        assert!(!streams.has_open_stream());
+    }
+
+    #[test]
+    fn elicitations_are_related_to_their_tool_call() {
+        let elicitation = JSONRPCMessage::Request(mcp_types::JSONRPCRequest {
+            jsonrpc: JSONRPC_VERSION.into(),
+            id: RequestId::Integer(0),
+            method: "elicitation/create".to_string(),
+            params: Some(json!({ "codex_mcp_tool_call_id": "3" })),
+        });
+        assert_eq!(
+            related_request_id(&elicitation),
+            Some(RequestId::Integer(3))
+        );
+    }
+}
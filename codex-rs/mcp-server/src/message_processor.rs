@@ -5,6 +5,8 @@ use crate::codex_tool_config::CodexToolCallParam;
 use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
+use crate::conversation_resources::ConversationResources;
+use crate::conversation_resources::resource_templates;
 use crate::error_code::INVALID_REQUEST_ERROR_CODE;
 use crate::outgoing_message::OutgoingMessageSender;
 use codex_protocol::ConversationId;
@@ -22,12 +24,15 @@ use mcp_types::ClientRequest as McpClientRequest;
 use mcp_types::ContentBlock;
 use mcp_types::JSONRPCError;
 use mcp_types::JSONRPCErrorError;
+use mcp_types::JSONRPCMessage;
 use mcp_types::JSONRPCNotification;
 use mcp_types::JSONRPCRequest;
 use mcp_types::JSONRPCResponse;
 use mcp_types::ListToolsResult;
 use mcp_types::ModelContextProtocolRequest;
+use mcp_types::ProgressToken;
 use mcp_types::RequestId;
+use mcp_types::ServerCapabilitiesResources;
 use mcp_types::ServerCapabilitiesTools;
 use mcp_types::ServerNotification;
 use mcp_types::TextContent;
@@ -36,38 +41,79 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::task;
 
+/// State shared by every client session served by this process, so that a
+/// conversation started by one client can be continued by another.
+#[derive(Clone)]
+pub(crate) struct ServerState {
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    conversation_manager: Arc<ConversationManager>,
+    resources: Arc<ConversationResources>,
+}
+
+impl ServerState {
+    pub(crate) fn new(codex_linux_sandbox_exe: Option<PathBuf>, config: &Config) -> Self {
+        let auth_manager = AuthManager::shared(config.codex_home.clone(), false);
+        let conversation_manager =
+            Arc::new(ConversationManager::new(auth_manager, SessionSource::Mcp));
+        let resources = Arc::new(ConversationResources::new(config.codex_home.clone()));
+        Self {
+            codex_linux_sandbox_exe,
+            conversation_manager,
+            resources,
+        }
+    }
+}
+
 pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
     initialized: bool,
     codex_linux_sandbox_exe: Option<PathBuf>,
     conversation_manager: Arc<ConversationManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ConversationId>>>,
+    resources: Arc<ConversationResources>,
 }
 
 impl MessageProcessor {
-    /// Create a new `MessageProcessor`, retaining a handle to the outgoing
-    /// `Sender` so handlers can enqueue messages to be written to stdout.
-    pub(crate) fn new(
-        outgoing: OutgoingMessageSender,
-        codex_linux_sandbox_exe: Option<PathBuf>,
-        config: Arc<Config>,
-    ) -> Self {
-        let outgoing = Arc::new(outgoing);
-        let auth_manager = AuthManager::shared(config.codex_home.clone(), false);
-        let conversation_manager =
-            Arc::new(ConversationManager::new(auth_manager, SessionSource::Mcp));
+    /// Create a new `MessageProcessor` for one client session, retaining a
+    /// handle to the outgoing `Sender` so handlers can enqueue messages for
+    /// that client.
+    pub(crate) fn new(outgoing: OutgoingMessageSender, state: ServerState) -> Self {
+        let ServerState {
+            codex_linux_sandbox_exe,
+            conversation_manager,
+            resources,
+        } = state;
         Self {
-            outgoing,
+            outgoing: Arc::new(outgoing),
             initialized: false,
             codex_linux_sandbox_exe,
             conversation_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            resources,
+        }
+    }
+
+    /// Dispatch a message received from the client.
+    pub(crate) async fn process_message(&mut self, message: JSONRPCMessage) {
+        match message {
+            JSONRPCMessage::Request(r) => self.process_request(r).await,
+            JSONRPCMessage::Response(r) => self.process_response(r).await,
+            JSONRPCMessage::Notification(n) => self.process_notification(n).await,
+            JSONRPCMessage::Error(e) => self.process_error(e),
         }
     }
 
     pub(crate) async fn process_request(&mut self, request: JSONRPCRequest) {
         // Hold on to the ID so we can respond.
         let request_id = request.id.clone();
+        // `_meta` is not part of the typed params, so pick out the progress
+        // token before the request is converted.
+        let progress_token = request
+            .params
+            .as_ref()
+            .and_then(|params| params.get("_meta"))
+            .and_then(|meta| meta.get("progressToken"))
+            .and_then(|token| serde_json::from_value::<ProgressToken>(token.clone()).ok());
 
         let client_request = match McpClientRequest::try_from(request) {
             Ok(client_request) => client_request,
@@ -86,13 +132,14 @@ impl MessageProcessor {
                 self.handle_ping(request_id, params).await;
             }
             McpClientRequest::ListResourcesRequest(params) => {
-                self.handle_list_resources(params);
+                self.handle_list_resources(request_id, params).await;
             }
             McpClientRequest::ListResourceTemplatesRequest(params) => {
-                self.handle_list_resource_templates(params);
+                self.handle_list_resource_templates(request_id, params)
+                    .await;
             }
             McpClientRequest::ReadResourceRequest(params) => {
-                self.handle_read_resource(params);
+                self.handle_read_resource(request_id, params).await;
             }
             McpClientRequest::SubscribeRequest(params) => {
                 self.handle_subscribe(params);
@@ -110,7 +157,8 @@ impl MessageProcessor {
                 self.handle_list_tools(request_id, params).await;
             }
             McpClientRequest::CallToolRequest(params) => {
-                self.handle_call_tool(request_id, params).await;
+                self.handle_call_tool(request_id, params, progress_token)
+                    .await;
             }
             McpClientRequest::SetLevelRequest(params) => {
                 self.handle_set_level(params);
@@ -205,7 +253,10 @@ impl MessageProcessor {
                 experimental: None,
                 logging: None,
                 prompts: None,
-                resources: None,
+                resources: Some(ServerCapabilitiesResources {
+                    list_changed: None,
+                    subscribe: None,
+                }),
                 tools: Some(ServerCapabilitiesTools {
                     list_changed: Some(true),
                 }),
@@ -242,26 +293,55 @@ impl MessageProcessor {
             .await;
     }
 
-    fn handle_list_resources(
+    async fn handle_list_resources(
         &self,
+        id: RequestId,
         params: <mcp_types::ListResourcesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/list -> params: {:?}", params);
+        let cursor = params.and_then(|p| p.cursor);
+        match self.resources.list(cursor).await {
+            Ok(result) => {
+                self.send_response::<mcp_types::ListResourcesRequest>(id, result)
+                    .await;
+            }
+            Err(message) => self.send_invalid_request(id, message).await,
+        }
     }
 
-    fn handle_list_resource_templates(
+    async fn handle_list_resource_templates(
         &self,
+        id: RequestId,
         params:
             <mcp_types::ListResourceTemplatesRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/templates/list -> params: {:?}", params);
+        self.send_response::<mcp_types::ListResourceTemplatesRequest>(id, resource_templates())
+            .await;
     }
 
-    fn handle_read_resource(
+    async fn handle_read_resource(
         &self,
+        id: RequestId,
         params: <mcp_types::ReadResourceRequest as mcp_types::ModelContextProtocolRequest>::Params,
     ) {
         tracing::info!("resources/read -> params: {:?}", params);
+        match self.resources.read(&params.uri).await {
+            Ok(result) => {
+                self.send_response::<mcp_types::ReadResourceRequest>(id, result)
+                    .await;
+            }
+            Err(message) => self.send_invalid_request(id, message).await,
+        }
+    }
+
+    async fn send_invalid_request(&self, id: RequestId, message: String) {
+        let error = JSONRPCErrorError {
+            code: INVALID_REQUEST_ERROR_CODE,
+            message,
+            data: None,
+        };
+        self.outgoing.send_error(id, error).await;
     }
 
     fn handle_subscribe(
@@ -314,14 +394,18 @@ impl MessageProcessor {
         &self,
         id: RequestId,
         params: <mcp_types::CallToolRequest as mcp_types::ModelContextProtocolRequest>::Params,
+        progress_token: Option<ProgressToken>,
     ) {
         tracing::info!("tools/call -> params: {:?}", params);
         let CallToolRequestParams { name, arguments } = params;
 
         match name.as_str() {
-            "codex" => self.handle_tool_call_codex(id, arguments).await,
+            "codex" => {
+                self.handle_tool_call_codex(id, arguments, progress_token)
+                    .await
+            }
             "codex-reply" => {
-                self.handle_tool_call_codex_session_reply(id, arguments)
+                self.handle_tool_call_codex_session_reply(id, arguments, progress_token)
                     .await
             }
             _ => {
@@ -339,7 +423,12 @@ impl MessageProcessor {
             }
         }
     }
-    async fn handle_tool_call_codex(&self, id: RequestId, arguments: Option<serde_json::Value>) {
+    async fn handle_tool_call_codex(
+        &self,
+        id: RequestId,
+        arguments: Option<serde_json::Value>,
+        progress_token: Option<ProgressToken>,
+    ) {
         let (initial_prompt, config): (String, Config) = match arguments {
             Some(json_val) => match serde_json::from_value::<CodexToolCallParam>(json_val) {
                 Ok(tool_cfg) => match tool_cfg
@@ -401,6 +490,7 @@ impl MessageProcessor {
         let outgoing = self.outgoing.clone();
         let conversation_manager = self.conversation_manager.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let resources = self.resources.clone();
 
         // Spawn an async task to handle the Codex session so that we do not
         // block the synchronous message-processing loop.
@@ -413,6 +503,8 @@ impl MessageProcessor {
                 outgoing,
                 conversation_manager,
                 running_requests_id_to_codex_uuid,
+                progress_token,
+                resources,
             )
             .await;
         });
@@ -422,6 +514,7 @@ impl MessageProcessor {
         &self,
         request_id: RequestId,
         arguments: Option<serde_json::Value>,
+        progress_token: Option<ProgressToken>,
     ) {
         tracing::info!("tools/call -> params: {:?}", arguments);
 
@@ -488,6 +581,7 @@ impl MessageProcessor {
         // Clone outgoing to move into async task.
         let outgoing = self.outgoing.clone();
         let running_requests_id_to_codex_uuid = self.running_requests_id_to_codex_uuid.clone();
+        let resources = self.resources.clone();
 
         let codex = match self
             .conversation_manager
@@ -525,6 +619,8 @@ impl MessageProcessor {
                     prompt,
                     running_requests_id_to_codex_uuid,
                     conversation_id,
+                    progress_token,
+                    resources,
                 )
                 .await;
             }
@@ -560,11 +656,12 @@ impl MessageProcessor {
             RequestId::Integer(i) => i.to_string(),
         };
 
-        // Obtain the conversation id while holding the first lock, then release.
+        // Unregister the request before interrupting so that the tool runner
+        // knows not to respond once it observes the aborted turn.
         let conversation_id = {
-            let map_guard = self.running_requests_id_to_codex_uuid.lock().await;
-            match map_guard.get(&request_id) {
-                Some(id) => *id,
+            let mut map_guard = self.running_requests_id_to_codex_uuid.lock().await;
+            match map_guard.remove(&request_id) {
+                Some(id) => id,
                 None => {
                     tracing::warn!("Session not found for request_id: {}", request_id_string);
                     return;
@@ -595,13 +692,7 @@ impl MessageProcessor {
             .await;
         if let Err(e) = err {
             tracing::error!("Failed to submit interrupt to Codex: {e}");
-            return;
         }
-        // unregister the id so we don't keep it in the map
-        self.running_requests_id_to_codex_uuid
-            .lock()
-            .await
-            .remove(&request_id);
     }
 
     fn handle_progress_notification(
@@ -156,6 +156,7 @@ impl McpProcess {
                 id: RequestId::Integer(request_id),
                 result: json!({
                     "capabilities": {
+                        "resources": {},
                         "tools": {
                             "listChanged": true
                         },
@@ -0,0 +1,157 @@
+use std::net::TcpListener;
+use std::process::Stdio;
+use std::time::Duration;
+
+use anyhow::Context;
+use assert_cmd::prelude::*;
+use codex_mcp_server::DEFAULT_BEARER_TOKEN_ENV_VAR;
+use mcp_types::JSONRPC_VERSION;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+use serde_json::json;
+use std::process::Command as StdCommand;
+use tempfile::TempDir;
+use tokio::process::Child;
+use tokio::process::Command;
+
+const TOKEN: &str = "test-token";
+const SESSION_ID_HEADER: &str = "mcp-session-id";
+
+struct HttpServer {
+    url: String,
+    #[allow(dead_code)]
+    process: Child,
+}
+
+impl HttpServer {
+    async fn start(codex_home: &TempDir) -> anyhow::Result<Self> {
+        // Reserve a free port, then hand it to the server.
+        let port = TcpListener::bind("127.0.0.1:0")?.local_addr()?.port();
+        let program = StdCommand::cargo_bin("codex-mcp-server")
+            .context("should find binary for codex-mcp-server")?
+            .get_program()
+            .to_owned();
+        let process = Command::new(program)
+            .arg("--listen")
+            .arg(format!("127.0.0.1:{port}"))
+            .env("CODEX_HOME", codex_home.path())
+            .env(DEFAULT_BEARER_TOKEN_ENV_VAR, TOKEN)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .context("codex-mcp-server failed to start")?;
+
+        for _ in 0..100 {
+            if tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .is_ok()
+            {
+                return Ok(Self {
+                    url: format!("http://127.0.0.1:{port}/mcp"),
+                    process,
+                });
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        anyhow::bail!("codex-mcp-server did not start listening on port {port}")
+    }
+}
+
+fn initialize_request() -> serde_json::Value {
+    json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": 0,
+        "method": "initialize",
+        "params": {
+            "capabilities": {},
+            "clientInfo": { "name": "http test", "version": "0.0.0" },
+            "protocolVersion": mcp_types::MCP_SCHEMA_VERSION,
+        },
+    })
+}
+
+/// Collect the JSON-RPC messages from an SSE response body.
+fn sse_messages(body: &str) -> Vec<serde_json::Value> {
+    body.lines()
+        .filter_map(|line| line.strip_prefix("data: "))
+        .filter_map(|data| serde_json::from_str(data).ok())
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn http_transport_requires_bearer_token_and_tracks_sessions() -> anyhow::Result<()> {
+    let codex_home = TempDir::new()?;
+    let server = HttpServer::start(&codex_home).await?;
+    let client = reqwest::Client::new();
+
+    let unauthorized = client
+        .post(&server.url)
+        .json(&initialize_request())
+        .send()
+        .await?;
+    assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+
+    let initialized = client
+        .post(&server.url)
+        .bearer_auth(TOKEN)
+        .json(&initialize_request())
+        .send()
+        .await?;
+    assert_eq!(initialized.status(), StatusCode::OK);
+    let session_id = initialized
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .context("initialize response should carry a session id")?
+        .to_str()?
+        .to_string();
+    let messages = sse_messages(&initialized.text().await?);
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["id"], json!(0));
+    assert_eq!(
+        messages[0]["result"]["serverInfo"]["name"],
+        json!("codex-mcp-server")
+    );
+
+    let list_tools = json!({
+        "jsonrpc": JSONRPC_VERSION,
+        "id": 1,
+        "method": "tools/list",
+    });
+    let tools = client
+        .post(&server.url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_ID_HEADER, &session_id)
+        .json(&list_tools)
+        .send()
+        .await?;
+    assert_eq!(tools.status(), StatusCode::OK);
+    let messages = sse_messages(&tools.text().await?);
+    let tool_names: Vec<_> = messages[0]["result"]["tools"]
+        .as_array()
+        .context("tools/list should return tools")?
+        .iter()
+        .map(|tool| tool["name"].clone())
+        .collect();
+    assert_eq!(tool_names, vec![json!("codex"), json!("codex-reply")]);
+
+    let deleted = client
+        .delete(&server.url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_ID_HEADER, &session_id)
+        .send()
+        .await?;
+    assert_eq!(deleted.status(), StatusCode::OK);
+
+    let after_delete = client
+        .post(&server.url)
+        .bearer_auth(TOKEN)
+        .header(SESSION_ID_HEADER, &session_id)
+        .json(&list_tools)
+        .send()
+        .await?;
+    assert_eq!(after_delete.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
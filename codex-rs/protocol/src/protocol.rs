@@ -143,6 +143,14 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Apply only some hunks of a code patch, after reviewing it hunk by hunk
+    PatchReview {
+        /// The id of the submission we are approving
+        id: String,
+        /// Which hunks to apply, and comments on the rejected ones.
+        review: PatchReview,
+    },
+
     /// Append an entry to the persistent cross-session message history.
     ///
     /// Note the entry is not guaranteed to be logged if the user has
@@ -166,6 +174,15 @@ pub enum Op {
     /// Request the list of available custom prompts.
     ListCustomPrompts,
 
+    /// Shut down and respawn a single MCP server, then refresh its tools.
+    /// Reply is delivered via `EventMsg::McpListToolsResponse`.
+    RestartMcpServer { server_name: String },
+
+    /// Re-read `mcp_servers` from `config.toml`, starting added or changed
+    /// servers and stopping removed ones.
+    /// Reply is delivered via `EventMsg::McpListToolsResponse`.
+    ReloadMcpServers,
+
     /// Request the agent to summarize the current conversation context.
     /// The agent will use its existing context (either conversation history or previous response id)
     /// to generate a summary which will be returned as an AgentMessage event.
@@ -522,6 +539,16 @@ pub enum EventMsg {
 
     /// Exited review mode with an optional final result to apply.
     ExitedReviewMode(ExitedReviewModeEvent),
+
+    /// Notification that a sub-agent started by the `spawn_agent` tool began
+    /// working on its task.
+    SubAgentBegin(SubAgentBeginEvent),
+
+    /// An event from the conversation of a running sub-agent.
+    SubAgentActivity(SubAgentActivityEvent),
+
+    /// Notification that a sub-agent finished.
+    SubAgentEnd(SubAgentEndEvent),
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, TS)]
@@ -546,7 +573,7 @@ pub struct TaskStartedEvent {
     pub model_context_window: Option<u64>,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, TS)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, Default, TS)]
 pub struct TokenUsage {
     pub input_tokens: u64,
     pub cached_input_tokens: u64,
@@ -928,6 +955,8 @@ pub enum SessionSource {
     VSCode,
     Exec,
     Mcp,
+    /// A sub-agent started by the `spawn_agent` tool.
+    SubAgent,
     #[serde(other)]
     Unknown,
 }
@@ -1114,6 +1143,37 @@ pub struct ViewImageToolCallEvent {
     pub path: PathBuf,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentBeginEvent {
+    /// Identifier for the originating `spawn_agent` call.
+    pub call_id: String,
+    /// Conversation the sub-agent runs in.
+    pub conversation_id: ConversationId,
+    /// The task the sub-agent was given.
+    pub task: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentActivityEvent {
+    /// Identifier for the originating `spawn_agent` call.
+    pub call_id: String,
+    /// The event from the sub-agent's conversation.
+    pub msg: Box<EventMsg>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, TS)]
+pub struct SubAgentEndEvent {
+    /// Identifier for the originating `spawn_agent` call.
+    pub call_id: String,
+    /// Whether the sub-agent completed its task rather than failing or being
+    /// aborted.
+    pub success: bool,
+    /// The sub-agent's final message.
+    pub last_agent_message: Option<String>,
+    /// The changes the sub-agent made, as a unified diff.
+    pub unified_diff: Option<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecOutputStream {
@@ -1266,6 +1326,28 @@ pub enum ReviewDecision {
     Abort,
 }
 
+/// Outcome of reviewing an `ApplyPatchApprovalRequest` hunk by hunk.
+///
+/// Hunks are numbered per file from 0, in the order of the `@@` sections of
+/// an `Update` change's `unified_diff`. An `Add`, a `Delete`, or an `Update`
+/// whose diff has no hunks counts as a single hunk.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct PatchReview {
+    /// Indices of the hunks to apply, per file. Files that are missing or
+    /// have no accepted hunks are left unchanged.
+    pub accepted_hunks: HashMap<PathBuf, Vec<usize>>,
+    /// Comments left on rejected hunks; they are returned to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<HunkComment>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, TS)]
+pub struct HunkComment {
+    pub path: PathBuf,
+    pub hunk: usize,
+    pub comment: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum FileChange {
@@ -1300,6 +1382,9 @@ pub enum TurnAbortReason {
     Interrupted,
     Replaced,
     ReviewEnded,
+    /// The task ran out of turns, tokens, tool calls or time as configured
+    /// in its budget.
+    BudgetExceeded,
 }
 
 #[cfg(test)]
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
 use rmcp::model::CancelledNotificationParam;
@@ -11,6 +13,7 @@ use rmcp::model::ProgressNotificationParam;
 use rmcp::model::ResourceUpdatedNotificationParam;
 use rmcp::service::NotificationContext;
 use rmcp::service::RequestContext;
+use tokio::sync::Notify;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
@@ -19,11 +22,15 @@ use tracing::warn;
 #[derive(Debug, Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
+    tool_list_changed: Arc<Notify>,
 }
 
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo) -> Self {
-        Self { client_info }
+    pub(crate) fn new(client_info: ClientInfo, tool_list_changed: Arc<Notify>) -> Self {
+        Self {
+            client_info,
+            tool_list_changed,
+        }
     }
 }
 
@@ -80,6 +87,7 @@ impl ClientHandler for LoggingClientHandler {
 
     async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server tool list changed");
+        self.tool_list_changed.notify_one();
     }
 
     async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
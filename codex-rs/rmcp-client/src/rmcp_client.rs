@@ -29,6 +29,7 @@ use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::Notify;
 use tokio::time;
 use tracing::info;
 use tracing::warn;
@@ -68,6 +69,7 @@ enum ClientState {
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
     state: Mutex<ClientState>,
+    tool_list_changed: Arc<Notify>,
 }
 
 impl RmcpClient {
@@ -112,6 +114,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(PendingTransport::ChildProcess(transport)),
             }),
+            tool_list_changed: Arc::new(Notify::new()),
         })
     }
 
@@ -147,6 +150,7 @@ impl RmcpClient {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
             }),
+            tool_list_changed: Arc::new(Notify::new()),
         })
     }
 
@@ -158,7 +162,8 @@ impl RmcpClient {
         timeout: Option<Duration>,
     ) -> Result<InitializeResult> {
         let rmcp_params: InitializeRequestParam = convert_to_rmcp(params.clone())?;
-        let client_handler = LoggingClientHandler::new(rmcp_params);
+        let client_handler =
+            LoggingClientHandler::new(rmcp_params, Arc::clone(&self.tool_list_changed));
 
         let (transport, oauth_persistor) = {
             let mut guard = self.state.lock().await;
@@ -251,6 +256,23 @@ impl RmcpClient {
         Ok(converted)
     }
 
+    /// Returns `false` once the underlying transport has closed, e.g. because
+    /// the server process exited. A client that is still connecting is
+    /// considered alive.
+    pub async fn is_alive(&self) -> bool {
+        let guard = self.state.lock().await;
+        match &*guard {
+            ClientState::Ready { service, .. } => !service.peer().is_transport_closed(),
+            ClientState::Connecting { .. } => true,
+        }
+    }
+
+    /// Handle that is notified whenever the server sends
+    /// `notifications/tools/list_changed`.
+    pub fn tool_list_changed(&self) -> Arc<Notify> {
+        Arc::clone(&self.tool_list_changed)
+    }
+
     async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
         let guard = self.state.lock().await;
         match &*guard {
@@ -5,18 +5,27 @@ use crate::bottom_pane::ApprovalRequest;
 use crate::chatwidget::ChatWidget;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
+use crate::external_editor;
 use crate::file_search::FileSearchManager;
+use crate::get_git_diff::ParsedDiff;
+use crate::get_git_diff::parse_git_diff;
 use crate::history_cell::HistoryCell;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
 use crate::pager_overlay::Overlay;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::resume_picker::ResumeSelection;
+use crate::theme;
 use crate::tui;
 use crate::tui::TuiEvent;
 use codex_ansi_escape::ansi_escape_line;
 use codex_core::AuthManager;
 use codex_core::ConversationManager;
 use codex_core::config::Config;
+use codex_core::config::load_global_mcp_servers;
 use codex_core::config::persist_model_selection;
+use codex_core::config::persist_tui_theme;
+use codex_core::config_types::DiffView;
 use codex_core::model_family::find_family_for_model;
 use codex_core::protocol::SessionSource;
 use codex_core::protocol::TokenUsage;
@@ -27,6 +36,7 @@ use color_eyre::eyre::WrapErr;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use std::path::PathBuf;
@@ -293,17 +303,31 @@ impl App {
             AppEvent::DiffResult(text) => {
                 // Clear the in-progress state in the bottom pane
                 self.chat_widget.on_diff_complete();
-                // Enter alternate screen using TUI helper and build pager lines
+                // Enter alternate screen using TUI helper and build the pager.
+                // With the split view, parsed diffs go through the patch
+                // renderer; otherwise (and for errors or "not a git
+                // repository") git's colored output is shown as-is.
                 let _ = tui.enter_alt_screen();
-                let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
-                    vec!["No changes detected.".italic().into()]
+                let parsed = if self.config.tui_diff_view == DiffView::Split {
+                    parse_git_diff(&text)
                 } else {
-                    text.lines().map(ansi_escape_line).collect()
+                    ParsedDiff::default()
                 };
-                self.overlay = Some(Overlay::new_static_with_lines(
-                    pager_lines,
-                    "D I F F".to_string(),
-                ));
+                self.overlay = Some(if parsed.changes.is_empty() {
+                    let pager_lines: Vec<ratatui::text::Line<'static>> = if text.trim().is_empty() {
+                        vec!["No changes detected.".italic().into()]
+                    } else {
+                        text.lines().map(ansi_escape_line).collect()
+                    };
+                    Overlay::new_static_with_lines(pager_lines, "D I F F".to_string())
+                } else {
+                    let diff_summary = DiffSummary::new(parsed.changes, self.config.cwd.clone())
+                        .with_notes(parsed.notes);
+                    Overlay::new_static_with_renderables(
+                        vec![diff_summary.into()],
+                        "D I F F".to_string(),
+                    )
+                });
                 tui.frame_requester().schedule_frame();
             }
             AppEvent::StartFileSearch(query) => {
@@ -317,6 +341,27 @@ impl App {
             AppEvent::UpdateReasoningEffort(effort) => {
                 self.on_update_reasoning_effort(effort);
             }
+            AppEvent::WorkspaceSnapshotTaken {
+                text,
+                image_paths,
+                result,
+            } => {
+                self.chat_widget
+                    .on_workspace_snapshot_taken(text, image_paths, result);
+            }
+            AppEvent::ReloadMcpServers => {
+                // The app owns the config; the widget gets the reloaded
+                // servers so `/mcp` lists what the session runs. When the
+                // file cannot be read, codex reports the error.
+                match load_global_mcp_servers(&self.config.codex_home).await {
+                    Ok(servers) => self.config.mcp_servers = servers.into_iter().collect(),
+                    Err(err) => {
+                        tracing::warn!("failed to read MCP servers from config.toml: {err}");
+                    }
+                }
+                self.chat_widget
+                    .reload_mcp_servers(self.config.mcp_servers.clone());
+            }
             AppEvent::UpdateModel(model) => {
                 self.chat_widget.set_model(&model);
                 self.config.model = model.clone();
@@ -366,6 +411,33 @@ impl App {
                     }
                 }
             }
+            AppEvent::SetTheme(name) => match theme::resolve(&name, &self.config.tui_themes) {
+                Ok(resolved) => {
+                    theme::set_theme(resolved);
+                    self.config.tui_theme = Some(name.clone());
+                    match persist_tui_theme(&self.config.codex_home, &name).await {
+                        Ok(()) => self.chat_widget.add_info_message(
+                            format!("Theme changed to {name}"),
+                            Some("Earlier output keeps its colors.".to_string()),
+                        ),
+                        Err(err) => {
+                            tracing::error!(error = %err, "failed to persist theme");
+                            self.chat_widget.add_error_message(format!(
+                                "Theme changed to {name}, but failed to save it: {err}"
+                            ));
+                        }
+                    }
+                }
+                Err(err) => self.chat_widget.add_error_message(err),
+            },
+            AppEvent::OpenExternalEditor => {
+                let draft = self.chat_widget.draft_for_external_editor();
+                match external_editor::edit_draft(tui, &draft).await {
+                    Ok(edited) => self.chat_widget.apply_external_edit(edited),
+                    Err(err) => self.chat_widget.add_error_message(err),
+                }
+                tui.frame_requester().schedule_frame();
+            }
             AppEvent::UpdateAskForApprovalPolicy(policy) => {
                 self.chat_widget.set_approval_policy(policy);
             }
@@ -381,6 +453,9 @@ impl App {
             AppEvent::OpenReviewCustomPrompt => {
                 self.chat_widget.show_review_custom_prompt();
             }
+            AppEvent::QueuedMessage(action) => {
+                self.chat_widget.on_queued_message_action(action);
+            }
             AppEvent::FullScreenApprovalRequest(request) => match request {
                 ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
                     let _ = tui.enter_alt_screen();
@@ -415,25 +490,19 @@ impl App {
 
     async fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) {
         match key_event {
-            KeyEvent {
-                code: KeyCode::Char('t'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } => {
+            _ if key_event.kind == KeyEventKind::Press
+                && keymap().is_press(KeymapAction::OpenTranscript, key_event) =>
+            {
                 // Enter alternate screen and set viewport to full size.
                 let _ = tui.enter_alt_screen();
                 self.overlay = Some(Overlay::new_transcript(self.transcript_cells.clone()));
                 tui.frame_requester().schedule_frame();
             }
-            // Esc primes/advances backtracking only in normal (not working) mode
-            // with an empty composer. In any other state, forward Esc so the
-            // active UI (e.g. status indicator, modals, popups) handles it.
-            KeyEvent {
-                code: KeyCode::Esc,
-                kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                ..
-            } => {
+            // Esc (the backtrack key) primes/advances backtracking only in normal
+            // (not working) mode with an empty composer. In any other state,
+            // forward it so the active UI (e.g. status indicator, modals, popups)
+            // handles it.
+            _ if keymap().is_press(KeymapAction::Backtrack, key_event) => {
                 if self.chat_widget.is_normal_backtrack_mode()
                     && self.chat_widget.composer_is_empty()
                 {
@@ -452,7 +521,21 @@ impl App {
                 && self.chat_widget.composer_is_empty() =>
             {
                 // Delegate to helper for clarity; preserves behavior.
-                self.confirm_backtrack_from_main();
+                self.confirm_backtrack_from_main(false);
+            }
+            // `r` confirms the same way and also rewinds files to the snapshot
+            // taken before the selected message.
+            KeyEvent {
+                code: KeyCode::Char('r'),
+                modifiers: KeyModifiers::NONE,
+                kind: KeyEventKind::Press,
+                ..
+            } if self.backtrack.primed
+                && self.backtrack.nth_user_message != usize::MAX
+                && self.chat_widget.composer_is_empty()
+                && self.selected_user_cell_snapshot().is_some() =>
+            {
+                self.confirm_backtrack_from_main(true);
             }
             KeyEvent {
                 kind: KeyEventKind::Press | KeyEventKind::Repeat,
@@ -461,7 +544,7 @@ impl App {
                 // Any non-Esc key press should cancel a primed backtrack.
                 // This avoids stale "Esc-primed" state after the user starts typing
                 // (even if they later backspace to empty).
-                if key_event.code != KeyCode::Esc && self.backtrack.primed {
+                if !keymap().is_press(KeymapAction::Backtrack, key_event) && self.backtrack.primed {
                     self.reset_backtrack_state();
                 }
                 self.chat_widget.handle_key_event(key_event);
@@ -484,10 +567,12 @@ mod tests {
     use crate::history_cell::HistoryCell;
     use crate::history_cell::UserHistoryCell;
     use crate::history_cell::new_session_info;
+    use crate::workspace_snapshot::WorkspaceSnapshot;
     use codex_core::AuthManager;
     use codex_core::CodexAuth;
     use codex_core::ConversationManager;
     use codex_core::protocol::SessionConfiguredEvent;
+    use codex_git_tooling::FileSnapshotStore;
     use codex_protocol::ConversationId;
     use ratatui::prelude::Line;
     use std::path::PathBuf;
@@ -549,6 +634,7 @@ mod tests {
         let user_cell = |text: &str| -> Arc<dyn HistoryCell> {
             Arc::new(UserHistoryCell {
                 message: text.to_string(),
+                snapshot: None,
             }) as Arc<dyn HistoryCell>
         };
         let agent_cell = |text: &str| -> Arc<dyn HistoryCell> {
@@ -597,10 +683,35 @@ mod tests {
         app.backtrack.primed = true;
         app.backtrack.nth_user_message = user_count(&app.transcript_cells).saturating_sub(1);
 
-        app.confirm_backtrack_from_main();
+        app.confirm_backtrack_from_main(false);
 
         let (_, nth, prefill) = app.backtrack.pending.clone().expect("pending backtrack");
         assert_eq!(nth, 1);
         assert_eq!(prefill, "follow-up (edited)");
     }
+
+    #[test]
+    fn backtrack_from_main_with_r_stages_the_snapshot_restore() {
+        let mut app = make_test_app();
+        let dir = tempfile::tempdir().expect("tempdir");
+        let snapshot = FileSnapshotStore::new(dir.path())
+            .and_then(|mut store| store.capture())
+            .map(WorkspaceSnapshot::Files)
+            .expect("capture snapshot");
+        app.transcript_cells = vec![Arc::new(UserHistoryCell {
+            message: "first question".to_string(),
+            snapshot: Some(snapshot),
+        }) as Arc<dyn HistoryCell>];
+
+        app.backtrack.base_id = Some(ConversationId::new());
+        app.backtrack.primed = true;
+        app.backtrack.nth_user_message = 0;
+        app.confirm_backtrack_from_main(true);
+
+        assert!(app.backtrack.pending.is_some());
+        assert!(matches!(
+            app.backtrack.pending_restore,
+            Some(WorkspaceSnapshot::Files(_))
+        ));
+    }
 }
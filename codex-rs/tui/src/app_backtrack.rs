@@ -5,15 +5,19 @@ use std::sync::Arc;
 use crate::app::App;
 use crate::history_cell::CompositeHistoryCell;
 use crate::history_cell::UserHistoryCell;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
 use crate::pager_overlay::Overlay;
 use crate::tui;
 use crate::tui::TuiEvent;
+use crate::workspace_snapshot::WorkspaceSnapshot;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_protocol::ConversationId;
 use color_eyre::eyre::Result;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 
 /// Aggregates all backtrack-related state used by the App.
 #[derive(Default)]
@@ -28,6 +32,8 @@ pub(crate) struct BacktrackState {
     pub(crate) overlay_preview_active: bool,
     /// Pending fork request: (base_id, nth_user_message, prefill).
     pub(crate) pending: Option<(ConversationId, usize, String)>,
+    /// Snapshot to restore once the pending fork has been installed.
+    pub(crate) pending_restore: Option<WorkspaceSnapshot>,
 }
 
 impl App {
@@ -40,13 +46,19 @@ impl App {
         tui: &mut tui::Tui,
         event: TuiEvent,
     ) -> Result<bool> {
-        if self.backtrack.overlay_preview_active {
+        // While the `/` search prompt is open, Esc and Enter belong to it.
+        if self
+            .overlay
+            .as_ref()
+            .is_some_and(Overlay::is_search_input_active)
+        {
+            self.overlay_forward_event(tui, event)?;
+            Ok(true)
+        } else if self.backtrack.overlay_preview_active {
             match event {
-                TuiEvent::Key(KeyEvent {
-                    code: KeyCode::Esc,
-                    kind: KeyEventKind::Press | KeyEventKind::Repeat,
-                    ..
-                }) => {
+                TuiEvent::Key(key_event)
+                    if keymap().is_press(KeymapAction::Backtrack, key_event) =>
+                {
                     self.overlay_step_backtrack(tui, event)?;
                     Ok(true)
                 }
@@ -55,7 +67,16 @@ impl App {
                     kind: KeyEventKind::Press,
                     ..
                 }) => {
-                    self.overlay_confirm_backtrack(tui);
+                    self.overlay_confirm_backtrack(tui, false);
+                    Ok(true)
+                }
+                TuiEvent::Key(KeyEvent {
+                    code: KeyCode::Char('r'),
+                    modifiers: KeyModifiers::NONE,
+                    kind: KeyEventKind::Press,
+                    ..
+                }) if self.selected_user_cell_snapshot().is_some() => {
+                    self.overlay_confirm_backtrack(tui, true);
                     Ok(true)
                 }
                 // Catchall: forward any other events to the overlay widget.
@@ -64,11 +85,8 @@ impl App {
                     Ok(true)
                 }
             }
-        } else if let TuiEvent::Key(KeyEvent {
-            code: KeyCode::Esc,
-            kind: KeyEventKind::Press | KeyEventKind::Repeat,
-            ..
-        }) = event
+        } else if let TuiEvent::Key(key_event) = event
+            && keymap().is_press(KeymapAction::Backtrack, key_event)
         {
             // First Esc in transcript overlay: begin backtrack preview at latest user message.
             self.begin_overlay_backtrack_preview(tui);
@@ -219,21 +237,39 @@ impl App {
         Ok(())
     }
 
-    /// Handle Enter in overlay backtrack preview: confirm selection and reset state.
-    fn overlay_confirm_backtrack(&mut self, tui: &mut tui::Tui) {
+    /// Handle Enter (or `r` with `restore_files`) in overlay backtrack preview:
+    /// confirm selection and reset state.
+    fn overlay_confirm_backtrack(&mut self, tui: &mut tui::Tui, restore_files: bool) {
         let nth_user_message = self.backtrack.nth_user_message;
         if let Some(base_id) = self.backtrack.base_id {
-            let prefill = nth_user_position(&self.transcript_cells, nth_user_message)
-                .and_then(|idx| self.transcript_cells.get(idx))
-                .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+            let prefill = self
+                .selected_user_cell()
                 .map(|c| c.message.clone())
                 .unwrap_or_default();
+            self.backtrack.pending_restore = if restore_files {
+                self.selected_user_cell_snapshot()
+            } else {
+                None
+            };
             self.close_transcript_overlay(tui);
             self.request_backtrack(prefill, base_id, nth_user_message);
         }
         self.reset_backtrack_state();
     }
 
+    /// The user message currently selected for backtracking.
+    fn selected_user_cell(&self) -> Option<&UserHistoryCell> {
+        nth_user_position(&self.transcript_cells, self.backtrack.nth_user_message)
+            .and_then(|idx| self.transcript_cells.get(idx))
+            .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+    }
+
+    /// Workspace snapshot taken before the selected user message, if any.
+    pub(crate) fn selected_user_cell_snapshot(&self) -> Option<WorkspaceSnapshot> {
+        self.selected_user_cell()
+            .and_then(|cell| cell.snapshot.clone())
+    }
+
     /// Handle Esc in overlay backtrack preview: step selection if armed, else forward.
     fn overlay_step_backtrack(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         if self.backtrack.base_id.is_some() {
@@ -245,15 +281,19 @@ impl App {
     }
 
     /// Confirm a primed backtrack from the main view (no overlay visible).
-    /// Computes the prefill from the selected user message and requests history.
-    pub(crate) fn confirm_backtrack_from_main(&mut self) {
+    /// Computes the prefill from the selected user message and requests history;
+    /// with `restore_files` (`r`), also rewinds the workspace to its snapshot.
+    pub(crate) fn confirm_backtrack_from_main(&mut self, restore_files: bool) {
         if let Some(base_id) = self.backtrack.base_id {
-            let prefill =
-                nth_user_position(&self.transcript_cells, self.backtrack.nth_user_message)
-                    .and_then(|idx| self.transcript_cells.get(idx))
-                    .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
-                    .map(|c| c.message.clone())
-                    .unwrap_or_default();
+            let prefill = self
+                .selected_user_cell()
+                .map(|c| c.message.clone())
+                .unwrap_or_default();
+            self.backtrack.pending_restore = if restore_files {
+                self.selected_user_cell_snapshot()
+            } else {
+                None
+            };
             self.request_backtrack(prefill, base_id, self.backtrack.nth_user_message);
         }
         self.reset_backtrack_state();
@@ -300,9 +340,15 @@ impl App {
             .await;
         match result {
             Ok(new_conv) => {
-                self.install_forked_conversation(tui, cfg, new_conv, nth_user_message, &prefill)
+                self.install_forked_conversation(tui, cfg, new_conv, nth_user_message, &prefill);
+                if let Some(snapshot) = self.backtrack.pending_restore.take() {
+                    self.chat_widget.restore_workspace_snapshot(&snapshot);
+                }
+            }
+            Err(e) => {
+                self.backtrack.pending_restore = None;
+                tracing::error!("error forking conversation: {e:#}");
             }
-            Err(e) => tracing::error!("error forking conversation: {e:#}"),
         }
     }
 
@@ -413,6 +459,7 @@ mod tests {
         let mut cells: Vec<Arc<dyn HistoryCell>> = vec![
             Arc::new(UserHistoryCell {
                 message: "first user".to_string(),
+                snapshot: None,
             }) as Arc<dyn HistoryCell>,
             Arc::new(AgentMessageCell::new(vec![Line::from("assistant")], true))
                 as Arc<dyn HistoryCell>,
@@ -429,6 +476,7 @@ mod tests {
                 as Arc<dyn HistoryCell>,
             Arc::new(UserHistoryCell {
                 message: "first".to_string(),
+                snapshot: None,
             }) as Arc<dyn HistoryCell>,
             Arc::new(AgentMessageCell::new(vec![Line::from("after")], false))
                 as Arc<dyn HistoryCell>,
@@ -457,11 +505,13 @@ mod tests {
                 as Arc<dyn HistoryCell>,
             Arc::new(UserHistoryCell {
                 message: "first".to_string(),
+                snapshot: None,
             }) as Arc<dyn HistoryCell>,
             Arc::new(AgentMessageCell::new(vec![Line::from("between")], false))
                 as Arc<dyn HistoryCell>,
             Arc::new(UserHistoryCell {
                 message: "second".to_string(),
+                snapshot: None,
             }) as Arc<dyn HistoryCell>,
             Arc::new(AgentMessageCell::new(vec![Line::from("tail")], false))
                 as Arc<dyn HistoryCell>,
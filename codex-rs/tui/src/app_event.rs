@@ -4,9 +4,12 @@ use codex_common::model_presets::ModelPreset;
 use codex_core::protocol::ConversationPathResponseEvent;
 use codex_core::protocol::Event;
 use codex_file_search::FileMatch;
+use codex_git_tooling::GitToolingError;
 
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::QueuedMessageAction;
 use crate::history_cell::HistoryCell;
+use crate::workspace_snapshot::WorkspaceSnapshot;
 
 use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
@@ -61,6 +64,24 @@ pub(crate) enum AppEvent {
         effort: Option<ReasoningEffort>,
     },
 
+    /// Re-read `mcp_servers` from config.toml for `/mcp reload` and have
+    /// codex apply them.
+    ReloadMcpServers,
+
+    /// Workspace snapshot taken in the background before sending a user
+    /// message; the message is sent once it arrives.
+    WorkspaceSnapshotTaken {
+        text: String,
+        image_paths: Vec<PathBuf>,
+        result: Result<WorkspaceSnapshot, GitToolingError>,
+    },
+
+    /// Switch to the named color theme and persist it to config.toml.
+    SetTheme(String),
+
+    /// Suspend the TUI and edit the composer draft in `$VISUAL`/`$EDITOR`.
+    OpenExternalEditor,
+
     /// Open the reasoning selection popup after picking a model.
     OpenReasoningPopup {
         model: String,
@@ -87,4 +108,7 @@ pub(crate) enum AppEvent {
 
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
+
+    /// Edit, delete, reorder or send now a message queued during a turn.
+    QueuedMessage(QueuedMessageAction),
 }
@@ -5,6 +5,8 @@ use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::BottomPaneView;
 use crate::bottom_pane::CancellationEvent;
+use crate::bottom_pane::hunk_review::HunkReview;
+use crate::bottom_pane::hunk_review::HunkReviewOutcome;
 use crate::bottom_pane::list_selection_view::ListSelectionView;
 use crate::bottom_pane::list_selection_view::SelectionItem;
 use crate::bottom_pane::list_selection_view::SelectionViewParams;
@@ -17,6 +19,7 @@ use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use crate::text_formatting::truncate_text;
+use crate::theme;
 use codex_core::protocol::FileChange;
 use codex_core::protocol::Op;
 use codex_core::protocol::ReviewDecision;
@@ -56,6 +59,8 @@ pub(crate) struct ApprovalOverlay {
     app_event_tx: AppEventSender,
     list: ListSelectionView,
     options: Vec<ApprovalOption>,
+    /// Set while the user steps through the hunks of the current patch.
+    hunk_review: Option<HunkReview>,
     current_complete: bool,
     done: bool,
 }
@@ -69,6 +74,7 @@ impl ApprovalOverlay {
             app_event_tx: app_event_tx.clone(),
             list: ListSelectionView::new(Default::default(), app_event_tx),
             options: Vec::new(),
+            hunk_review: None,
             current_complete: false,
             done: false,
         };
@@ -84,6 +90,7 @@ impl ApprovalOverlay {
         self.current_request = Some(request.clone());
         let ApprovalRequestState { variant, header } = ApprovalRequestState::from(request);
         self.current_variant = Some(variant.clone());
+        self.hunk_review = None;
         self.current_complete = false;
         let (options, params) = Self::build_options(variant, header);
         self.options = options;
@@ -145,13 +152,18 @@ impl ApprovalOverlay {
             return;
         };
         if let Some(variant) = self.current_variant.as_ref() {
-            match (&variant, option.decision) {
-                (ApprovalVariant::Exec { id, command }, decision) => {
+            match (&variant, option.action) {
+                (ApprovalVariant::Exec { id, command }, ApprovalAction::Decide(decision)) => {
                     self.handle_exec_decision(id, command, decision);
                 }
-                (ApprovalVariant::ApplyPatch { id, .. }, decision) => {
+                (ApprovalVariant::ApplyPatch { id, .. }, ApprovalAction::Decide(decision)) => {
                     self.handle_patch_decision(id, decision);
                 }
+                (ApprovalVariant::ApplyPatch { cwd, changes, .. }, ApprovalAction::ReviewHunks) => {
+                    self.hunk_review = Some(HunkReview::new(changes, cwd.clone()));
+                    return;
+                }
+                (ApprovalVariant::Exec { .. }, ApprovalAction::ReviewHunks) => return,
             }
         }
 
@@ -178,6 +190,43 @@ impl ApprovalOverlay {
         }));
     }
 
+    fn handle_hunk_review_key(&mut self, key_event: KeyEvent) {
+        let Some(review) = self.hunk_review.as_mut() else {
+            return;
+        };
+        match review.handle_key_event(key_event) {
+            HunkReviewOutcome::Pending => {}
+            HunkReviewOutcome::Back => self.hunk_review = None,
+            HunkReviewOutcome::Submit(patch_review) => {
+                let (accepted, total) = review.counts();
+                let accepts_everything = review.accepts_everything();
+                self.hunk_review = None;
+                if let Some(ApprovalVariant::ApplyPatch { id, .. }) = self.current_variant.as_ref()
+                {
+                    self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                        history_cell::new_user_approval_decision(build_hunk_review_history_lines(
+                            accepted, total,
+                        )),
+                    )));
+                    let op = if accepts_everything {
+                        Op::PatchApproval {
+                            id: id.clone(),
+                            decision: ReviewDecision::Approved,
+                        }
+                    } else {
+                        Op::PatchReview {
+                            id: id.clone(),
+                            review: patch_review,
+                        }
+                    };
+                    self.app_event_tx.send(AppEvent::CodexOp(op));
+                }
+                self.current_complete = true;
+                self.advance_queue();
+            }
+        }
+    }
+
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
@@ -220,6 +269,10 @@ impl ApprovalOverlay {
 
 impl BottomPaneView for ApprovalOverlay {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if self.hunk_review.is_some() {
+            self.handle_hunk_review_key(key_event);
+            return;
+        }
         if self.try_handle_shortcut(&key_event) {
             return;
         }
@@ -263,17 +316,26 @@ impl BottomPaneView for ApprovalOverlay {
     }
 
     fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        if self.hunk_review.is_some() {
+            return None;
+        }
         self.list.cursor_pos(area)
     }
 }
 
 impl Renderable for ApprovalOverlay {
     fn desired_height(&self, width: u16) -> u16 {
-        self.list.desired_height(width)
+        match &self.hunk_review {
+            Some(review) => review.desired_height(width),
+            None => self.list.desired_height(width),
+        }
     }
 
     fn render(&self, area: Rect, buf: &mut Buffer) {
-        self.list.render(area, buf);
+        match &self.hunk_review {
+            Some(review) => review.render(area, buf),
+            None => self.list.render(area, buf),
+        }
     }
 }
 
@@ -315,7 +377,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 changes,
             } => {
                 let mut header: Vec<Box<dyn Renderable>> = Vec::new();
-                header.push(DiffSummary::new(changes, cwd).into());
+                header.push(DiffSummary::new(changes.clone(), cwd.clone()).into());
                 if let Some(reason) = reason
                     && !reason.is_empty()
                 {
@@ -325,7 +387,7 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     ));
                 }
                 Self {
-                    variant: ApprovalVariant::ApplyPatch { id },
+                    variant: ApprovalVariant::ApplyPatch { id, cwd, changes },
                     header: Box::new(ColumnRenderable::new(header)),
                 }
             }
@@ -335,14 +397,28 @@ impl From<ApprovalRequest> for ApprovalRequestState {
 
 #[derive(Clone)]
 enum ApprovalVariant {
-    Exec { id: String, command: Vec<String> },
-    ApplyPatch { id: String },
+    Exec {
+        id: String,
+        command: Vec<String>,
+    },
+    ApplyPatch {
+        id: String,
+        cwd: PathBuf,
+        changes: HashMap<PathBuf, FileChange>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum ApprovalAction {
+    Decide(ReviewDecision),
+    /// Step through the patch and accept or reject each hunk.
+    ReviewHunks,
 }
 
 #[derive(Clone)]
 struct ApprovalOption {
     label: String,
-    decision: ReviewDecision,
+    action: ApprovalAction,
     display_shortcut: Option<KeyBinding>,
     additional_shortcuts: Vec<KeyBinding>,
 }
@@ -359,19 +435,19 @@ fn exec_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            action: ApprovalAction::Decide(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
             label: "Yes, and don't ask again for this command".to_string(),
-            decision: ReviewDecision::ApprovedForSession,
+            action: ApprovalAction::Decide(ReviewDecision::ApprovedForSession),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            action: ApprovalAction::Decide(ReviewDecision::Abort),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
@@ -382,19 +458,44 @@ fn patch_options() -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
             label: "Yes, proceed".to_string(),
-            decision: ReviewDecision::Approved,
+            action: ApprovalAction::Decide(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
+        ApprovalOption {
+            label: "Review each hunk".to_string(),
+            action: ApprovalAction::ReviewHunks,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+        },
         ApprovalOption {
             label: "No, and tell Codex what to do differently".to_string(),
-            decision: ReviewDecision::Abort,
+            action: ApprovalAction::Decide(ReviewDecision::Abort),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
     ]
 }
 
+fn build_hunk_review_history_lines(accepted: usize, total: usize) -> Vec<Line<'static>> {
+    let line = if accepted == 0 {
+        Line::from(vec![
+            Span::styled("✗ ", theme::error()),
+            "You ".into(),
+            "rejected".bold(),
+            format!(" all {total} proposed hunks").into(),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("✔ ", theme::success()),
+            "You ".into(),
+            "applied".bold(),
+            format!(" {accepted} of {total} proposed hunks").into(),
+        ])
+    };
+    vec![line]
+}
+
 fn build_exec_history_lines(
     command: Vec<String>,
     decision: ReviewDecision,
@@ -405,7 +506,7 @@ fn build_exec_history_lines(
         Approved => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
-                "✔ ".green(),
+                Span::styled("✔ ", theme::success()),
                 vec![
                     "You ".into(),
                     "approved".bold(),
@@ -418,7 +519,7 @@ fn build_exec_history_lines(
         ApprovedForSession => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
-                "✔ ".green(),
+                Span::styled("✔ ", theme::success()),
                 vec![
                     "You ".into(),
                     "approved".bold(),
@@ -431,7 +532,7 @@ fn build_exec_history_lines(
         Denied => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
-                "✗ ".red(),
+                Span::styled("✗ ", theme::error()),
                 vec![
                     "You ".into(),
                     "did not approve".bold(),
@@ -443,7 +544,7 @@ fn build_exec_history_lines(
         Abort => {
             let snippet = Span::from(exec_snippet(&command)).dim();
             (
-                "✗ ".red(),
+                Span::styled("✗ ", theme::error()),
                 vec![
                     "You ".into(),
                     "canceled".bold(),
@@ -549,6 +650,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hunk_review_sends_partial_approval() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let changes = HashMap::from([
+            (
+                PathBuf::from("/repo/a.txt"),
+                FileChange::Add {
+                    content: "a\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("/repo/b.txt"),
+                FileChange::Delete {
+                    content: "b\n".to_string(),
+                },
+            ),
+        ]);
+        let request = ApprovalRequest::ApplyPatch {
+            id: "sub".to_string(),
+            reason: None,
+            cwd: PathBuf::from("/repo"),
+            changes,
+        };
+        let mut view = ApprovalOverlay::new(request, tx);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(view.is_complete());
+
+        let mut review = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CodexOp(Op::PatchReview { id, review: r }) = ev {
+                assert_eq!(id, "sub");
+                review = Some(r);
+            }
+        }
+        assert_eq!(
+            review.map(|r| r.accepted_hunks),
+            Some(HashMap::from([(PathBuf::from("/repo/a.txt"), vec![0])]))
+        );
+    }
+
     #[test]
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
@@ -32,6 +32,9 @@ pub(crate) trait BottomPaneView: Renderable {
         None
     }
 
+    /// Called when the queued user messages change while the view is shown.
+    fn update_queued_messages(&mut self, _queued: &[String]) {}
+
     /// Try to handle approval request; return the original value if not
     /// consumed.
     fn try_consume_approval_request(
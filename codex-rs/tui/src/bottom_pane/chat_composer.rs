@@ -28,6 +28,9 @@ use super::footer::reset_mode_after_activity;
 use super::footer::toggle_shortcut_mode;
 use super::paste_burst::CharDecision;
 use super::paste_burst::PasteBurst;
+use super::vim::Vim;
+use super::vim::VimKeyResult;
+use super::vim::VimMode;
 use crate::bottom_pane::paste_burst::FlushResult;
 use crate::bottom_pane::prompt_args::expand_custom_prompt;
 use crate::bottom_pane::prompt_args::expand_if_numeric_with_positional_args;
@@ -49,6 +52,8 @@ use crate::bottom_pane::textarea::TextAreaState;
 use crate::clipboard_paste::normalize_pasted_path;
 use crate::clipboard_paste::pasted_image_format;
 use crate::history_cell;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
 use crate::ui_consts::LIVE_PREFIX_COLS;
 use codex_file_search::FileMatch;
 use std::cell::RefCell;
@@ -67,6 +72,9 @@ const LARGE_PASTE_CHAR_THRESHOLD: usize = 1000;
 pub enum InputResult {
     Submitted(String),
     Command(SlashCommand),
+    /// A built-in command that accepts arguments, with the (trimmed) text
+    /// following the command name.
+    CommandWithArgs(SlashCommand, String),
     None,
 }
 
@@ -110,6 +118,8 @@ pub(crate) struct ChatComposer {
     footer_mode: FooterMode,
     footer_hint_override: Option<Vec<(String, String)>>,
     context_window_percent: Option<u8>,
+    /// Vi-style modal editing, when enabled via `[tui] vim_mode`.
+    vim: Option<Vim>,
 }
 
 /// Popup state – at most one can be visible at any time.
@@ -153,6 +163,7 @@ impl ChatComposer {
             footer_mode: FooterMode::ShortcutPrompt,
             footer_hint_override: None,
             context_window_percent: None,
+            vim: None,
         };
         // Apply configuration via the setter to keep side-effects centralized.
         this.set_disable_paste_burst(disable_paste_burst);
@@ -296,6 +307,18 @@ impl ChatComposer {
         }
     }
 
+    /// Turn vi-style editing on or off. With `use_system_clipboard`, yanks
+    /// and puts go through the system clipboard (`[tui] vim_clipboard`).
+    pub(crate) fn set_vim_mode(&mut self, enabled: bool, use_system_clipboard: bool) {
+        self.vim = enabled.then(|| Vim::new(use_system_clipboard));
+    }
+
+    /// Whether Esc currently belongs to vi mode editing (leaving insert or
+    /// visual mode) rather than the interrupt/backtrack shortcuts.
+    pub(crate) fn vim_captures_esc(&self) -> bool {
+        self.vim.as_ref().is_some_and(Vim::captures_esc)
+    }
+
     /// Override the footer hint items displayed beneath the composer. Passing
     /// `None` restores the default shortcut footer.
     pub(crate) fn set_footer_hint_override(&mut self, items: Option<Vec<(String, String)>>) {
@@ -319,6 +342,47 @@ impl ChatComposer {
         self.textarea.text().to_string()
     }
 
+    /// The draft as it should appear in an external editor: large pastes are
+    /// expanded, while image placeholders are kept so the images stay attached.
+    pub(crate) fn draft_for_external_editor(&self) -> String {
+        let mut text = self.textarea.text().to_string();
+        for (placeholder, actual) in &self.pending_pastes {
+            if text.contains(placeholder) {
+                text = text.replace(placeholder, actual);
+            }
+        }
+        text
+    }
+
+    /// Replace the draft with text edited in an external editor. Attached
+    /// images whose placeholders are still present stay attached; the others
+    /// are dropped.
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        let mut images = std::mem::take(&mut self.attached_images);
+        self.pending_pastes.clear();
+        self.textarea.set_text("");
+        let mut rest = text.as_str();
+        loop {
+            let next = images
+                .iter()
+                .enumerate()
+                .filter_map(|(idx, img)| rest.find(&img.placeholder).map(|pos| (pos, idx)))
+                .min();
+            let Some((pos, idx)) = next else {
+                self.textarea.insert_str(rest);
+                break;
+            };
+            let image = images.remove(idx);
+            self.textarea.insert_str(&rest[..pos]);
+            self.textarea.insert_element(&image.placeholder);
+            rest = &rest[pos + image.placeholder.len()..];
+            self.attached_images.push(image);
+        }
+        self.textarea.set_cursor(self.textarea.text().len());
+        self.sync_command_popup();
+        self.sync_file_search_popup();
+    }
+
     /// Attempt to start a burst by retro-capturing recent chars before the cursor.
     pub fn attach_image(&mut self, path: PathBuf, width: u32, height: u32, format_label: &str) {
         let placeholder = format!("[image {width}x{height} {format_label}]");
@@ -381,6 +445,17 @@ impl ChatComposer {
 
     /// Handle a key event coming from the main UI.
     pub fn handle_key_event(&mut self, key_event: KeyEvent) -> (InputResult, bool) {
+        if let Some(vim) = self.vim.as_mut()
+            && key_event.kind != KeyEventKind::Release
+            && (vim.mode() != VimMode::Insert
+                || (key_event.code == KeyCode::Esc
+                    && matches!(self.active_popup, ActivePopup::None)))
+            && vim.handle_key(&mut self.textarea, key_event) == VimKeyResult::Handled
+        {
+            self.active_popup = ActivePopup::None;
+            return (InputResult::None, true);
+        }
+
         let result = match &mut self.active_popup {
             ActivePopup::Command(_) => self.handle_key_event_with_slash_popup(key_event),
             ActivePopup::File(_) => self.handle_key_event_with_file_popup(key_event),
@@ -395,6 +470,12 @@ impl ChatComposer {
             self.sync_file_search_popup();
         }
 
+        if !matches!(result.0, InputResult::None)
+            && let Some(vim) = self.vim.as_mut()
+        {
+            vim.reset(&mut self.textarea);
+        }
+
         result
     }
 
@@ -845,7 +926,8 @@ impl ChatComposer {
         if self.handle_shortcut_overlay_key(&key_event) {
             return (InputResult::None, true);
         }
-        if key_event.code == KeyCode::Esc {
+        let keymap = keymap();
+        if keymap.is_press(KeymapAction::Backtrack, key_event) {
             let next_mode = esc_hint_mode(self.footer_mode, self.is_task_running);
             if next_mode != self.footer_mode {
                 self.footer_mode = next_mode;
@@ -865,22 +947,21 @@ impl ChatComposer {
                 (InputResult::None, true)
             }
             // -------------------------------------------------------------
-            // History navigation (Up / Down) – only when the composer is not
-            // empty or when the cursor is at the correct position, to avoid
-            // interfering with normal cursor movement.
+            // History navigation (Up / Down by default) – only when the
+            // composer is not empty or when the cursor is at the correct
+            // position, to avoid interfering with normal cursor movement.
             // -------------------------------------------------------------
-            KeyEvent {
-                code: KeyCode::Up | KeyCode::Down,
-                ..
-            } => {
+            _ if keymap.is_press(KeymapAction::HistoryPrev, key_event)
+                || keymap.is_press(KeymapAction::HistoryNext, key_event) =>
+            {
                 if self
                     .history
                     .should_handle_navigation(self.textarea.text(), self.textarea.cursor())
                 {
-                    let replace_text = match key_event.code {
-                        KeyCode::Up => self.history.navigate_up(&self.app_event_tx),
-                        KeyCode::Down => self.history.navigate_down(&self.app_event_tx),
-                        _ => unreachable!(),
+                    let replace_text = if keymap.is_press(KeymapAction::HistoryPrev, key_event) {
+                        self.history.navigate_up(&self.app_event_tx)
+                    } else {
+                        self.history.navigate_down(&self.app_event_tx)
                     };
                     if let Some(text) = replace_text {
                         self.textarea.set_text(&text);
@@ -890,11 +971,7 @@ impl ChatComposer {
                 }
                 self.handle_input_basic(key_event)
             }
-            KeyEvent {
-                code: KeyCode::Enter,
-                modifiers: KeyModifiers::NONE,
-                ..
-            } => {
+            _ if keymap.is_press(KeymapAction::Submit, key_event) => {
                 // If the first line is a bare built-in slash command (no args),
                 // dispatch it even when the slash popup isn't visible. This preserves
                 // the workflow: type a prefix ("/di"), press Tab to complete to
@@ -904,13 +981,19 @@ impl ChatComposer {
                 // literal text.
                 let first_line = self.textarea.text().lines().next().unwrap_or("");
                 if let Some((name, rest)) = parse_slash_name(first_line)
-                    && rest.is_empty()
                     && let Some((_n, cmd)) = built_in_slash_commands()
                         .into_iter()
                         .find(|(n, _)| *n == name)
                 {
-                    self.textarea.set_text("");
-                    return (InputResult::Command(cmd), true);
+                    if rest.is_empty() {
+                        self.textarea.set_text("");
+                        return (InputResult::Command(cmd), true);
+                    }
+                    if cmd.accepts_args() {
+                        let args = rest.trim_end().to_string();
+                        self.textarea.set_text("");
+                        return (InputResult::CommandWithArgs(cmd, args), true);
+                    }
                 }
                 // If we're in a paste-like burst capture, treat Enter as part of the burst
                 // and accumulate it rather than submitting or inserting immediately.
@@ -995,6 +1078,14 @@ impl ChatComposer {
                 // Do not clear attached_images here; ChatWidget drains them via take_recent_submission_images().
                 (InputResult::Submitted(text), true)
             }
+            // Route configured newline chords through the plain Enter path so
+            // paste-burst handling sees them like any other newline.
+            _ if keymap.is_press(KeymapAction::Newline, key_event) => {
+                self.handle_input_basic(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            }
+            // Default newline keys that `newline` was rebound away from would
+            // still reach the textarea's built-in newline handling; drop them.
+            _ if keymap.is_unbound_newline(key_event) => (InputResult::None, false),
             input => self.handle_input_basic(input),
         }
     }
@@ -1338,6 +1429,7 @@ impl ChatComposer {
             use_shift_enter_hint: self.use_shift_enter_hint,
             is_task_running: self.is_task_running,
             context_window_percent: self.context_window_percent,
+            vim_mode: self.vim.as_ref().map(Vim::mode),
         }
     }
 
@@ -2249,7 +2341,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/init'"),
+            InputResult::CommandWithArgs(..) | InputResult::None => {
+                panic!("expected Command result for '/init'")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
     }
@@ -2322,7 +2416,9 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch after Tab completion, got literal submit: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/diff'"),
+            InputResult::CommandWithArgs(..) | InputResult::None => {
+                panic!("expected Command result for '/diff'")
+            }
         }
         assert!(composer.textarea.is_empty());
     }
@@ -2355,13 +2451,40 @@ mod tests {
             InputResult::Submitted(text) => {
                 panic!("expected command dispatch, but composer submitted literal text: {text}")
             }
-            InputResult::None => panic!("expected Command result for '/mention'"),
+            InputResult::CommandWithArgs(..) | InputResult::None => {
+                panic!("expected Command result for '/mention'")
+            }
         }
         assert!(composer.textarea.is_empty(), "composer should be cleared");
         composer.insert_str("@");
         assert_eq!(composer.textarea.text(), "@");
     }
 
+    #[test]
+    fn slash_mcp_with_args_dispatches_command_with_args() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+
+        composer.textarea.set_text("/mcp restart docs");
+        let (result, _needs_redraw) =
+            composer.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        match result {
+            InputResult::CommandWithArgs(cmd, args) => {
+                assert_eq!(cmd, SlashCommand::Mcp);
+                assert_eq!(args, "restart docs");
+            }
+            other => panic!("expected CommandWithArgs for '/mcp restart docs', got {other:?}"),
+        }
+        assert!(composer.textarea.is_empty());
+    }
+
     #[test]
     fn test_multiple_pastes_submission() {
         use crossterm::event::KeyCode;
@@ -2614,6 +2737,33 @@ mod tests {
         assert!(composer.attached_images.is_empty());
     }
 
+    #[test]
+    fn external_edit_keeps_images_whose_placeholders_survive() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let sender = AppEventSender::new(tx);
+        let mut composer = ChatComposer::new(
+            true,
+            sender,
+            false,
+            "Ask Codex to do anything".to_string(),
+            false,
+        );
+        let kept = PathBuf::from("/tmp/kept.png");
+        let dropped = PathBuf::from("/tmp/dropped.png");
+        composer.attach_image(kept.clone(), 32, 16, "PNG");
+        composer.attach_image(dropped, 10, 5, "PNG");
+        let large = "x".repeat(LARGE_PASTE_CHAR_THRESHOLD + 1);
+        composer.handle_paste(large.clone());
+
+        let draft = composer.draft_for_external_editor();
+        assert_eq!(draft, format!("[image 32x16 PNG][image 10x5 PNG]{large}"));
+
+        composer.apply_external_edit("Look at [image 32x16 PNG] please".to_string());
+        assert_eq!(composer.current_text(), "Look at [image 32x16 PNG] please");
+        assert!(composer.pending_pastes.is_empty());
+        assert_eq!(composer.take_recent_submission_images(), vec![kept]);
+    }
+
     #[test]
     fn image_placeholder_backspace_behaves_like_text_placeholder() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
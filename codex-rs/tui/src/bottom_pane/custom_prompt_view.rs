@@ -13,6 +13,7 @@ use ratatui::widgets::Widget;
 use std::cell::RefCell;
 
 use crate::render::renderable::Renderable;
+use crate::theme;
 
 use super::popup_consts::standard_popup_hint_line;
 
@@ -157,7 +158,10 @@ impl Renderable for CustomPromptView {
                 width: area.width,
                 height: 1,
             };
-            let spans: Vec<Span<'static>> = vec![gutter(), context_label.clone().cyan()];
+            let spans: Vec<Span<'static>> = vec![
+                gutter(),
+                Span::styled(context_label.clone(), theme::accent()),
+            ];
             Paragraph::new(Line::from(spans)).render(context_area, buf);
             input_y = input_y.saturating_add(1);
         }
@@ -243,5 +247,5 @@ impl CustomPromptView {
 }
 
 fn gutter() -> Span<'static> {
-    "▌ ".cyan()
+    Span::styled("▌ ", theme::accent())
 }
@@ -1,6 +1,10 @@
+use crate::bottom_pane::vim::VimMode;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
 use crate::render::line_utils::prefix_lines;
+use crate::theme;
 use crate::ui_consts::FOOTER_INDENT_COLS;
 use crossterm::event::KeyCode;
 use ratatui::buffer::Buffer;
@@ -18,6 +22,8 @@ pub(crate) struct FooterProps {
     pub(crate) use_shift_enter_hint: bool,
     pub(crate) is_task_running: bool,
     pub(crate) context_window_percent: Option<u8>,
+    /// Current vi editing mode, when `[tui] vim_mode` is enabled.
+    pub(crate) vim_mode: Option<VimMode>,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -72,6 +78,24 @@ pub(crate) fn render_footer(area: Rect, buf: &mut Buffer, props: FooterProps) {
 }
 
 fn footer_lines(props: FooterProps) -> Vec<Line<'static>> {
+    let mut lines = mode_footer_lines(props);
+    if let Some(vim_mode) = props.vim_mode {
+        let label = match vim_mode {
+            VimMode::Insert => Span::from(vim_mode.label()).dim(),
+            _ => Span::styled(vim_mode.label(), theme::accent()),
+        };
+        match lines.first_mut() {
+            Some(line) => {
+                line.spans.insert(0, "  ".into());
+                line.spans.insert(0, label);
+            }
+            None => lines.push(Line::from(label)),
+        }
+    }
+    lines
+}
+
+fn mode_footer_lines(props: FooterProps) -> Vec<Line<'static>> {
     match props.mode {
         FooterMode::CtrlCReminder => vec![ctrl_c_reminder_line(CtrlCReminderState {
             is_task_running: props.is_task_running,
@@ -113,14 +137,21 @@ fn ctrl_c_reminder_line(state: CtrlCReminderState) -> Line<'static> {
         "quit"
     };
     Line::from(vec![
-        key_hint::ctrl(KeyCode::Char('c')).into(),
+        action_binding(KeymapAction::Interrupt).into(),
         format!(" again to {action}").into(),
     ])
     .dim()
 }
 
+/// The active binding for `action`, as shown in hints.
+fn action_binding(action: KeymapAction) -> KeyBinding {
+    keymap()
+        .primary(action)
+        .unwrap_or(key_hint::plain(KeyCode::Null))
+}
+
 fn esc_hint_line(esc_backtrack_hint: bool) -> Line<'static> {
-    let esc = key_hint::plain(KeyCode::Esc);
+    let esc = action_binding(KeymapAction::Backtrack);
     if esc_backtrack_hint {
         Line::from(vec![esc.into(), " again to edit previous message".into()]).dim()
     } else {
@@ -142,6 +173,7 @@ fn shortcut_overlay_lines(state: ShortcutsState) -> Vec<Line<'static>> {
     let mut edit_previous = Line::from("");
     let mut quit = Line::from("");
     let mut show_transcript = Line::from("");
+    let mut external_editor = Line::from("");
 
     for descriptor in SHORTCUTS {
         if let Some(text) = descriptor.overlay_entry(state) {
@@ -153,6 +185,7 @@ fn shortcut_overlay_lines(state: ShortcutsState) -> Vec<Line<'static>> {
                 ShortcutId::EditPrevious => edit_previous = text,
                 ShortcutId::Quit => quit = text,
                 ShortcutId::ShowTranscript => show_transcript = text,
+                ShortcutId::ExternalEditor => external_editor = text,
             }
         }
     }
@@ -164,7 +197,7 @@ fn shortcut_overlay_lines(state: ShortcutsState) -> Vec<Line<'static>> {
         paste_image,
         edit_previous,
         quit,
-        Line::from(""),
+        external_editor,
         show_transcript,
     ];
 
@@ -242,6 +275,7 @@ enum ShortcutId {
     EditPrevious,
     Quit,
     ShowTranscript,
+    ExternalEditor,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -275,19 +309,34 @@ impl DisplayCondition {
 
 struct ShortcutDescriptor {
     id: ShortcutId,
+    /// Default bindings shown when the shortcut is not configurable, or when
+    /// they are still bound in the active keymap.
     bindings: &'static [ShortcutBinding],
+    /// Keymap action whose active bindings this shortcut reflects.
+    action: Option<KeymapAction>,
     prefix: &'static str,
     label: &'static str,
 }
 
 impl ShortcutDescriptor {
-    fn binding_for(&self, state: ShortcutsState) -> Option<&'static ShortcutBinding> {
-        self.bindings.iter().find(|binding| binding.matches(state))
+    fn binding_for(&self, state: ShortcutsState) -> Option<KeyBinding> {
+        let mut defaults = self
+            .bindings
+            .iter()
+            .filter(|binding| binding.matches(state));
+        let Some(action) = self.action else {
+            return defaults.next().map(|binding| binding.key);
+        };
+        let bound = keymap().bindings(action);
+        defaults
+            .map(|binding| binding.key)
+            .find(|key| bound.contains(key))
+            .or_else(|| bound.first().copied())
     }
 
     fn overlay_entry(&self, state: ShortcutsState) -> Option<Line<'static>> {
         let binding = self.binding_for(state)?;
-        let mut line = Line::from(vec![self.prefix.into(), binding.key.into()]);
+        let mut line = Line::from(vec![self.prefix.into(), binding.into()]);
         match self.id {
             ShortcutId::EditPrevious => {
                 if state.esc_backtrack_hint {
@@ -295,7 +344,7 @@ impl ShortcutDescriptor {
                 } else {
                     line.extend(vec![
                         " ".into(),
-                        key_hint::plain(KeyCode::Esc).into(),
+                        binding.into(),
                         " to edit previous message".into(),
                     ]);
                 }
@@ -313,6 +362,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::plain(KeyCode::Char('/')),
             condition: DisplayCondition::Always,
         }],
+        action: None,
         prefix: "",
         label: " for commands",
     },
@@ -328,6 +378,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
                 condition: DisplayCondition::WhenNotShiftEnterHint,
             },
         ],
+        action: Some(KeymapAction::Newline),
         prefix: "",
         label: " for newline",
     },
@@ -337,6 +388,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::plain(KeyCode::Char('@')),
             condition: DisplayCondition::Always,
         }],
+        action: None,
         prefix: "",
         label: " for file paths",
     },
@@ -346,6 +398,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::ctrl(KeyCode::Char('v')),
             condition: DisplayCondition::Always,
         }],
+        action: Some(KeymapAction::PasteImage),
         prefix: "",
         label: " to paste images",
     },
@@ -355,6 +408,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::plain(KeyCode::Esc),
             condition: DisplayCondition::Always,
         }],
+        action: Some(KeymapAction::Backtrack),
         prefix: "",
         label: "",
     },
@@ -364,6 +418,7 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::ctrl(KeyCode::Char('c')),
             condition: DisplayCondition::Always,
         }],
+        action: Some(KeymapAction::Interrupt),
         prefix: "",
         label: " to exit",
     },
@@ -373,9 +428,20 @@ const SHORTCUTS: &[ShortcutDescriptor] = &[
             key: key_hint::ctrl(KeyCode::Char('t')),
             condition: DisplayCondition::Always,
         }],
+        action: Some(KeymapAction::OpenTranscript),
         prefix: "",
         label: " to view transcript",
     },
+    ShortcutDescriptor {
+        id: ShortcutId::ExternalEditor,
+        bindings: &[ShortcutBinding {
+            key: key_hint::ctrl(KeyCode::Char('g')),
+            condition: DisplayCondition::Always,
+        }],
+        action: Some(KeymapAction::ExternalEditor),
+        prefix: "",
+        label: " to edit in $EDITOR",
+    },
 ];
 
 #[cfg(test)]
@@ -407,6 +473,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -418,6 +485,7 @@ mod tests {
                 use_shift_enter_hint: true,
                 is_task_running: false,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -429,6 +497,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -440,6 +509,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -451,6 +521,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -462,6 +533,7 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: false,
                 context_window_percent: None,
+                vim_mode: None,
             },
         );
 
@@ -473,6 +545,19 @@ mod tests {
                 use_shift_enter_hint: false,
                 is_task_running: true,
                 context_window_percent: Some(72),
+                vim_mode: None,
+            },
+        );
+
+        snapshot_footer(
+            "footer_vim_normal_mode",
+            FooterProps {
+                mode: FooterMode::ShortcutPrompt,
+                esc_backtrack_hint: false,
+                use_shift_enter_hint: false,
+                is_task_running: false,
+                context_window_percent: None,
+                vim_mode: Some(VimMode::Normal),
             },
         );
     }
@@ -0,0 +1,508 @@
+//! Hunk-by-hunk review of a proposed patch, shown inside the approval overlay.
+//!
+//! The reviewer steps through every hunk of every file, accepts or rejects
+//! it, and can leave a comment on a rejected hunk. Submitting produces a
+//! [`PatchReview`] that core uses to apply only the accepted hunks; the
+//! comments are returned to the model. Submitting while hunks are still
+//! undecided asks for a second Enter, since those hunks are rejected.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use codex_core::protocol::FileChange;
+use codex_core::protocol::HunkComment;
+use codex_core::protocol::PatchReview;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+use ratatui::widgets::Wrap;
+
+use crate::diff_render::display_path_for;
+use crate::diff_render::hunk_count;
+use crate::diff_render::render_hunk;
+use crate::key_hint;
+use crate::render::renderable::Renderable;
+use crate::theme;
+
+/// Diff lines shown at once; longer hunks scroll with ↑/↓.
+const MAX_HUNK_LINES: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HunkDecision {
+    Accept,
+    Reject,
+}
+
+struct ReviewHunk {
+    file: usize,
+    hunk: usize,
+    decision: Option<HunkDecision>,
+    comment: Option<String>,
+}
+
+/// What the overlay should do after a key press.
+#[derive(Debug, PartialEq)]
+pub(crate) enum HunkReviewOutcome {
+    Pending,
+    /// Leave the review and go back to the approve/deny options.
+    Back,
+    Submit(PatchReview),
+}
+
+pub(crate) struct HunkReview {
+    cwd: PathBuf,
+    files: Vec<(PathBuf, FileChange)>,
+    hunks: Vec<ReviewHunk>,
+    selected: usize,
+    scroll: usize,
+    /// Text typed while leaving a comment on the selected hunk.
+    comment_input: Option<String>,
+    /// Enter was pressed with undecided hunks; another Enter submits anyway.
+    confirming_submit: bool,
+}
+
+impl HunkReview {
+    pub(crate) fn new(changes: &HashMap<PathBuf, FileChange>, cwd: PathBuf) -> Self {
+        let mut files: Vec<(PathBuf, FileChange)> = changes
+            .iter()
+            .map(|(path, change)| (path.clone(), change.clone()))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        let hunks = files
+            .iter()
+            .enumerate()
+            .flat_map(|(file, (_, change))| {
+                (0..hunk_count(change)).map(move |hunk| ReviewHunk {
+                    file,
+                    hunk,
+                    decision: None,
+                    comment: None,
+                })
+            })
+            .collect();
+        Self {
+            cwd,
+            files,
+            hunks,
+            selected: 0,
+            scroll: 0,
+            comment_input: None,
+            confirming_submit: false,
+        }
+    }
+
+    pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) -> HunkReviewOutcome {
+        if key_event.kind == KeyEventKind::Release {
+            return HunkReviewOutcome::Pending;
+        }
+        if self.hunks.is_empty() {
+            return match key_event.code {
+                KeyCode::Esc => HunkReviewOutcome::Back,
+                KeyCode::Enter => HunkReviewOutcome::Submit(self.review()),
+                _ => HunkReviewOutcome::Pending,
+            };
+        }
+        if self.comment_input.is_some() {
+            self.handle_comment_key(key_event);
+            return HunkReviewOutcome::Pending;
+        }
+        // Any key other than a second Enter cancels the confirmation.
+        let confirming = std::mem::take(&mut self.confirming_submit);
+        let shift_or_none = matches!(
+            key_event.modifiers,
+            KeyModifiers::NONE | KeyModifiers::SHIFT
+        );
+        match key_event.code {
+            KeyCode::Esc => return HunkReviewOutcome::Back,
+            KeyCode::Enter if confirming || self.undecided() == 0 => {
+                return HunkReviewOutcome::Submit(self.review());
+            }
+            KeyCode::Enter => self.confirming_submit = true,
+            KeyCode::Char('y') if shift_or_none => self.decide(HunkDecision::Accept),
+            KeyCode::Char('n') if shift_or_none => self.decide(HunkDecision::Reject),
+            KeyCode::Char('A') if shift_or_none => self.decide_rest_of_file(HunkDecision::Accept),
+            KeyCode::Char('N') if shift_or_none => self.decide_rest_of_file(HunkDecision::Reject),
+            KeyCode::Char('c') if shift_or_none => {
+                let hunk = &mut self.hunks[self.selected];
+                hunk.decision = Some(HunkDecision::Reject);
+                self.comment_input = Some(hunk.comment.clone().unwrap_or_default());
+            }
+            KeyCode::Right | KeyCode::Tab => self.select(self.selected + 1),
+            KeyCode::Left | KeyCode::BackTab => self.select(self.selected.saturating_sub(1)),
+            KeyCode::Char(']') => self.select_file(true),
+            KeyCode::Char('[') => self.select_file(false),
+            KeyCode::Up => self.scroll = self.scroll.saturating_sub(1),
+            KeyCode::Down => self.scroll = (self.scroll + 1).min(self.max_scroll()),
+            _ => {}
+        }
+        HunkReviewOutcome::Pending
+    }
+
+    fn handle_comment_key(&mut self, key_event: KeyEvent) {
+        let Some(input) = self.comment_input.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.comment_input = None,
+            KeyCode::Enter => {
+                let comment = input.trim().to_string();
+                self.hunks[self.selected].comment = (!comment.is_empty()).then_some(comment);
+                self.comment_input = None;
+                self.advance();
+            }
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c)
+                if matches!(
+                    key_event.modifiers,
+                    KeyModifiers::NONE | KeyModifiers::SHIFT
+                ) =>
+            {
+                input.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn decide(&mut self, decision: HunkDecision) {
+        let hunk = &mut self.hunks[self.selected];
+        hunk.decision = Some(decision);
+        if decision == HunkDecision::Accept {
+            hunk.comment = None;
+        }
+        self.advance();
+    }
+
+    fn decide_rest_of_file(&mut self, decision: HunkDecision) {
+        let file = self.hunks[self.selected].file;
+        for hunk in self.hunks[self.selected..]
+            .iter_mut()
+            .filter(|hunk| hunk.file == file && hunk.decision.is_none())
+        {
+            hunk.decision = Some(decision);
+        }
+        self.hunks[self.selected].decision = Some(decision);
+        self.advance();
+    }
+
+    /// Move to the next undecided hunk after the selected one, wrapping
+    /// around; stay put once everything is decided.
+    fn advance(&mut self) {
+        let len = self.hunks.len();
+        if let Some(next) = (1..len)
+            .map(|offset| (self.selected + offset) % len)
+            .find(|idx| self.hunks[*idx].decision.is_none())
+        {
+            self.select(next);
+        }
+    }
+
+    fn select(&mut self, idx: usize) {
+        let idx = idx.min(self.hunks.len().saturating_sub(1));
+        if idx != self.selected {
+            self.selected = idx;
+            self.scroll = 0;
+        }
+    }
+
+    fn select_file(&mut self, forward: bool) {
+        let file = self.hunks[self.selected].file;
+        let target = if forward {
+            self.hunks.iter().position(|hunk| hunk.file > file)
+        } else {
+            let previous = file.checked_sub(1);
+            self.hunks
+                .iter()
+                .position(|hunk| Some(hunk.file) == previous)
+        };
+        if let Some(idx) = target {
+            self.select(idx);
+        }
+    }
+
+    fn max_scroll(&self) -> usize {
+        self.hunk_lines(u16::MAX)
+            .len()
+            .saturating_sub(MAX_HUNK_LINES)
+    }
+
+    fn hunk_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let Some(hunk) = self.hunks.get(self.selected) else {
+            return Vec::new();
+        };
+        let (_, change) = &self.files[hunk.file];
+        render_hunk(change, hunk.hunk, width as usize)
+    }
+
+    fn undecided(&self) -> usize {
+        self.hunks
+            .iter()
+            .filter(|hunk| hunk.decision.is_none())
+            .count()
+    }
+
+    /// The selection to send to core. Undecided hunks are not applied.
+    pub(crate) fn review(&self) -> PatchReview {
+        let mut review = PatchReview::default();
+        for hunk in &self.hunks {
+            let path = &self.files[hunk.file].0;
+            match hunk.decision {
+                Some(HunkDecision::Accept) => review
+                    .accepted_hunks
+                    .entry(path.clone())
+                    .or_default()
+                    .push(hunk.hunk),
+                _ => {
+                    if let Some(comment) = &hunk.comment {
+                        review.comments.push(HunkComment {
+                            path: path.clone(),
+                            hunk: hunk.hunk,
+                            comment: comment.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        review
+    }
+
+    /// True when every hunk was accepted and there is nothing to tell the
+    /// model, i.e. the review is equivalent to a plain approval.
+    pub(crate) fn accepts_everything(&self) -> bool {
+        self.hunks
+            .iter()
+            .all(|hunk| hunk.decision == Some(HunkDecision::Accept))
+    }
+
+    /// `(accepted, total)` hunk counts.
+    pub(crate) fn counts(&self) -> (usize, usize) {
+        let accepted = self
+            .hunks
+            .iter()
+            .filter(|hunk| hunk.decision == Some(HunkDecision::Accept))
+            .count();
+        (accepted, self.hunks.len())
+    }
+
+    fn lines(&self, width: u16) -> Vec<Line<'static>> {
+        let mut lines: Vec<Line<'static>> = Vec::new();
+        let Some(hunk) = self.hunks.get(self.selected) else {
+            return lines;
+        };
+        let (path, change) = &self.files[hunk.file];
+        let (accepted, total) = self.counts();
+        let pending = self.undecided();
+        let rejected = total - accepted - pending;
+
+        lines.push(Line::from(vec![
+            "Review changes".bold(),
+            format!("  hunk {} of {total}", self.selected + 1).dim(),
+            "  ".into(),
+            Span::styled(format!("✔ {accepted}"), theme::success()),
+            "  ".into(),
+            Span::styled(format!("✗ {rejected}"), theme::error()),
+            format!("  {pending} pending").dim(),
+        ]));
+        lines.push(Line::from(""));
+
+        let mut title = vec![Span::from(display_path_for(path, &self.cwd)).bold()];
+        let in_file = hunk_count(change);
+        if in_file > 1 {
+            title.push(format!(" (hunk {} of {in_file})", hunk.hunk + 1).dim());
+        }
+        title.push("  ".into());
+        title.push(match hunk.decision {
+            Some(HunkDecision::Accept) => Span::styled("accepted", theme::success()),
+            Some(HunkDecision::Reject) => Span::styled("rejected", theme::error()),
+            None => "pending".dim(),
+        });
+        lines.push(Line::from(title));
+
+        let diff = self.hunk_lines(width);
+        let scroll = self.scroll.min(diff.len().saturating_sub(MAX_HUNK_LINES));
+        if scroll > 0 {
+            lines.push(format!("    ⋮ {scroll} lines above").dim().into());
+        }
+        let remaining = diff.len().saturating_sub(scroll + MAX_HUNK_LINES);
+        lines.extend(diff.into_iter().skip(scroll).take(MAX_HUNK_LINES));
+        if remaining > 0 {
+            lines.push(
+                format!("    ⋮ {remaining} more lines (↑/↓ to scroll)")
+                    .dim()
+                    .into(),
+            );
+        }
+        lines.push(Line::from(""));
+
+        if let Some(input) = &self.comment_input {
+            lines.push(Line::from(vec![
+                "Comment: ".bold(),
+                input.clone().into(),
+                " ".reversed(),
+            ]));
+            lines.push(Line::from(vec![
+                key_hint::plain(KeyCode::Enter).into(),
+                " to save  ".dim(),
+                key_hint::plain(KeyCode::Esc).into(),
+                " to cancel".dim(),
+            ]));
+            return lines;
+        }
+        if let Some(comment) = &hunk.comment {
+            lines.push(Line::from(vec![
+                "Comment: ".dim(),
+                comment.clone().italic(),
+            ]));
+        }
+        lines.push(Line::from(vec![
+            "y".into(),
+            " accept  ".dim(),
+            "n".into(),
+            " reject  ".dim(),
+            "c".into(),
+            " reject with comment  ".dim(),
+            "A/N".into(),
+            " rest of file  ".dim(),
+            "←/→".into(),
+            " hunks  ".dim(),
+            "[/]".into(),
+            " files".dim(),
+        ]));
+        if self.confirming_submit {
+            lines.push(Line::from(vec![
+                format!("{pending} undecided hunks will be rejected. ").bold(),
+                key_hint::plain(KeyCode::Enter).into(),
+                format!(
+                    " to apply {accepted} of {total} hunks anyway, any other key to keep reviewing"
+                )
+                .dim(),
+            ]));
+            return lines;
+        }
+        let apply = if pending == 0 {
+            format!(" to apply {accepted} of {total} hunks  ")
+        } else {
+            format!(" to apply {accepted} of {total} hunks ({pending} undecided)  ")
+        };
+        lines.push(Line::from(vec![
+            key_hint::plain(KeyCode::Enter).into(),
+            apply.dim(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to go back".dim(),
+        ]));
+        lines
+    }
+}
+
+impl Renderable for HunkReview {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(self.lines(area.width))
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        Paragraph::new(self.lines(width))
+            .wrap(Wrap { trim: false })
+            .line_count(width) as u16
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn changes() -> HashMap<PathBuf, FileChange> {
+        let original = "a\nb\nc\nd\ne\nf\ng\nh\ni\n";
+        let modified = "A\nb\nc\nd\ne\nf\ng\nh\nI\n";
+        HashMap::from([
+            (
+                PathBuf::from("/repo/lib.rs"),
+                FileChange::Update {
+                    unified_diff: diffy::create_patch(original, modified).to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("/repo/new.txt"),
+                FileChange::Add {
+                    content: "hello\n".to_string(),
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn collects_accepted_hunks_and_comments() {
+        let mut review = HunkReview::new(&changes(), PathBuf::from("/repo"));
+        assert_eq!(review.counts(), (0, 3));
+
+        // lib.rs hunk 0: accept; lib.rs hunk 1: reject with a comment.
+        review.handle_key_event(key(KeyCode::Char('y')));
+        review.handle_key_event(key(KeyCode::Char('c')));
+        for c in "keep i".chars() {
+            review.handle_key_event(key(KeyCode::Char(c)));
+        }
+        review.handle_key_event(key(KeyCode::Enter));
+        // new.txt: reject.
+        review.handle_key_event(key(KeyCode::Char('n')));
+
+        let expected = PatchReview {
+            accepted_hunks: HashMap::from([(PathBuf::from("/repo/lib.rs"), vec![0])]),
+            comments: vec![HunkComment {
+                path: PathBuf::from("/repo/lib.rs"),
+                hunk: 1,
+                comment: "keep i".to_string(),
+            }],
+        };
+        assert_eq!(
+            review.handle_key_event(key(KeyCode::Enter)),
+            HunkReviewOutcome::Submit(expected)
+        );
+        assert!(!review.accepts_everything());
+        assert_eq!(
+            review.handle_key_event(key(KeyCode::Esc)),
+            HunkReviewOutcome::Back
+        );
+    }
+
+    #[test]
+    fn submitting_with_undecided_hunks_needs_confirmation() {
+        let mut review = HunkReview::new(&changes(), PathBuf::from("/repo"));
+        review.handle_key_event(key(KeyCode::Char('y')));
+
+        // The first Enter only asks; another key goes back to reviewing.
+        assert_eq!(
+            review.handle_key_event(key(KeyCode::Enter)),
+            HunkReviewOutcome::Pending
+        );
+        review.handle_key_event(key(KeyCode::Right));
+        assert_eq!(
+            review.handle_key_event(key(KeyCode::Enter)),
+            HunkReviewOutcome::Pending
+        );
+
+        let expected = PatchReview {
+            accepted_hunks: HashMap::from([(PathBuf::from("/repo/lib.rs"), vec![0])]),
+            comments: Vec::new(),
+        };
+        assert_eq!(
+            review.handle_key_event(key(KeyCode::Enter)),
+            HunkReviewOutcome::Submit(expected)
+        );
+    }
+}
@@ -24,14 +24,18 @@ mod command_popup;
 pub mod custom_prompt_view;
 mod file_search_popup;
 mod footer;
+mod hunk_review;
 mod list_selection_view;
 mod prompt_args;
+mod queued_messages_view;
 pub(crate) use list_selection_view::SelectionViewParams;
+pub(crate) use queued_messages_view::QueuedMessageAction;
 mod paste_burst;
 pub mod popup_consts;
 mod scroll_state;
 mod selection_popup_common;
 mod textarea;
+mod vim;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum CancellationEvent {
@@ -78,20 +82,24 @@ pub(crate) struct BottomPaneParams {
     pub(crate) enhanced_keys_supported: bool,
     pub(crate) placeholder_text: String,
     pub(crate) disable_paste_burst: bool,
+    pub(crate) vim_mode: bool,
+    pub(crate) vim_clipboard: bool,
 }
 
 impl BottomPane {
     const BOTTOM_PAD_LINES: u16 = 1;
     pub fn new(params: BottomPaneParams) -> Self {
         let enhanced_keys_supported = params.enhanced_keys_supported;
+        let mut composer = ChatComposer::new(
+            params.has_input_focus,
+            params.app_event_tx.clone(),
+            enhanced_keys_supported,
+            params.placeholder_text,
+            params.disable_paste_burst,
+        );
+        composer.set_vim_mode(params.vim_mode, params.vim_clipboard);
         Self {
-            composer: ChatComposer::new(
-                params.has_input_focus,
-                params.app_event_tx.clone(),
-                enhanced_keys_supported,
-                params.placeholder_text,
-                params.disable_paste_burst,
-            ),
+            composer,
             view_stack: Vec::new(),
             app_event_tx: params.app_event_tx,
             frame_requester: params.frame_requester,
@@ -198,9 +206,11 @@ impl BottomPane {
             InputResult::None
         } else {
             // If a task is running and a status line is visible, allow Esc to
-            // send an interrupt even while the composer has focus.
+            // send an interrupt even while the composer has focus, unless vi
+            // mode needs it to leave insert or visual mode.
             if matches!(key_event.code, crossterm::event::KeyCode::Esc)
                 && self.is_task_running
+                && !self.composer.vim_captures_esc()
                 && let Some(status) = &self.status
             {
                 // Send Op::Interrupt
@@ -275,6 +285,15 @@ impl BottomPane {
         self.composer.current_text()
     }
 
+    pub(crate) fn draft_for_external_editor(&self) -> String {
+        self.composer.draft_for_external_editor()
+    }
+
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        self.composer.apply_external_edit(text);
+        self.request_redraw();
+    }
+
     /// Update the animated header shown to the left of the brackets in the
     /// status indicator (defaults to "Working"). No-ops if the status
     /// indicator is not active.
@@ -370,11 +389,30 @@ impl BottomPane {
     pub(crate) fn set_queued_user_messages(&mut self, queued: Vec<String>) {
         self.queued_user_messages = queued.clone();
         if let Some(status) = self.status.as_mut() {
-            status.set_queued_messages(queued);
+            status.set_queued_messages(queued.clone());
+        }
+        if let Some(view) = self.view_stack.last_mut() {
+            view.update_queued_messages(&queued);
+            if view.is_complete() {
+                self.view_stack.pop();
+                self.on_active_view_complete();
+            }
         }
         self.request_redraw();
     }
 
+    /// Open the panel for editing, deleting and reordering queued messages.
+    pub(crate) fn show_queued_messages(&mut self) {
+        if self.queued_user_messages.is_empty() {
+            return;
+        }
+        let view = queued_messages_view::QueuedMessagesView::new(
+            self.queued_user_messages.clone(),
+            self.app_event_tx.clone(),
+        );
+        self.push_view(Box::new(view));
+    }
+
     /// Update custom prompts available for the slash popup.
     pub(crate) fn set_custom_prompts(&mut self, prompts: Vec<CustomPrompt>) {
         self.composer.set_custom_prompts(prompts);
@@ -393,7 +431,16 @@ impl BottomPane {
     /// overlays or popups and not running a task. This is the safe context to
     /// use Esc-Esc for backtracking from the main view.
     pub(crate) fn is_normal_backtrack_mode(&self) -> bool {
-        !self.is_task_running && self.view_stack.is_empty() && !self.composer.popup_active()
+        !self.is_task_running
+            && self.view_stack.is_empty()
+            && !self.composer.popup_active()
+            && !self.composer.vim_captures_esc()
+    }
+
+    /// True when the composer has input focus, i.e. no modal view such as an
+    /// approval request or a selection list is shown on top of it.
+    pub(crate) fn composer_has_focus(&self) -> bool {
+        self.view_stack.is_empty()
     }
 
     pub(crate) fn show_view(&mut self, view: Box<dyn BottomPaneView>) {
@@ -545,6 +592,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
         pane.push_approval_request(exec_request());
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
@@ -565,6 +614,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
 
         // Create an approval modal (active view).
@@ -596,6 +647,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
 
         // Start a running task so the status indicator is active above the composer.
@@ -664,6 +717,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
 
         // Begin a task: show initial status.
@@ -695,6 +750,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
 
         // Activate spinner (status view replaces composer) with no live ring.
@@ -746,6 +803,8 @@ mod tests {
             enhanced_keys_supported: false,
             placeholder_text: "Ask Codex to do anything".to_string(),
             disable_paste_burst: false,
+            vim_mode: false,
+            vim_clipboard: false,
         });
 
         pane.set_task_running(true);
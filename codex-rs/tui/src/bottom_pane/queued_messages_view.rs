@@ -0,0 +1,342 @@
+//! Panel listing the messages queued while a turn is running, opened with
+//! alt+↑. Each entry can be edited, deleted, moved, or sent into the running
+//! turn right away instead of waiting for it to finish.
+
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Widget;
+
+use crate::app_event::AppEvent;
+use crate::app_event_sender::AppEventSender;
+use crate::key_hint;
+use crate::render::Insets;
+use crate::render::RectExt as _;
+use crate::render::renderable::Renderable;
+use crate::style::user_message_style;
+use crate::terminal_palette;
+
+use super::CancellationEvent;
+use super::bottom_pane_view::BottomPaneView;
+use super::popup_consts::MAX_POPUP_ROWS;
+use super::scroll_state::ScrollState;
+use super::selection_popup_common::GenericDisplayRow;
+use super::selection_popup_common::measure_rows_height;
+use super::selection_popup_common::render_rows;
+
+/// Longest preview shown for one queued message.
+const MAX_PREVIEW_CHARS: usize = 80;
+
+/// Change to the queue requested from the panel, by queue index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum QueuedMessageAction {
+    /// Move the message into the composer; submitting it puts it back in
+    /// the same place.
+    Edit(usize),
+    Delete(usize),
+    /// Interrupt the running turn and send the message as the next one
+    /// instead of waiting for the turn to complete.
+    SendNow(usize),
+    Move {
+        from: usize,
+        to: usize,
+    },
+}
+
+pub(crate) struct QueuedMessagesView {
+    messages: Vec<String>,
+    state: ScrollState,
+    complete: bool,
+    app_event_tx: AppEventSender,
+}
+
+impl QueuedMessagesView {
+    /// Open the panel with the most recently queued message selected.
+    pub(crate) fn new(messages: Vec<String>, app_event_tx: AppEventSender) -> Self {
+        let mut state = ScrollState::new();
+        state.selected_idx = messages.len().checked_sub(1);
+        state.ensure_visible(messages.len(), MAX_POPUP_ROWS);
+        Self {
+            messages,
+            state,
+            complete: false,
+            app_event_tx,
+        }
+    }
+
+    fn send(&self, action: QueuedMessageAction) {
+        self.app_event_tx.send(AppEvent::QueuedMessage(action));
+    }
+
+    fn selected(&self) -> Option<usize> {
+        self.state
+            .selected_idx
+            .filter(|idx| *idx < self.messages.len())
+    }
+
+    fn move_selected(&mut self, up: bool) {
+        let Some(from) = self.selected() else {
+            return;
+        };
+        let to = if up {
+            from.checked_sub(1)
+        } else {
+            Some(from + 1).filter(|to| *to < self.messages.len())
+        };
+        if let Some(to) = to {
+            self.send(QueuedMessageAction::Move { from, to });
+            // Keep the moved message selected once the new order arrives.
+            self.state.selected_idx = Some(to);
+        }
+    }
+
+    fn build_rows(&self) -> Vec<GenericDisplayRow> {
+        self.messages
+            .iter()
+            .enumerate()
+            .map(|(idx, message)| {
+                let prefix = if self.state.selected_idx == Some(idx) {
+                    '›'
+                } else {
+                    ' '
+                };
+                GenericDisplayRow {
+                    name: format!("{prefix} {}. {}", idx + 1, preview(message)),
+                    display_shortcut: None,
+                    match_indices: None,
+                    is_current: false,
+                    description: None,
+                }
+            })
+            .collect()
+    }
+
+    fn footer_hint() -> Line<'static> {
+        Line::from(vec![
+            key_hint::plain(KeyCode::Enter).into(),
+            " edit  ".into(),
+            key_hint::plain(KeyCode::Char('s')).into(),
+            " send now  ".into(),
+            key_hint::plain(KeyCode::Char('d')).into(),
+            " delete  ".into(),
+            key_hint::shift(KeyCode::Up).into(),
+            "/↓ move  ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " close".into(),
+        ])
+    }
+}
+
+/// First line of `message`, shortened to fit on one row.
+fn preview(message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default();
+    let more_lines = message.lines().nth(1).is_some();
+    if first_line.chars().count() > MAX_PREVIEW_CHARS {
+        let truncated: String = first_line.chars().take(MAX_PREVIEW_CHARS - 1).collect();
+        format!("{truncated}…")
+    } else if more_lines {
+        format!("{first_line} …")
+    } else {
+        first_line.to_string()
+    }
+}
+
+impl BottomPaneView for QueuedMessagesView {
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if key_event.kind == KeyEventKind::Release {
+            return;
+        }
+        let len = self.messages.len();
+        match (key_event.code, key_event.modifiers) {
+            (KeyCode::Up, KeyModifiers::SHIFT) | (KeyCode::Char('K'), _) => {
+                self.move_selected(true)
+            }
+            (KeyCode::Down, KeyModifiers::SHIFT) | (KeyCode::Char('J'), _) => {
+                self.move_selected(false)
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('k'), KeyModifiers::NONE) => {
+                self.state.move_up_wrap(len);
+                self.state.ensure_visible(len, MAX_POPUP_ROWS);
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('j'), KeyModifiers::NONE) => {
+                self.state.move_down_wrap(len);
+                self.state.ensure_visible(len, MAX_POPUP_ROWS);
+            }
+            (KeyCode::Enter, _) | (KeyCode::Char('e'), KeyModifiers::NONE) => {
+                if let Some(idx) = self.selected() {
+                    self.send(QueuedMessageAction::Edit(idx));
+                    self.complete = true;
+                }
+            }
+            (KeyCode::Char('s'), KeyModifiers::NONE) => {
+                if let Some(idx) = self.selected() {
+                    self.send(QueuedMessageAction::SendNow(idx));
+                }
+            }
+            (KeyCode::Char('d'), KeyModifiers::NONE) | (KeyCode::Delete, _) => {
+                if let Some(idx) = self.selected() {
+                    self.send(QueuedMessageAction::Delete(idx));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    fn on_ctrl_c(&mut self) -> CancellationEvent {
+        self.complete = true;
+        CancellationEvent::Handled
+    }
+
+    fn update_queued_messages(&mut self, queued: &[String]) {
+        self.messages = queued.to_vec();
+        if self.messages.is_empty() {
+            self.complete = true;
+            return;
+        }
+        self.state.clamp_selection(self.messages.len());
+        self.state
+            .ensure_visible(self.messages.len(), MAX_POPUP_ROWS);
+    }
+}
+
+impl Renderable for QueuedMessagesView {
+    fn desired_height(&self, width: u16) -> u16 {
+        let rows = self.build_rows();
+        let rows_height = measure_rows_height(&rows, &self.state, MAX_POPUP_ROWS, width);
+        // Title, blank line, rows, vertical padding and the footer hint.
+        rows_height.saturating_add(5)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.height == 0 || area.width == 0 {
+            return;
+        }
+
+        let [content_area, footer_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(area);
+
+        Block::default()
+            .style(user_message_style(terminal_palette::default_bg()))
+            .render(content_area, buf);
+
+        let rows = self.build_rows();
+        let rows_height =
+            measure_rows_height(&rows, &self.state, MAX_POPUP_ROWS, content_area.width);
+        let [title_area, _, list_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Max(1),
+            Constraint::Length(rows_height),
+        ])
+        .areas(content_area.inset(Insets::vh(1, 2)));
+
+        Line::from(vec![
+            "Queued messages".bold(),
+            format!(" ({})", self.messages.len()).dim(),
+        ])
+        .render(title_area, buf);
+
+        if list_area.height > 0 {
+            let list_area = Rect {
+                x: list_area.x - 2,
+                y: list_area.y,
+                width: list_area.width + 2,
+                height: list_area.height,
+            };
+            render_rows(
+                list_area,
+                buf,
+                &rows,
+                &self.state,
+                list_area.height as usize,
+                "no queued messages",
+            );
+        }
+
+        let hint_area = Rect {
+            x: footer_area.x + 2,
+            y: footer_area.y,
+            width: footer_area.width.saturating_sub(2),
+            height: footer_area.height,
+        };
+        Self::footer_hint().dim().render(hint_area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn render_lines(view: &QueuedMessagesView, width: u16) -> String {
+        let height = view.desired_height(width);
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        (0..area.height)
+            .map(|row| {
+                (0..area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn renders_queued_messages() {
+        let (tx_raw, _rx) = unbounded_channel::<AppEvent>();
+        let view = QueuedMessagesView::new(
+            vec![
+                "also add a test".to_string(),
+                "then update the docs\nand the changelog".to_string(),
+            ],
+            AppEventSender::new(tx_raw),
+        );
+        assert_snapshot!("queued_messages_view", render_lines(&view, 72));
+    }
+
+    #[test]
+    fn keys_map_to_queue_actions() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let mut view = QueuedMessagesView::new(
+            vec!["first".to_string(), "second".to_string()],
+            AppEventSender::new(tx_raw),
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::SHIFT));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut actions = Vec::new();
+        while let Ok(AppEvent::QueuedMessage(action)) = rx.try_recv() {
+            actions.push(action);
+        }
+        assert_eq!(
+            actions,
+            vec![
+                QueuedMessageAction::SendNow(1),
+                QueuedMessageAction::Move { from: 1, to: 0 },
+                QueuedMessageAction::Delete(0),
+                QueuedMessageAction::Edit(0),
+            ]
+        );
+        assert!(view.is_complete());
+    }
+}
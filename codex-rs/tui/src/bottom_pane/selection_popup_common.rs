@@ -2,7 +2,6 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 // Note: Table-based layout previously used Constraint; the manual renderer
 // below no longer requires it.
-use ratatui::style::Color;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
@@ -10,6 +9,7 @@ use ratatui::widgets::Widget;
 use unicode_width::UnicodeWidthChar;
 
 use crate::key_hint::KeyBinding;
+use crate::theme;
 
 use super::scroll_state::ScrollState;
 
@@ -178,9 +178,9 @@ pub(crate) fn render_rows(
             desc_col,
         );
         if Some(i) == state.selected_idx {
-            // Match previous behavior: cyan + bold for the selected row.
+            // Accent + bold for the selected row.
             full_line.spans.iter_mut().for_each(|span| {
-                span.style = span.style.fg(Color::Cyan).bold();
+                span.style = span.style.patch(theme::accent()).bold();
             });
         }
 
@@ -1,9 +1,10 @@
+use crate::theme;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
+use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::widgets::StatefulWidgetRef;
 use ratatui::widgets::WidgetRef;
@@ -26,6 +27,8 @@ pub(crate) struct TextArea {
     wrap_cache: RefCell<Option<WrapCache>>,
     preferred_col: Option<usize>,
     elements: Vec<TextElement>,
+    /// Highlighted byte range, e.g. a vi-mode visual selection.
+    selection: Option<Range<usize>>,
 }
 
 #[derive(Debug, Clone)]
@@ -48,6 +51,7 @@ impl TextArea {
             wrap_cache: RefCell::new(None),
             preferred_col: None,
             elements: Vec::new(),
+            selection: None,
         }
     }
 
@@ -57,6 +61,7 @@ impl TextArea {
         self.wrap_cache.replace(None);
         self.preferred_col = None;
         self.elements.clear();
+        self.selection = None;
     }
 
     pub fn text(&self) -> &str {
@@ -126,6 +131,10 @@ impl TextArea {
         self.preferred_col = None;
     }
 
+    pub(crate) fn set_selection(&mut self, selection: Option<Range<usize>>) {
+        self.selection = selection;
+    }
+
     pub fn desired_height(&self, width: u16) -> u16 {
         self.wrapped_lines(width).len() as u16
     }
@@ -939,9 +948,25 @@ impl TextArea {
                 }
                 let styled = &self.text[overlap_start..overlap_end];
                 let x_off = self.text[line_range.start..overlap_start].width() as u16;
-                let style = Style::default().fg(Color::Cyan);
+                let style = theme::accent();
                 buf.set_string(area.x + x_off, y, styled, style);
             }
+
+            if let Some(selection) = &self.selection {
+                let overlap_start = selection.start.max(line_range.start);
+                let overlap_end = selection.end.min(line_range.end);
+                if overlap_start < overlap_end
+                    && self.text.is_char_boundary(overlap_start)
+                    && self.text.is_char_boundary(overlap_end)
+                {
+                    let x_off = self.text[line_range.start..overlap_start].width() as u16;
+                    let width = self.text[overlap_start..overlap_end].width() as u16;
+                    buf.set_style(
+                        Rect::new(area.x + x_off, y, width, 1).intersection(area),
+                        Style::default().add_modifier(Modifier::REVERSED),
+                    );
+                }
+            }
         }
     }
 }
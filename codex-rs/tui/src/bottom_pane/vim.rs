@@ -0,0 +1,1404 @@
+//! Optional vi-style modal editing for the chat composer (`[tui] vim_mode`).
+//!
+//! [`Vim`] sits in front of the [`TextArea`]: in insert mode keys pass through
+//! untouched, while normal and visual mode keys are parsed into motions,
+//! operators and text objects and applied to the textarea directly. Keys the
+//! composer itself relies on (Enter, Up/Down history, Ctrl shortcuts) always
+//! pass through so that submitting and the other bindings keep working.
+
+use std::ops::Range;
+
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+use crate::bottom_pane::textarea::TextArea;
+use crate::clipboard_paste::copy_text_to_clipboard;
+use crate::clipboard_paste::paste_text_from_clipboard;
+
+/// Counts above this are clamped so `99999999dd` cannot stall the UI.
+const MAX_COUNT: usize = 999;
+/// Number of changes `u` can undo.
+const MAX_UNDO: usize = 100;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum VimMode {
+    Normal,
+    Insert,
+    Visual,
+    VisualLine,
+}
+
+impl VimMode {
+    /// Indicator shown in the composer footer.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            VimMode::Normal => "-- NORMAL --",
+            VimMode::Insert => "-- INSERT --",
+            VimMode::Visual => "-- VISUAL --",
+            VimMode::VisualLine => "-- VISUAL LINE --",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum VimKeyResult {
+    /// The key was consumed by the vi layer.
+    Handled,
+    /// The composer should process the key as usual.
+    PassThrough,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Motion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordStart { big: bool },
+    WordBack { big: bool },
+    WordEnd { big: bool },
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    FirstLine,
+    LastLine,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum TextObject {
+    Word { big: bool },
+    Quote(char),
+    Bracket(char, char),
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Target {
+    Motion(Motion),
+    /// `dd`, `cc`, `yy`.
+    Line,
+    Object {
+        inner: bool,
+        object: TextObject,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Command {
+    Move(Motion),
+    Operate(Operator, Target),
+    Replace(char),
+    /// A single-key command such as `x`, `p` or `A`.
+    Simple(char),
+}
+
+#[derive(Debug, Eq, PartialEq)]
+enum Parsed {
+    Incomplete,
+    Invalid,
+    Complete {
+        command: Command,
+        count: Option<usize>,
+    },
+}
+
+/// Text captured by the last delete or yank.
+#[derive(Debug, Default)]
+struct Register {
+    text: String,
+    linewise: bool,
+}
+
+/// Range produced by a motion or text object, before an operator applies it.
+struct Region {
+    range: Range<usize>,
+    linewise: bool,
+}
+
+/// Draft contents saved before a change, restored by `u`.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    text: String,
+    cursor: usize,
+}
+
+impl Snapshot {
+    fn of(textarea: &TextArea) -> Self {
+        Self {
+            text: textarea.text().to_string(),
+            cursor: textarea.cursor(),
+        }
+    }
+}
+
+/// The last normal mode change, replayed by `.`.
+#[derive(Clone, Debug)]
+struct LastChange {
+    /// The command as typed, including its count, e.g. `2dw` or `ciw`.
+    keys: String,
+    /// Text typed in the insert mode the command started, if any.
+    inserted: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Vim {
+    mode: VimMode,
+    pending: String,
+    register: Register,
+    visual_anchor: usize,
+    use_system_clipboard: bool,
+    undo: Vec<Snapshot>,
+    last_change: Option<LastChange>,
+    /// Draft when the last change entered insert mode, to find what was typed.
+    insert_start: Option<Snapshot>,
+}
+
+impl Vim {
+    /// Start in insert mode so the composer behaves as usual until Esc.
+    pub(crate) fn new(use_system_clipboard: bool) -> Self {
+        Self {
+            mode: VimMode::Insert,
+            pending: String::new(),
+            register: Register::default(),
+            visual_anchor: 0,
+            use_system_clipboard,
+            undo: Vec::new(),
+            last_change: None,
+            insert_start: None,
+        }
+    }
+
+    pub(crate) fn mode(&self) -> VimMode {
+        self.mode
+    }
+
+    /// Whether Esc is meaningful to the vi layer right now, i.e. it should not
+    /// trigger the composer's own Esc handling (interrupt, backtrack).
+    pub(crate) fn captures_esc(&self) -> bool {
+        self.mode != VimMode::Normal || !self.pending.is_empty()
+    }
+
+    /// Return to insert mode, e.g. after the draft was submitted.
+    pub(crate) fn reset(&mut self, textarea: &mut TextArea) {
+        self.mode = VimMode::Insert;
+        self.pending.clear();
+        self.undo.clear();
+        self.insert_start = None;
+        textarea.set_selection(None);
+    }
+
+    pub(crate) fn handle_key(&mut self, textarea: &mut TextArea, key: KeyEvent) -> VimKeyResult {
+        if self.mode == VimMode::Insert {
+            if key.code == KeyCode::Esc && key.modifiers == KeyModifiers::NONE {
+                self.leave_insert(textarea);
+                return VimKeyResult::Handled;
+            }
+            return VimKeyResult::PassThrough;
+        }
+
+        if key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return VimKeyResult::PassThrough;
+        }
+        let c = match key.code {
+            KeyCode::Char(c) => c,
+            KeyCode::Esc => {
+                if !self.pending.is_empty() {
+                    self.pending.clear();
+                } else if self.is_visual() {
+                    self.leave_visual(textarea);
+                } else {
+                    return VimKeyResult::PassThrough;
+                }
+                return VimKeyResult::Handled;
+            }
+            KeyCode::Left | KeyCode::Backspace => 'h',
+            KeyCode::Right => 'l',
+            KeyCode::Delete if !self.is_visual() => 'x',
+            KeyCode::Enter
+            | KeyCode::Up
+            | KeyCode::Down
+            | KeyCode::Home
+            | KeyCode::End
+            | KeyCode::Tab
+            | KeyCode::PageUp
+            | KeyCode::PageDown => return VimKeyResult::PassThrough,
+            _ => return VimKeyResult::Handled,
+        };
+
+        if self.is_visual() && self.pending.is_empty() && self.visual_key(textarea, c) {
+            return VimKeyResult::Handled;
+        }
+
+        self.pending.push(c);
+        match parse(&self.pending) {
+            Parsed::Incomplete => {}
+            Parsed::Invalid => self.pending.clear(),
+            Parsed::Complete { command, count } => {
+                let keys = std::mem::take(&mut self.pending);
+                match (self.is_visual(), command) {
+                    (false, Command::Simple('u')) => self.undo(textarea, count.unwrap_or(1)),
+                    (false, Command::Simple('.')) => self.repeat_change(textarea, count),
+                    (false, command) => {
+                        let before = Snapshot::of(textarea);
+                        self.execute(textarea, command, count);
+                        if is_change(command) {
+                            self.record_change(textarea, before, keys);
+                        }
+                    }
+                    (true, Command::Move(motion)) => {
+                        let text = textarea.text().to_string();
+                        let target = apply_motion(&text, textarea.cursor(), motion, count);
+                        textarea.set_cursor(target.pos);
+                    }
+                    (true, _) => {}
+                }
+            }
+        }
+        self.finish_key(textarea);
+        VimKeyResult::Handled
+    }
+
+    fn is_visual(&self) -> bool {
+        matches!(self.mode, VimMode::Visual | VimMode::VisualLine)
+    }
+
+    /// Esc in insert mode: back to normal mode with the cursor on the last
+    /// typed character, remembering the typed text for `.`.
+    fn leave_insert(&mut self, textarea: &mut TextArea) {
+        self.mode = VimMode::Normal;
+        if let Some(start) = self.insert_start.take()
+            && let Some(change) = &mut self.last_change
+        {
+            change.inserted = inserted_text(&start, textarea.text(), textarea.cursor());
+        }
+        let text = textarea.text().to_string();
+        let pos = textarea.cursor();
+        if pos > line_start(&text, pos) {
+            textarea.set_cursor(prev_char(&text, pos));
+        }
+    }
+
+    /// Save `before` for `u` if the draft changed or is about to change in
+    /// insert mode.
+    fn push_undo(&mut self, textarea: &TextArea, before: Snapshot) {
+        if textarea.text() == before.text && self.mode != VimMode::Insert {
+            return;
+        }
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+    }
+
+    fn record_change(&mut self, textarea: &TextArea, before: Snapshot, keys: String) {
+        self.push_undo(textarea, before);
+        self.last_change = Some(LastChange {
+            keys,
+            inserted: None,
+        });
+        self.insert_start = (self.mode == VimMode::Insert).then(|| Snapshot::of(textarea));
+    }
+
+    fn undo(&mut self, textarea: &mut TextArea, n: usize) {
+        for _ in 0..n {
+            let Some(snapshot) = self.undo.pop() else {
+                break;
+            };
+            textarea.set_text(&snapshot.text);
+            textarea.set_cursor(snapshot.cursor);
+        }
+    }
+
+    /// Replay the last change; a count replaces the one it was typed with.
+    fn repeat_change(&mut self, textarea: &mut TextArea, count: Option<usize>) {
+        let Some(change) = self.last_change.clone() else {
+            return;
+        };
+        let Parsed::Complete {
+            command,
+            count: typed_count,
+        } = parse(&change.keys)
+        else {
+            return;
+        };
+        let before = Snapshot::of(textarea);
+        self.execute(textarea, command, count.or(typed_count));
+        if self.mode == VimMode::Insert {
+            if let Some(inserted) = &change.inserted {
+                textarea.insert_str(inserted);
+            }
+            self.push_undo(textarea, before);
+            self.leave_insert(textarea);
+        } else {
+            self.push_undo(textarea, before);
+        }
+    }
+
+    fn leave_visual(&mut self, textarea: &mut TextArea) {
+        self.mode = VimMode::Normal;
+        textarea.set_selection(None);
+        self.finish_key(textarea);
+    }
+
+    /// Keep the cursor on a character in normal mode and mirror the visual
+    /// selection into the textarea.
+    fn finish_key(&mut self, textarea: &mut TextArea) {
+        let text = textarea.text().to_string();
+        let pos = textarea.cursor().min(text.len());
+        match self.mode {
+            VimMode::Normal | VimMode::Visual | VimMode::VisualLine => {
+                if pos == line_end(&text, pos) && pos > line_start(&text, pos) {
+                    textarea.set_cursor(prev_char(&text, pos));
+                }
+            }
+            VimMode::Insert => {}
+        }
+        if self.is_visual() {
+            let range = self.visual_range(&text, textarea.cursor());
+            textarea.set_selection(Some(range));
+        }
+    }
+
+    fn visual_range(&self, text: &str, cursor: usize) -> Range<usize> {
+        let start = self.visual_anchor.min(cursor).min(text.len());
+        let end = self.visual_anchor.max(cursor).min(text.len());
+        match self.mode {
+            VimMode::VisualLine => line_start(text, start)..line_end(text, end),
+            _ => start..next_char(text, end),
+        }
+    }
+
+    /// Handle keys that act on the visual selection. Returns `false` for keys
+    /// that should be parsed as motions instead.
+    fn visual_key(&mut self, textarea: &mut TextArea, c: char) -> bool {
+        let before = Snapshot::of(textarea);
+        let text = textarea.text().to_string();
+        let cursor = textarea.cursor();
+        let linewise = self.mode == VimMode::VisualLine;
+        let selection = self.visual_range(&text, cursor);
+        let span = if linewise {
+            Region {
+                range: linewise_range(&text, selection.start, selection.end),
+                linewise,
+            }
+        } else {
+            Region {
+                range: selection,
+                linewise,
+            }
+        };
+        match c {
+            'd' | 'x' => {
+                self.leave_visual(textarea);
+                self.operate(textarea, Operator::Delete, span);
+            }
+            'c' | 's' => {
+                self.leave_visual(textarea);
+                self.operate(textarea, Operator::Change, span);
+            }
+            'y' => {
+                self.leave_visual(textarea);
+                self.operate(textarea, Operator::Yank, span);
+            }
+            'p' | 'P' => {
+                self.leave_visual(textarea);
+                let register = self.read_register();
+                delete_range(textarea, span.range.clone());
+                if register.linewise && !span.linewise {
+                    textarea.insert_str(&format!("\n{}", register.text));
+                } else {
+                    textarea.insert_str(&register.text);
+                }
+            }
+            'o' => {
+                textarea.set_cursor(self.visual_anchor);
+                self.visual_anchor = cursor;
+                self.finish_key(textarea);
+            }
+            'v' | 'V' => {
+                let mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                if self.mode == mode {
+                    self.leave_visual(textarea);
+                } else {
+                    self.mode = mode;
+                    self.finish_key(textarea);
+                }
+            }
+            _ => return false,
+        }
+        if matches!(c, 'd' | 'x' | 'c' | 's' | 'p' | 'P') {
+            self.push_undo(textarea, before);
+        }
+        true
+    }
+
+    fn execute(&mut self, textarea: &mut TextArea, command: Command, count: Option<usize>) {
+        let text = textarea.text().to_string();
+        let pos = textarea.cursor().min(text.len());
+        let n = count.unwrap_or(1);
+        match command {
+            Command::Move(motion) => {
+                let target = apply_motion(&text, pos, motion, count);
+                textarea.set_cursor(target.pos);
+            }
+            Command::Operate(op, target) => {
+                if let Some(span) = operator_span(&text, pos, op, target, count) {
+                    self.operate(textarea, op, span);
+                }
+            }
+            Command::Replace(c) => {
+                let end = advance_in_line(&text, pos, n);
+                if char_count(&text[pos..end]) == n && c != '\n' {
+                    textarea.replace_range(pos..end, &c.to_string().repeat(n));
+                    textarea.set_cursor(pos + (n - 1) * c.len_utf8());
+                }
+            }
+            Command::Simple(c) => self.simple(textarea, &text, pos, c, count),
+        }
+    }
+
+    fn simple(
+        &mut self,
+        textarea: &mut TextArea,
+        text: &str,
+        pos: usize,
+        c: char,
+        count: Option<usize>,
+    ) {
+        let n = count.unwrap_or(1);
+        let start = line_start(text, pos);
+        let end = line_end(text, pos);
+        let charwise = |range: Range<usize>| Region {
+            range,
+            linewise: false,
+        };
+        match c {
+            'x' => self.operate(
+                textarea,
+                Operator::Delete,
+                charwise(pos..advance_in_line(text, pos, n)),
+            ),
+            'X' => {
+                let target = apply_motion(text, pos, Motion::Left, count);
+                self.operate(textarea, Operator::Delete, charwise(target.pos..pos));
+            }
+            's' => self.operate(
+                textarea,
+                Operator::Change,
+                charwise(pos..advance_in_line(text, pos, n)),
+            ),
+            'S' => self.execute(
+                textarea,
+                Command::Operate(Operator::Change, Target::Line),
+                count,
+            ),
+            'D' => self.operate(textarea, Operator::Delete, charwise(pos..end)),
+            'C' => self.operate(textarea, Operator::Change, charwise(pos..end)),
+            'Y' => self.execute(
+                textarea,
+                Command::Operate(Operator::Yank, Target::Line),
+                count,
+            ),
+            'p' | 'P' => self.put(textarea, text, pos, c == 'p', n),
+            'i' => self.mode = VimMode::Insert,
+            'a' => {
+                textarea.set_cursor(if pos < end { next_char(text, pos) } else { pos });
+                self.mode = VimMode::Insert;
+            }
+            'I' => {
+                textarea.set_cursor(first_non_blank(text, start));
+                self.mode = VimMode::Insert;
+            }
+            'A' => {
+                textarea.set_cursor(end);
+                self.mode = VimMode::Insert;
+            }
+            'o' => {
+                textarea.insert_str_at(end, "\n");
+                textarea.set_cursor(end + 1);
+                self.mode = VimMode::Insert;
+            }
+            'O' => {
+                textarea.insert_str_at(start, "\n");
+                textarea.set_cursor(start);
+                self.mode = VimMode::Insert;
+            }
+            'v' | 'V' => {
+                self.mode = if c == 'v' {
+                    VimMode::Visual
+                } else {
+                    VimMode::VisualLine
+                };
+                self.visual_anchor = pos;
+            }
+            _ => {}
+        }
+    }
+
+    fn operate(&mut self, textarea: &mut TextArea, op: Operator, span: Region) {
+        let text = textarea.text().to_string();
+        let range = span.range.start.min(text.len())..span.range.end.min(text.len());
+        if range.is_empty() && !span.linewise {
+            if op == Operator::Change {
+                self.mode = VimMode::Insert;
+            }
+            return;
+        }
+        self.write_register(register_text(&text, &range, span.linewise), span.linewise);
+        match op {
+            Operator::Yank => {
+                if !span.linewise {
+                    textarea.set_cursor(range.start);
+                }
+            }
+            Operator::Delete => {
+                delete_range(textarea, range);
+                if span.linewise {
+                    let text = textarea.text().to_string();
+                    let pos = textarea.cursor().min(text.len());
+                    textarea.set_cursor(first_non_blank(&text, line_start(&text, pos)));
+                }
+            }
+            Operator::Change => {
+                let mut range = range;
+                if span.linewise {
+                    // Keep an empty line to type into, like `cc` in vi.
+                    if text[range.clone()].ends_with('\n') {
+                        range.end -= 1;
+                    } else if text[range.clone()].starts_with('\n') {
+                        range.start += 1;
+                    }
+                }
+                delete_range(textarea, range);
+                self.mode = VimMode::Insert;
+            }
+        }
+    }
+
+    fn put(&mut self, textarea: &mut TextArea, text: &str, pos: usize, after: bool, n: usize) {
+        let register = self.read_register();
+        if register.text.is_empty() {
+            return;
+        }
+        let content = register.text.repeat(n);
+        if register.linewise {
+            let start = line_start(text, pos);
+            let end = line_end(text, pos);
+            let line_pos = if !after {
+                textarea.insert_str_at(start, &content);
+                start
+            } else if end < text.len() {
+                textarea.insert_str_at(end + 1, &content);
+                end + 1
+            } else {
+                let body = content.strip_suffix('\n').unwrap_or(&content);
+                textarea.insert_str_at(end, &format!("\n{body}"));
+                end + 1
+            };
+            let text = textarea.text().to_string();
+            textarea.set_cursor(first_non_blank(&text, line_pos));
+        } else {
+            let at = if after && pos < line_end(text, pos) {
+                next_char(text, pos)
+            } else {
+                pos
+            };
+            textarea.insert_str_at(at, &content);
+            let text = textarea.text().to_string();
+            textarea.set_cursor(prev_char(&text, at + content.len()));
+        }
+    }
+
+    fn write_register(&mut self, text: String, linewise: bool) {
+        if self.use_system_clipboard {
+            // Best effort: the internal register still works without a clipboard.
+            let _ = copy_text_to_clipboard(&text);
+        }
+        self.register = Register { text, linewise };
+    }
+
+    /// Prefer the system clipboard when it holds something other than what we
+    /// last yanked, so text copied in other applications can be put with `p`.
+    fn read_register(&self) -> Register {
+        if self.use_system_clipboard
+            && let Ok(text) = paste_text_from_clipboard()
+            && !text.is_empty()
+            && text != self.register.text
+        {
+            return Register {
+                linewise: text.ends_with('\n'),
+                text,
+            };
+        }
+        Register {
+            text: self.register.text.clone(),
+            linewise: self.register.linewise,
+        }
+    }
+}
+
+fn delete_range(textarea: &mut TextArea, range: Range<usize>) {
+    let start = range.start;
+    textarea.replace_range(range, "");
+    textarea.set_cursor(start);
+}
+
+fn register_text(text: &str, range: &Range<usize>, linewise: bool) -> String {
+    let selected = &text[range.clone()];
+    if !linewise {
+        return selected.to_string();
+    }
+    // Linewise ranges carry either a trailing or (on the last line) a leading
+    // newline; normalize to "lines\n".
+    let body = selected
+        .strip_suffix('\n')
+        .or_else(|| selected.strip_prefix('\n'))
+        .unwrap_or(selected);
+    format!("{body}\n")
+}
+
+/// Resolve the range an operator acts on, applying the vi special cases.
+fn operator_span(
+    text: &str,
+    pos: usize,
+    op: Operator,
+    target: Target,
+    count: Option<usize>,
+) -> Option<Region> {
+    let n = count.unwrap_or(1);
+    match target {
+        Target::Line => {
+            let last = nth_line_below(text, pos, n - 1);
+            Some(Region {
+                range: linewise_range(text, pos, last),
+                linewise: true,
+            })
+        }
+        Target::Object { inner, object } => {
+            text_object(text, pos, inner, object).map(|range| Region {
+                range,
+                linewise: false,
+            })
+        }
+        Target::Motion(motion) => {
+            // `cw` changes to the end of the word rather than eating the
+            // following whitespace.
+            let motion = match motion {
+                Motion::WordStart { big }
+                    if op == Operator::Change
+                        && char_at(text, pos).is_some_and(|c| !c.is_whitespace()) =>
+                {
+                    Motion::WordEnd { big }
+                }
+                other => other,
+            };
+            let target = apply_motion(text, pos, motion, count);
+            let (mut start, mut end) = if target.pos < pos {
+                (target.pos, pos)
+            } else {
+                (pos, target.pos)
+            };
+            if target.inclusive {
+                end = next_char(text, end);
+            }
+            if target.linewise {
+                return Some(Region {
+                    range: linewise_range(text, start, end),
+                    linewise: true,
+                });
+            }
+            if matches!(motion, Motion::WordStart { .. }) {
+                // `dw` on the last word of a line stops at the line end.
+                end = end.min(line_end(text, start)).max(start);
+                start = start.min(end);
+            }
+            Some(Region {
+                range: start..end,
+                linewise: false,
+            })
+        }
+    }
+}
+
+/// Whole lines spanning `start..=end`, including one adjoining newline.
+fn linewise_range(text: &str, start: usize, end: usize) -> Range<usize> {
+    let first = line_start(text, start);
+    let last = line_end(text, end.max(start));
+    if last < text.len() {
+        first..last + 1
+    } else if first > 0 {
+        first - 1..last
+    } else {
+        first..last
+    }
+}
+
+fn parse(pending: &str) -> Parsed {
+    let mut chars = pending.chars().peekable();
+    let count = take_count(&mut chars);
+    let Some(c) = chars.next() else {
+        return Parsed::Incomplete;
+    };
+    let command = match c {
+        'd' | 'c' | 'y' => {
+            let op = match c {
+                'd' => Operator::Delete,
+                'c' => Operator::Change,
+                _ => Operator::Yank,
+            };
+            let motion_count = take_count(&mut chars);
+            let Some(t) = chars.next() else {
+                return Parsed::Incomplete;
+            };
+            let count = match (count, motion_count) {
+                (None, None) => None,
+                (a, b) => Some((a.unwrap_or(1) * b.unwrap_or(1)).min(MAX_COUNT)),
+            };
+            let target = if t == c {
+                Target::Line
+            } else if t == 'i' || t == 'a' {
+                let Some(o) = chars.next() else {
+                    return Parsed::Incomplete;
+                };
+                match parse_object(o) {
+                    Some(object) => Target::Object {
+                        inner: t == 'i',
+                        object,
+                    },
+                    None => return Parsed::Invalid,
+                }
+            } else {
+                match parse_motion(t, chars.next()) {
+                    MotionParse::Motion(motion) => Target::Motion(motion),
+                    MotionParse::Incomplete => return Parsed::Incomplete,
+                    MotionParse::NotMotion => return Parsed::Invalid,
+                }
+            };
+            return Parsed::Complete {
+                command: Command::Operate(op, target),
+                count,
+            };
+        }
+        'r' => match chars.next() {
+            Some(r) => Command::Replace(r),
+            None => return Parsed::Incomplete,
+        },
+        'x' | 'X' | 's' | 'S' | 'D' | 'C' | 'Y' | 'p' | 'P' | 'i' | 'a' | 'I' | 'A' | 'o' | 'O'
+        | 'v' | 'V' | 'u' | '.' => Command::Simple(c),
+        _ => match parse_motion(c, chars.next()) {
+            MotionParse::Motion(motion) => Command::Move(motion),
+            MotionParse::Incomplete => return Parsed::Incomplete,
+            MotionParse::NotMotion => return Parsed::Invalid,
+        },
+    };
+    Parsed::Complete { command, count }
+}
+
+/// Whether `command` edits the draft, making it undoable and repeatable.
+fn is_change(command: Command) -> bool {
+    match command {
+        Command::Move(_) => false,
+        Command::Operate(op, _) => op != Operator::Yank,
+        Command::Replace(_) => true,
+        Command::Simple(c) => !matches!(c, 'Y' | 'v' | 'V'),
+    }
+}
+
+/// The text typed since insert mode started at `start`, if the draft only
+/// grew at the insertion point.
+fn inserted_text(start: &Snapshot, text: &str, cursor: usize) -> Option<String> {
+    let from = start.cursor;
+    let added = text.len().checked_sub(start.text.len())?;
+    let unchanged = cursor == from + added
+        && text.get(..from)? == start.text.get(..from)?
+        && text.get(cursor..)? == start.text.get(from..)?;
+    unchanged.then(|| text[from..cursor].to_string())
+}
+
+fn take_count(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> Option<usize> {
+    let mut count: Option<usize> = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        // A leading `0` is the line-start motion, not a count.
+        if digit == 0 && count.is_none() {
+            break;
+        }
+        chars.next();
+        count = Some((count.unwrap_or(0) * 10 + digit as usize).min(MAX_COUNT));
+    }
+    count
+}
+
+enum MotionParse {
+    Motion(Motion),
+    Incomplete,
+    NotMotion,
+}
+
+fn parse_motion(c: char, next: Option<char>) -> MotionParse {
+    let motion = match c {
+        'h' => Motion::Left,
+        'l' | ' ' => Motion::Right,
+        'j' => Motion::Down,
+        'k' => Motion::Up,
+        'w' | 'W' => Motion::WordStart { big: c == 'W' },
+        'b' | 'B' => Motion::WordBack { big: c == 'B' },
+        'e' | 'E' => Motion::WordEnd { big: c == 'E' },
+        '0' => Motion::LineStart,
+        '^' => Motion::FirstNonBlank,
+        '$' => Motion::LineEnd,
+        'G' => Motion::LastLine,
+        'g' => match next {
+            Some('g') => Motion::FirstLine,
+            Some(_) => return MotionParse::NotMotion,
+            None => return MotionParse::Incomplete,
+        },
+        _ => return MotionParse::NotMotion,
+    };
+    MotionParse::Motion(motion)
+}
+
+fn parse_object(c: char) -> Option<TextObject> {
+    Some(match c {
+        'w' => TextObject::Word { big: false },
+        'W' => TextObject::Word { big: true },
+        '"' | '\'' | '`' => TextObject::Quote(c),
+        '(' | ')' | 'b' => TextObject::Bracket('(', ')'),
+        '[' | ']' => TextObject::Bracket('[', ']'),
+        '{' | '}' | 'B' => TextObject::Bracket('{', '}'),
+        '<' | '>' => TextObject::Bracket('<', '>'),
+        _ => return None,
+    })
+}
+
+struct MotionTarget {
+    pos: usize,
+    linewise: bool,
+    inclusive: bool,
+}
+
+fn apply_motion(text: &str, pos: usize, motion: Motion, count: Option<usize>) -> MotionTarget {
+    let n = count.unwrap_or(1);
+    let charwise = |pos| MotionTarget {
+        pos,
+        linewise: false,
+        inclusive: false,
+    };
+    let linewise = |pos| MotionTarget {
+        pos,
+        linewise: true,
+        inclusive: false,
+    };
+    match motion {
+        Motion::Left => {
+            let start = line_start(text, pos);
+            let mut p = pos;
+            for _ in 0..n {
+                if p <= start {
+                    break;
+                }
+                p = prev_char(text, p);
+            }
+            charwise(p)
+        }
+        Motion::Right => charwise(advance_in_line(text, pos, n)),
+        Motion::Down | Motion::Up => {
+            let col = char_count(&text[line_start(text, pos)..pos]);
+            let line = if motion == Motion::Down {
+                nth_line_below(text, pos, n)
+            } else {
+                nth_line_above(text, pos, n)
+            };
+            linewise(advance_in_line(text, line, col))
+        }
+        Motion::WordStart { big } => charwise(repeat(n, pos, |p| next_word_start(text, p, big))),
+        Motion::WordBack { big } => charwise(repeat(n, pos, |p| prev_word_start(text, p, big))),
+        Motion::WordEnd { big } => MotionTarget {
+            pos: repeat(n, pos, |p| word_end(text, p, big)),
+            linewise: false,
+            inclusive: true,
+        },
+        Motion::LineStart => charwise(line_start(text, pos)),
+        Motion::FirstNonBlank => charwise(first_non_blank(text, line_start(text, pos))),
+        Motion::LineEnd => {
+            let line = nth_line_below(text, pos, n - 1);
+            charwise(line_end(text, line))
+        }
+        Motion::FirstLine | Motion::LastLine => {
+            let line = match (motion, count) {
+                (_, Some(line)) => nth_line_below(text, 0, line - 1),
+                (Motion::FirstLine, None) => 0,
+                _ => line_start(text, text.len()),
+            };
+            linewise(first_non_blank(text, line))
+        }
+    }
+}
+
+fn repeat(n: usize, pos: usize, step: impl Fn(usize) -> usize) -> usize {
+    (0..n).fold(pos, |p, _| step(p))
+}
+
+fn text_object(text: &str, pos: usize, inner: bool, object: TextObject) -> Option<Range<usize>> {
+    match object {
+        TextObject::Word { big } => {
+            let start_of_line = line_start(text, pos);
+            let end_of_line = line_end(text, pos);
+            let class = char_at(text, pos).map(|c| char_class(c, big))?;
+            let mut start = pos;
+            while start > start_of_line && char_class(char_before(text, start)?, big) == class {
+                start = prev_char(text, start);
+            }
+            let mut end = pos;
+            while end < end_of_line && char_at(text, end).map(|c| char_class(c, big)) == Some(class)
+            {
+                end = next_char(text, end);
+            }
+            if !inner {
+                let trailing = skip_blanks(text, end, end_of_line);
+                if trailing > end {
+                    end = trailing;
+                } else {
+                    while start > start_of_line && char_before(text, start)?.is_whitespace() {
+                        start = prev_char(text, start);
+                    }
+                }
+            }
+            Some(start..end)
+        }
+        TextObject::Quote(quote) => {
+            let start_of_line = line_start(text, pos);
+            let line = &text[start_of_line..line_end(text, pos)];
+            let quotes: Vec<usize> = line
+                .match_indices(quote)
+                .map(|(idx, _)| start_of_line + idx)
+                .collect();
+            let (open, close) = quotes
+                .chunks_exact(2)
+                .map(|pair| (pair[0], pair[1]))
+                .find(|(open, close)| (*open..=*close).contains(&pos))
+                .or_else(|| {
+                    quotes
+                        .chunks_exact(2)
+                        .map(|pair| (pair[0], pair[1]))
+                        .find(|(open, _)| *open > pos)
+                })?;
+            Some(if inner {
+                open + 1..close
+            } else {
+                open..close + 1
+            })
+        }
+        TextObject::Bracket(open_char, close_char) => {
+            let bytes = text.as_bytes();
+            let (open_byte, close_byte) = (open_char as u8, close_char as u8);
+            let mut depth = 0usize;
+            let mut open = None;
+            let search_from = if bytes.get(pos) == Some(&close_byte) {
+                pos
+            } else {
+                pos + 1
+            };
+            for idx in (0..search_from.min(bytes.len())).rev() {
+                if bytes[idx] == close_byte && idx != pos {
+                    depth += 1;
+                } else if bytes[idx] == open_byte {
+                    if depth == 0 {
+                        open = Some(idx);
+                        break;
+                    }
+                    depth -= 1;
+                }
+            }
+            let open = open?;
+            let mut depth = 0usize;
+            let close = (open + 1..bytes.len()).find(|&idx| {
+                if bytes[idx] == open_byte {
+                    depth += 1;
+                } else if bytes[idx] == close_byte {
+                    if depth == 0 {
+                        return true;
+                    }
+                    depth -= 1;
+                }
+                false
+            })?;
+            Some(if inner {
+                open + 1..close
+            } else {
+                open..close + 1
+            })
+        }
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq)]
+enum CharClass {
+    Blank,
+    Word,
+    Punct,
+}
+
+fn char_class(c: char, big: bool) -> CharClass {
+    if c.is_whitespace() {
+        CharClass::Blank
+    } else if big || c.is_alphanumeric() || c == '_' {
+        CharClass::Word
+    } else {
+        CharClass::Punct
+    }
+}
+
+fn next_word_start(text: &str, pos: usize, big: bool) -> usize {
+    let Some(c) = char_at(text, pos) else {
+        return pos;
+    };
+    let class = char_class(c, big);
+    let mut p = pos;
+    if class != CharClass::Blank {
+        while char_at(text, p).is_some_and(|c| char_class(c, big) == class) {
+            p = next_char(text, p);
+        }
+    }
+    while char_at(text, p).is_some_and(char::is_whitespace) {
+        p = next_char(text, p);
+    }
+    p
+}
+
+fn prev_word_start(text: &str, pos: usize, big: bool) -> usize {
+    let mut p = pos;
+    while char_before(text, p).is_some_and(char::is_whitespace) {
+        p = prev_char(text, p);
+    }
+    let Some(class) = char_before(text, p).map(|c| char_class(c, big)) else {
+        return p;
+    };
+    while char_before(text, p).is_some_and(|c| char_class(c, big) == class) {
+        p = prev_char(text, p);
+    }
+    p
+}
+
+/// Last character of the current or next word.
+fn word_end(text: &str, pos: usize, big: bool) -> usize {
+    let mut p = next_char(text, pos);
+    while char_at(text, p).is_some_and(char::is_whitespace) {
+        p = next_char(text, p);
+    }
+    let Some(class) = char_at(text, p).map(|c| char_class(c, big)) else {
+        return pos;
+    };
+    while char_at(text, next_char(text, p)).is_some_and(|c| char_class(c, big) == class) {
+        p = next_char(text, p);
+    }
+    p
+}
+
+fn char_at(text: &str, pos: usize) -> Option<char> {
+    text.get(pos..).and_then(|rest| rest.chars().next())
+}
+
+fn char_before(text: &str, pos: usize) -> Option<char> {
+    text.get(..pos).and_then(|head| head.chars().next_back())
+}
+
+fn next_char(text: &str, pos: usize) -> usize {
+    char_at(text, pos).map_or(pos, |c| pos + c.len_utf8())
+}
+
+fn prev_char(text: &str, pos: usize) -> usize {
+    char_before(text, pos).map_or(pos, |c| pos - c.len_utf8())
+}
+
+fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Move right by up to `n` characters without leaving the line.
+fn advance_in_line(text: &str, pos: usize, n: usize) -> usize {
+    let end = line_end(text, pos);
+    text[pos..end]
+        .char_indices()
+        .nth(n)
+        .map_or(end, |(idx, _)| pos + idx)
+}
+
+fn skip_blanks(text: &str, pos: usize, limit: usize) -> usize {
+    let mut p = pos;
+    while p < limit && char_at(text, p).is_some_and(char::is_whitespace) {
+        p = next_char(text, p);
+    }
+    p
+}
+
+fn line_start(text: &str, pos: usize) -> usize {
+    text[..pos].rfind('\n').map_or(0, |idx| idx + 1)
+}
+
+fn line_end(text: &str, pos: usize) -> usize {
+    text[pos..].find('\n').map_or(text.len(), |idx| pos + idx)
+}
+
+fn first_non_blank(text: &str, line: usize) -> usize {
+    skip_blanks(text, line, line_end(text, line))
+}
+
+/// Start of the line `n` lines below the one containing `pos` (clamped).
+fn nth_line_below(text: &str, pos: usize, n: usize) -> usize {
+    let mut line = line_start(text, pos);
+    for _ in 0..n {
+        let end = line_end(text, line);
+        if end >= text.len() {
+            break;
+        }
+        line = end + 1;
+    }
+    line
+}
+
+/// Start of the line `n` lines above the one containing `pos` (clamped).
+fn nth_line_above(text: &str, pos: usize, n: usize) -> usize {
+    let mut line = line_start(text, pos);
+    for _ in 0..n {
+        if line == 0 {
+            break;
+        }
+        line = line_start(text, line - 1);
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn setup(text: &str, cursor: usize) -> (Vim, TextArea) {
+        let mut textarea = TextArea::new();
+        textarea.set_text(text);
+        textarea.set_cursor(cursor);
+        let mut vim = Vim::new(false);
+        vim.handle_key(&mut textarea, KeyEvent::from(KeyCode::Esc));
+        textarea.set_cursor(cursor);
+        (vim, textarea)
+    }
+
+    fn keys(vim: &mut Vim, textarea: &mut TextArea, keys: &str) {
+        for c in keys.chars() {
+            let key = match c {
+                '\u{1b}' => KeyEvent::from(KeyCode::Esc),
+                c => KeyEvent::from(KeyCode::Char(c)),
+            };
+            if vim.handle_key(textarea, key) == VimKeyResult::PassThrough {
+                textarea.input(key);
+            }
+        }
+    }
+
+    #[test]
+    fn word_operators() {
+        let (mut vim, mut textarea) = setup("foo bar baz", 4);
+        keys(&mut vim, &mut textarea, "dw");
+        assert_eq!(textarea.text(), "foo baz");
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        keys(&mut vim, &mut textarea, "0cwqux\u{1b}");
+        assert_eq!(textarea.text(), "qux baz");
+        assert_eq!(textarea.cursor(), 2);
+
+        keys(&mut vim, &mut textarea, "$dw");
+        assert_eq!(textarea.text(), "qux ba");
+    }
+
+    #[test]
+    fn linewise_delete_and_put() {
+        let (mut vim, mut textarea) = setup("one\ntwo\nthree", 5);
+        keys(&mut vim, &mut textarea, "dd");
+        assert_eq!(textarea.text(), "one\nthree");
+        assert_eq!(textarea.cursor(), 4);
+
+        keys(&mut vim, &mut textarea, "p");
+        assert_eq!(textarea.text(), "one\nthree\ntwo");
+
+        keys(&mut vim, &mut textarea, "ggP");
+        assert_eq!(textarea.text(), "two\none\nthree\ntwo");
+
+        keys(&mut vim, &mut textarea, "G2dk");
+        assert_eq!(textarea.text(), "two");
+    }
+
+    #[test]
+    fn text_objects() {
+        let (mut vim, mut textarea) = setup(r#"say "hello there" (a (b) c)"#, 7);
+        keys(&mut vim, &mut textarea, "ci\"hi\u{1b}");
+        assert_eq!(textarea.text(), r#"say "hi" (a (b) c)"#);
+
+        keys(&mut vim, &mut textarea, "$hhhhda(");
+        assert_eq!(textarea.text(), r#"say "hi" (a  c)"#);
+
+        keys(&mut vim, &mut textarea, "0diw");
+        assert_eq!(textarea.text(), r#" "hi" (a  c)"#);
+    }
+
+    #[test]
+    fn visual_yank_and_counts() {
+        let (mut vim, mut textarea) = setup("alpha beta gamma", 0);
+        keys(&mut vim, &mut textarea, "2w");
+        assert_eq!(textarea.cursor(), 11);
+
+        keys(&mut vim, &mut textarea, "0ve");
+        assert_eq!(vim.mode(), VimMode::Visual);
+        keys(&mut vim, &mut textarea, "y");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(textarea.cursor(), 0);
+
+        keys(&mut vim, &mut textarea, "$p");
+        assert_eq!(textarea.text(), "alpha beta gammaalpha");
+
+        keys(&mut vim, &mut textarea, "03x");
+        assert_eq!(textarea.text(), "ha beta gammaalpha");
+    }
+
+    #[test]
+    fn operators_with_motions_and_counts() {
+        let (mut vim, mut textarea) = setup("one two three four five", 0);
+        keys(&mut vim, &mut textarea, "2dw");
+        assert_eq!(textarea.text(), "three four five");
+
+        keys(&mut vim, &mut textarea, "wd$");
+        assert_eq!(textarea.text(), "three ");
+
+        keys(&mut vim, &mut textarea, "0c2etwo words\u{1b}");
+        assert_eq!(textarea.text(), "two words ");
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        let (mut vim, mut textarea) = setup("a\nb\nc\nd", 0);
+        keys(&mut vim, &mut textarea, "3dd");
+        assert_eq!(textarea.text(), "d");
+
+        let (mut vim, mut textarea) = setup("a\nb\nc\nd", 0);
+        keys(&mut vim, &mut textarea, "dj");
+        assert_eq!(textarea.text(), "c\nd");
+    }
+
+    #[test]
+    fn insert_and_normal_mode_switching() {
+        let (mut vim, mut textarea) = setup("abc", 1);
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert!(!vim.captures_esc());
+
+        keys(&mut vim, &mut textarea, "iX");
+        assert_eq!(vim.mode(), VimMode::Insert);
+        assert!(vim.captures_esc());
+        assert_eq!(textarea.text(), "aXbc");
+
+        // Esc steps back onto the last inserted character.
+        keys(&mut vim, &mut textarea, "\u{1b}");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(textarea.cursor(), 1);
+
+        keys(&mut vim, &mut textarea, "AY\u{1b}");
+        assert_eq!(textarea.text(), "aXbcY");
+        keys(&mut vim, &mut textarea, "IZ\u{1b}");
+        assert_eq!(textarea.text(), "ZaXbcY");
+        keys(&mut vim, &mut textarea, "onext\u{1b}Oprev\u{1b}");
+        assert_eq!(textarea.text(), "ZaXbcY\nprev\nnext");
+
+        // Keys the composer owns pass through in normal mode.
+        assert_eq!(
+            vim.handle_key(&mut textarea, KeyEvent::from(KeyCode::Enter)),
+            VimKeyResult::PassThrough
+        );
+        assert_eq!(
+            vim.handle_key(&mut textarea, KeyEvent::from(KeyCode::Esc)),
+            VimKeyResult::PassThrough
+        );
+
+        vim.reset(&mut textarea);
+        assert_eq!(vim.mode(), VimMode::Insert);
+    }
+
+    #[test]
+    fn dot_repeats_the_last_change() {
+        let (mut vim, mut textarea) = setup("a b c d e f", 0);
+        keys(&mut vim, &mut textarea, "dw..");
+        assert_eq!(textarea.text(), "d e f");
+
+        // A count given to `.` replaces the original one.
+        keys(&mut vim, &mut textarea, "2.");
+        assert_eq!(textarea.text(), "f");
+
+        let (mut vim, mut textarea) = setup("foo bar baz", 0);
+        keys(&mut vim, &mut textarea, "cwqux\u{1b}w.");
+        assert_eq!(textarea.text(), "qux qux baz");
+        assert_eq!(vim.mode(), VimMode::Normal);
+
+        // Motions and yanks do not replace the change to repeat.
+        keys(&mut vim, &mut textarea, "wyw.");
+        assert_eq!(textarea.text(), "qux qux qux");
+    }
+
+    #[test]
+    fn undo_restores_earlier_drafts() {
+        let (mut vim, mut textarea) = setup("one two three", 0);
+        keys(&mut vim, &mut textarea, "dwx");
+        assert_eq!(textarea.text(), "wo three");
+
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "two three");
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "one two three");
+        assert_eq!(textarea.cursor(), 0);
+        // Nothing left to undo.
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "one two three");
+
+        // Text typed in insert mode is undone together with the command.
+        keys(&mut vim, &mut textarea, "$cwend\u{1b}");
+        assert_eq!(textarea.text(), "one two threend");
+        keys(&mut vim, &mut textarea, "u");
+        assert_eq!(textarea.text(), "one two three");
+
+        keys(&mut vim, &mut textarea, "0vjd");
+        keys(&mut vim, &mut textarea, "ddu2u");
+        assert_eq!(textarea.text(), "one two three");
+    }
+
+    #[test]
+    fn visual_modes() {
+        let (mut vim, mut textarea) = setup("one\ntwo\nthree", 0);
+        keys(&mut vim, &mut textarea, "vl");
+        assert_eq!(vim.mode(), VimMode::Visual);
+        keys(&mut vim, &mut textarea, "V");
+        assert_eq!(vim.mode(), VimMode::VisualLine);
+        keys(&mut vim, &mut textarea, "jd");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(textarea.text(), "three");
+
+        keys(&mut vim, &mut textarea, "P");
+        assert_eq!(textarea.text(), "one\ntwo\nthree");
+
+        // `o` swaps the ends of the selection, Esc leaves visual mode.
+        keys(&mut vim, &mut textarea, "G$vhho");
+        keys(&mut vim, &mut textarea, "\u{1b}");
+        assert_eq!(vim.mode(), VimMode::Normal);
+        assert_eq!(textarea.cursor(), 12);
+
+        keys(&mut vim, &mut textarea, "hhvecX\u{1b}");
+        assert_eq!(textarea.text(), "one\ntwo\nthX");
+    }
+}
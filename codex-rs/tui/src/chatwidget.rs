@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use codex_core::config::Config;
+use codex_core::config_types::DiffView;
+use codex_core::config_types::McpServerConfig;
 use codex_core::config_types::Notifications;
 use codex_core::git_info::current_branch_name;
 use codex_core::git_info::local_git_branches;
@@ -33,6 +35,9 @@ use codex_core::protocol::PatchApplyBeginEvent;
 use codex_core::protocol::RateLimitSnapshot;
 use codex_core::protocol::ReviewRequest;
 use codex_core::protocol::StreamErrorEvent;
+use codex_core::protocol::SubAgentActivityEvent;
+use codex_core::protocol::SubAgentBeginEvent;
+use codex_core::protocol::SubAgentEndEvent;
 use codex_core::protocol::TaskCompleteEvent;
 use codex_core::protocol::TokenUsage;
 use codex_core::protocol::TokenUsageInfo;
@@ -42,6 +47,7 @@ use codex_core::protocol::UserMessageEvent;
 use codex_core::protocol::ViewImageToolCallEvent;
 use codex_core::protocol::WebSearchBeginEvent;
 use codex_core::protocol::WebSearchEndEvent;
+use codex_core::review_prompts;
 use codex_protocol::ConversationId;
 use codex_protocol::parse_command::ParsedCommand;
 use crossterm::event::KeyCode;
@@ -65,6 +71,7 @@ use crate::bottom_pane::BottomPane;
 use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::InputResult;
+use crate::bottom_pane::QueuedMessageAction;
 use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
@@ -75,16 +82,23 @@ use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
 use crate::exec_cell::ExecCell;
 use crate::exec_cell::new_active_exec_command;
+use crate::exec_command::strip_bash_lc_and_escape;
 use crate::get_git_diff::get_git_diff;
 use crate::history_cell;
 use crate::history_cell::AgentMessageCell;
 use crate::history_cell::HistoryCell;
 use crate::history_cell::McpToolCallCell;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
 use crate::markdown::append_markdown;
 use crate::slash_command::SlashCommand;
 use crate::status::RateLimitSnapshotDisplay;
 use crate::text_formatting::truncate_text;
+use crate::theme;
 use crate::tui::FrameRequester;
+use crate::workspace_snapshot::SnapshotWorker;
+use crate::workspace_snapshot::WorkspaceSnapshot;
+use crate::workspace_snapshot::WorkspaceSnapshotter;
 mod interrupts;
 use self::interrupts::InterruptManager;
 mod agent;
@@ -106,15 +120,11 @@ use codex_core::protocol::AskForApproval;
 use codex_core::protocol::SandboxPolicy;
 use codex_core::protocol_config_types::ReasoningEffort as ReasoningEffortConfig;
 use codex_file_search::FileMatch;
-use codex_git_tooling::CreateGhostCommitOptions;
-use codex_git_tooling::GhostCommit;
 use codex_git_tooling::GitToolingError;
-use codex_git_tooling::create_ghost_commit;
-use codex_git_tooling::restore_ghost_commit;
 use codex_protocol::plan_tool::UpdatePlanArgs;
 use strum::IntoEnumIterator;
 
-const MAX_TRACKED_GHOST_COMMITS: usize = 20;
+const MAX_TRACKED_SNAPSHOTS: usize = 20;
 
 // Track information about an in-flight exec command.
 struct RunningCommand {
@@ -249,13 +259,25 @@ pub(crate) struct ChatWidget {
     suppress_session_configured_redraw: bool,
     // User messages queued while a turn is in progress
     queued_user_messages: VecDeque<UserMessage>,
+    // Queue position of a queued message copied into the composer for
+    // editing. The original stays queued until the edit is submitted and
+    // replaces it; clearing the draft abandons the edit. While the front of
+    // the queue is being edited, the queue does not advance.
+    editing_queued_message: Option<usize>,
+    // Queued message picked with "send now" while a turn was running; it
+    // starts the next turn once the interrupt lands.
+    send_after_interrupt: Option<UserMessage>,
     // Pending notification to show when unfocused on next Draw
     pending_notification: Option<Notification>,
     // Simple review mode flag; used to adjust layout and banners.
     is_review_mode: bool,
-    // List of ghost commits corresponding to each turn.
-    ghost_snapshots: Vec<GhostCommit>,
-    ghost_snapshots_disabled: bool,
+    // Workspace snapshots taken before each turn, newest last, for `/undo`.
+    workspace_snapshots: Vec<WorkspaceSnapshot>,
+    // Started on the first snapshot; captures run off the UI thread.
+    workspace_snapshot_worker: Option<SnapshotWorker>,
+    // User messages waiting for their snapshot before being sent.
+    workspace_snapshots_pending: usize,
+    workspace_snapshots_disabled: bool,
     // Whether to add a final message separator after the last message
     needs_final_message_separator: bool,
 
@@ -484,6 +506,20 @@ impl ChatWidget {
             ));
         }
 
+        // The interrupt came from "send now": that message starts the next
+        // turn and the rest of the queue stays queued behind it.
+        if let Some(user_message) = self.send_after_interrupt.take() {
+            self.submit_user_message(user_message);
+            self.refresh_queued_user_messages();
+            self.request_redraw();
+            return;
+        }
+
+        // A queued message being edited is already in the composer as its
+        // draft, so only the draft is kept.
+        if let Some(index) = self.editing_queued_message.take() {
+            self.queued_user_messages.remove(index);
+        }
         // If any messages were queued during the task, restore them into the composer.
         if !self.queued_user_messages.is_empty() {
             let queued_text = self
@@ -560,6 +596,45 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_sub_agent_begin(&mut self, event: SubAgentBeginEvent) {
+        self.flush_answer_stream_with_separator();
+        self.add_to_history(history_cell::new_sub_agent_begin(&event.task));
+        self.request_redraw();
+    }
+
+    /// Show the sub-agent events worth following under its header; the rest
+    /// are left out.
+    fn on_sub_agent_activity(&mut self, event: SubAgentActivityEvent) {
+        let text = match *event.msg {
+            EventMsg::AgentMessage(AgentMessageEvent { message }) => message,
+            EventMsg::ExecCommandBegin(ExecCommandBeginEvent { command, .. }) => {
+                format!("$ {}", strip_bash_lc_and_escape(&command))
+            }
+            EventMsg::ExecCommandEnd(ExecCommandEndEvent { exit_code, .. }) if exit_code != 0 => {
+                format!("exited {exit_code}")
+            }
+            EventMsg::McpToolCallBegin(McpToolCallBeginEvent { invocation, .. }) => {
+                format!("Called {}.{}", invocation.server, invocation.tool)
+            }
+            EventMsg::PatchApplyEnd(ev) => {
+                if ev.success {
+                    "Applied patch".to_string()
+                } else {
+                    "Patch failed".to_string()
+                }
+            }
+            EventMsg::Error(ErrorEvent { message }) => format!("Error: {message}"),
+            _ => return,
+        };
+        self.add_to_history(history_cell::new_sub_agent_activity(&text));
+        self.request_redraw();
+    }
+
+    fn on_sub_agent_end(&mut self, event: SubAgentEndEvent) {
+        self.add_to_history(history_cell::new_sub_agent_end(event.success));
+        self.request_redraw();
+    }
+
     fn on_patch_apply_end(&mut self, event: codex_core::protocol::PatchApplyEndEvent) {
         let ev2 = event.clone();
         self.defer_or_handle(
@@ -910,6 +985,8 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                vim_mode: config.tui_vim_mode,
+                vim_clipboard: config.tui_vim_clipboard,
             }),
             active_cell: None,
             config: config.clone(),
@@ -930,12 +1007,16 @@ impl ChatWidget {
             full_reasoning_buffer: String::new(),
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            editing_queued_message: None,
+            send_after_interrupt: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: false,
             pending_notification: None,
             is_review_mode: false,
-            ghost_snapshots: Vec::new(),
-            ghost_snapshots_disabled: true,
+            workspace_snapshots: Vec::new(),
+            workspace_snapshot_worker: None,
+            workspace_snapshots_pending: 0,
+            workspace_snapshots_disabled: false,
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
         }
@@ -973,6 +1054,8 @@ impl ChatWidget {
                 enhanced_keys_supported,
                 placeholder_text: placeholder,
                 disable_paste_burst: config.disable_paste_burst,
+                vim_mode: config.tui_vim_mode,
+                vim_clipboard: config.tui_vim_clipboard,
             }),
             active_cell: None,
             config: config.clone(),
@@ -993,12 +1076,16 @@ impl ChatWidget {
             full_reasoning_buffer: String::new(),
             conversation_id: None,
             queued_user_messages: VecDeque::new(),
+            editing_queued_message: None,
+            send_after_interrupt: None,
             show_welcome_banner: true,
             suppress_session_configured_redraw: true,
             pending_notification: None,
             is_review_mode: false,
-            ghost_snapshots: Vec::new(),
-            ghost_snapshots_disabled: true,
+            workspace_snapshots: Vec::new(),
+            workspace_snapshot_worker: None,
+            workspace_snapshots_pending: 0,
+            workspace_snapshots_disabled: false,
             needs_final_message_separator: false,
             last_rendered_width: std::cell::Cell::new(None),
         }
@@ -1014,21 +1101,23 @@ impl ChatWidget {
 
     pub(crate) fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event {
-            KeyEvent {
-                code: KeyCode::Char('c'),
-                modifiers: crossterm::event::KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } => {
+            _ if key_event.kind == KeyEventKind::Press
+                && keymap().is_press(KeymapAction::Interrupt, key_event) =>
+            {
                 self.on_ctrl_c();
+                self.abandon_queued_edit_if_cleared();
                 return;
             }
-            KeyEvent {
-                code: KeyCode::Char('v'),
-                modifiers: KeyModifiers::CONTROL,
-                kind: KeyEventKind::Press,
-                ..
-            } => {
+            _ if key_event.kind == KeyEventKind::Press
+                && keymap().is_press(KeymapAction::ExternalEditor, key_event)
+                && self.bottom_pane.composer_has_focus() =>
+            {
+                self.app_event_tx.send(AppEvent::OpenExternalEditor);
+                return;
+            }
+            _ if key_event.kind == KeyEventKind::Press
+                && keymap().is_press(KeymapAction::PasteImage, key_event) =>
+            {
                 if let Ok((path, info)) = paste_image_to_temp_png() {
                     self.attach_image(path, info.width, info.height, info.encoded_format.label());
                 }
@@ -1046,24 +1135,28 @@ impl ChatWidget {
                 modifiers: KeyModifiers::ALT,
                 kind: KeyEventKind::Press,
                 ..
-            } if !self.queued_user_messages.is_empty() => {
-                // Prefer the most recently queued item.
-                if let Some(user_message) = self.queued_user_messages.pop_back() {
-                    self.bottom_pane.set_composer_text(user_message.text);
-                    self.refresh_queued_user_messages();
-                    self.request_redraw();
-                }
+            } if !self.queued_user_messages.is_empty() && self.bottom_pane.composer_has_focus() => {
+                self.bottom_pane.show_queued_messages();
+                self.request_redraw();
             }
             _ => {
                 match self.bottom_pane.handle_key_event(key_event) {
                     InputResult::Submitted(text) => {
+                        let mut image_paths = self.bottom_pane.take_recent_submission_images();
+                        let editing = self.editing_queued_message.take().and_then(|index| {
+                            let original = self.queued_user_messages.remove(index)?;
+                            Some((index, original.image_paths))
+                        });
+                        if let Some((_, queued_images)) = &editing {
+                            image_paths.splice(0..0, queued_images.iter().cloned());
+                        }
+                        let user_message = UserMessage { text, image_paths };
                         // If a task is running, queue the user input to be sent after the turn completes.
-                        let user_message = UserMessage {
-                            text,
-                            image_paths: self.bottom_pane.take_recent_submission_images(),
-                        };
                         if self.bottom_pane.is_task_running() {
-                            self.queued_user_messages.push_back(user_message);
+                            let index = editing
+                                .map_or(self.queued_user_messages.len(), |(index, _)| index)
+                                .min(self.queued_user_messages.len());
+                            self.queued_user_messages.insert(index, user_message);
                             self.refresh_queued_user_messages();
                         } else {
                             self.submit_user_message(user_message);
@@ -1072,10 +1165,23 @@ impl ChatWidget {
                     InputResult::Command(cmd) => {
                         self.dispatch_command(cmd);
                     }
+                    InputResult::CommandWithArgs(cmd, args) => {
+                        self.dispatch_command_with_args(cmd, &args);
+                    }
                     InputResult::None => {}
                 }
             }
         }
+        self.abandon_queued_edit_if_cleared();
+    }
+
+    /// Forget the queued message being edited once its draft is cleared; the
+    /// original stays in the queue, which resumes if it was held for the edit.
+    fn abandon_queued_edit_if_cleared(&mut self) {
+        if self.editing_queued_message.is_some() && self.bottom_pane.composer_is_empty() {
+            self.editing_queued_message = None;
+            self.maybe_send_next_queued_input();
+        }
     }
 
     pub(crate) fn attach_image(
@@ -1139,8 +1245,10 @@ impl ChatWidget {
             SlashCommand::Diff => {
                 self.add_diff_in_progress();
                 let tx = self.app_event_tx.clone();
+                // The split view parses the diff, so it needs it uncolored.
+                let color = self.config.tui_diff_view != DiffView::Split;
                 tokio::spawn(async move {
-                    let text = match get_git_diff().await {
+                    let text = match get_git_diff(color).await {
                         Ok((is_git_repo, diff_text)) => {
                             if is_git_repo {
                                 diff_text
@@ -1162,6 +1270,9 @@ impl ChatWidget {
             SlashCommand::Mcp => {
                 self.add_mcp_output();
             }
+            SlashCommand::Theme => {
+                self.open_theme_popup();
+            }
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => {
                 use codex_core::protocol::EventMsg;
@@ -1246,13 +1357,58 @@ impl ChatWidget {
     }
 
     fn submit_user_message(&mut self, user_message: UserMessage) {
+        if user_message.text.is_empty() && user_message.image_paths.is_empty() {
+            return;
+        }
+        // Once snapshots are off, send right away unless earlier messages are
+        // still waiting for theirs, which would reorder them.
+        if self.workspace_snapshots_disabled && self.workspace_snapshots_pending == 0 {
+            self.send_user_message(user_message, None);
+            return;
+        }
+        let UserMessage { text, image_paths } = user_message;
+        let tx = self.app_event_tx.clone();
+        let worker = self.workspace_snapshot_worker.get_or_insert_with(|| {
+            SnapshotWorker::spawn(WorkspaceSnapshotter::new(
+                self.config.cwd.clone(),
+                self.config.tui_file_snapshots,
+            ))
+        });
+        self.workspace_snapshots_pending += 1;
+        worker.capture(move |result| {
+            tx.send(AppEvent::WorkspaceSnapshotTaken {
+                text,
+                image_paths,
+                result,
+            });
+        });
+    }
+
+    /// Send a user message whose workspace snapshot has been taken.
+    pub(crate) fn on_workspace_snapshot_taken(
+        &mut self,
+        text: String,
+        image_paths: Vec<PathBuf>,
+        result: Result<WorkspaceSnapshot, GitToolingError>,
+    ) {
+        self.workspace_snapshots_pending = self.workspace_snapshots_pending.saturating_sub(1);
+        let snapshot = self.record_workspace_snapshot(result);
+        self.send_user_message(UserMessage { text, image_paths }, snapshot);
+    }
+
+    /// Send `user_message` to the agent and record it in the transcript. While
+    /// a turn is running, core injects it into that turn instead of starting
+    /// a new one.
+    fn send_user_message(
+        &mut self,
+        user_message: UserMessage,
+        snapshot: Option<WorkspaceSnapshot>,
+    ) {
         let UserMessage { text, image_paths } = user_message;
         if text.is_empty() && image_paths.is_empty() {
             return;
         }
 
-        self.capture_ghost_snapshot();
-
         let mut items: Vec<InputItem> = Vec::new();
 
         if !text.is_empty() {
@@ -1280,60 +1436,65 @@ impl ChatWidget {
 
         // Only show the text portion in conversation history.
         if !text.is_empty() {
-            self.add_to_history(history_cell::new_user_prompt(text));
+            self.add_to_history(history_cell::new_user_prompt(text, snapshot));
         }
         self.needs_final_message_separator = false;
     }
 
-    fn capture_ghost_snapshot(&mut self) {
-        if self.ghost_snapshots_disabled {
-            return;
-        }
-
-        let options = CreateGhostCommitOptions::new(&self.config.cwd);
-        match create_ghost_commit(&options) {
-            Ok(commit) => {
-                self.ghost_snapshots.push(commit);
-                if self.ghost_snapshots.len() > MAX_TRACKED_GHOST_COMMITS {
-                    self.ghost_snapshots.remove(0);
+    /// Keep a snapshot taken before a turn for `/undo` and backtracking, or
+    /// turn snapshots off for the session when taking it failed.
+    fn record_workspace_snapshot(
+        &mut self,
+        result: Result<WorkspaceSnapshot, GitToolingError>,
+    ) -> Option<WorkspaceSnapshot> {
+        match result {
+            Ok(snapshot) => {
+                self.workspace_snapshots.push(snapshot.clone());
+                if self.workspace_snapshots.len() > MAX_TRACKED_SNAPSHOTS {
+                    self.workspace_snapshots.remove(0);
                 }
+                Some(snapshot)
             }
+            Err(_) if self.workspace_snapshots_disabled => None,
             Err(err) => {
-                self.ghost_snapshots_disabled = true;
-                let (message, hint) = match &err {
-                    GitToolingError::NotAGitRepository { .. } => (
-                        "Snapshots disabled: current directory is not a Git repository."
+                self.workspace_snapshots_disabled = true;
+                let hint = match &err {
+                    // Outside git without `file_snapshots` there is nothing
+                    // to report.
+                    GitToolingError::NotAGitRepository { .. } => return None,
+                    GitToolingError::SnapshotTooLarge { .. } => None,
+                    _ => Some(
+                        "Restart Codex after resolving the issue to re-enable snapshots."
                             .to_string(),
-                        None,
-                    ),
-                    _ => (
-                        format!("Snapshots disabled after error: {err}"),
-                        Some(
-                            "Restart Codex after resolving the issue to re-enable snapshots."
-                                .to_string(),
-                        ),
                     ),
                 };
-                self.add_info_message(message, hint);
-                tracing::warn!("failed to create ghost snapshot: {err}");
+                self.add_info_message(format!("Snapshots disabled: {err}"), hint);
+                tracing::warn!("failed to create workspace snapshot: {err}");
+                None
             }
         }
     }
 
     fn undo_last_snapshot(&mut self) {
-        let Some(commit) = self.ghost_snapshots.pop() else {
+        let Some(snapshot) = self.workspace_snapshots.pop() else {
             self.add_info_message("No snapshot available to undo.".to_string(), None);
             return;
         };
 
-        if let Err(err) = restore_ghost_commit(&self.config.cwd, &commit) {
-            self.add_error_message(format!("Failed to restore snapshot: {err}"));
-            self.ghost_snapshots.push(commit);
-            return;
+        if !self.restore_workspace_snapshot(&snapshot) {
+            self.workspace_snapshots.push(snapshot);
         }
+    }
 
-        let short_id: String = commit.id().chars().take(8).collect();
-        self.add_info_message(format!("Restored workspace to snapshot {short_id}"), None);
+    /// Put the workspace back to `snapshot`, reporting the outcome in the
+    /// transcript. Returns whether the restore succeeded.
+    pub(crate) fn restore_workspace_snapshot(&mut self, snapshot: &WorkspaceSnapshot) -> bool {
+        if let Err(err) = snapshot.restore(&self.config.cwd) {
+            self.add_error_message(format!("Failed to restore snapshot: {err}"));
+            return false;
+        }
+        self.add_info_message(format!("Restored workspace to {}", snapshot.label()), None);
+        true
     }
 
     /// Replay a subset of initial events into the UI to seed the transcript when
@@ -1406,6 +1567,9 @@ impl ChatWidget {
                 TurnAbortReason::ReviewEnded => {
                     self.on_interrupted_turn(ev.reason);
                 }
+                TurnAbortReason::BudgetExceeded => {
+                    self.on_error("Turn aborted: task budget exceeded".to_owned())
+                }
             },
             EventMsg::PlanUpdate(update) => self.on_plan_update(update),
             EventMsg::ExecApprovalRequest(ev) => {
@@ -1447,6 +1611,9 @@ impl ChatWidget {
                 self.on_entered_review_mode(review_request)
             }
             EventMsg::ExitedReviewMode(review) => self.on_exited_review_mode(review),
+            EventMsg::SubAgentBegin(ev) => self.on_sub_agent_begin(ev),
+            EventMsg::SubAgentActivity(ev) => self.on_sub_agent_activity(ev),
+            EventMsg::SubAgentEnd(ev) => self.on_sub_agent_end(ev),
         }
     }
 
@@ -1508,7 +1675,7 @@ impl ChatWidget {
             Some(InputMessageKind::Plain) | None => {
                 let message = event.message.trim();
                 if !message.is_empty() {
-                    self.add_to_history(history_cell::new_user_prompt(message.to_string()));
+                    self.add_to_history(history_cell::new_user_prompt(message.to_string(), None));
                 }
             }
         }
@@ -1546,17 +1713,84 @@ impl ChatWidget {
     }
 
     // If idle and there are queued inputs, submit exactly one to start the next turn.
+    // The queue waits while its front message is being edited, so the stale
+    // original is not sent ahead of the edit.
     fn maybe_send_next_queued_input(&mut self) {
         if self.bottom_pane.is_task_running() {
             return;
         }
-        if let Some(user_message) = self.queued_user_messages.pop_front() {
+        if let Some(user_message) = self.send_after_interrupt.take() {
+            self.submit_user_message(user_message);
+        } else if self.editing_queued_message == Some(0) {
+            return;
+        } else if let Some(user_message) = self.queued_user_messages.pop_front() {
+            self.on_queued_message_removed(0);
             self.submit_user_message(user_message);
         }
         // Update the list to reflect the remaining queued messages (if any).
         self.refresh_queued_user_messages();
     }
 
+    /// Apply a change requested from the queued messages panel.
+    pub(crate) fn on_queued_message_action(&mut self, action: QueuedMessageAction) {
+        match action {
+            QueuedMessageAction::Edit(index) => {
+                if !self.bottom_pane.composer_is_empty() {
+                    self.add_info_message(
+                        "Send or clear the current draft before editing a queued message."
+                            .to_string(),
+                        None,
+                    );
+                } else if let Some(user_message) = self.queued_user_messages.get(index) {
+                    self.bottom_pane
+                        .set_composer_text(user_message.text.clone());
+                    self.editing_queued_message = Some(index);
+                }
+            }
+            QueuedMessageAction::Delete(index) => {
+                if self.queued_user_messages.remove(index).is_some() {
+                    self.on_queued_message_removed(index);
+                }
+            }
+            QueuedMessageAction::SendNow(index) => {
+                if let Some(user_message) = self.queued_user_messages.remove(index) {
+                    self.on_queued_message_removed(index);
+                    if self.bottom_pane.is_task_running() {
+                        // Interrupt the running turn; the message starts the
+                        // next one once the abort comes back.
+                        self.send_after_interrupt = Some(user_message);
+                        self.submit_op(Op::Interrupt);
+                    } else {
+                        self.submit_user_message(user_message);
+                    }
+                }
+            }
+            QueuedMessageAction::Move { from, to } => {
+                if from < self.queued_user_messages.len() && to < self.queued_user_messages.len() {
+                    self.queued_user_messages.swap(from, to);
+                    if self.editing_queued_message == Some(from) {
+                        self.editing_queued_message = Some(to);
+                    } else if self.editing_queued_message == Some(to) {
+                        self.editing_queued_message = Some(from);
+                    }
+                }
+            }
+        }
+        self.refresh_queued_user_messages();
+        self.request_redraw();
+    }
+
+    /// Keep the edited message's queue position in step after the message
+    /// at `index` left the queue. If it was the edited one, the draft in the
+    /// composer becomes a new message.
+    fn on_queued_message_removed(&mut self, index: usize) {
+        self.editing_queued_message = match self.editing_queued_message {
+            Some(editing) if editing == index => None,
+            Some(editing) if editing > index => Some(editing - 1),
+            editing => editing,
+        };
+    }
+
     /// Rebuild and update the queued user messages from the current queue.
     fn refresh_queued_user_messages(&mut self) {
         let messages: Vec<String> = self
@@ -1810,6 +2044,46 @@ impl ChatWidget {
         });
     }
 
+    /// Open a popup to choose a built-in or user-defined color theme.
+    pub(crate) fn open_theme_popup(&mut self) {
+        let current_theme = theme::current_name();
+        let items: Vec<SelectionItem> = theme::theme_names(&self.config.tui_themes)
+            .into_iter()
+            .map(|name| {
+                let is_current = name == current_theme;
+                let description = match name.as_str() {
+                    "dark" => Some("Default colors for dark terminals".to_string()),
+                    "light" => Some("Darker accents for light terminals".to_string()),
+                    "high-contrast" => Some("Bright, bold colors".to_string()),
+                    "deuteranopia" => {
+                        Some("Blue/yellow instead of green/red for diffs and results".to_string())
+                    }
+                    _ => Some("Defined in config.toml".to_string()),
+                };
+                let theme_name = name.clone();
+                let actions: Vec<SelectionAction> = vec![Box::new(move |tx| {
+                    tx.send(AppEvent::SetTheme(theme_name.clone()));
+                })];
+                SelectionItem {
+                    name,
+                    description,
+                    is_current,
+                    actions,
+                    dismiss_on_select: true,
+                    ..Default::default()
+                }
+            })
+            .collect();
+
+        self.bottom_pane.show_selection_view(SelectionViewParams {
+            title: Some("Select Theme".to_string()),
+            subtitle: Some("Applies to output rendered from now on".to_string()),
+            footer_hint: Some(standard_popup_hint_line()),
+            items,
+            ..Default::default()
+        });
+    }
+
     /// Set the approval policy in the widget's config copy.
     pub(crate) fn set_approval_policy(&mut self, policy: AskForApproval) {
         self.config.approval_policy = policy;
@@ -1841,6 +2115,43 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn dispatch_command_with_args(&mut self, cmd: SlashCommand, args: &str) {
+        match cmd {
+            SlashCommand::Mcp => self.handle_mcp_subcommand(args),
+            SlashCommand::Theme => self.app_event_tx.send(AppEvent::SetTheme(args.to_string())),
+            _ => self.dispatch_command(cmd),
+        }
+    }
+
+    /// Handle `/mcp restart <server>` and `/mcp reload`.
+    fn handle_mcp_subcommand(&mut self, args: &str) {
+        let mut parts = args.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("reload"), None, None) => {
+                self.add_info_message("Reloading MCP servers from config.toml…".to_string(), None);
+                self.app_event_tx.send(AppEvent::ReloadMcpServers);
+            }
+            (Some("restart"), Some(server_name), None) => {
+                self.add_info_message(format!("Restarting MCP server `{server_name}`…"), None);
+                self.submit_op(Op::RestartMcpServer {
+                    server_name: server_name.to_string(),
+                });
+            }
+            _ => {
+                self.add_error_message(
+                    "Usage: /mcp, /mcp restart <server>, or /mcp reload".to_string(),
+                );
+            }
+        }
+    }
+
+    /// Show the servers the app reloaded for `/mcp reload` and ask codex to
+    /// apply them.
+    pub(crate) fn reload_mcp_servers(&mut self, servers: HashMap<String, McpServerConfig>) {
+        self.config.mcp_servers = servers;
+        self.submit_op(Op::ReloadMcpServers);
+    }
+
     pub(crate) fn add_mcp_output(&mut self) {
         if self.config.mcp_servers.is_empty() {
             self.add_to_history(history_cell::empty_mcp_output());
@@ -1889,6 +2200,15 @@ impl ChatWidget {
         self.bottom_pane.set_composer_text(text);
     }
 
+    pub(crate) fn draft_for_external_editor(&self) -> String {
+        self.bottom_pane.draft_for_external_editor()
+    }
+
+    /// Load a draft edited in `$VISUAL`/`$EDITOR` back into the composer.
+    pub(crate) fn apply_external_edit(&mut self, text: String) {
+        self.bottom_pane.apply_external_edit(text);
+    }
+
     pub(crate) fn show_esc_backtrack_hint(&mut self) {
         self.bottom_pane.show_esc_backtrack_hint();
     }
@@ -1934,16 +2254,11 @@ impl ChatWidget {
 
         items.push(SelectionItem {
             name: "Review uncommitted changes".to_string(),
-            actions: vec![Box::new(
-                move |tx: &AppEventSender| {
-                    tx.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: "Review the current code changes (staged, unstaged, and untracked files) and provide prioritized findings.".to_string(),
-                            user_facing_hint: "current changes".to_string(),
-                        },
-                    }));
-                },
-            )],
+            actions: vec![Box::new(move |tx: &AppEventSender| {
+                tx.send(AppEvent::CodexOp(Op::Review {
+                    review_request: review_prompts::uncommitted_changes(),
+                }));
+            })],
             dismiss_on_select: true,
             ..Default::default()
         });
@@ -1991,12 +2306,7 @@ impl ChatWidget {
                 name: format!("{current_branch} -> {branch}"),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt: format!(
-                                "Review the code changes against the base branch '{branch}'. Start by finding the merge diff between the current branch and {branch}'s upstream e.g. (`git merge-base HEAD \"$(git rev-parse --abbrev-ref \"{branch}@{{upstream}}\")\"`), then run `git diff` against that SHA to see what changes we would merge into the {branch} branch. Provide prioritized, actionable findings."
-                            ),
-                            user_facing_hint: format!("changes against '{branch}'"),
-                        },
+                        review_request: review_prompts::base_branch(&branch),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2022,21 +2332,13 @@ impl ChatWidget {
         for entry in commits {
             let subject = entry.subject.clone();
             let sha = entry.sha.clone();
-            let short = sha.chars().take(7).collect::<String>();
             let search_val = format!("{subject} {sha}");
 
             items.push(SelectionItem {
                 name: subject.clone(),
                 actions: vec![Box::new(move |tx3: &AppEventSender| {
-                    let hint = format!("commit {short}");
-                    let prompt = format!(
-                        "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                    );
                     tx3.send(AppEvent::CodexOp(Op::Review {
-                        review_request: ReviewRequest {
-                            prompt,
-                            user_facing_hint: hint,
-                        },
+                        review_request: review_prompts::commit(&sha, Some(&subject)),
                     }));
                 })],
                 dismiss_on_select: true,
@@ -2246,21 +2548,13 @@ pub(crate) fn show_review_commit_picker_with_entries(
     for entry in entries {
         let subject = entry.subject.clone();
         let sha = entry.sha.clone();
-        let short = sha.chars().take(7).collect::<String>();
         let search_val = format!("{subject} {sha}");
 
         items.push(SelectionItem {
             name: subject.clone(),
             actions: vec![Box::new(move |tx3: &AppEventSender| {
-                let hint = format!("commit {short}");
-                let prompt = format!(
-                    "Review the code changes introduced by commit {sha} (\"{subject}\"). Provide prioritized, actionable findings."
-                );
                 tx3.send(AppEvent::CodexOp(Op::Review {
-                    review_request: ReviewRequest {
-                        prompt,
-                        user_facing_hint: hint,
-                    },
+                    review_request: review_prompts::commit(&sha, Some(&subject)),
                 }));
             })],
             dismiss_on_select: true,
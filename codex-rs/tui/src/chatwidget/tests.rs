@@ -255,6 +255,8 @@ fn make_chatwidget_manual() -> (
         enhanced_keys_supported: false,
         placeholder_text: "Ask Codex to do anything".to_string(),
         disable_paste_burst: false,
+        vim_mode: false,
+        vim_clipboard: false,
     });
     let auth_manager = AuthManager::from_auth_for_testing(CodexAuth::from_api_key("test"));
     let widget = ChatWidget {
@@ -279,11 +281,14 @@ fn make_chatwidget_manual() -> (
         frame_requester: FrameRequester::test_dummy(),
         show_welcome_banner: true,
         queued_user_messages: VecDeque::new(),
+        editing_queued_message: None,
         suppress_session_configured_redraw: false,
         pending_notification: None,
         is_review_mode: false,
-        ghost_snapshots: Vec::new(),
-        ghost_snapshots_disabled: false,
+        workspace_snapshots: Vec::new(),
+        workspace_snapshot_worker: None,
+        workspace_snapshots_pending: 0,
+        workspace_snapshots_disabled: true,
         needs_final_message_separator: false,
         last_rendered_width: std::cell::Cell::new(None),
     };
@@ -586,7 +591,7 @@ fn empty_enter_during_task_does_not_queue() {
 
 #[test]
 fn alt_up_edits_most_recent_queued_message() {
-    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
 
     // Simulate a running task so messages would normally be queued.
     chat.bottom_pane.set_task_running(true);
@@ -598,20 +603,185 @@ fn alt_up_edits_most_recent_queued_message() {
         .push_back(UserMessage::from("second queued".to_string()));
     chat.refresh_queued_user_messages();
 
-    // Press Alt+Up to edit the most recent (last) queued message.
+    // Alt+Up opens the queue with the most recent message selected; Enter
+    // asks to edit it.
     chat.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::ALT));
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let action = std::iter::from_fn(|| rx.try_recv().ok())
+        .find_map(|event| match event {
+            AppEvent::QueuedMessage(action) => Some(action),
+            _ => None,
+        })
+        .expect("queued message action");
+    assert_eq!(action, QueuedMessageAction::Edit(1));
+    chat.on_queued_message_action(action);
 
     // Composer should now contain the last queued message.
     assert_eq!(
         chat.bottom_pane.composer_text(),
         "second queued".to_string()
     );
-    // And the queue should now contain only the remaining (older) item.
-    assert_eq!(chat.queued_user_messages.len(), 1);
-    assert_eq!(
-        chat.queued_user_messages.front().unwrap().text,
-        "first queued"
-    );
+    // The original stays queued until the edit is submitted.
+    assert_eq!(chat.queued_user_messages.len(), 2);
+
+    // Submitting the edit puts it back in its original place.
+    chat.bottom_pane
+        .set_composer_text("second queued, edited".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    let queued: Vec<&str> = chat
+        .queued_user_messages
+        .iter()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(queued, vec!["first queued", "second queued, edited"]);
+}
+
+#[test]
+fn abandoned_queued_edit_keeps_the_original() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.queued_user_messages
+        .push_back(UserMessage::from("second queued".to_string()));
+    chat.refresh_queued_user_messages();
+
+    chat.on_queued_message_action(QueuedMessageAction::Edit(0));
+    // Clearing the draft abandons the edit; the next submission is a new
+    // message instead of replacing the original.
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    assert!(chat.bottom_pane.composer_is_empty());
+    assert!(chat.editing_queued_message.is_none());
+    chat.bottom_pane.set_composer_text("unrelated".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+    let queued: Vec<&str> = chat
+        .queued_user_messages
+        .iter()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(queued, vec!["first queued", "second queued", "unrelated"]);
+}
+
+#[test]
+fn queue_waits_while_its_front_message_is_edited() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.queued_user_messages
+        .push_back(UserMessage::from("second queued".to_string()));
+    chat.refresh_queued_user_messages();
+
+    chat.on_queued_message_action(QueuedMessageAction::Edit(0));
+    // The turn ends while the front message is being edited: the stale
+    // original is not sent.
+    chat.on_task_complete(None);
+    assert!(matches!(op_rx.try_recv(), Err(TryRecvError::Empty)));
+    assert_eq!(chat.editing_queued_message, Some(0));
+    assert_eq!(chat.queued_user_messages.len(), 2);
+
+    // Submitting the edit sends it in place of the original.
+    chat.bottom_pane
+        .set_composer_text("first queued, edited".to_string());
+    chat.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items }) => assert_eq!(
+            items,
+            vec![InputItem::Text {
+                text: "first queued, edited".to_string()
+            }]
+        ),
+        other => panic!("expected Op::UserInput, got {other:?}"),
+    }
+    assert_eq!(chat.editing_queued_message, None);
+    let queued: Vec<&str> = chat
+        .queued_user_messages
+        .iter()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(queued, vec!["second queued"]);
+}
+
+#[test]
+fn abandoning_a_held_edit_resumes_the_queue() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.refresh_queued_user_messages();
+
+    chat.on_queued_message_action(QueuedMessageAction::Edit(0));
+    chat.on_task_complete(None);
+    assert!(matches!(op_rx.try_recv(), Err(TryRecvError::Empty)));
+
+    chat.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items }) => assert_eq!(
+            items,
+            vec![InputItem::Text {
+                text: "first queued".to_string()
+            }]
+        ),
+        other => panic!("expected Op::UserInput, got {other:?}"),
+    }
+    assert!(chat.queued_user_messages.is_empty());
+}
+
+#[test]
+fn edit_is_refused_while_the_composer_has_a_draft() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.refresh_queued_user_messages();
+    chat.bottom_pane.set_composer_text("my draft".to_string());
+
+    chat.on_queued_message_action(QueuedMessageAction::Edit(0));
+
+    assert_eq!(chat.bottom_pane.composer_text(), "my draft".to_string());
+    assert_eq!(chat.editing_queued_message, None);
+}
+
+#[test]
+fn send_now_interrupts_and_starts_the_next_turn() {
+    let (mut chat, _rx, mut op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.queued_user_messages
+        .push_back(UserMessage::from("use the other API".to_string()));
+    chat.refresh_queued_user_messages();
+
+    chat.on_queued_message_action(QueuedMessageAction::SendNow(1));
+    match op_rx.try_recv() {
+        Ok(Op::Interrupt) => {}
+        other => panic!("expected Op::Interrupt, got {other:?}"),
+    }
+
+    chat.handle_codex_event(Event {
+        id: "abort".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+        }),
+    });
+    match op_rx.try_recv() {
+        Ok(Op::UserInput { items }) => assert_eq!(
+            items,
+            vec![InputItem::Text {
+                text: "use the other API".to_string()
+            }]
+        ),
+        other => panic!("expected Op::UserInput, got {other:?}"),
+    }
+    // The rest of the queue stays queued rather than moving to the composer.
+    let queued: Vec<&str> = chat
+        .queued_user_messages
+        .iter()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(queued, vec!["first queued"]);
+    assert!(chat.bottom_pane.composer_is_empty());
 }
 
 #[test]
@@ -1419,6 +1589,34 @@ fn interrupt_restores_queued_messages_into_composer() {
     let _ = drain_insert_history(&mut rx);
 }
 
+#[test]
+fn interrupt_while_editing_a_queued_message_keeps_only_the_draft() {
+    let (mut chat, mut rx, _op_rx) = make_chatwidget_manual();
+    chat.bottom_pane.set_task_running(true);
+    chat.queued_user_messages
+        .push_back(UserMessage::from("first queued".to_string()));
+    chat.queued_user_messages
+        .push_back(UserMessage::from("second queued".to_string()));
+    chat.refresh_queued_user_messages();
+
+    chat.on_queued_message_action(QueuedMessageAction::Edit(1));
+    chat.bottom_pane
+        .set_composer_text("second queued, edited".to_string());
+    chat.handle_codex_event(Event {
+        id: "turn-1".into(),
+        msg: EventMsg::TurnAborted(codex_core::protocol::TurnAbortedEvent {
+            reason: TurnAbortReason::Interrupted,
+        }),
+    });
+
+    assert_eq!(
+        chat.bottom_pane.composer_text(),
+        "first queued\nsecond queued, edited"
+    );
+    assert!(chat.editing_queued_message.is_none());
+    let _ = drain_insert_history(&mut rx);
+}
+
 #[test]
 fn interrupt_prepends_queued_messages_before_existing_composer_text() {
     let (mut chat, mut rx, mut op_rx) = make_chatwidget_manual();
@@ -22,6 +22,25 @@ impl std::fmt::Display for PasteImageError {
 }
 impl std::error::Error for PasteImageError {}
 
+/// Errors from copying text to or reading text from the system clipboard.
+#[derive(Debug)]
+pub enum ClipboardTextError {
+    ClipboardUnavailable(String),
+    AccessFailed(String),
+}
+
+impl std::fmt::Display for ClipboardTextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ClipboardTextError::ClipboardUnavailable(msg) => {
+                write!(f, "clipboard unavailable: {msg}")
+            }
+            ClipboardTextError::AccessFailed(msg) => write!(f, "clipboard access failed: {msg}"),
+        }
+    }
+}
+impl std::error::Error for ClipboardTextError {}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EncodedImageFormat {
     Png,
@@ -142,6 +161,60 @@ pub fn paste_image_to_temp_png() -> Result<(PathBuf, PastedImageInfo), PasteImag
     ))
 }
 
+/// Clipboard handle shared by text copy/paste. On X11 and Wayland copied text
+/// is only served while a handle is alive, so keep one for the whole session.
+#[cfg(not(target_os = "android"))]
+static TEXT_CLIPBOARD: std::sync::Mutex<Option<arboard::Clipboard>> = std::sync::Mutex::new(None);
+
+#[cfg(not(target_os = "android"))]
+fn with_text_clipboard<T>(
+    f: impl FnOnce(&mut arboard::Clipboard) -> Result<T, arboard::Error>,
+) -> Result<T, ClipboardTextError> {
+    let mut guard = TEXT_CLIPBOARD
+        .lock()
+        .map_err(|e| ClipboardTextError::ClipboardUnavailable(e.to_string()))?;
+    if guard.is_none() {
+        *guard = Some(
+            arboard::Clipboard::new()
+                .map_err(|e| ClipboardTextError::ClipboardUnavailable(e.to_string()))?,
+        );
+    }
+    match guard.as_mut() {
+        Some(clipboard) => {
+            f(clipboard).map_err(|e| ClipboardTextError::AccessFailed(e.to_string()))
+        }
+        None => Err(ClipboardTextError::ClipboardUnavailable(
+            "clipboard not initialized".into(),
+        )),
+    }
+}
+
+/// Copy `text` to the system clipboard.
+#[cfg(not(target_os = "android"))]
+pub fn copy_text_to_clipboard(text: &str) -> Result<(), ClipboardTextError> {
+    with_text_clipboard(|clipboard| clipboard.set_text(text.to_string()))
+}
+
+/// Read text from the system clipboard.
+#[cfg(not(target_os = "android"))]
+pub fn paste_text_from_clipboard() -> Result<String, ClipboardTextError> {
+    with_text_clipboard(arboard::Clipboard::get_text)
+}
+
+#[cfg(target_os = "android")]
+pub fn copy_text_to_clipboard(_text: &str) -> Result<(), ClipboardTextError> {
+    Err(ClipboardTextError::ClipboardUnavailable(
+        "clipboard access is unsupported on Android".into(),
+    ))
+}
+
+#[cfg(target_os = "android")]
+pub fn paste_text_from_clipboard() -> Result<String, ClipboardTextError> {
+    Err(ClipboardTextError::ClipboardUnavailable(
+        "clipboard access is unsupported on Android".into(),
+    ))
+}
+
 /// Normalize pasted text that may represent a filesystem path.
 ///
 /// Supports:
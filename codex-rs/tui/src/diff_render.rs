@@ -1,7 +1,6 @@
 use diffy::Hunk;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
@@ -9,18 +8,43 @@ use ratatui::text::Line as RtLine;
 use ratatui::text::Span as RtSpan;
 use ratatui::widgets::Paragraph;
 use std::collections::HashMap;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::OnceLock;
 
 use crate::exec_command::relativize_to_home;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use crate::theme;
+use codex_core::config_types::DiffView;
 use codex_core::git_info::get_git_repo_root;
+use codex_core::patch_review::update_hunk_count;
 use codex_core::protocol::FileChange;
 
 const SPACES_AFTER_LINE_NUMBER: usize = 6;
 
+/// Narrowest width that gets the side-by-side layout when
+/// `diff_view = "split"`; anything narrower is rendered unified.
+const SPLIT_VIEW_MIN_WIDTH: usize = 120;
+
+/// Largest token grid compared when highlighting words inside a modified
+/// line. Longer pairs are shown without word-level emphasis.
+const MAX_WORD_DIFF_CELLS: usize = 40_000;
+
+static DIFF_VIEW: OnceLock<DiffView> = OnceLock::new();
+
+/// Install the `[tui] diff_view` setting. Only the first call has an effect.
+pub(crate) fn set_diff_view(view: DiffView) {
+    let _ = DIFF_VIEW.set(view);
+}
+
+fn split_view_for(width: usize) -> bool {
+    DIFF_VIEW.get() == Some(&DiffView::Split) && width >= SPLIT_VIEW_MIN_WIDTH
+}
+
 // Internal representation for diff line rendering
+#[derive(Clone, Copy, PartialEq, Eq)]
 enum DiffLineType {
     Insert,
     Delete,
@@ -29,25 +53,39 @@ enum DiffLineType {
 
 pub struct DiffSummary {
     changes: HashMap<PathBuf, FileChange>,
+    notes: HashMap<PathBuf, String>,
     cwd: PathBuf,
 }
 
 impl DiffSummary {
     pub fn new(changes: HashMap<PathBuf, FileChange>, cwd: PathBuf) -> Self {
-        Self { changes, cwd }
+        Self {
+            changes,
+            notes: HashMap::new(),
+            cwd,
+        }
+    }
+
+    /// Rows shown under a file's header in place of (or before) its hunks,
+    /// e.g. "Binary file changed".
+    pub(crate) fn with_notes(mut self, notes: HashMap<PathBuf, String>) -> Self {
+        self.notes = notes;
+        self
     }
 }
 
 impl Renderable for FileChange {
     fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = area.width as usize;
         let mut lines = vec![];
-        render_change(self, &mut lines, area.width as usize);
+        render_change(self, &mut lines, width, split_view_for(width));
         Paragraph::new(lines).render(area, buf);
     }
 
     fn desired_height(&self, width: u16) -> u16 {
+        let width = width as usize;
         let mut lines = vec![];
-        render_change(self, &mut lines, width as usize);
+        render_change(self, &mut lines, width, split_view_for(width));
         lines.len() as u16
     }
 }
@@ -65,6 +103,12 @@ impl From<DiffSummary> for Box<dyn Renderable> {
             path.extend(render_line_count_summary(row.added, row.removed));
             rows.push(Box::new(path));
             rows.push(Box::new(RtLine::from("")));
+            if let Some(note) = val.notes.get(&row.path) {
+                rows.push(Box::new(RtLine::from(vec![
+                    "    ".into(),
+                    note.clone().dim().italic(),
+                ])));
+            }
             rows.push(Box::new(row.change));
         }
 
@@ -122,9 +166,9 @@ fn collect_rows(changes: &HashMap<PathBuf, FileChange>) -> Vec<Row> {
 fn render_line_count_summary(added: usize, removed: usize) -> Vec<RtSpan<'static>> {
     let mut spans = Vec::new();
     spans.push("(".into());
-    spans.push(format!("+{added}").green());
+    spans.push(RtSpan::styled(format!("+{added}"), theme::diff_added()));
     spans.push(" ".into());
-    spans.push(format!("-{removed}").red());
+    spans.push(RtSpan::styled(format!("-{removed}"), theme::diff_removed()));
     spans.push(")".into());
     spans
 }
@@ -181,13 +225,16 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
             out.push(RtLine::from(header));
         }
 
-        render_change(&r.change, &mut out, wrap_cols);
+        render_change(&r.change, &mut out, wrap_cols, false);
     }
 
     out
 }
 
-fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize) {
+/// Render `change` into `out`. With `split`, updates are laid out old | new
+/// side by side; added and deleted files have only one side and always use
+/// the unified layout.
+fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usize, split: bool) {
     match change {
         FileChange::Add { content } => {
             for (i, raw) in content.lines().enumerate() {
@@ -195,6 +242,7 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                     i + 1,
                     DiffLineType::Insert,
                     raw,
+                    &[],
                     width,
                 ));
             }
@@ -205,6 +253,7 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                     i + 1,
                     DiffLineType::Delete,
                     raw,
+                    &[],
                     width,
                 ));
             }
@@ -217,50 +266,285 @@ fn render_change(change: &FileChange, out: &mut Vec<RtLine<'static>>, width: usi
                         out.push(RtLine::from(vec!["    ".into(), "⋮".dim()]));
                     }
                     is_first_hunk = false;
-
-                    let mut old_ln = h.old_range().start();
-                    let mut new_ln = h.new_range().start();
-                    for l in h.lines() {
-                        match l {
-                            diffy::Line::Insert(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    new_ln,
-                                    DiffLineType::Insert,
-                                    s,
-                                    width,
-                                ));
-                                new_ln += 1;
-                            }
-                            diffy::Line::Delete(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    old_ln,
-                                    DiffLineType::Delete,
-                                    s,
-                                    width,
-                                ));
-                                old_ln += 1;
-                            }
-                            diffy::Line::Context(text) => {
-                                let s = text.trim_end_matches('\n');
-                                out.extend(push_wrapped_diff_line(
-                                    new_ln,
-                                    DiffLineType::Context,
-                                    s,
-                                    width,
-                                ));
-                                old_ln += 1;
-                                new_ln += 1;
-                            }
-                        }
-                    }
+                    render_hunk_lines(h, out, width, split);
                 }
             }
         }
     }
 }
 
+fn render_hunk_lines(h: &Hunk<'_, str>, out: &mut Vec<RtLine<'static>>, width: usize, split: bool) {
+    let rows = hunk_rows(h);
+    if split {
+        render_split_rows(&rows, out, width);
+        return;
+    }
+    for row in &rows {
+        let line_number = match row.kind {
+            DiffLineType::Delete => row.old_ln,
+            DiffLineType::Insert | DiffLineType::Context => row.new_ln,
+        };
+        out.extend(push_wrapped_diff_line(
+            line_number,
+            row.kind,
+            row.text,
+            &row.emphasis,
+            width,
+        ));
+    }
+}
+
+/// One line of a hunk, with the byte ranges of `text` that differ from the
+/// line it replaces (or is replaced by).
+struct HunkRow<'a> {
+    kind: DiffLineType,
+    old_ln: usize,
+    new_ln: usize,
+    text: &'a str,
+    emphasis: Vec<Range<usize>>,
+}
+
+fn hunk_rows<'a>(h: &Hunk<'a, str>) -> Vec<HunkRow<'a>> {
+    let mut old_ln = h.old_range().start();
+    let mut new_ln = h.new_range().start();
+    let mut rows = Vec::with_capacity(h.lines().len());
+    for l in h.lines() {
+        let (kind, text) = match l {
+            diffy::Line::Insert(text) => (DiffLineType::Insert, text),
+            diffy::Line::Delete(text) => (DiffLineType::Delete, text),
+            diffy::Line::Context(text) => (DiffLineType::Context, text),
+        };
+        rows.push(HunkRow {
+            kind,
+            old_ln,
+            new_ln,
+            text: text.trim_end_matches('\n'),
+            emphasis: Vec::new(),
+        });
+        if kind != DiffLineType::Insert {
+            old_ln += 1;
+        }
+        if kind != DiffLineType::Delete {
+            new_ln += 1;
+        }
+    }
+
+    // Pair the n-th deleted line of each change with its n-th inserted line
+    // and highlight the words that differ between them.
+    let mut i = 0;
+    while i < rows.len() {
+        if rows[i].kind == DiffLineType::Context {
+            i += 1;
+            continue;
+        }
+        let (deletes, inserts) = change_run_at(&rows, i);
+        i = inserts.end;
+        for (d, n) in deletes.zip(inserts) {
+            if let Some((old, new)) = word_diff(rows[d].text, rows[n].text) {
+                rows[d].emphasis = old;
+                rows[n].emphasis = new;
+            }
+        }
+    }
+    rows
+}
+
+/// The run of deleted rows starting at `start` and the inserted rows that
+/// immediately follow it, as index ranges into `rows`.
+fn change_run_at(rows: &[HunkRow<'_>], start: usize) -> (Range<usize>, Range<usize>) {
+    let mut i = start;
+    while i < rows.len() && rows[i].kind == DiffLineType::Delete {
+        i += 1;
+    }
+    let inserts_start = i;
+    while i < rows.len() && rows[i].kind == DiffLineType::Insert {
+        i += 1;
+    }
+    (start..inserts_start, inserts_start..i)
+}
+
+fn render_split_rows(rows: &[HunkRow<'_>], out: &mut Vec<RtLine<'static>>, width: usize) {
+    let left_width = width.saturating_sub(1) / 2;
+    let right_width = width.saturating_sub(1 + left_width);
+    let mut i = 0;
+    while i < rows.len() {
+        if rows[i].kind == DiffLineType::Context {
+            push_split_row(Some(&rows[i]), Some(&rows[i]), out, left_width, right_width);
+            i += 1;
+            continue;
+        }
+        let (deletes, inserts) = change_run_at(rows, i);
+        i = inserts.end;
+        for k in 0..deletes.len().max(inserts.len()) {
+            let old = (deletes.start + k < deletes.end).then(|| &rows[deletes.start + k]);
+            let new = (inserts.start + k < inserts.end).then(|| &rows[inserts.start + k]);
+            push_split_row(old, new, out, left_width, right_width);
+        }
+    }
+}
+
+/// Render `old` and `new` next to each other, padding whichever side wraps
+/// onto fewer terminal rows.
+fn push_split_row(
+    old: Option<&HunkRow<'_>>,
+    new: Option<&HunkRow<'_>>,
+    out: &mut Vec<RtLine<'static>>,
+    left_width: usize,
+    right_width: usize,
+) {
+    let left = old
+        .map(|row| {
+            push_wrapped_diff_line(row.old_ln, row.kind, row.text, &row.emphasis, left_width)
+        })
+        .unwrap_or_default();
+    let right = new
+        .map(|row| {
+            push_wrapped_diff_line(row.new_ln, row.kind, row.text, &row.emphasis, right_width)
+        })
+        .unwrap_or_default();
+    let height = left.len().max(right.len());
+    let mut left = left.into_iter();
+    let mut right = right.into_iter();
+    for _ in 0..height {
+        let mut spans = Vec::new();
+        let mut used = 0;
+        if let Some(line) = left.next() {
+            used = line.width();
+            spans.extend(line.spans);
+        }
+        spans.push(" ".repeat(left_width.saturating_sub(used)).into());
+        spans.push(RtSpan::styled("│", style_gutter()));
+        if let Some(line) = right.next() {
+            spans.extend(line.spans);
+        }
+        out.push(RtLine::from(spans));
+    }
+}
+
+/// Changed byte ranges of an old line and of the new line replacing it.
+type WordDiff = (Vec<Range<usize>>, Vec<Range<usize>>);
+
+/// Byte ranges of `old` and `new` that differ at word granularity, or `None`
+/// when the lines share too little for highlighting to help.
+fn word_diff(old: &str, new: &str) -> Option<WordDiff> {
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    if n.saturating_mul(m) > MAX_WORD_DIFF_CELLS {
+        return None;
+    }
+    let same = |i: usize, j: usize| old[old_tokens[i].clone()] == new[new_tokens[j].clone()];
+
+    // lcs[i][j]: longest common token subsequence of old[i..] and new[j..].
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if same(i, j) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_changed = Vec::new();
+    let mut new_changed = Vec::new();
+    let mut common_bytes = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && same(i, j) {
+            common_bytes += old_tokens[i].len();
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push_range(&mut new_changed, new_tokens[j].clone());
+            j += 1;
+        } else {
+            push_range(&mut old_changed, old_tokens[i].clone());
+            i += 1;
+        }
+    }
+
+    // Highlighting nearly every word of a rewritten line is just noise.
+    if common_bytes * 2 < old.len().max(new.len()) {
+        return None;
+    }
+    Some((old_changed, new_changed))
+}
+
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+#[derive(PartialEq, Eq)]
+enum TokenClass {
+    Word,
+    Space,
+    Punct,
+}
+
+fn token_class(c: char) -> TokenClass {
+    if c.is_alphanumeric() || c == '_' {
+        TokenClass::Word
+    } else if c.is_whitespace() {
+        TokenClass::Space
+    } else {
+        TokenClass::Punct
+    }
+}
+
+/// Split `text` into words, runs of whitespace and single punctuation
+/// characters, as byte ranges.
+fn tokenize(text: &str) -> Vec<Range<usize>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let class = token_class(c);
+        let mut end = start + c.len_utf8();
+        if class != TokenClass::Punct {
+            while let Some(&(i, next)) = chars.peek()
+                && token_class(next) == class
+            {
+                end = i + next.len_utf8();
+                chars.next();
+            }
+        }
+        tokens.push(start..end);
+    }
+    tokens
+}
+
+/// Number of hunks a reviewer can accept or reject separately in `change`:
+/// the `@@` sections of an update, or the whole file for adds, deletes and
+/// pure renames. Counted by core so the numbering matches `PatchReview`.
+pub(crate) fn hunk_count(change: &FileChange) -> usize {
+    match change {
+        FileChange::Update { unified_diff, .. } => update_hunk_count(unified_diff),
+        FileChange::Add { .. } | FileChange::Delete { .. } => 1,
+    }
+}
+
+/// Render hunk `index` of `change` (see [`hunk_count`]).
+pub(crate) fn render_hunk(change: &FileChange, index: usize, width: usize) -> Vec<RtLine<'static>> {
+    let mut out = Vec::new();
+    match change {
+        FileChange::Update { unified_diff, .. } => {
+            if let Ok(patch) = diffy::Patch::from_str(unified_diff)
+                && let Some(h) = patch.hunks().get(index)
+            {
+                render_hunk_lines(h, &mut out, width, split_view_for(width));
+            }
+        }
+        FileChange::Add { .. } | FileChange::Delete { .. } => {
+            render_change(change, &mut out, width, false)
+        }
+    }
+    out
+}
+
 pub(crate) fn display_path_for(path: &Path, cwd: &Path) -> String {
     let path_in_same_repo = match (get_git_repo_root(cwd), get_git_repo_root(path)) {
         (Some(cwd_repo), Some(path_repo)) => cwd_repo == path_repo,
@@ -297,11 +581,12 @@ fn push_wrapped_diff_line(
     line_number: usize,
     kind: DiffLineType,
     text: &str,
+    emphasis: &[Range<usize>],
     width: usize,
 ) -> Vec<RtLine<'static>> {
     let indent = "    ";
     let ln_str = line_number.to_string();
-    let mut remaining_text: &str = text;
+    let mut offset = 0;
 
     // Reserve a fixed number of spaces after the line number so that content starts
     // at a consistent column. Content includes a 1-character diff sign prefix
@@ -323,39 +608,70 @@ fn push_wrapped_diff_line(
         // compute how many columns are available after the prefix, then split
         // at a UTF-8 character boundary so this row's chunk fits exactly.
         let available_content_cols = width.saturating_sub(prefix_cols + 1).max(1);
-        let split_at_byte_index = remaining_text
+        let chunk_end = text[offset..]
             .char_indices()
             .nth(available_content_cols)
-            .map(|(i, _)| i)
-            .unwrap_or_else(|| remaining_text.len());
-        let (chunk, rest) = remaining_text.split_at(split_at_byte_index);
-        remaining_text = rest;
+            .map(|(i, _)| offset + i)
+            .unwrap_or_else(|| text.len());
+        let chunk = emphasized_spans(text, offset..chunk_end, emphasis, line_style);
+        offset = chunk_end;
 
         if first {
             // Build gutter (indent + line number + spacing) as a dimmed span
             let gutter = format!("{indent}{ln_str}{}", " ".repeat(gap_after_ln));
             // Content with a sign ('+'/'-'/' ') styled per diff kind
-            let content = format!("{sign_char}{chunk}");
-            lines.push(RtLine::from(vec![
+            let mut spans = vec![
                 RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(content, line_style),
-            ]));
+                RtSpan::styled(sign_char.to_string(), line_style),
+            ];
+            spans.extend(chunk);
+            lines.push(RtLine::from(spans));
             first = false;
         } else {
             // Continuation lines keep a space for the sign column so content aligns
             let gutter = format!("{indent}{} ", " ".repeat(ln_str.len() + gap_after_ln));
-            lines.push(RtLine::from(vec![
-                RtSpan::styled(gutter, style_gutter()),
-                RtSpan::styled(chunk.to_string(), line_style),
-            ]));
+            let mut spans = vec![RtSpan::styled(gutter, style_gutter())];
+            spans.extend(chunk);
+            lines.push(RtLine::from(spans));
         }
-        if remaining_text.is_empty() {
+        if offset >= text.len() {
             break;
         }
     }
     lines
 }
 
+/// Spans for `text[range]`, with the parts covered by `emphasis` reversed so
+/// changed words stand out from the rest of the line.
+fn emphasized_spans(
+    text: &str,
+    range: Range<usize>,
+    emphasis: &[Range<usize>],
+    style: Style,
+) -> Vec<RtSpan<'static>> {
+    let mut spans = Vec::new();
+    let mut pos = range.start;
+    for e in emphasis {
+        let start = e.start.clamp(pos, range.end);
+        let end = e.end.clamp(pos, range.end);
+        if start >= end {
+            continue;
+        }
+        if pos < start {
+            spans.push(RtSpan::styled(text[pos..start].to_string(), style));
+        }
+        spans.push(RtSpan::styled(
+            text[start..end].to_string(),
+            style.add_modifier(Modifier::REVERSED),
+        ));
+        pos = end;
+    }
+    if pos < range.end {
+        spans.push(RtSpan::styled(text[pos..range.end].to_string(), style));
+    }
+    spans
+}
+
 fn style_gutter() -> Style {
     Style::default().add_modifier(Modifier::DIM)
 }
@@ -365,17 +681,18 @@ fn style_context() -> Style {
 }
 
 fn style_add() -> Style {
-    Style::default().fg(Color::Green)
+    theme::diff_added()
 }
 
 fn style_del() -> Style {
-    Style::default().fg(Color::Red)
+    theme::diff_removed()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use insta::assert_snapshot;
+    use pretty_assertions::assert_eq;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
     use ratatui::text::Text;
@@ -421,7 +738,7 @@ mod tests {
         let long_line = "this is a very long line that should wrap across multiple terminal columns and continue";
 
         // Call the wrapping function directly so we can precisely control the width
-        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, 80);
+        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, long_line, &[], 80);
 
         // Render into a small terminal to capture the visual layout
         snapshot_lines("wrap_behavior_insert", lines, 90, 8);
@@ -581,6 +898,51 @@ mod tests {
         snapshot_lines_text("apply_update_block_wraps_long_lines_text", &lines);
     }
 
+    #[test]
+    fn ui_snapshot_apply_update_block_split() {
+        let original = "fn main() {\n    let total = add(1, 2);\n    println!(\"{total}\");\n}\n";
+        let modified = "fn main() {\n    let total = add(1, 3);\n    let doubled = total * 2;\n    println!(\"{doubled}\");\n}\n";
+        let change = FileChange::Update {
+            unified_diff: diffy::create_patch(original, modified).to_string(),
+            move_path: None,
+        };
+
+        let mut lines = Vec::new();
+        render_change(&change, &mut lines, 120, true);
+
+        snapshot_lines_text("apply_update_block_split", &lines);
+    }
+
+    #[test]
+    fn word_diff_highlights_changed_words() {
+        let old = "let total = add(1, 2);";
+        let new = "let sum = add(1, 3);";
+
+        assert_eq!(
+            word_diff(old, new),
+            Some((vec![4..9, 19..20], vec![4..7, 17..18]))
+        );
+    }
+
+    #[test]
+    fn word_diff_skips_rewritten_lines() {
+        assert_eq!(word_diff("return first;", "panic!(\"second\")"), None);
+    }
+
+    #[test]
+    fn emphasis_survives_wrapping() {
+        let text = "aaaa bbbb cccc";
+        let lines = push_wrapped_diff_line(1, DiffLineType::Insert, text, &[5..9, 10..11], 18);
+
+        let emphasized: Vec<String> = lines
+            .iter()
+            .flat_map(|line| line.spans.iter())
+            .filter(|span| span.style.add_modifier.contains(Modifier::REVERSED))
+            .map(|span| span.content.to_string())
+            .collect();
+        assert_eq!(emphasized, vec!["bb", "bb", "c"]);
+    }
+
     #[test]
     fn ui_snapshot_apply_update_block_relativizes_path() {
         let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
@@ -8,6 +8,7 @@ use crate::history_cell::HistoryCell;
 use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::line_utils::prefix_lines;
 use crate::render::line_utils::push_owned_lines;
+use crate::theme;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use codex_ansi_escape::ansi_escape_line;
@@ -152,10 +153,10 @@ impl HistoryCell for ExecCell {
                     .map(format_duration)
                     .unwrap_or_else(|| "unknown".to_string());
                 let mut result: Line = if output.exit_code == 0 {
-                    Line::from("✓".green().bold())
+                    Line::from(Span::styled("✓", theme::success()).bold())
                 } else {
                     Line::from(vec![
-                        "✗".red().bold(),
+                        Span::styled("✗", theme::error()).bold(),
                         format!(" ({})", output.exit_code).into(),
                     ])
                 };
@@ -304,8 +305,8 @@ impl ExecCell {
         let layout = EXEC_DISPLAY_LAYOUT;
         let success = call.output.as_ref().map(|o| o.exit_code == 0);
         let bullet = match success {
-            Some(true) => "•".green().bold(),
-            Some(false) => "•".red().bold(),
+            Some(true) => Span::styled("•", theme::success()).bold(),
+            Some(false) => Span::styled("•", theme::error()).bold(),
             None => spinner(call.start_time),
         };
         let title = if self.is_active() { "Running" } else { "Ran" };
@@ -0,0 +1,106 @@
+//! Edit the composer draft in the user's `$VISUAL`/`$EDITOR`.
+//!
+//! The draft is written to a temporary Markdown file, the TUI hands the
+//! terminal to the editor via [`Tui::with_restored`], and the saved file is
+//! read back once the editor exits.
+
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use tokio::process::Command;
+
+use crate::tui::Tui;
+
+/// Editor command from `$VISUAL`, falling back to `$EDITOR`, split into the
+/// program and its arguments (e.g. `code --wait`).
+fn editor_command() -> Result<Vec<String>, String> {
+    let value = ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .ok_or_else(|| {
+            "Set $VISUAL or $EDITOR to edit the draft in an external editor.".to_string()
+        })?;
+    parse_editor_command(&value)
+}
+
+fn parse_editor_command(value: &str) -> Result<Vec<String>, String> {
+    match shlex::split(value) {
+        Some(args) if !args.is_empty() => Ok(args),
+        _ => Err(format!("Could not parse editor command `{value}`.")),
+    }
+}
+
+/// Open `draft` in the external editor and return the edited text, or an
+/// error message suitable for showing in the transcript.
+pub(crate) async fn edit_draft(tui: &mut Tui, draft: &str) -> Result<String, String> {
+    let command = editor_command()?;
+    let mut file = tempfile::Builder::new()
+        .prefix("codex-prompt-")
+        .suffix(".md")
+        .tempfile()
+        .map_err(|err| format!("Failed to create a temporary file: {err}"))?;
+    file.write_all(draft.as_bytes())
+        .and_then(|()| file.flush())
+        .map_err(|err| format!("Failed to write the draft: {err}"))?;
+
+    // Close our handle so editors that replace the file (or Windows, which
+    // locks open files) can write it; the path is still removed on drop.
+    let path = file.into_temp_path();
+    let (program, args) = (&command[0], &command[1..]);
+    let file_path: &Path = &path;
+    let status = tui
+        .with_restored(|| async move {
+            Command::new(program)
+                .args(args)
+                .arg(file_path)
+                .status()
+                .await
+        })
+        .await
+        .and_then(|status| status)
+        .map_err(|err| match err.kind() {
+            io::ErrorKind::NotFound => format!("Editor `{program}` not found."),
+            _ => format!("Failed to run editor `{program}`: {err}"),
+        })?;
+    if !status.success() {
+        return Err(format!(
+            "Editor `{program}` exited with {status}; the draft was left unchanged."
+        ));
+    }
+
+    let edited = std::fs::read_to_string(&path)
+        .map_err(|err| format!("Failed to read the edited draft: {err}"))?;
+    // Most editors terminate the file with a newline the user never typed.
+    Ok(edited
+        .strip_suffix('\n')
+        .map(|text| text.strip_suffix('\r').unwrap_or(text))
+        .unwrap_or(&edited)
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_editor_commands_with_arguments() {
+        assert_eq!(
+            parse_editor_command("code --wait"),
+            Ok(vec!["code".to_string(), "--wait".to_string()])
+        );
+        assert_eq!(
+            parse_editor_command("'/opt/my editor/bin/edit' -f"),
+            Ok(vec![
+                "/opt/my editor/bin/edit".to_string(),
+                "-f".to_string()
+            ])
+        );
+        assert_eq!(
+            parse_editor_command("vim 'unterminated"),
+            Err("Could not parse editor command `vim 'unterminated`.".to_string())
+        );
+    }
+}
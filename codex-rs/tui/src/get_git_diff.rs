@@ -5,8 +5,11 @@
 //! untracked files. When the current directory is not inside a Git
 //! repository, the function returns `Ok((false, String::new()))`.
 
+use codex_core::protocol::FileChange;
+use std::collections::HashMap;
 use std::io;
 use std::path::Path;
+use std::path::PathBuf;
 use std::process::Stdio;
 use tokio::process::Command;
 
@@ -14,15 +17,19 @@ use tokio::process::Command;
 ///
 /// * `bool` – Whether the current working directory is inside a Git repo.
 /// * `String` – The concatenated diff (may be empty).
-pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
+///
+/// With `color`, git adds ANSI colors for display as-is; leave it off when
+/// the output is fed to [`parse_git_diff`].
+pub(crate) async fn get_git_diff(color: bool) -> io::Result<(bool, String)> {
     // First check if we are inside a Git repository.
     if !inside_git_repo().await? {
         return Ok((false, String::new()));
     }
 
     // Run tracked diff and untracked file listing in parallel.
+    let tracked_args = diff_args(color, &[]);
     let (tracked_diff_res, untracked_output_res) = tokio::join!(
-        run_git_capture_diff(&["diff", "--color"]),
+        run_git_capture_diff(&tracked_args),
         run_git_capture_stdout(&["ls-files", "--others", "--exclude-standard"]),
     );
     let tracked_diff = tracked_diff_res?;
@@ -45,7 +52,7 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
         let null_path = null_path.clone();
         let file = file.to_string();
         join_set.spawn(async move {
-            let args = ["diff", "--color", "--no-index", "--", &null_path, &file];
+            let args = diff_args(color, &["--no-index", "--", &null_path, &file]);
             run_git_capture_diff(&args).await
         });
     }
@@ -61,6 +68,135 @@ pub(crate) async fn get_git_diff() -> io::Result<(bool, String)> {
     Ok((true, format!("{tracked_diff}{untracked_diff}")))
 }
 
+fn diff_args<'a>(color: bool, rest: &[&'a str]) -> Vec<&'a str> {
+    let mut args = vec!["diff"];
+    if color {
+        args.push("--color");
+    }
+    args.extend_from_slice(rest);
+    args
+}
+
+/// `git diff` output split into per-file changes keyed by repo-relative path
+/// so `/diff` can reuse the patch renderer.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct ParsedDiff {
+    pub changes: HashMap<PathBuf, FileChange>,
+    /// Placeholder rows for what the renderer cannot show as hunks, such as
+    /// binary files and mode changes.
+    pub notes: HashMap<PathBuf, String>,
+}
+
+pub(crate) fn parse_git_diff(diff: &str) -> ParsedDiff {
+    let mut sections: Vec<Vec<&str>> = Vec::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            sections.push(vec![line]);
+        } else if let Some(section) = sections.last_mut() {
+            section.push(line);
+        }
+    }
+    let mut parsed = ParsedDiff::default();
+    for (path, change, note) in sections
+        .iter()
+        .filter_map(|section| parse_diff_section(section))
+    {
+        if let Some(note) = note {
+            parsed.notes.insert(path.clone(), note);
+        }
+        parsed.changes.insert(path, change);
+    }
+    parsed
+}
+
+fn parse_diff_section(lines: &[&str]) -> Option<(PathBuf, FileChange, Option<String>)> {
+    let hunks_start = lines
+        .iter()
+        .position(|line| line.starts_with("@@"))
+        .unwrap_or(lines.len());
+    let (header, hunks) = lines.split_at(hunks_start);
+
+    let mut old_path = None;
+    let mut new_path = None;
+    let mut renamed = false;
+    let mut binary = false;
+    let mut old_mode = None;
+    let mut new_mode = None;
+    for line in header {
+        if let Some(path) = line.strip_prefix("--- ") {
+            old_path = diff_header_path(path, "a/");
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            new_path = diff_header_path(path, "b/");
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            old_path = Some(PathBuf::from(path));
+            renamed = true;
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            new_path = Some(PathBuf::from(path));
+            renamed = true;
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            old_mode = Some(mode);
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            new_mode = Some(mode);
+        } else if line.starts_with("Binary files ") || *line == "GIT binary patch" {
+            binary = true;
+        }
+    }
+    let note = match (old_mode, new_mode) {
+        _ if binary => Some("Binary file changed".to_string()),
+        (Some(old), Some(new)) => Some(format!("mode {old} → {new}")),
+        _ => None,
+    };
+
+    let content_with = |sign: char| -> String {
+        hunks
+            .iter()
+            .filter_map(|line| line.strip_prefix(sign))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    };
+    if header.contains(&"--- /dev/null") {
+        let content = content_with('+');
+        return new_path.map(|path| (path, FileChange::Add { content }, note));
+    }
+    if header.contains(&"+++ /dev/null") {
+        let content = content_with('-');
+        return old_path.map(|path| (path, FileChange::Delete { content }, note));
+    }
+    if hunks.is_empty() && !renamed && note.is_none() {
+        return None;
+    }
+    let mut unified_diff = hunks.join("\n");
+    if !unified_diff.is_empty() {
+        unified_diff.push('\n');
+    }
+    // Binary sections have no `---`/`+++` lines; fall back to the
+    // `diff --git a/<old> b/<new>` line.
+    let git_path = header
+        .first()
+        .and_then(|line| line.strip_prefix("diff --git "))
+        .and_then(|paths| paths.rsplit_once(" b/"))
+        .map(|(_, path)| PathBuf::from(path));
+    let path = old_path.clone().or_else(|| new_path.clone()).or(git_path)?;
+    let move_path = new_path.filter(|new| renamed && Some(new) != old_path.as_ref());
+    Some((
+        path,
+        FileChange::Update {
+            unified_diff,
+            move_path,
+        },
+        note,
+    ))
+}
+
+/// Path from a `---`/`+++` header line, or `None` for `/dev/null`.
+fn diff_header_path(path: &str, prefix: &str) -> Option<PathBuf> {
+    let path = path.trim_end_matches('\t').trim_matches('"');
+    if path == "/dev/null" {
+        return None;
+    }
+    Some(PathBuf::from(path.strip_prefix(prefix).unwrap_or(path)))
+}
+
 /// Helper that executes `git` with the given `args` and returns `stdout` as a
 /// UTF-8 string. Any non-zero exit status is considered an *error*.
 async fn run_git_capture_stdout(args: &[&str]) -> io::Result<String> {
@@ -117,3 +253,103 @@ async fn inside_git_repo() -> io::Result<bool> {
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parse_git_diff_splits_files() {
+        let diff = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,2 +1,2 @@
+ fn main() {}
+-fn old() {}
++fn new() {}
+diff --git a/notes.txt b/notes.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/notes.txt
+@@ -0,0 +1,2 @@
++first
++second
+diff --git a/gone.txt b/gone.txt
+deleted file mode 100644
+index 4444444..0000000
+--- a/gone.txt
++++ /dev/null
+@@ -1 +0,0 @@
+-bye
+diff --git a/old.rs b/new.rs
+similarity index 100%
+rename from old.rs
+rename to new.rs
+diff --git a/logo.png b/logo.png
+index 5555555..6666666 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/run.sh b/run.sh
+old mode 100644
+new mode 100755
+";
+
+        let expected = HashMap::from([
+            (
+                PathBuf::from("src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "@@ -1,2 +1,2 @@\n fn main() {}\n-fn old() {}\n+fn new() {}\n"
+                        .to_string(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("notes.txt"),
+                FileChange::Add {
+                    content: "first\nsecond\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("gone.txt"),
+                FileChange::Delete {
+                    content: "bye\n".to_string(),
+                },
+            ),
+            (
+                PathBuf::from("old.rs"),
+                FileChange::Update {
+                    unified_diff: String::new(),
+                    move_path: Some(PathBuf::from("new.rs")),
+                },
+            ),
+            (
+                PathBuf::from("logo.png"),
+                FileChange::Update {
+                    unified_diff: String::new(),
+                    move_path: None,
+                },
+            ),
+            (
+                PathBuf::from("run.sh"),
+                FileChange::Update {
+                    unified_diff: String::new(),
+                    move_path: None,
+                },
+            ),
+        ]);
+        let notes = HashMap::from([
+            (PathBuf::from("logo.png"), "Binary file changed".to_string()),
+            (PathBuf::from("run.sh"), "mode 100644 → 100755".to_string()),
+        ]);
+        assert_eq!(
+            parse_git_diff(diff),
+            ParsedDiff {
+                changes: expected,
+                notes
+            }
+        );
+    }
+}
@@ -13,7 +13,9 @@ use crate::render::line_utils::prefix_lines;
 use crate::style::user_message_style;
 use crate::terminal_palette::default_bg;
 use crate::text_formatting::format_and_truncate_tool_result;
+use crate::theme;
 use crate::ui_consts::LIVE_PREFIX_COLS;
+use crate::workspace_snapshot::WorkspaceSnapshot;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use crate::wrapping::word_wrap_lines;
@@ -86,6 +88,9 @@ impl dyn HistoryCell {
 #[derive(Debug)]
 pub(crate) struct UserHistoryCell {
     pub message: String,
+    /// Workspace state from just before this message was sent, used to
+    /// rewind files when backtracking to it.
+    pub snapshot: Option<WorkspaceSnapshot>,
 }
 
 impl HistoryCell for UserHistoryCell {
@@ -443,8 +448,11 @@ pub(crate) fn new_session_info(
     }
 }
 
-pub(crate) fn new_user_prompt(message: String) -> UserHistoryCell {
-    UserHistoryCell { message }
+pub(crate) fn new_user_prompt(
+    message: String,
+    snapshot: Option<WorkspaceSnapshot>,
+) -> UserHistoryCell {
+    UserHistoryCell { message, snapshot }
 }
 
 pub(crate) fn new_user_approval_decision(lines: Vec<Line<'static>>) -> PlainHistoryCell {
@@ -672,8 +680,8 @@ impl HistoryCell for McpToolCallCell {
         let mut lines: Vec<Line<'static>> = Vec::new();
         let status = self.success();
         let bullet = match status {
-            Some(true) => "•".green().bold(),
-            Some(false) => "•".red().bold(),
+            Some(true) => Span::styled("•", theme::success()).bold(),
+            Some(false) => Span::styled("•", theme::error()).bold(),
             None => spinner(Some(self.start_time)),
         };
         let header_text = if status.is_some() {
@@ -925,7 +933,8 @@ pub(crate) fn new_error_event(message: String) -> PlainHistoryCell {
     // Use a hair space (U+200A) to create a subtle, near-invisible separation
     // before the text. VS16 is intentionally omitted to keep spacing tighter
     // in terminals like Ghostty.
-    let lines: Vec<Line<'static>> = vec![vec![format!("■ {message}").red()].into()];
+    let lines: Vec<Line<'static>> =
+        vec![vec![Span::styled(format!("■ {message}"), theme::error())].into()];
     PlainHistoryCell { lines }
 }
 
@@ -1017,7 +1026,9 @@ pub(crate) fn new_patch_apply_failure(stderr: String) -> PlainHistoryCell {
     let mut lines: Vec<Line<'static>> = Vec::new();
 
     // Failure title
-    lines.push(Line::from("✘ Failed to apply patch".magenta().bold()));
+    lines.push(Line::from(
+        Span::styled("✘ Failed to apply patch", theme::brand()).bold(),
+    ));
 
     if !stderr.trim().is_empty() {
         lines.extend(output_lines(
@@ -1049,6 +1060,32 @@ pub(crate) fn new_view_image_tool_call(path: PathBuf, cwd: &Path) -> PlainHistor
     PlainHistoryCell { lines }
 }
 
+/// Header for a task delegated to a sub-agent, showing the task's first line.
+pub(crate) fn new_sub_agent_begin(task: &str) -> PlainHistoryCell {
+    let task = task.lines().next().unwrap_or_default().to_string();
+    let lines: Vec<Line<'static>> = vec![vec!["• ".dim(), "Sub-agent ".bold(), task.into()].into()];
+    PlainHistoryCell { lines }
+}
+
+/// Something a sub-agent did, indented under its header.
+pub(crate) fn new_sub_agent_activity(text: &str) -> PlainHistoryCell {
+    let lines: Vec<Line<'static>> = text
+        .lines()
+        .map(|line| vec!["  │ ".dim(), line.to_string().dim()].into())
+        .collect();
+    PlainHistoryCell { lines }
+}
+
+pub(crate) fn new_sub_agent_end(success: bool) -> PlainHistoryCell {
+    let status = if success {
+        Span::styled("Sub-agent completed", theme::success())
+    } else {
+        Span::styled("Sub-agent failed", theme::error())
+    };
+    let lines: Vec<Line<'static>> = vec![vec!["  └ ".dim(), status].into()];
+    PlainHistoryCell { lines }
+}
+
 pub(crate) fn new_reasoning_block(
     full_reasoning_buffer: String,
     config: &Config,
@@ -1806,6 +1843,7 @@ mod tests {
         let msg = "one two three four five six seven";
         let cell = UserHistoryCell {
             message: msg.to_string(),
+            snapshot: None,
         };
 
         // Small width to force wrapping more clearly. Effective wrap width is width-2 due to the ▌ prefix and trailing space.
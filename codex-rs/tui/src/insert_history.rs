@@ -327,7 +327,7 @@ mod tests {
 
         // Build a blockquote-like line: apply line-level green style and prefix "> "
         let mut line: Line<'static> = Line::from(vec!["> ".into(), "Hello world".into()]);
-        line = line.style(Color::Green);
+        line = line.style(crate::theme::success());
         insert_history_lines(&mut term, vec![line]);
 
         let mut saw_colored = false;
@@ -364,7 +364,7 @@ mod tests {
             "> ".into(),
             "This is a long quoted line that should wrap".into(),
         ]);
-        line = line.style(Color::Green);
+        line = line.style(crate::theme::success());
 
         insert_history_lines(&mut term, vec![line]);
 
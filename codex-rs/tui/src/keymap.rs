@@ -0,0 +1,350 @@
+//! Rebindable key bindings for TUI actions (`[tui.keymap]` in config.toml).
+//!
+//! The keymap is resolved once at startup and read through [`keymap()`] by the
+//! widgets that handle the corresponding keys, and by the footer so that the
+//! shortcut hints show the active bindings.
+
+use std::sync::OnceLock;
+
+use codex_core::config_types::KeyChords;
+use codex_core::config_types::TuiKeymap;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyModifiers;
+
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum KeymapAction {
+    Submit,
+    Newline,
+    Interrupt,
+    OpenTranscript,
+    Backtrack,
+    HistoryPrev,
+    HistoryNext,
+    PasteImage,
+    ExternalEditor,
+}
+
+impl KeymapAction {
+    const ALL: [KeymapAction; 9] = [
+        KeymapAction::Submit,
+        KeymapAction::Newline,
+        KeymapAction::Interrupt,
+        KeymapAction::OpenTranscript,
+        KeymapAction::Backtrack,
+        KeymapAction::HistoryPrev,
+        KeymapAction::HistoryNext,
+        KeymapAction::PasteImage,
+        KeymapAction::ExternalEditor,
+    ];
+
+    /// Key used for this action under `[tui.keymap]`.
+    pub(crate) fn config_key(self) -> &'static str {
+        match self {
+            KeymapAction::Submit => "submit",
+            KeymapAction::Newline => "newline",
+            KeymapAction::Interrupt => "interrupt",
+            KeymapAction::OpenTranscript => "open_transcript",
+            KeymapAction::Backtrack => "backtrack",
+            KeymapAction::HistoryPrev => "history_prev",
+            KeymapAction::HistoryNext => "history_next",
+            KeymapAction::PasteImage => "paste_image",
+            KeymapAction::ExternalEditor => "external_editor",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct Keymap {
+    pub submit: Vec<KeyBinding>,
+    pub newline: Vec<KeyBinding>,
+    pub interrupt: Vec<KeyBinding>,
+    pub open_transcript: Vec<KeyBinding>,
+    pub backtrack: Vec<KeyBinding>,
+    pub history_prev: Vec<KeyBinding>,
+    pub history_next: Vec<KeyBinding>,
+    pub paste_image: Vec<KeyBinding>,
+    pub external_editor: Vec<KeyBinding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            submit: vec![key_hint::plain(KeyCode::Enter)],
+            newline: vec![
+                key_hint::shift(KeyCode::Enter),
+                key_hint::ctrl(KeyCode::Char('j')),
+            ],
+            interrupt: vec![key_hint::ctrl(KeyCode::Char('c'))],
+            open_transcript: vec![key_hint::ctrl(KeyCode::Char('t'))],
+            backtrack: vec![key_hint::plain(KeyCode::Esc)],
+            history_prev: vec![key_hint::plain(KeyCode::Up)],
+            history_next: vec![key_hint::plain(KeyCode::Down)],
+            paste_image: vec![key_hint::ctrl(KeyCode::Char('v'))],
+            external_editor: vec![key_hint::ctrl(KeyCode::Char('g'))],
+        }
+    }
+}
+
+impl Keymap {
+    /// Apply the overrides from `[tui.keymap]` on top of the defaults and
+    /// reject chords that are bound to more than one action.
+    pub(crate) fn from_config(config: &TuiKeymap) -> Result<Self, String> {
+        let mut keymap = Self::default();
+        let overrides = [
+            (KeymapAction::Submit, &config.submit),
+            (KeymapAction::Newline, &config.newline),
+            (KeymapAction::Interrupt, &config.interrupt),
+            (KeymapAction::OpenTranscript, &config.open_transcript),
+            (KeymapAction::Backtrack, &config.backtrack),
+            (KeymapAction::HistoryPrev, &config.history_prev),
+            (KeymapAction::HistoryNext, &config.history_next),
+            (KeymapAction::PasteImage, &config.paste_image),
+            (KeymapAction::ExternalEditor, &config.external_editor),
+        ];
+        for (action, chords) in overrides {
+            if let Some(chords) = chords {
+                *keymap.bindings_mut(action) = parse_chords(action, chords)?;
+            }
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    pub(crate) fn bindings(&self, action: KeymapAction) -> &[KeyBinding] {
+        match action {
+            KeymapAction::Submit => &self.submit,
+            KeymapAction::Newline => &self.newline,
+            KeymapAction::Interrupt => &self.interrupt,
+            KeymapAction::OpenTranscript => &self.open_transcript,
+            KeymapAction::Backtrack => &self.backtrack,
+            KeymapAction::HistoryPrev => &self.history_prev,
+            KeymapAction::HistoryNext => &self.history_next,
+            KeymapAction::PasteImage => &self.paste_image,
+            KeymapAction::ExternalEditor => &self.external_editor,
+        }
+    }
+
+    fn bindings_mut(&mut self, action: KeymapAction) -> &mut Vec<KeyBinding> {
+        match action {
+            KeymapAction::Submit => &mut self.submit,
+            KeymapAction::Newline => &mut self.newline,
+            KeymapAction::Interrupt => &mut self.interrupt,
+            KeymapAction::OpenTranscript => &mut self.open_transcript,
+            KeymapAction::Backtrack => &mut self.backtrack,
+            KeymapAction::HistoryPrev => &mut self.history_prev,
+            KeymapAction::HistoryNext => &mut self.history_next,
+            KeymapAction::PasteImage => &mut self.paste_image,
+            KeymapAction::ExternalEditor => &mut self.external_editor,
+        }
+    }
+
+    /// Whether `event` presses one of the keys bound to `action`.
+    pub(crate) fn is_press(&self, action: KeymapAction, event: KeyEvent) -> bool {
+        self.bindings(action)
+            .iter()
+            .any(|binding| binding.is_press(event))
+    }
+
+    /// Whether `event` presses a default `newline` key that the config has
+    /// rebound away. The textarea inserts a newline for these on its own, so
+    /// the composer drops them to make the override replace the defaults.
+    pub(crate) fn is_unbound_newline(&self, event: KeyEvent) -> bool {
+        Keymap::default()
+            .newline
+            .iter()
+            .any(|binding| binding.is_press(event))
+            && !self.is_press(KeymapAction::Newline, event)
+    }
+
+    /// The binding to show in hints for `action`.
+    pub(crate) fn primary(&self, action: KeymapAction) -> Option<KeyBinding> {
+        self.bindings(action).first().copied()
+    }
+
+    fn check_conflicts(&self) -> Result<(), String> {
+        let mut conflicts = Vec::new();
+        for (idx, action) in KeymapAction::ALL.iter().enumerate() {
+            for other in &KeymapAction::ALL[idx + 1..] {
+                for binding in self.bindings(*action) {
+                    if self.bindings(*other).contains(binding) {
+                        conflicts.push(format!(
+                            "`{}` and `{}` are both bound to {}",
+                            action.config_key(),
+                            other.config_key(),
+                            binding.label()
+                        ));
+                    }
+                }
+            }
+        }
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(format!(
+                "conflicting key bindings in [tui.keymap]: {}",
+                conflicts.join("; ")
+            ))
+        }
+    }
+}
+
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+/// Install the keymap resolved from config. Only the first call has an effect.
+pub(crate) fn init(keymap: Keymap) {
+    let _ = KEYMAP.set(keymap);
+}
+
+pub(crate) fn keymap() -> &'static Keymap {
+    KEYMAP.get_or_init(Keymap::default)
+}
+
+fn parse_chords(action: KeymapAction, chords: &KeyChords) -> Result<Vec<KeyBinding>, String> {
+    let chords = chords.chords();
+    if chords.is_empty() {
+        return Err(format!(
+            "`{}` in [tui.keymap] needs at least one key",
+            action.config_key()
+        ));
+    }
+    chords
+        .into_iter()
+        .map(|chord| {
+            parse_chord(chord).map_err(|err| {
+                format!(
+                    "invalid key `{chord}` for `{}` in [tui.keymap]: {err}",
+                    action.config_key()
+                )
+            })
+        })
+        .collect()
+}
+
+/// Parse a chord such as `ctrl+j`, `shift+enter`, `alt+up` or `f2`.
+pub(crate) fn parse_chord(chord: &str) -> Result<KeyBinding, String> {
+    let parts: Vec<String> = chord
+        .split('+')
+        .map(|part| part.trim().to_ascii_lowercase())
+        .collect();
+    let Some((key, modifier_names)) = parts.split_last() else {
+        return Err("empty key".to_string());
+    };
+
+    let mut modifiers = KeyModifiers::NONE;
+    for name in modifier_names {
+        modifiers |= match name.as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" | "option" | "meta" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            other => return Err(format!("unknown modifier `{other}`")),
+        };
+    }
+
+    let code = match key.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "space" => KeyCode::Char(' '),
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" | "pgup" => KeyCode::PageUp,
+        "pagedown" | "pgdn" => KeyCode::PageDown,
+        "" => return Err("missing key".to_string()),
+        other => {
+            let mut chars = other.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if modifiers.contains(KeyModifiers::SHIFT) => {
+                    // Terminals report shifted letters as uppercase characters.
+                    KeyCode::Char(c.to_ascii_uppercase())
+                }
+                (Some(c), None) => KeyCode::Char(c),
+                _ => match other.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+                    Some(n) if (1..=12).contains(&n) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key `{other}`")),
+                },
+            }
+        }
+    };
+    Ok(KeyBinding::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(
+            parse_chord("Ctrl+J"),
+            Ok(key_hint::ctrl(KeyCode::Char('j')))
+        );
+        assert_eq!(
+            parse_chord("shift + enter"),
+            Ok(key_hint::shift(KeyCode::Enter))
+        );
+        assert_eq!(parse_chord("alt+up"), Ok(key_hint::alt(KeyCode::Up)));
+        assert_eq!(parse_chord("f2"), Ok(key_hint::plain(KeyCode::F(2))));
+        assert_eq!(
+            parse_chord("hyper+x"),
+            Err("unknown modifier `hyper`".to_string())
+        );
+        assert_eq!(parse_chord("ctrl+"), Err("missing key".to_string()));
+    }
+
+    #[test]
+    fn overrides_replace_defaults_and_conflicts_are_rejected() {
+        let config = TuiKeymap {
+            submit: Some(KeyChords::One("ctrl+enter".to_string())),
+            newline: Some(KeyChords::Many(vec!["enter".to_string()])),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_config(&config).expect("valid keymap");
+        assert_eq!(keymap.submit, vec![key_hint::ctrl(KeyCode::Enter)]);
+        assert_eq!(keymap.newline, vec![key_hint::plain(KeyCode::Enter)]);
+        assert_eq!(keymap.interrupt, Keymap::default().interrupt);
+
+        let config = TuiKeymap {
+            open_transcript: Some(KeyChords::One("ctrl+c".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            Keymap::from_config(&config),
+            Err(
+                "conflicting key bindings in [tui.keymap]: `interrupt` and `open_transcript` are both bound to ctrl + c"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn rebound_newline_replaces_the_default_newline_keys() {
+        let ctrl_j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::CONTROL);
+        let shift_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::SHIFT);
+        let alt_enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::ALT);
+
+        let keymap = Keymap::default();
+        assert!(!keymap.is_unbound_newline(ctrl_j));
+        assert!(!keymap.is_unbound_newline(shift_enter));
+
+        let config = TuiKeymap {
+            newline: Some(KeyChords::One("alt+enter".to_string())),
+            ..Default::default()
+        };
+        let keymap = Keymap::from_config(&config).expect("valid keymap");
+        assert!(keymap.is_unbound_newline(ctrl_j));
+        assert!(keymap.is_unbound_newline(shift_enter));
+        assert!(!keymap.is_unbound_newline(alt_enter));
+        assert!(!keymap.is_unbound_newline(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE)));
+    }
+}
@@ -44,18 +44,21 @@ pub mod custom_terminal;
 mod diff_render;
 mod exec_cell;
 mod exec_command;
+mod external_editor;
 mod file_search;
 mod frames;
 mod get_git_diff;
 mod history_cell;
 pub mod insert_history;
 mod key_hint;
+mod keymap;
 pub mod live_wrap;
 mod markdown;
 mod markdown_render;
 mod markdown_stream;
 pub mod onboarding;
 mod pager_overlay;
+mod pager_search;
 pub mod public_widgets;
 mod render;
 mod resume_picker;
@@ -68,9 +71,11 @@ mod streaming;
 mod style;
 mod terminal_palette;
 mod text_formatting;
+mod theme;
 mod tui;
 mod ui_consts;
 mod version;
+mod workspace_snapshot;
 mod wrapping;
 
 #[cfg(test)]
@@ -171,6 +176,19 @@ pub async fn run_main(
         }
     };
 
+    // Resolve `[tui.keymap]` up front so conflicting bindings are reported
+    // before the terminal is taken over.
+    match keymap::Keymap::from_config(&config.tui_keymap) {
+        Ok(keymap) => keymap::init(keymap),
+        #[allow(clippy::print_stderr)]
+        Err(err) => {
+            eprintln!("Error loading configuration: {err}");
+            std::process::exit(1);
+        }
+    }
+
+    diff_render::set_diff_view(config.tui_diff_view);
+
     // we load config.toml here to determine project state.
     #[allow(clippy::print_stderr)]
     let config_toml = {
@@ -292,6 +310,14 @@ async fn run_ratatui_app(
         tracing::error!("panic: {info}");
         prev_hook(info);
     }));
+
+    if let Some(theme_name) = config.tui_theme.as_deref() {
+        match theme::resolve(theme_name, &config.tui_themes) {
+            Ok(resolved) => theme::set_theme(resolved),
+            Err(err) => tracing::warn!("ignoring `tui.theme`: {err}"),
+        }
+    }
+
     let mut terminal = tui::init()?;
     terminal.clear()?;
 
@@ -1,5 +1,7 @@
 use crate::citation_regex::CITATION_REGEX;
+use crate::render::highlight::CodeHighlighter;
 use crate::render::line_utils::line_to_static;
+use crate::theme;
 use crate::wrapping::RtOptions;
 use crate::wrapping::word_wrap_line;
 use pulldown_cmark::CodeBlockKind;
@@ -79,6 +81,7 @@ where
     scheme: Option<String>,
     cwd: Option<std::path::PathBuf>,
     in_code_block: bool,
+    code_highlighter: Option<CodeHighlighter>,
     wrap_width: Option<usize>,
     current_line_content: Option<Line<'static>>,
     current_initial_indent: Vec<Span<'static>>,
@@ -110,6 +113,7 @@ where
             scheme,
             cwd,
             in_code_block: false,
+            code_highlighter: None,
             wrap_width,
             current_line_content: None,
             current_initial_indent: Vec::new(),
@@ -297,6 +301,19 @@ where
                     content = s;
                 }
             }
+            if self.in_code_block
+                && let Some(highlighter) = self.code_highlighter.as_mut()
+            {
+                let spans = highlighter.highlight_line(&content);
+                // Keep blank lines as an empty span, like unhighlighted code.
+                if spans.is_empty() {
+                    self.push_span(Span::raw(""));
+                }
+                for span in spans {
+                    self.push_span(span);
+                }
+                continue;
+            }
             let span = Span::styled(
                 content,
                 self.inline_styles.last().copied().unwrap_or_default(),
@@ -382,12 +399,13 @@ where
         self.needs_newline = false;
     }
 
-    fn start_codeblock(&mut self, _lang: Option<String>, indent: Option<Span<'static>>) {
+    fn start_codeblock(&mut self, lang: Option<String>, indent: Option<Span<'static>>) {
         self.flush_current_line();
         if !self.text.lines.is_empty() {
             self.push_blank_line();
         }
         self.in_code_block = true;
+        self.code_highlighter = lang.as_deref().and_then(CodeHighlighter::for_fence);
         self.indent_stack.push(IndentContext::new(
             vec![indent.unwrap_or_default()],
             None,
@@ -399,6 +417,7 @@ where
     fn end_codeblock(&mut self) {
         self.needs_newline = true;
         self.in_code_block = false;
+        self.code_highlighter = None;
         self.indent_stack.pop();
     }
 
@@ -457,7 +476,7 @@ where
             .iter()
             .any(|ctx| ctx.prefix.iter().any(|s| s.content.contains('>')));
         let style = if blockquote_active {
-            Style::new().green()
+            theme::success()
         } else {
             line.style
         };
@@ -658,6 +658,16 @@ fn code_block_unhighlighted() {
     assert_eq!(text, expected);
 }
 
+#[test]
+fn code_block_comments_are_highlighted() {
+    let text = render_markdown_text("```python\nx  # note\n\n```\n");
+    let expected = Text::from_iter([
+        Line::from_iter([Span::from(""), Span::from("x  "), "# note".dim().italic()]),
+        Line::from_iter(["", ""]),
+    ]);
+    assert_eq!(text, expected);
+}
+
 #[test]
 fn code_block_multiple_lines_root() {
     let md = "```\nfirst\nsecond\n```\n";
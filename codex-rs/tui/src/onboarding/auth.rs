@@ -15,11 +15,11 @@ use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
 use ratatui::layout::Rect;
 use ratatui::prelude::Widget;
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
+use ratatui::text::Span;
 use ratatui::widgets::Block;
 use ratatui::widgets::BorderType;
 use ratatui::widgets::Borders;
@@ -34,6 +34,7 @@ use crate::LoginStatus;
 use crate::onboarding::onboarding_screen::KeyboardHandler;
 use crate::onboarding::onboarding_screen::StepStateProvider;
 use crate::shimmer::shimmer_spans;
+use crate::theme;
 use crate::tui::FrameRequester;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -157,8 +158,8 @@ impl AuthModeWidget {
 
             let line1 = if is_selected {
                 Line::from(vec![
-                    format!("{} {}. ", caret, idx + 1).cyan().dim(),
-                    text.to_string().cyan(),
+                    Span::styled(format!("{} {}. ", caret, idx + 1), theme::accent()).dim(),
+                    Span::styled(text.to_string(), theme::accent()),
                 ])
             } else {
                 format!("  {}. {text}", idx + 1).into()
@@ -166,7 +167,7 @@ impl AuthModeWidget {
 
             let line2 = if is_selected {
                 Line::from(format!("     {description}"))
-                    .fg(Color::Cyan)
+                    .style(theme::accent())
                     .add_modifier(Modifier::DIM)
             } else {
                 Line::from(format!("     {description}"))
@@ -197,7 +198,7 @@ impl AuthModeWidget {
         );
         if let Some(err) = &self.error {
             lines.push("".into());
-            lines.push(err.as_str().red().into());
+            lines.push(Span::styled(err.as_str(), theme::error()).into());
         }
 
         Paragraph::new(lines)
@@ -219,7 +220,9 @@ impl AuthModeWidget {
         {
             lines.push("  If the link doesn't open automatically, open the following link to authenticate:".into());
             lines.push("".into());
-            lines.push(Line::from(state.auth_url.as_str().cyan().underlined()));
+            lines.push(Line::from(
+                Span::styled(state.auth_url.as_str(), theme::accent()).underlined(),
+            ));
             lines.push("".into());
         }
 
@@ -231,7 +234,7 @@ impl AuthModeWidget {
 
     fn render_chatgpt_success_message(&self, area: Rect, buf: &mut Buffer) {
         let lines = vec![
-            "✓ Signed in with your ChatGPT account".fg(Color::Green).into(),
+            Span::styled("✓ Signed in with your ChatGPT account", theme::success()).into(),
             "".into(),
             "  Before you start:".into(),
             "".into(),
@@ -252,7 +255,7 @@ impl AuthModeWidget {
             ])
             .dim(),
             "".into(),
-            "  Press Enter to continue".fg(Color::Cyan).into(),
+            Span::styled("  Press Enter to continue", theme::accent()).into(),
         ];
 
         Paragraph::new(lines)
@@ -261,11 +264,8 @@ impl AuthModeWidget {
     }
 
     fn render_chatgpt_success(&self, area: Rect, buf: &mut Buffer) {
-        let lines = vec![
-            "✓ Signed in with your ChatGPT account"
-                .fg(Color::Green)
-                .into(),
-        ];
+        let lines =
+            vec![Span::styled("✓ Signed in with your ChatGPT account", theme::success()).into()];
 
         Paragraph::new(lines)
             .wrap(Wrap { trim: false })
@@ -274,7 +274,7 @@ impl AuthModeWidget {
 
     fn render_api_key_configured(&self, area: Rect, buf: &mut Buffer) {
         let lines = vec![
-            "✓ API key configured".fg(Color::Green).into(),
+            Span::styled("✓ API key configured", theme::success()).into(),
             "".into(),
             "  Codex will use usage-based billing with your API key.".into(),
         ];
@@ -326,7 +326,7 @@ impl AuthModeWidget {
                     .title("API key")
                     .borders(Borders::ALL)
                     .border_type(BorderType::Rounded)
-                    .border_style(Style::default().fg(Color::Cyan)),
+                    .border_style(theme::accent()),
             )
             .render(input_area, buf);
 
@@ -336,7 +336,7 @@ impl AuthModeWidget {
         ];
         if let Some(error) = &self.error {
             footer_lines.push("".into());
-            footer_lines.push(error.as_str().red().into());
+            footer_lines.push(Span::styled(error.as_str(), theme::error()).into());
         }
         Paragraph::new(footer_lines)
             .wrap(Wrap { trim: false })
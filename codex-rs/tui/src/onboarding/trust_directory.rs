@@ -7,7 +7,6 @@ use crossterm::event::KeyEvent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::prelude::Widget;
-use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
@@ -17,6 +16,7 @@ use ratatui::widgets::Wrap;
 
 use crate::onboarding::onboarding_screen::KeyboardHandler;
 use crate::onboarding::onboarding_screen::StepStateProvider;
+use crate::theme;
 
 use super::onboarding_screen::StepState;
 
@@ -63,7 +63,7 @@ impl WidgetRef for &TrustDirectoryWidget {
             |idx: usize, option: TrustDirectorySelection, text: &str| -> Line<'static> {
                 let is_selected = self.highlighted == option;
                 if is_selected {
-                    Line::from(format!("> {}. {text}", idx + 1)).cyan()
+                    Line::from(format!("> {}. {text}", idx + 1)).style(theme::accent())
                 } else {
                     Line::from(format!("  {}. {}", idx + 1, text))
                 }
@@ -94,7 +94,7 @@ impl WidgetRef for &TrustDirectoryWidget {
         }
         lines.push("".into());
         if let Some(error) = &self.error {
-            lines.push(Line::from(format!("  {error}")).fg(Color::Red));
+            lines.push(Line::from(format!("  {error}")).style(theme::error()));
             lines.push("".into());
         }
         // AE: Following styles.md, this should probably be Cyan because it's a user input tip.
@@ -3,13 +3,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
+use crate::keymap::KeymapAction;
+use crate::keymap::keymap;
+use crate::pager_search::PagerSearch;
 use crate::render::renderable::Renderable;
 use crate::tui;
 use crate::tui::TuiEvent;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
 use ratatui::buffer::Buffer;
 use ratatui::buffer::Cell;
 use ratatui::layout::Rect;
@@ -57,6 +63,14 @@ impl Overlay {
             Overlay::Static(o) => o.is_done(),
         }
     }
+
+    /// True while the `/` search prompt is open and owns the keyboard.
+    pub(crate) fn is_search_input_active(&self) -> bool {
+        match self {
+            Overlay::Transcript(o) => o.view.search.is_input_active(),
+            Overlay::Static(o) => o.view.search.is_input_active(),
+        }
+    }
 }
 
 const KEY_UP: KeyBinding = key_hint::plain(KeyCode::Up);
@@ -67,16 +81,22 @@ const KEY_SPACE: KeyBinding = key_hint::plain(KeyCode::Char(' '));
 const KEY_HOME: KeyBinding = key_hint::plain(KeyCode::Home);
 const KEY_END: KeyBinding = key_hint::plain(KeyCode::End);
 const KEY_Q: KeyBinding = key_hint::plain(KeyCode::Char('q'));
-const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
+const KEY_R: KeyBinding = key_hint::plain(KeyCode::Char('r'));
 const KEY_ENTER: KeyBinding = key_hint::plain(KeyCode::Enter);
-const KEY_CTRL_T: KeyBinding = key_hint::ctrl(KeyCode::Char('t'));
-const KEY_CTRL_C: KeyBinding = key_hint::ctrl(KeyCode::Char('c'));
+const KEY_ESC: KeyBinding = key_hint::plain(KeyCode::Esc);
+const KEY_BACKSPACE: KeyBinding = key_hint::plain(KeyCode::Backspace);
+const KEY_SLASH: KeyBinding = key_hint::plain(KeyCode::Char('/'));
+const KEY_N: KeyBinding = key_hint::plain(KeyCode::Char('n'));
+const KEY_SHIFT_N: KeyBinding = key_hint::shift(KeyCode::Char('N'));
+// Some terminals report shifted letters without the SHIFT modifier.
+const KEY_UPPER_N: KeyBinding = key_hint::plain(KeyCode::Char('N'));
 
 // Common pager navigation hints rendered on the first line
 const PAGER_KEY_HINTS: &[(&[KeyBinding], &str)] = &[
     (&[KEY_UP, KEY_DOWN], "to scroll"),
     (&[KEY_PAGE_UP, KEY_PAGE_DOWN], "to page"),
     (&[KEY_HOME, KEY_END], "to jump"),
+    (&[KEY_SLASH], "to search"),
 ];
 
 // Render a single line of key hints from (key(s), description) pairs.
@@ -109,6 +129,7 @@ struct PagerView {
     last_rendered_height: Option<usize>,
     /// If set, on next render ensure this chunk is visible.
     pending_scroll_chunk: Option<usize>,
+    search: PagerSearch,
 }
 
 impl PagerView {
@@ -120,6 +141,7 @@ impl PagerView {
             last_content_height: None,
             last_rendered_height: None,
             pending_scroll_chunk: None,
+            search: PagerSearch::default(),
         }
     }
 
@@ -147,6 +169,10 @@ impl PagerView {
             .min(content_height.saturating_sub(content_area.height as usize));
 
         self.render_content(content_area, buf);
+        if self.search.has_query() {
+            self.search.refresh(&self.renderables, content_area.width);
+            self.search.highlight(content_area, buf, self.scroll_offset);
+        }
 
         self.render_bottom_bar(area, content_area, buf, content_height);
     }
@@ -228,7 +254,25 @@ impl PagerView {
     }
 
     fn handle_key_event(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) -> Result<()> {
+        if self.search.is_input_active() {
+            return self.handle_search_input(tui, key_event);
+        }
         match key_event {
+            e if KEY_SLASH.is_press(e) => {
+                self.search.start(self.scroll_offset);
+            }
+            e if KEY_N.is_press(e) && self.search.has_query() => {
+                if let Some(row) = self.search.step(true) {
+                    self.scroll_to_row(row);
+                }
+            }
+            e if (KEY_SHIFT_N.is_press(e) || KEY_UPPER_N.is_press(e))
+                && self.search.has_query() =>
+            {
+                if let Some(row) = self.search.step(false) {
+                    self.scroll_to_row(row);
+                }
+            }
             e if KEY_UP.is_press(e) => {
                 self.scroll_offset = self.scroll_offset.saturating_sub(1);
             }
@@ -258,6 +302,78 @@ impl PagerView {
         Ok(())
     }
 
+    /// Keys typed while the `/` prompt is open: edit the query and jump to
+    /// the first match below where the search started.
+    fn handle_search_input(&mut self, tui: &mut tui::Tui, key_event: KeyEvent) -> Result<()> {
+        let mut input = self.search.input().unwrap_or_default().to_string();
+        match key_event {
+            e if KEY_ESC.is_press(e) || keymap().is_press(KeymapAction::Interrupt, e) => {
+                self.search.cancel();
+                self.scroll_offset = self.search.origin_scroll();
+            }
+            e if KEY_ENTER.is_press(e) => {
+                self.search.confirm();
+            }
+            e if KEY_BACKSPACE.is_press(e) => {
+                input.pop();
+                self.update_search_input(tui, input);
+            }
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::NONE | KeyModifiers::SHIFT,
+                ..
+            } if key_event.kind != KeyEventKind::Release => {
+                input.push(c);
+                self.update_search_input(tui, input);
+            }
+            _ => return Ok(()),
+        }
+        tui.frame_requester()
+            .schedule_frame_in(Duration::from_millis(16));
+        Ok(())
+    }
+
+    fn update_search_input(&mut self, tui: &mut tui::Tui, input: String) {
+        let area = self.content_area(tui.terminal.viewport_area);
+        let width = area.width;
+        let origin = self.clamped_scroll(self.search.origin_scroll());
+        match self
+            .search
+            .set_input(input, &self.renderables, width, origin)
+        {
+            Some(row) => self.scroll_to_row(row),
+            None => self.scroll_offset = self.search.origin_scroll(),
+        }
+    }
+
+    fn clamped_scroll(&self, offset: usize) -> usize {
+        let height = self.last_content_height.unwrap_or(0);
+        let max_scroll = self
+            .last_rendered_height
+            .unwrap_or(0)
+            .saturating_sub(height);
+        offset.min(max_scroll)
+    }
+
+    /// Scroll so that content `row` is visible, about a third from the top
+    /// when it has to move.
+    fn scroll_to_row(&mut self, row: usize) {
+        let height = self.last_content_height.unwrap_or(1).max(1);
+        let top = self.clamped_scroll(self.scroll_offset);
+        if row < top || row >= top + height {
+            self.scroll_offset = row.saturating_sub(height / 3);
+        } else {
+            self.scroll_offset = top;
+        }
+    }
+
+    /// Search prompt or match summary, rendered in the row below the hints.
+    fn render_search_status(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(line) = self.search.status_line() {
+            Paragraph::new(vec![line]).render_ref(area, buf);
+        }
+    }
+
     fn update_last_content_height(&mut self, height: u16) {
         self.last_content_height = Some(height as usize);
     }
@@ -416,6 +532,7 @@ impl TranscriptOverlay {
     pub(crate) fn set_highlight_cell(&mut self, cell: Option<usize>) {
         self.highlight_cell = cell;
         self.view.renderables = Self::render_cells_to_texts(&self.cells, self.highlight_cell);
+        self.view.search.invalidate();
         if let Some(idx) = self.highlight_cell {
             self.view.scroll_chunk_into_view(idx);
         }
@@ -426,12 +543,24 @@ impl TranscriptOverlay {
         let line2 = Rect::new(area.x, area.y.saturating_add(1), area.width, 1);
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
 
-        let mut pairs: Vec<(&[KeyBinding], &str)> =
-            vec![(&[KEY_Q], "to quit"), (&[KEY_ESC], "to edit prev")];
-        if self.highlight_cell.is_some() {
+        let mut pairs: Vec<(&[KeyBinding], &str)> = vec![
+            (&[KEY_Q], "to quit"),
+            (keymap().bindings(KeymapAction::Backtrack), "to edit prev"),
+        ];
+        if let Some(idx) = self.highlight_cell {
             pairs.push((&[KEY_ENTER], "to edit message"));
+            let has_snapshot = self
+                .cells
+                .get(idx)
+                .and_then(|cell| cell.as_any().downcast_ref::<UserHistoryCell>())
+                .is_some_and(|cell| cell.snapshot.is_some());
+            if has_snapshot {
+                pairs.push((&[KEY_R], "to rewind files"));
+            }
         }
         render_key_hints(line2, buf, &pairs);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
+        self.view.render_search_status(line3, buf);
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
@@ -447,7 +576,11 @@ impl TranscriptOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
             TuiEvent::Key(key_event) => match key_event {
-                e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) || KEY_CTRL_T.is_press(e) => {
+                e if self.view.search.is_input_active() => self.view.handle_key_event(tui, e),
+                e if KEY_Q.is_press(e)
+                    || keymap().is_press(KeymapAction::Interrupt, e)
+                    || keymap().is_press(KeymapAction::OpenTranscript, e) =>
+                {
                     self.is_done = true;
                     Ok(())
                 }
@@ -495,6 +628,8 @@ impl StaticOverlay {
         render_key_hints(line1, buf, PAGER_KEY_HINTS);
         let pairs: Vec<(&[KeyBinding], &str)> = vec![(&[KEY_Q], "to quit")];
         render_key_hints(line2, buf, &pairs);
+        let line3 = Rect::new(area.x, area.y.saturating_add(2), area.width, 1);
+        self.view.render_search_status(line3, buf);
     }
 
     pub(crate) fn render(&mut self, area: Rect, buf: &mut Buffer) {
@@ -510,7 +645,8 @@ impl StaticOverlay {
     pub(crate) fn handle_event(&mut self, tui: &mut tui::Tui, event: TuiEvent) -> Result<()> {
         match event {
             TuiEvent::Key(key_event) => match key_event {
-                e if KEY_Q.is_press(e) || KEY_CTRL_C.is_press(e) => {
+                e if self.view.search.is_input_active() => self.view.handle_key_event(tui, e),
+                e if KEY_Q.is_press(e) || keymap().is_press(KeymapAction::Interrupt, e) => {
                     self.is_done = true;
                     Ok(())
                 }
@@ -569,7 +705,10 @@ mod tests {
     use crate::exec_cell::CommandOutput;
     use crate::history_cell::HistoryCell;
     use crate::history_cell::new_patch_event;
+    use crate::theme;
+    use crate::workspace_snapshot::WorkspaceSnapshot;
     use codex_core::protocol::FileChange;
+    use codex_git_tooling::GhostCommit;
     use codex_protocol::parse_command::ParsedCommand;
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
@@ -624,6 +763,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn restore_files_hint_requires_a_snapshot() {
+        let user_cell = |snapshot| {
+            Arc::new(UserHistoryCell {
+                message: "fix the bug".to_string(),
+                snapshot,
+            }) as Arc<dyn HistoryCell>
+        };
+        let ghost = WorkspaceSnapshot::Ghost(GhostCommit::new("1a2b3c4d".to_string(), None));
+        let mut overlay = TranscriptOverlay::new(vec![user_cell(None), user_cell(Some(ghost))]);
+        let area = Rect::new(0, 0, 80, 12);
+
+        overlay.set_highlight_cell(Some(0));
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        assert!(!buffer_to_text(&buf, area).contains("rewind files"));
+
+        overlay.set_highlight_cell(Some(1));
+        let mut buf = Buffer::empty(area);
+        overlay.render(area, &mut buf);
+        assert!(buffer_to_text(&buf, area).contains("to rewind files"));
+    }
+
     #[test]
     fn transcript_overlay_snapshot_basic() {
         // Prepare a transcript overlay with a few lines
@@ -691,7 +853,9 @@ mod tests {
 
         let apply_end_cell: Arc<dyn HistoryCell> =
             Arc::new(crate::history_cell::new_user_approval_decision(vec![
-                "✓ Patch applied".green().bold().into(),
+                Span::styled("✓ Patch applied", theme::success())
+                    .bold()
+                    .into(),
                 "src/foo.txt".dim().into(),
             ]));
         cells.push(apply_end_cell);
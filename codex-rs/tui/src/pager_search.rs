@@ -0,0 +1,408 @@
+//! `/` search for the pager overlays.
+//!
+//! The pager content is a list of arbitrary [`Renderable`]s (history cells in
+//! the transcript, diff renderables in the static pager), so matches are found
+//! in the rendered output rather than in the source lines: every chunk is
+//! rendered once at the current width and its rows are indexed as text. That
+//! makes matches line up with wrapping and lets the pager highlight them by
+//! restyling buffer cells.
+
+use std::ops::Range;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use crate::render::renderable::Renderable;
+use crate::theme;
+
+/// One rendered row as searchable text.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct SearchRow {
+    text: String,
+    /// For every cell with a visible symbol: byte offset in `text`, and the
+    /// cell's column range.
+    cells: Vec<(usize, u16, u16)>,
+}
+
+impl SearchRow {
+    fn from_buffer(buf: &Buffer, y: u16) -> Self {
+        let area = buf.area;
+        let mut row = SearchRow::default();
+        let mut x = area.x;
+        while x < area.right() {
+            let symbol = buf[(x, y)].symbol();
+            let width = unicode_width::UnicodeWidthStr::width(symbol).max(1) as u16;
+            if !symbol.is_empty() {
+                row.cells
+                    .push((row.text.len(), x - area.x, x - area.x + width));
+                row.text.push_str(symbol);
+            }
+            x = x.saturating_add(width);
+        }
+        // Ignore the padding to the right of the content.
+        let trimmed = row.text.trim_end().len();
+        row.text.truncate(trimmed);
+        row.cells.retain(|(offset, _, _)| *offset < trimmed);
+        row
+    }
+
+    /// Column ranges of the occurrences of `query` in this row.
+    fn find(&self, query: &str, case_sensitive: bool) -> Vec<Range<u16>> {
+        let haystack = if case_sensitive {
+            self.text.clone()
+        } else {
+            self.text.to_ascii_lowercase()
+        };
+        haystack
+            .match_indices(query)
+            .filter_map(|(start, matched)| {
+                let end = start + matched.len();
+                let first = self.cells.iter().rev().find(|cell| cell.0 <= start)?;
+                let last = self.cells.iter().rev().find(|cell| cell.0 < end)?;
+                Some(first.1..last.2)
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SearchMatch {
+    /// Row in the pager content, counted from the top of the first chunk.
+    pub row: usize,
+    pub columns: Range<u16>,
+}
+
+/// State of the `/` search in a pager.
+#[derive(Default)]
+pub(crate) struct PagerSearch {
+    /// Text typed after `/`, while the search prompt is open.
+    input: Option<String>,
+    query: String,
+    matches: Vec<SearchMatch>,
+    current: Option<usize>,
+    /// Scroll offset to return to when the prompt is cancelled.
+    origin_scroll: usize,
+    /// Rendered rows of each chunk at `index_width`.
+    index: Vec<Vec<SearchRow>>,
+    index_width: u16,
+}
+
+impl PagerSearch {
+    pub(crate) fn is_input_active(&self) -> bool {
+        self.input.is_some()
+    }
+
+    pub(crate) fn has_query(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    /// Open the prompt; `scroll_offset` is restored if it is cancelled.
+    pub(crate) fn start(&mut self, scroll_offset: usize) {
+        self.input = Some(String::new());
+        self.origin_scroll = scroll_offset;
+    }
+
+    pub(crate) fn origin_scroll(&self) -> usize {
+        self.origin_scroll
+    }
+
+    /// Close the prompt and drop the query and highlights.
+    pub(crate) fn cancel(&mut self) {
+        self.input = None;
+        self.query.clear();
+        self.matches.clear();
+        self.current = None;
+    }
+
+    /// Close the prompt but keep the matches for `n`/`N`.
+    pub(crate) fn confirm(&mut self) {
+        self.input = None;
+    }
+
+    /// Replace the typed query, re-run it and select the first match at or
+    /// below `from_row`. Returns the row of the selected match.
+    pub(crate) fn set_input(
+        &mut self,
+        input: String,
+        renderables: &[Box<dyn Renderable>],
+        width: u16,
+        from_row: usize,
+    ) -> Option<usize> {
+        self.query = input.clone();
+        self.input = Some(input);
+        self.refresh(renderables, width);
+        self.current = self
+            .matches
+            .iter()
+            .position(|m| m.row >= from_row)
+            .or_else(|| (!self.matches.is_empty()).then_some(0));
+        self.current_row()
+    }
+
+    pub(crate) fn input(&self) -> Option<&str> {
+        self.input.as_deref()
+    }
+
+    /// Drop the index after the pager replaced its content, e.g. to restyle
+    /// the highlighted cell, so the next `refresh` renders everything again.
+    pub(crate) fn invalidate(&mut self) {
+        self.index.clear();
+    }
+
+    /// Re-index the content if it changed or was re-wrapped, and recompute
+    /// the matches for the current query.
+    pub(crate) fn refresh(&mut self, renderables: &[Box<dyn Renderable>], width: u16) {
+        if self.query.is_empty() {
+            self.matches.clear();
+            self.current = None;
+            return;
+        }
+        if self.index_width != width || self.index.len() > renderables.len() {
+            self.index.clear();
+            self.index_width = width;
+        }
+        // A chunk whose height changed was edited in place (e.g. a streamed
+        // cell that grew); only that chunk needs rendering again.
+        for (rows, renderable) in self.index.iter_mut().zip(renderables) {
+            if rows.len() != renderable.desired_height(width) as usize {
+                *rows = render_rows(&**renderable, width);
+            }
+        }
+        for renderable in &renderables[self.index.len()..] {
+            self.index.push(render_rows(&**renderable, width));
+        }
+
+        // Smart case: only distinguish case when the query has uppercase.
+        let case_sensitive = self.query.chars().any(char::is_uppercase);
+        let query = if case_sensitive {
+            self.query.clone()
+        } else {
+            self.query.to_ascii_lowercase()
+        };
+        let mut matches = Vec::new();
+        let mut top = 0;
+        for rows in &self.index {
+            for (offset, row) in rows.iter().enumerate() {
+                matches.extend(row.find(&query, case_sensitive).into_iter().map(|columns| {
+                    SearchMatch {
+                        row: top + offset,
+                        columns,
+                    }
+                }));
+            }
+            top += rows.len();
+        }
+        self.current = self
+            .current
+            .filter(|_| !matches.is_empty())
+            .map(|idx| idx.min(matches.len() - 1));
+        self.matches = matches;
+    }
+
+    /// Move to the next (or previous) match, wrapping around. Returns its row.
+    pub(crate) fn step(&mut self, forward: bool) -> Option<usize> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        self.current = Some(match (self.current, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+        });
+        self.current_row()
+    }
+
+    fn current_row(&self) -> Option<usize> {
+        self.current
+            .and_then(|idx| self.matches.get(idx))
+            .map(|m| m.row)
+    }
+
+    /// Restyle the matches that fall inside the visible rows. `area` is where
+    /// content row `scroll_offset` was drawn.
+    pub(crate) fn highlight(&self, area: Rect, buf: &mut Buffer, scroll_offset: usize) {
+        let visible = scroll_offset..scroll_offset + area.height as usize;
+        for (idx, m) in self.matches.iter().enumerate() {
+            if !visible.contains(&m.row) {
+                continue;
+            }
+            let style = if Some(idx) == self.current {
+                theme::accent().add_modifier(Modifier::REVERSED | Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::REVERSED)
+            };
+            let y = area.y + (m.row - scroll_offset) as u16;
+            let start = area.x + m.columns.start.min(area.width);
+            let end = area.x + m.columns.end.min(area.width);
+            buf.set_style(Rect::new(start, y, end - start, 1), style);
+        }
+    }
+
+    /// Prompt or result summary shown under the pager.
+    pub(crate) fn status_line(&self) -> Option<Line<'static>> {
+        if let Some(input) = &self.input {
+            let mut spans: Vec<Span<'static>> = vec![" /".into(), input.clone().into()];
+            spans.push(" ".reversed());
+            if !input.is_empty() {
+                spans.push(format!("   {}", self.count_label()).dim());
+            }
+            return Some(Line::from(spans));
+        }
+        if self.query.is_empty() {
+            return None;
+        }
+        Some(Line::from(vec![
+            format!(" /{}", self.query).dim(),
+            format!("   {}", self.count_label()).dim(),
+            "   n/N to jump between matches".dim(),
+        ]))
+    }
+
+    fn count_label(&self) -> String {
+        match (self.current, self.matches.len()) {
+            (_, 0) => "no matches".to_string(),
+            (Some(idx), total) => format!("{} of {total}", idx + 1),
+            (None, total) => format!("{total} matches"),
+        }
+    }
+}
+
+fn render_rows(renderable: &dyn Renderable, width: u16) -> Vec<SearchRow> {
+    let height = renderable.desired_height(width);
+    let mut buf = Buffer::empty(Rect::new(0, 0, width, height));
+    renderable.render(buf.area, &mut buf);
+    (0..height)
+        .map(|y| SearchRow::from_buffer(&buf, y))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::text::Text;
+    use ratatui::widgets::Paragraph;
+    use ratatui::widgets::WidgetRef;
+    use ratatui::widgets::Wrap;
+
+    struct Chunk(Paragraph<'static>);
+
+    impl Renderable for Chunk {
+        fn render(&self, area: Rect, buf: &mut Buffer) {
+            self.0.render_ref(area, buf);
+        }
+        fn desired_height(&self, width: u16) -> u16 {
+            self.0.line_count(width) as u16
+        }
+    }
+
+    fn chunk(lines: &[&str]) -> Box<dyn Renderable> {
+        let lines: Vec<Line<'static>> = lines.iter().map(|l| Line::from(l.to_string())).collect();
+        Box::new(Chunk(
+            Paragraph::new(Text::from(lines)).wrap(Wrap { trim: false }),
+        ))
+    }
+
+    #[test]
+    fn finds_matches_across_chunks_with_smart_case() {
+        let renderables = vec![
+            chunk(&["$ cargo test", "test result: ok"]),
+            chunk(&["", "Cargo.toml changed", "café test"]),
+        ];
+        let mut search = PagerSearch::default();
+        search.start(0);
+
+        assert_eq!(
+            search.set_input("test".to_string(), &renderables, 40, 1),
+            Some(1)
+        );
+        assert_eq!(
+            search.matches,
+            vec![
+                SearchMatch {
+                    row: 0,
+                    columns: 8..12
+                },
+                SearchMatch {
+                    row: 1,
+                    columns: 0..4
+                },
+                SearchMatch {
+                    row: 4,
+                    columns: 5..9
+                },
+            ]
+        );
+        assert_eq!(search.step(true), Some(4));
+        assert_eq!(search.step(true), Some(0));
+        assert_eq!(search.step(false), Some(4));
+
+        search.set_input("Cargo".to_string(), &renderables, 40, 0);
+        assert_eq!(search.matches.len(), 1);
+        search.set_input("cargo".to_string(), &renderables, 40, 0);
+        assert_eq!(search.matches.len(), 2);
+
+        search.cancel();
+        assert!(!search.is_input_active());
+        assert!(!search.has_query());
+        assert_eq!(search.step(true), None);
+    }
+
+    #[test]
+    fn matches_follow_wrapping_and_new_chunks() {
+        let mut renderables = vec![chunk(&["aaaa needle bbbb"])];
+        let mut search = PagerSearch::default();
+        search.start(0);
+        search.set_input("needle".to_string(), &renderables, 8, 0);
+        assert_eq!(
+            search.matches,
+            vec![SearchMatch {
+                row: 1,
+                columns: 0..6
+            }]
+        );
+
+        renderables.push(chunk(&["needle again"]));
+        search.refresh(&renderables, 8);
+        assert_eq!(search.matches.len(), 2);
+        assert_eq!(search.matches[1].row, 3);
+    }
+
+    #[test]
+    fn content_changes_are_reindexed() {
+        let mut renderables = vec![chunk(&["needle"]), chunk(&["haystack"])];
+        let mut search = PagerSearch::default();
+        search.start(0);
+        search.set_input("needle".to_string(), &renderables, 20, 0);
+        assert_eq!(search.matches.len(), 1);
+
+        // A chunk that grew in place is rendered again.
+        renderables[1] = chunk(&["haystack", "needle"]);
+        search.refresh(&renderables, 20);
+        assert_eq!(
+            search.matches.iter().map(|m| m.row).collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+
+        // Same-sized replacements are only seen after `invalidate`.
+        renderables[0] = chunk(&["thread"]);
+        search.refresh(&renderables, 20);
+        assert_eq!(search.matches.len(), 2);
+        search.invalidate();
+        search.refresh(&renderables, 20);
+        assert_eq!(
+            search.matches,
+            vec![SearchMatch {
+                row: 2,
+                columns: 0..6
+            }]
+        );
+    }
+}
@@ -1,7 +1,19 @@
+//! Tree-sitter based highlighting for shell commands and for fenced code
+//! blocks in agent messages.
+
 use codex_core::bash::try_parse_bash;
+use ratatui::style::Style;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
 use ratatui::text::Span;
+use tree_sitter::InputEdit;
+use tree_sitter::Language;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter::Point;
+use tree_sitter::Tree;
+
+use crate::theme;
 
 /// Convert the full bash script into per-line styled content by first
 /// computing operator-dimmed spans across the entire script, then splitting
@@ -78,6 +90,242 @@ pub(crate) fn highlight_bash_to_lines(script: &str) -> Vec<Line<'static>> {
     lines
 }
 
+/// Semantic class of a highlighted stretch of code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Plain,
+    Keyword,
+    Literal,
+    String,
+    Comment,
+    Heading,
+}
+
+/// Tree-sitter grammar for a fenced code block's info string (e.g. `rust`).
+fn language_for_fence(info: &str) -> Option<Language> {
+    // Fence info strings may carry attributes, e.g. "rust,ignore" or "python title=x".
+    let name = info
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '{')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let language = match name.as_str() {
+        "rust" | "rs" => tree_sitter_rust::LANGUAGE,
+        "python" | "py" | "python3" => tree_sitter_python::LANGUAGE,
+        "javascript" | "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE,
+        "typescript" | "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "go" | "golang" => tree_sitter_go::LANGUAGE,
+        "c" | "h" => tree_sitter_c::LANGUAGE,
+        "cpp" | "c++" | "cc" | "cxx" | "hpp" => tree_sitter_cpp::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "bash" | "sh" | "shell" | "zsh" => tree_sitter_bash::LANGUAGE,
+        "toml" => tree_sitter_toml_ng::LANGUAGE,
+        "yaml" | "yml" => tree_sitter_yaml::LANGUAGE,
+        "json" | "jsonc" | "json5" => tree_sitter_json::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+/// Classify a syntax node by its kind. The grammars share enough naming
+/// (`*comment`, `*string*`, `*_literal`, bare keyword tokens) that one set
+/// of rules covers all of them.
+fn classify(node: Node<'_>) -> Option<TokenKind> {
+    let kind = node.kind();
+    if kind.ends_with("comment") {
+        return Some(TokenKind::Comment);
+    }
+    // TOML table headers: everything in `[table]` up to its first pair.
+    if let Some(parent) = node.parent()
+        && matches!(parent.kind(), "table" | "table_array_element")
+        && kind != "pair"
+    {
+        return Some(TokenKind::Heading);
+    }
+    if !node.is_named() {
+        // Anonymous tokens spelled as words are keywords (`fn`, `def`,
+        // `#include`); punctuation and operators stay plain.
+        let word = kind.trim_start_matches('#');
+        let is_word = word.starts_with(|c: char| c.is_ascii_alphabetic())
+            && word.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        return is_word.then_some(TokenKind::Keyword);
+    }
+    // YAML's `string_scalar` is any unquoted value, keys included.
+    if (kind.contains("string") && kind != "string_scalar")
+        || matches!(
+            kind,
+            "char_literal"
+                | "character_literal"
+                | "rune_literal"
+                | "double_quote_scalar"
+                | "single_quote_scalar"
+                | "block_scalar"
+        )
+    {
+        return Some(TokenKind::String);
+    }
+    if matches!(
+        kind,
+        "true"
+            | "false"
+            | "null"
+            | "none"
+            | "nil"
+            | "boolean"
+            | "number"
+            | "integer"
+            | "float"
+            | "boolean_scalar"
+            | "null_scalar"
+            | "integer_scalar"
+            | "float_scalar"
+    ) || (kind.ends_with("_literal")
+        && !matches!(kind, "composite_literal" | "func_literal" | "class_literal"))
+    {
+        return Some(TokenKind::Literal);
+    }
+    None
+}
+
+/// Highlights a fenced code block one line at a time with tree-sitter.
+///
+/// Each line is styled from a parse of the block up to and including that
+/// line, never from the lines after it. The styling of a line therefore does
+/// not change once later lines arrive, which keeps it stable while
+/// `MarkdownStreamCollector` re-renders a partially streamed code block.
+///
+/// Colors come from the active theme (see `crate::theme`), and are dropped
+/// entirely when the terminal does not report color support.
+pub(crate) struct CodeHighlighter {
+    parser: Parser,
+    tree: Option<Tree>,
+    /// The lines highlighted so far, each followed by `\n`.
+    source: String,
+    rows: usize,
+    colors: bool,
+}
+
+impl CodeHighlighter {
+    /// Returns a highlighter for the fence info string (e.g. `rust`), or
+    /// `None` for languages we do not know.
+    pub(crate) fn for_fence(info: &str) -> Option<Self> {
+        let colors = supports_color::on_cached(supports_color::Stream::Stdout).is_some();
+        Self::with_colors(info, colors)
+    }
+
+    fn with_colors(info: &str, colors: bool) -> Option<Self> {
+        let language = language_for_fence(info)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        Some(Self {
+            parser,
+            tree: None,
+            source: String::new(),
+            rows: 0,
+            colors,
+        })
+    }
+
+    /// Highlight the next line of the code block. Spans with the same style
+    /// are merged, so uncolored output is a single span per line.
+    pub(crate) fn highlight_line(&mut self, line: &str) -> Vec<Span<'static>> {
+        let line_start = self.source.len();
+        let line_end = line_start + line.len();
+        self.source.push_str(line);
+        self.source.push('\n');
+        // Reparse incrementally: the previous tree only gains a line at the end.
+        if let Some(tree) = self.tree.as_mut() {
+            let start_position = Point::new(self.rows, 0);
+            tree.edit(&InputEdit {
+                start_byte: line_start,
+                old_end_byte: line_start,
+                new_end_byte: self.source.len(),
+                start_position,
+                old_end_position: start_position,
+                new_end_position: Point::new(self.rows + 1, 0),
+            });
+        }
+        self.rows += 1;
+        self.tree = self.parser.parse(&self.source, self.tree.as_ref());
+
+        let mut ranges: Vec<(usize, usize, TokenKind)> = Vec::new();
+        if let Some(tree) = &self.tree {
+            let mut stack = vec![tree.root_node()];
+            while let Some(node) = stack.pop() {
+                if node.end_byte() <= line_start || node.start_byte() >= line_end {
+                    continue;
+                }
+                if let Some(kind) = classify(node) {
+                    ranges.push((
+                        node.start_byte().max(line_start),
+                        node.end_byte().min(line_end),
+                        kind,
+                    ));
+                    continue;
+                }
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+            }
+        }
+        ranges.sort_by_key(|(start, _, _)| *start);
+
+        let mut tokens = Tokens::default();
+        let mut i = line_start;
+        for (start, end, kind) in ranges {
+            if start < i {
+                continue;
+            }
+            tokens.push(&self.source[i..start], TokenKind::Plain);
+            tokens.push(&self.source[start..end], kind);
+            i = end;
+        }
+        tokens.push(&self.source[i..line_end], TokenKind::Plain);
+        tokens.into_spans(self.colors)
+    }
+}
+
+#[derive(Default)]
+struct Tokens {
+    tokens: Vec<(String, TokenKind)>,
+}
+
+impl Tokens {
+    fn push(&mut self, text: &str, kind: TokenKind) {
+        if text.is_empty() {
+            return;
+        }
+        match self.tokens.last_mut() {
+            Some((last, last_kind)) if *last_kind == kind => last.push_str(text),
+            _ => self.tokens.push((text.to_string(), kind)),
+        }
+    }
+
+    fn into_spans(self, colors: bool) -> Vec<Span<'static>> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (text, kind) in self.tokens {
+            let style = style_for(kind, colors);
+            match spans.last_mut() {
+                Some(last) if last.style == style => last.content.to_mut().push_str(&text),
+                _ => spans.push(Span::styled(text, style)),
+            }
+        }
+        spans
+    }
+}
+
+fn style_for(kind: TokenKind, colors: bool) -> Style {
+    match kind {
+        TokenKind::Plain => Style::new(),
+        TokenKind::Comment => Style::new().dim().italic(),
+        _ if !colors => Style::new(),
+        TokenKind::Keyword => theme::brand(),
+        TokenKind::Literal => theme::accent(),
+        TokenKind::String => theme::success(),
+        TokenKind::Heading => theme::brand().bold(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -142,4 +390,100 @@ mod tests {
         assert!(!dimmed.contains(&"\"".to_string()));
         assert!(!dimmed.contains(&"'".to_string()));
     }
+
+    fn highlight(info: &str, source: &str) -> Vec<Vec<Span<'static>>> {
+        let mut highlighter = CodeHighlighter::with_colors(info, true).expect("known language");
+        source
+            .lines()
+            .map(|line| highlighter.highlight_line(line))
+            .collect()
+    }
+
+    #[test]
+    fn highlights_rust_keywords_strings_and_comments() {
+        let lines = highlight("rust", "fn main() { // entry\n    let s = \"hi\";\n}");
+        assert_eq!(
+            lines,
+            vec![
+                vec![
+                    Span::styled("fn", Style::new().magenta()),
+                    Span::raw(" main() { "),
+                    Span::styled("// entry", Style::new().dim().italic()),
+                ],
+                vec![
+                    Span::raw("    "),
+                    Span::styled("let", Style::new().magenta()),
+                    Span::raw(" s = "),
+                    Span::styled("\"hi\"", Style::new().green()),
+                    Span::raw(";"),
+                ],
+                vec![Span::raw("}")],
+            ]
+        );
+    }
+
+    #[test]
+    fn carries_multiline_strings_and_block_comments_across_lines() {
+        let lines = highlight("python,linenums", "x = \"\"\"doc\nstill # doc\"\"\" + 1");
+        assert_eq!(
+            lines[1],
+            vec![
+                Span::styled("still # doc\"\"\"", Style::new().green()),
+                Span::raw(" + "),
+                Span::styled("1", Style::new().cyan()),
+            ]
+        );
+
+        let lines = highlight("js", "/* a\n b */ return");
+        assert_eq!(
+            lines[1],
+            vec![
+                Span::styled(" b */", Style::new().dim().italic()),
+                Span::raw(" "),
+                Span::styled("return", Style::new().magenta()),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_table_headers_and_unknown_languages() {
+        let lines = highlight("toml", "[tui]\nnotifications = true");
+        assert_eq!(
+            lines,
+            vec![
+                vec![Span::styled("[tui]", Style::new().magenta().bold())],
+                vec![
+                    Span::raw("notifications = "),
+                    Span::styled("true", Style::new().cyan()),
+                ],
+            ]
+        );
+        assert!(CodeHighlighter::with_colors("brainfuck", true).is_none());
+    }
+
+    #[test]
+    fn earlier_lines_keep_their_styles_as_the_block_grows() {
+        let source = "let s = \"a\nb\";\nlet n = 1;";
+        let mut whole = CodeHighlighter::with_colors("rust", true).expect("rust");
+        let all: Vec<_> = source.lines().map(|l| whole.highlight_line(l)).collect();
+        let mut partial = CodeHighlighter::with_colors("rust", true).expect("rust");
+        let first = partial.highlight_line("let s = \"a");
+        assert_eq!(first, all[0]);
+        assert_eq!(
+            all[1],
+            vec![Span::styled("b\"", Style::new().green()), Span::raw(";")]
+        );
+    }
+
+    #[test]
+    fn without_color_support_only_comments_are_styled() {
+        let mut highlighter = CodeHighlighter::with_colors("rust", false).expect("rust");
+        assert_eq!(
+            highlighter.highlight_line("let x = 1; // one"),
+            vec![
+                Span::raw("let x = 1; "),
+                Span::styled("// one", Style::new().dim().italic()),
+            ]
+        );
+    }
 }
@@ -10,6 +10,7 @@ use ratatui::text::Span;
 use crate::color::blend;
 use crate::terminal_palette::default_fg;
 use crate::terminal_palette::terminal_palette;
+use crate::theme;
 
 const FALLBACK_DARK_GRAY: (u8, u8, u8) = (103, 103, 103);
 
@@ -35,6 +36,8 @@ pub(crate) fn shimmer_spans(text: &str) -> Vec<Span<'static>> {
     let has_true_color = supports_color::on_cached(supports_color::Stream::Stdout)
         .map(|level| level.has_16m)
         .unwrap_or(false);
+    // High-contrast themes skip the dimmed RGB gradient.
+    let high_contrast = theme::palette().high_contrast;
     let band_half_width = 3.0;
 
     let mut spans: Vec<Span<'static>> = Vec::with_capacity(chars.len());
@@ -51,7 +54,9 @@ pub(crate) fn shimmer_spans(text: &str) -> Vec<Span<'static>> {
         } else {
             0.0
         };
-        let style = if has_true_color {
+        let style = if high_contrast {
+            color_for_level(t).remove_modifier(Modifier::DIM)
+        } else if has_true_color {
             let base = palette_dark_gray
                 .or(default_fg)
                 .unwrap_or(FALLBACK_DARK_GRAY);
@@ -23,6 +23,7 @@ pub enum SlashCommand {
     Mention,
     Status,
     Mcp,
+    Theme,
     Logout,
     Quit,
     #[cfg(debug_assertions)]
@@ -44,7 +45,8 @@ impl SlashCommand {
             SlashCommand::Status => "show current session configuration and token usage",
             SlashCommand::Model => "choose what model and reasoning effort to use",
             SlashCommand::Approvals => "choose what Codex can do without approval",
-            SlashCommand::Mcp => "list configured MCP tools",
+            SlashCommand::Mcp => "list MCP tools; `/mcp restart <server>` or `/mcp reload`",
+            SlashCommand::Theme => "choose a color theme; `/theme <name>`",
             SlashCommand::Logout => "log out of Codex",
             #[cfg(debug_assertions)]
             SlashCommand::TestApproval => "test approval request",
@@ -57,6 +59,12 @@ impl SlashCommand {
         self.into()
     }
 
+    /// Whether this command takes arguments after its name, e.g.
+    /// `/mcp restart <server>`.
+    pub fn accepts_args(self) -> bool {
+        matches!(self, SlashCommand::Mcp | SlashCommand::Theme)
+    }
+
     /// Whether this command can be run while a task is in progress.
     pub fn available_during_task(self) -> bool {
         match self {
@@ -72,6 +80,7 @@ impl SlashCommand {
             | SlashCommand::Mention
             | SlashCommand::Status
             | SlashCommand::Mcp
+            | SlashCommand::Theme
             | SlashCommand::Quit => true,
 
             #[cfg(debug_assertions)]
@@ -193,7 +193,7 @@ impl WidgetRef for StatusIndicatorWidget {
                 Line::from(vec![
                     "   ".into(),
                     key_hint::alt(KeyCode::Up).into(),
-                    " edit".into(),
+                    " edit queue".into(),
                 ])
                 .dim(),
             );
@@ -2,6 +2,7 @@ use crate::color::blend;
 use crate::color::is_light;
 use crate::color::perceptual_distance;
 use crate::terminal_palette::terminal_palette;
+use crate::theme;
 use ratatui::style::Color;
 use ratatui::style::Style;
 
@@ -25,7 +26,12 @@ pub fn user_message_bg(terminal_bg: (u8, u8, u8)) -> Color {
         return Color::default();
     };
 
-    let target = blend(top, bottom, 0.1);
+    let alpha = if theme::palette().high_contrast {
+        0.25
+    } else {
+        0.1
+    };
+    let target = blend(top, bottom, alpha);
     if color_level.has_16m {
         let (r, g, b) = target;
         Color::Rgb(r, g, b)
@@ -0,0 +1,275 @@
+//! Named color themes for the TUI.
+//!
+//! Widgets that color text by meaning (accent, success, error, diffs) ask this
+//! module for a [`Style`] instead of hardcoding an ANSI color, so that users
+//! can pick a theme in `config.toml` (`[tui] theme`) or with `/theme`.
+//! Switching themes only affects output rendered afterwards; history lines
+//! that were already written to the terminal scrollback keep their colors.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use codex_core::config_types::ThemePalette;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+
+pub(crate) const DEFAULT_THEME: &str = "dark";
+
+/// Colors used for each semantic role.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Palette {
+    pub accent: Color,
+    pub success: Color,
+    pub error: Color,
+    pub brand: Color,
+    pub diff_added: Color,
+    pub diff_removed: Color,
+    /// Bold colored text, a stronger user message background and a plain
+    /// status shimmer.
+    pub high_contrast: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            success: Color::Green,
+            error: Color::Red,
+            brand: Color::Magenta,
+            diff_added: Color::Green,
+            diff_removed: Color::Red,
+            high_contrast: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Theme {
+    pub name: String,
+    pub palette: Palette,
+}
+
+const BUILT_IN_THEMES: [&str; 4] = ["dark", "light", "high-contrast", "deuteranopia"];
+
+fn built_in(name: &str) -> Option<Palette> {
+    let dark = Palette::default();
+    match name {
+        "dark" => Some(dark),
+        // Cyan washes out on light backgrounds; `styles.md` allows blue here.
+        "light" => Some(Palette {
+            accent: Color::Blue,
+            ..dark
+        }),
+        "high-contrast" => Some(Palette {
+            accent: Color::LightCyan,
+            success: Color::LightGreen,
+            error: Color::LightRed,
+            brand: Color::LightMagenta,
+            diff_added: Color::LightGreen,
+            diff_removed: Color::LightRed,
+            high_contrast: true,
+        }),
+        // Blue/yellow stay distinguishable for red-green color blindness;
+        // `styles.md` allows them here.
+        "deuteranopia" => Some(Palette {
+            success: Color::Blue,
+            error: Color::Yellow,
+            diff_added: Color::Blue,
+            diff_removed: Color::Yellow,
+            ..dark
+        }),
+        _ => None,
+    }
+}
+
+/// Built-in theme names followed by the user-defined ones, sorted.
+pub(crate) fn theme_names(custom: &HashMap<String, ThemePalette>) -> Vec<String> {
+    let mut custom_names: Vec<String> = custom
+        .keys()
+        .filter(|name| built_in(name).is_none())
+        .cloned()
+        .collect();
+    custom_names.sort();
+    BUILT_IN_THEMES
+        .iter()
+        .map(|name| (*name).to_string())
+        .chain(custom_names)
+        .collect()
+}
+
+/// Look up `name` among the user-defined themes first, then the built-ins.
+pub(crate) fn resolve(name: &str, custom: &HashMap<String, ThemePalette>) -> Result<Theme, String> {
+    let palette = match custom.get(name) {
+        Some(custom_palette) => custom_palette_to_palette(name, custom_palette)?,
+        None => built_in(name).ok_or_else(|| {
+            format!(
+                "Unknown theme `{name}`. Available themes: {}",
+                theme_names(custom).join(", ")
+            )
+        })?,
+    };
+    Ok(Theme {
+        name: name.to_string(),
+        palette,
+    })
+}
+
+fn custom_palette_to_palette(name: &str, custom: &ThemePalette) -> Result<Palette, String> {
+    let base_name = custom.base.as_deref().unwrap_or(DEFAULT_THEME);
+    let base = built_in(base_name)
+        .ok_or_else(|| format!("Theme `{name}` uses unknown base theme `{base_name}`"))?;
+    // Only ANSI names: like the rest of the TUI (see `styles.md`), themes
+    // leave the exact shade to the terminal's own color scheme.
+    let color = |value: &Option<String>, fallback: Color| -> Result<Color, String> {
+        match value {
+            Some(value) => match Color::from_str(value) {
+                Ok(Color::Rgb(..) | Color::Indexed(_)) => Err(format!(
+                    "Theme `{name}` color `{value}` is not an ANSI color name"
+                )),
+                Ok(color) => Ok(color),
+                Err(_) => Err(format!("Theme `{name}` has an invalid color `{value}`")),
+            },
+            None => Ok(fallback),
+        }
+    };
+    Ok(Palette {
+        accent: color(&custom.accent, base.accent)?,
+        success: color(&custom.success, base.success)?,
+        error: color(&custom.error, base.error)?,
+        brand: color(&custom.brand, base.brand)?,
+        diff_added: color(&custom.diff_added, base.diff_added)?,
+        diff_removed: color(&custom.diff_removed, base.diff_removed)?,
+        high_contrast: custom.high_contrast.unwrap_or(base.high_contrast),
+    })
+}
+
+static CURRENT: LazyLock<RwLock<Theme>> = LazyLock::new(|| {
+    RwLock::new(Theme {
+        name: DEFAULT_THEME.to_string(),
+        palette: Palette::default(),
+    })
+});
+
+pub(crate) fn set_theme(theme: Theme) {
+    match CURRENT.write() {
+        Ok(mut current) => *current = theme,
+        Err(poisoned) => *poisoned.into_inner() = theme,
+    }
+}
+
+pub(crate) fn current_name() -> String {
+    match CURRENT.read() {
+        Ok(current) => current.name.clone(),
+        Err(poisoned) => poisoned.into_inner().name.clone(),
+    }
+}
+
+pub(crate) fn palette() -> Palette {
+    match CURRENT.read() {
+        Ok(current) => current.palette,
+        Err(poisoned) => poisoned.into_inner().palette,
+    }
+}
+
+fn colored(color: Color) -> Style {
+    let style = Style::default().fg(color);
+    if palette().high_contrast {
+        style.add_modifier(Modifier::BOLD)
+    } else {
+        style
+    }
+}
+
+pub(crate) fn accent() -> Style {
+    colored(palette().accent)
+}
+
+pub(crate) fn success() -> Style {
+    colored(palette().success)
+}
+
+pub(crate) fn error() -> Style {
+    colored(palette().error)
+}
+
+pub(crate) fn brand() -> Style {
+    colored(palette().brand)
+}
+
+pub(crate) fn diff_added() -> Style {
+    colored(palette().diff_added)
+}
+
+pub(crate) fn diff_removed() -> Style {
+    colored(palette().diff_removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn custom_theme_overrides_its_base() {
+        let custom = HashMap::from([(
+            "solar".to_string(),
+            ThemePalette {
+                base: Some("deuteranopia".to_string()),
+                accent: Some("light-magenta".to_string()),
+                diff_added: Some("magenta".to_string()),
+                ..Default::default()
+            },
+        )]);
+
+        let theme = resolve("solar", &custom).expect("theme should resolve");
+
+        assert_eq!(
+            theme.palette,
+            Palette {
+                accent: Color::LightMagenta,
+                diff_added: Color::Magenta,
+                ..built_in("deuteranopia").expect("built-in theme")
+            }
+        );
+        assert_eq!(
+            theme_names(&custom),
+            vec!["dark", "light", "high-contrast", "deuteranopia", "solar"]
+        );
+    }
+
+    #[test]
+    fn invalid_themes_are_reported() {
+        let custom = HashMap::from([
+            (
+                "broken".to_string(),
+                ThemePalette {
+                    error: Some("not-a-color".to_string()),
+                    ..Default::default()
+                },
+            ),
+            (
+                "hex".to_string(),
+                ThemePalette {
+                    accent: Some("#268bd2".to_string()),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            resolve("broken", &custom),
+            Err("Theme `broken` has an invalid color `not-a-color`".to_string())
+        );
+        assert_eq!(
+            resolve("hex", &custom),
+            Err("Theme `hex` color `#268bd2` is not an ANSI color name".to_string())
+        );
+        assert_eq!(
+            resolve("neon", &custom),
+            Err("Unknown theme `neon`. Available themes: dark, light, high-contrast, deuteranopia, broken, hex".to_string())
+        );
+    }
+}
@@ -1,15 +1,20 @@
+use std::future::Future;
 use std::io::IsTerminal;
 use std::io::Result;
 use std::io::Stdout;
 use std::io::stdout;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
 use std::sync::atomic::AtomicBool;
 #[cfg(unix)]
 use std::sync::atomic::AtomicU8;
 #[cfg(unix)]
 use std::sync::atomic::AtomicU16;
 use std::sync::atomic::Ordering;
+use std::task::Poll;
+use std::task::Waker;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -22,6 +27,7 @@ use crossterm::event::DisableFocusChange;
 use crossterm::event::EnableBracketedPaste;
 use crossterm::event::EnableFocusChange;
 use crossterm::event::Event;
+use crossterm::event::EventStream;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyboardEnhancementFlags;
 use crossterm::event::PopKeyboardEnhancementFlags;
@@ -166,6 +172,32 @@ pub struct Tui {
     // True when terminal/tab is focused; updated internally from crossterm events
     terminal_focused: Arc<AtomicBool>,
     enhanced_keys_supported: bool,
+    event_source: Arc<Mutex<EventSource>>,
+}
+
+/// Terminal input shared between [`Tui::event_stream`] and
+/// [`Tui::with_restored`]. Dropping the crossterm `EventStream` stops its
+/// reader thread, so a child process can read stdin without competing with us.
+#[derive(Default)]
+struct EventSource {
+    stream: Option<EventStream>,
+    paused: bool,
+    waker: Option<Waker>,
+}
+
+/// Next crossterm event, or pending for as long as input is paused.
+fn next_terminal_event(
+    source: &Mutex<EventSource>,
+) -> impl Future<Output = Option<std::io::Result<Event>>> + '_ {
+    std::future::poll_fn(move |cx| {
+        let mut source = source.lock().unwrap_or_else(PoisonError::into_inner);
+        if source.paused {
+            source.waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        let stream = source.stream.get_or_insert_with(EventStream::new);
+        Pin::new(stream).poll_next(cx)
+    })
 }
 
 #[cfg(unix)]
@@ -293,6 +325,7 @@ impl Tui {
             alt_screen_active: Arc::new(AtomicBool::new(false)),
             terminal_focused: Arc::new(AtomicBool::new(true)),
             enhanced_keys_supported,
+            event_source: Arc::new(Mutex::new(EventSource::default())),
         }
     }
 
@@ -307,8 +340,7 @@ impl Tui {
     }
 
     pub fn event_stream(&self) -> Pin<Box<dyn Stream<Item = TuiEvent> + Send + 'static>> {
-        use tokio_stream::StreamExt;
-        let mut crossterm_events = crossterm::event::EventStream::new();
+        let event_source = self.event_source.clone();
         let mut draw_rx = self.draw_tx.subscribe();
         #[cfg(unix)]
         let resume_pending = self.resume_pending.clone();
@@ -320,7 +352,7 @@ impl Tui {
         let event_stream = async_stream::stream! {
             loop {
                 select! {
-                    Some(Ok(event)) = crossterm_events.next() => {
+                    Some(Ok(event)) = next_terminal_event(&event_source) => {
                         match event {
                             crossterm::event::Event::Key(key_event) => {
                                 #[cfg(unix)]
@@ -449,6 +481,51 @@ impl Tui {
         Ok(())
     }
 
+    /// Hand the terminal to a child process such as an external editor: stop
+    /// reading input, restore the normal terminal modes, run `f`, then take
+    /// the terminal back and redraw from scratch.
+    pub async fn with_restored<F, Fut, R>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = R>,
+    {
+        self.set_events_paused(true);
+        let alt_screen_active = self.alt_screen_active.load(Ordering::Relaxed);
+        if alt_screen_active {
+            let _ = execute!(self.terminal.backend_mut(), DisableAlternateScroll);
+            let _ = execute!(self.terminal.backend_mut(), LeaveAlternateScreen);
+        }
+        let _ = execute!(self.terminal.backend_mut(), crossterm::cursor::Show);
+        let result = match restore() {
+            Ok(()) => Ok(f().await),
+            Err(err) => Err(err),
+        };
+        let modes = set_modes();
+        if alt_screen_active {
+            let _ = execute!(self.terminal.backend_mut(), EnterAlternateScreen);
+            let _ = execute!(self.terminal.backend_mut(), EnableAlternateScroll);
+        }
+        self.set_events_paused(false);
+        let result = result?;
+        modes?;
+        self.terminal.clear()?;
+        self.frame_requester().schedule_frame();
+        Ok(result)
+    }
+
+    fn set_events_paused(&self, paused: bool) {
+        let mut source = self
+            .event_source
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        source.paused = paused;
+        if paused {
+            source.stream = None;
+        } else if let Some(waker) = source.waker.take() {
+            waker.wake();
+        }
+    }
+
     /// Enter alternate screen and expand the viewport to full terminal size, saving the current
     /// inline viewport for restoration when leaving.
     pub fn enter_alt_screen(&mut self) -> Result<()> {
@@ -0,0 +1,129 @@
+//! Workspace snapshots taken before each user turn, used by `/undo` and by
+//! backtracking to put files back the way they were.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_git_tooling::CreateGhostCommitOptions;
+use codex_git_tooling::FileSnapshot;
+use codex_git_tooling::FileSnapshotStore;
+use codex_git_tooling::GhostCommit;
+use codex_git_tooling::GitToolingError;
+use codex_git_tooling::create_ghost_commit;
+use codex_git_tooling::restore_file_snapshot;
+use codex_git_tooling::restore_ghost_commit;
+use tokio::sync::mpsc;
+
+#[derive(Debug, Clone)]
+pub(crate) enum WorkspaceSnapshot {
+    /// Unreferenced commit of the working tree, for git repositories.
+    Ghost(GhostCommit),
+    /// Copy of the files, for directories outside git.
+    Files(FileSnapshot),
+}
+
+impl WorkspaceSnapshot {
+    pub(crate) fn restore(&self, cwd: &Path) -> Result<(), GitToolingError> {
+        match self {
+            WorkspaceSnapshot::Ghost(commit) => restore_ghost_commit(cwd, commit),
+            WorkspaceSnapshot::Files(snapshot) => restore_file_snapshot(snapshot),
+        }
+    }
+
+    /// Short description for messages, e.g. `snapshot 1a2b3c4d`.
+    pub(crate) fn label(&self) -> String {
+        match self {
+            WorkspaceSnapshot::Ghost(commit) => {
+                let short_id: String = commit.id().chars().take(8).collect();
+                format!("snapshot {short_id}")
+            }
+            WorkspaceSnapshot::Files(snapshot) => {
+                format!("file snapshot ({} files)", snapshot.file_count())
+            }
+        }
+    }
+}
+
+/// Captures [`WorkspaceSnapshot`]s of one directory: ghost commits inside a
+/// git repository and, when `file_snapshots` is on, file copies elsewhere.
+pub(crate) struct WorkspaceSnapshotter {
+    cwd: PathBuf,
+    file_snapshots: bool,
+    files: Option<FileSnapshotStore>,
+}
+
+impl WorkspaceSnapshotter {
+    pub(crate) fn new(cwd: PathBuf, file_snapshots: bool) -> Self {
+        Self {
+            cwd,
+            file_snapshots,
+            files: None,
+        }
+    }
+
+    pub(crate) fn capture(&mut self) -> Result<WorkspaceSnapshot, GitToolingError> {
+        if let Some(store) = self.files.as_mut() {
+            return store.capture().map(WorkspaceSnapshot::Files);
+        }
+        match create_ghost_commit(&CreateGhostCommitOptions::new(&self.cwd)) {
+            Ok(commit) => Ok(WorkspaceSnapshot::Ghost(commit)),
+            Err(GitToolingError::NotAGitRepository { .. }) if self.file_snapshots => {
+                let store = self.files.insert(FileSnapshotStore::new(&self.cwd)?);
+                store.capture().map(WorkspaceSnapshot::Files)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+type SnapshotCallback = Box<dyn FnOnce(Result<WorkspaceSnapshot, GitToolingError>) + Send>;
+
+/// Runs a [`WorkspaceSnapshotter`] on a blocking thread so large workspaces
+/// do not stall the UI. Captures happen one at a time, in request order.
+pub(crate) struct SnapshotWorker {
+    requests: mpsc::UnboundedSender<SnapshotCallback>,
+}
+
+impl SnapshotWorker {
+    pub(crate) fn spawn(snapshotter: WorkspaceSnapshotter) -> Self {
+        let (requests, mut rx) = mpsc::unbounded_channel::<SnapshotCallback>();
+        tokio::spawn(async move {
+            let mut snapshotter = Some(snapshotter);
+            while let Some(done) = rx.recv().await {
+                let Some(mut current) = snapshotter.take() else {
+                    done(Err(
+                        io::Error::other("workspace snapshotter panicked").into()
+                    ));
+                    continue;
+                };
+                let result = tokio::task::spawn_blocking(move || {
+                    let result = current.capture();
+                    (current, result)
+                })
+                .await;
+                match result {
+                    Ok((current, result)) => {
+                        snapshotter = Some(current);
+                        done(result);
+                    }
+                    Err(err) => done(Err(io::Error::other(err).into())),
+                }
+            }
+        });
+        Self { requests }
+    }
+
+    /// Take a snapshot after the ones already requested and pass the result
+    /// to `done` on the worker task.
+    pub(crate) fn capture(
+        &self,
+        done: impl FnOnce(Result<WorkspaceSnapshot, GitToolingError>) + Send + 'static,
+    ) {
+        if let Err(mpsc::error::SendError(done)) = self.requests.send(Box::new(done)) {
+            done(Err(
+                io::Error::other("workspace snapshot worker stopped").into()
+            ));
+        }
+    }
+}